@@ -7,14 +7,25 @@ use ic_cdk::{api, caller, trap};
 use ic_cdk_macros::*;
 
 use ego_dev_mod::backup::*;
+use ego_dev_mod::c2c::c2c_types::{AppStatsRequest, AppStatsResponse};
 use ego_dev_mod::c2c::ego_file::EgoFile;
 use ego_dev_mod::c2c::ego_store::EgoStore;
 use ego_dev_mod::service::*;
 use ego_dev_mod::state::*;
-use ego_dev_mod::types::{AdminAppCreateBackendRequest, AppMainNewRequest, AppVersionSetFrontendAddressRequest, AppVersionUploadWasmRequest, EgoDevErr, UserRoleSetRequest};
+use ego_dev_mod::types::{AdminAppCreateBackendRequest, AppCollaboratorRemoveRequest, AppCollaboratorSetRequest, AppMainNewRequest, AppMetadataSetRequest, AppReviewHideRequest, AppReviewReplyRequest, AppTransferRequest, AppVersionNewRequest, AppVersionReleaseNoteSetRequest, AppVersionReleaseRequest, AppVersionReviewRequest, AppVersionSetFrontendAddressRequest, AppVersionSourceSetRequest, AppVersionUploadWasmRequest, AuditPolicySetRequest, DelegateSetRequest, DeveloperProfileSetRequest, EgoDevErr, TeamAppTransferRequest, TeamMemberRequest, UserRoleSetRequest, UserVerifiedSetRequest};
+use ego_dev_mod::types::app_collaborator::{AppCollaborator, AppRole};
+use ego_dev_mod::types::app_metadata::EgoDevAppMetadata;
+use ego_dev_mod::types::app_transfer::AppTransfer;
 use ego_dev_mod::types::app_version::AppVersion;
+use ego_dev_mod::types::app_version_transition::AppVersionTransition;
+use ego_dev_mod::types::audit_policy::AuditPolicy;
+use ego_dev_mod::types::audit_review::AuditReview;
+use ego_dev_mod::types::delegate::Delegate;
+use ego_dev_mod::types::delegate_action::DelegateAction;
 use ego_dev_mod::types::developer::Developer;
+use ego_dev_mod::types::developer_profile::DeveloperProfile;
 use ego_dev_mod::types::ego_dev_app::EgoDevApp;
+use ego_dev_mod::types::team::Team;
 use ego_macros::{inject_cycle_info_api, inject_ego_api};
 use ego_types::app::{AppId, Version};
 use ego_types::app::EgoError;
@@ -77,13 +88,32 @@ pub fn developer_main_get() -> Result<Developer, EgoError> {
   }
 }
 
-// 创建的应用列表
+// 设置个人主页, 联系方式和头像
+#[update(name = "developer_profile_set", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_profile_set")]
+pub fn developer_profile_set(request: DeveloperProfileSetRequest) -> Result<DeveloperProfile, EgoError> {
+  info_log_add("developer_profile_set");
+
+  EgoDevService::developer_profile_set(&caller(), request)
+}
+
+// 公开的开发者信息
+#[query(name = "developer_profile_get")]
+#[candid_method(query, rename = "developer_profile_get")]
+pub fn developer_profile_get(developer_id: Principal) -> Result<DeveloperProfile, EgoError> {
+  info_log_add("developer_profile_get");
+
+  Developer::get(&developer_id).ok_or(EgoError::from(EgoDevErr::NotADeveloper))?;
+  Ok(DeveloperProfile::get(&developer_id).unwrap_or_else(|| DeveloperProfile::new(&developer_id)))
+}
+
+// 可访问的应用列表, 包括团队和协作的应用
 #[query(name = "developer_app_list", guard = "developer_guard")]
 #[candid_method(query, rename = "developer_app_list")]
 pub fn developer_app_list() -> Result<Vec<EgoDevApp>, EgoError> {
   info_log_add("developer_app_list");
 
-  let apps = EgoDevApp::by_developer_id(&caller());
+  let apps = EgoDevApp::by_user_id(&caller());
 
   Ok(apps)
 }
@@ -94,14 +124,7 @@ pub fn developer_app_list() -> Result<Vec<EgoDevApp>, EgoError> {
 pub fn developer_app_get(app_id: AppId) -> Result<EgoDevApp, EgoError> {
   info_log_add("developer_app_get");
 
-  match EgoDevApp::by_developer_id_and_id(&caller(), &app_id) {
-    None => {
-      Err(EgoDevErr::AppNotExists.into())
-    }
-    Some(ego_dev_app) => {
-      Ok(ego_dev_app)
-    }
-  }
+  EgoDevApp::by_role(&caller(), &app_id, AppRole::VIEWER)
 }
 
 // 新建App
@@ -123,7 +146,7 @@ pub fn developer_app_new(request: AppMainNewRequest) -> Result<EgoDevApp, EgoErr
 }
 
 // 新建版本
-#[update(name = "app_version_new", guard = "developer_guard")]
+#[update(name = "app_version_new", guard = "developer_or_delegate_guard")]
 #[candid_method(update, rename = "app_version_new")]
 pub fn app_version_new(app_id: AppId, version: Version) -> Result<AppVersion, EgoError> {
   info_log_add("new_app_version");
@@ -132,7 +155,16 @@ pub fn app_version_new(app_id: AppId, version: Version) -> Result<AppVersion, Eg
   Ok(app_version)
 }
 
-#[update(name = "app_version_upload_wasm", guard = "developer_guard")]
+// 新建版本, 支持 semver 预发布标签, 如 1.2.0-beta.1
+#[update(name = "app_version_new_v2", guard = "developer_or_delegate_guard")]
+#[candid_method(update, rename = "app_version_new_v2")]
+pub fn app_version_new_v2(request: AppVersionNewRequest) -> Result<AppVersion, EgoError> {
+  info_log_add("app_version_new_v2");
+
+  EgoDevService::app_version_new_v2(&caller(), &request.app_id, &request.version)
+}
+
+#[update(name = "app_version_upload_wasm", guard = "developer_or_delegate_guard")]
 #[candid_method(update, rename = "app_version_upload_wasm")]
 async fn app_version_upload_wasm(request: AppVersionUploadWasmRequest) -> Result<bool, EgoError> {
   info_log_add("app_version_upload_wasm");
@@ -149,7 +181,7 @@ async fn app_version_upload_wasm(request: AppVersionUploadWasmRequest) -> Result
   Ok(ret)
 }
 
-#[update(name = "app_version_set_frontend_address", guard = "developer_guard")]
+#[update(name = "app_version_set_frontend_address", guard = "developer_or_delegate_guard")]
 #[candid_method(update, rename = "app_version_set_frontend_address")]
 pub fn app_version_set_frontend_address(
   request: AppVersionSetFrontendAddressRequest,
@@ -167,8 +199,17 @@ pub fn app_version_set_frontend_address(
   Ok(ret)
 }
 
+// 设置版本源码信息, 用于审核人员复现构建
+#[update(name = "app_version_source_set", guard = "developer_or_delegate_guard")]
+#[candid_method(update, rename = "app_version_source_set")]
+pub fn app_version_source_set(request: AppVersionSourceSetRequest) -> Result<AppVersion, EgoError> {
+  info_log_add("app_version_source_set");
+  let app_version = EgoDevService::app_version_source_set(&caller(), &request.app_id, &request.version, request.source)?;
+  Ok(app_version)
+}
+
 // 提交审核
-#[update(name = "app_version_submit", guard = "developer_guard")]
+#[update(name = "app_version_submit", guard = "developer_or_delegate_guard")]
 #[candid_method(update, rename = "app_version_submit")]
 pub fn app_version_submit(app_id: AppId, version: Version) -> Result<AppVersion, EgoError> {
   info_log_add("app_version_submit");
@@ -176,8 +217,33 @@ pub fn app_version_submit(app_id: AppId, version: Version) -> Result<AppVersion,
   Ok(app_version)
 }
 
+// 删除未提交的版本
+#[update(name = "app_version_delete", guard = "developer_guard")]
+#[candid_method(update, rename = "app_version_delete")]
+pub async fn app_version_delete(app_id: AppId, version: Version) -> Result<AppVersion, EgoError> {
+  info_log_add("app_version_delete");
+  let app_version = EgoDevService::app_version_delete(EgoFile::new(), &caller(), &app_id, &version).await?;
+  Ok(app_version)
+}
+
+// 预发布版本转为正式版本, 需重新审核
+#[update(name = "app_version_promote", guard = "developer_or_delegate_guard")]
+#[candid_method(update, rename = "app_version_promote")]
+pub fn app_version_promote(app_id: AppId, version: Version) -> Result<AppVersion, EgoError> {
+  info_log_add("app_version_promote");
+  EgoDevService::app_version_promote(&caller(), &app_id, &version)
+}
+
+// 版本状态变更记录
+#[query(name = "developer_app_version_transition_list", guard = "developer_guard")]
+#[candid_method(query, rename = "developer_app_version_transition_list")]
+pub fn developer_app_version_transition_list(app_id: AppId, version: Version) -> Result<Vec<AppVersionTransition>, EgoError> {
+  info_log_add("developer_app_version_transition_list");
+  EgoDevService::app_version_transition_list(&caller(), &app_id, &version)
+}
+
 // 撤回审核
-#[update(name = "app_version_revoke", guard = "developer_guard")]
+#[update(name = "app_version_revoke", guard = "developer_or_delegate_guard")]
 #[candid_method(update, rename = "app_version_revoke")]
 pub fn app_version_revoke(app_id: AppId, version: Version) -> Result<AppVersion, EgoError> {
   info_log_add("app_version_revoke");
@@ -186,7 +252,7 @@ pub fn app_version_revoke(app_id: AppId, version: Version) -> Result<AppVersion,
 }
 
 // 发布版本
-#[update(name = "app_version_release", guard = "developer_guard")]
+#[update(name = "app_version_release", guard = "developer_or_delegate_guard")]
 #[candid_method(update, rename = "app_version_release")]
 pub async fn app_version_release(app_id: AppId, version: Version) -> Result<AppVersion, EgoError> {
   info_log_add("app_version_release");
@@ -194,7 +260,228 @@ pub async fn app_version_release(app_id: AppId, version: Version) -> Result<AppV
 
   let ego_store_id = canister_get_one("ego_store").unwrap();
   let ego_store = EgoStore::new(ego_store_id);
-  EgoDevService::app_version_release(&caller, &app_id, &version, ego_store)
+  EgoDevService::app_version_release(&caller, &app_id, &version, false, ego_store)
+}
+
+// 发布版本, hotfix 可发布低于最新版本的修复版本
+#[update(name = "app_version_release_v2", guard = "developer_or_delegate_guard")]
+#[candid_method(update, rename = "app_version_release_v2")]
+pub async fn app_version_release_v2(request: AppVersionReleaseRequest) -> Result<AppVersion, EgoError> {
+  info_log_add("app_version_release_v2");
+  let caller = caller();
+
+  let ego_store_id = canister_get_one("ego_store").unwrap();
+  let ego_store = EgoStore::new(ego_store_id);
+  EgoDevService::app_version_release(&caller, &request.app_id, &request.version, request.hotfix, ego_store)
+}
+
+// 设置应用商店展示信息
+#[update(name = "developer_app_metadata_set", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_app_metadata_set")]
+pub fn developer_app_metadata_set(request: AppMetadataSetRequest) -> Result<EgoDevAppMetadata, EgoError> {
+  info_log_add("developer_app_metadata_set");
+
+  let ego_store_id = canister_get_one("ego_store").unwrap();
+  let ego_store = EgoStore::new(ego_store_id);
+  EgoDevService::app_metadata_set(&caller(), request, ego_store)
+}
+
+#[query(name = "developer_app_metadata_get", guard = "developer_guard")]
+#[candid_method(query, rename = "developer_app_metadata_get")]
+pub fn developer_app_metadata_get(app_id: AppId) -> Result<EgoDevAppMetadata, EgoError> {
+  EgoDevApp::by_role(&caller(), &app_id, AppRole::VIEWER)?;
+
+  Ok(EgoDevAppMetadata::get(&app_id).unwrap_or_else(|| EgoDevAppMetadata::new(&app_id)))
+}
+
+// 设置版本更新说明
+#[update(name = "app_version_release_note_set", guard = "developer_guard")]
+#[candid_method(update, rename = "app_version_release_note_set")]
+pub fn app_version_release_note_set(request: AppVersionReleaseNoteSetRequest) -> Result<EgoDevAppMetadata, EgoError> {
+  info_log_add("app_version_release_note_set");
+
+  let ego_store_id = canister_get_one("ego_store").unwrap();
+  let ego_store = EgoStore::new(ego_store_id);
+  EgoDevService::app_version_release_note_set(&caller(), &request.app_id, &request.version, request.notes, ego_store)
+}
+
+// 回复用户评价
+#[update(name = "developer_app_review_reply", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_app_review_reply")]
+pub fn developer_app_review_reply(request: AppReviewReplyRequest) -> Result<(), EgoError> {
+  info_log_add("developer_app_review_reply");
+
+  let ego_store_id = canister_get_one("ego_store").unwrap();
+  let ego_store = EgoStore::new(ego_store_id);
+  EgoDevService::app_review_reply(&caller(), &request.app_id, &request.wallet_id, request.reply, ego_store)
+}
+
+// 转让应用, 需要接收方在期限内确认
+#[update(name = "developer_app_transfer_request", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_app_transfer_request")]
+pub fn developer_app_transfer_request(request: AppTransferRequest) -> Result<AppTransfer, EgoError> {
+  info_log_add("developer_app_transfer_request");
+
+  EgoDevService::app_transfer_request(&caller(), &request.app_id, &request.to_id)
+}
+
+#[update(name = "developer_app_transfer_accept", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_app_transfer_accept")]
+pub fn developer_app_transfer_accept(app_id: AppId) -> Result<EgoDevApp, EgoError> {
+  info_log_add("developer_app_transfer_accept");
+
+  let ego_store_id = canister_get_one("ego_store").unwrap();
+  let ego_store = EgoStore::new(ego_store_id);
+  EgoDevService::app_transfer_accept(&caller(), &app_id, ego_store)
+}
+
+// 发起方撤回或接收方拒绝
+#[update(name = "developer_app_transfer_cancel", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_app_transfer_cancel")]
+pub fn developer_app_transfer_cancel(app_id: AppId) -> Result<(), EgoError> {
+  info_log_add("developer_app_transfer_cancel");
+
+  EgoDevService::app_transfer_cancel(&caller(), &app_id)
+}
+
+#[query(name = "developer_app_transfer_list", guard = "developer_guard")]
+#[candid_method(query, rename = "developer_app_transfer_list")]
+pub fn developer_app_transfer_list() -> Result<Vec<AppTransfer>, EgoError> {
+  info_log_add("developer_app_transfer_list");
+
+  Ok(AppTransfer::by_user_id(&caller()))
+}
+
+// 新建团队
+#[update(name = "developer_team_new", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_team_new")]
+pub fn developer_team_new(name: String) -> Result<Team, EgoError> {
+  info_log_add("developer_team_new");
+
+  EgoDevService::team_new(&caller(), &name)
+}
+
+// 所在的团队, 包括收到邀请的
+#[query(name = "developer_team_list", guard = "developer_guard")]
+#[candid_method(query, rename = "developer_team_list")]
+pub fn developer_team_list() -> Result<Vec<Team>, EgoError> {
+  info_log_add("developer_team_list");
+
+  Ok(Team::by_user_id(&caller()))
+}
+
+// 邀请开发者加入团队
+#[update(name = "developer_team_member_invite", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_team_member_invite")]
+pub fn developer_team_member_invite(request: TeamMemberRequest) -> Result<Team, EgoError> {
+  info_log_add("developer_team_member_invite");
+
+  EgoDevService::team_member_invite(&caller(), request.team_id, &request.user_id)
+}
+
+// 接受团队邀请
+#[update(name = "developer_team_invitation_accept", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_team_invitation_accept")]
+pub fn developer_team_invitation_accept(team_id: u64) -> Result<Team, EgoError> {
+  info_log_add("developer_team_invitation_accept");
+
+  EgoDevService::team_invitation_accept(&caller(), team_id)
+}
+
+// 移除团队成员, 或者退出团队
+#[update(name = "developer_team_member_remove", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_team_member_remove")]
+pub fn developer_team_member_remove(request: TeamMemberRequest) -> Result<Team, EgoError> {
+  info_log_add("developer_team_member_remove");
+
+  EgoDevService::team_member_remove(&caller(), request.team_id, &request.user_id)
+}
+
+// 转移应用到团队, 或者移出团队
+#[update(name = "developer_team_app_transfer", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_team_app_transfer")]
+pub fn developer_team_app_transfer(request: TeamAppTransferRequest) -> Result<EgoDevApp, EgoError> {
+  info_log_add("developer_team_app_transfer");
+
+  EgoDevService::team_app_transfer(&caller(), &request.app_id, request.team_id)
+}
+
+// 设置应用协作者角色
+#[update(name = "developer_app_collaborator_set", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_app_collaborator_set")]
+pub fn developer_app_collaborator_set(request: AppCollaboratorSetRequest) -> Result<AppCollaborator, EgoError> {
+  info_log_add("developer_app_collaborator_set");
+
+  EgoDevService::app_collaborator_set(&caller(), &request.app_id, &request.user_id, request.role)
+}
+
+#[update(name = "developer_app_collaborator_remove", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_app_collaborator_remove")]
+pub fn developer_app_collaborator_remove(request: AppCollaboratorRemoveRequest) -> Result<(), EgoError> {
+  info_log_add("developer_app_collaborator_remove");
+
+  EgoDevService::app_collaborator_remove(&caller(), &request.app_id, &request.user_id)
+}
+
+#[query(name = "developer_app_collaborator_list", guard = "developer_guard")]
+#[candid_method(query, rename = "developer_app_collaborator_list")]
+pub fn developer_app_collaborator_list(app_id: AppId) -> Result<Vec<AppCollaborator>, EgoError> {
+  info_log_add("developer_app_collaborator_list");
+
+  EgoDevService::app_collaborator_list(&caller(), &app_id)
+}
+
+// 授权其它身份 (如 CI) 代为上传或发布指定的应用
+#[update(name = "developer_delegate_set", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_delegate_set")]
+pub fn developer_delegate_set(request: DelegateSetRequest) -> Result<Delegate, EgoError> {
+  info_log_add("developer_delegate_set");
+
+  EgoDevService::delegate_set(&caller(), request)
+}
+
+#[update(name = "developer_delegate_remove", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_delegate_remove")]
+pub fn developer_delegate_remove(delegate_id: Principal) -> Result<(), EgoError> {
+  info_log_add("developer_delegate_remove");
+
+  EgoDevService::delegate_remove(&caller(), &delegate_id)
+}
+
+#[query(name = "developer_delegate_list", guard = "developer_guard")]
+#[candid_method(query, rename = "developer_delegate_list")]
+pub fn developer_delegate_list() -> Result<Vec<Delegate>, EgoError> {
+  info_log_add("developer_delegate_list");
+
+  Ok(Delegate::by_developer_id(&caller()))
+}
+
+// 被授权身份的操作记录
+#[query(name = "developer_delegate_action_list", guard = "developer_guard")]
+#[candid_method(query, rename = "developer_delegate_action_list")]
+pub fn developer_delegate_action_list() -> Result<Vec<DelegateAction>, EgoError> {
+  info_log_add("developer_delegate_action_list");
+
+  Ok(DelegateAction::by_developer_id(&caller()))
+}
+
+// 应用安装统计
+#[update(name = "developer_app_stats", guard = "developer_guard")]
+#[candid_method(update, rename = "developer_app_stats")]
+pub async fn developer_app_stats(request: AppStatsRequest) -> Result<AppStatsResponse, EgoError> {
+  info_log_add("developer_app_stats");
+
+  let ego_store_id = canister_get_one("ego_store").unwrap();
+  let ego_store = EgoStore::new(ego_store_id);
+  EgoDevService::developer_app_stats(&caller(), request, ego_store).await
+}
+
+// 版本审核记录
+#[query(name = "developer_app_version_audit_review_list", guard = "developer_guard")]
+#[candid_method(query, rename = "developer_app_version_audit_review_list")]
+pub fn developer_app_version_audit_review_list(app_id: AppId, version: Version) -> Result<Vec<AuditReview>, EgoError> {
+  info_log_add("developer_app_version_audit_review_list");
+  EgoDevService::app_version_audit_review_list(&caller(), &app_id, &version)
 }
 
 // TODO: developer_cycle_list
@@ -210,12 +497,20 @@ pub fn app_version_wait_for_audit() -> Result<Vec<EgoDevApp>, EgoError> {
   Ok(wait_for_audit_apps)
 }
 
+// 所有待审核的版本
+#[query(name = "app_version_audit_list", guard = "auditor_guard")]
+#[candid_method(query, rename = "app_version_audit_list")]
+pub fn app_version_audit_list() -> Result<Vec<AppVersion>, EgoError> {
+  info_log_add("app_version_audit_list");
+  Ok(EgoDevService::app_version_audit_list())
+}
+
 // 通过当前版本审核
 #[update(name = "app_version_approve", guard = "auditor_guard")]
 #[candid_method(update, rename = "app_version_approve")]
 pub fn app_version_approve(app_id: AppId) -> Result<AppVersion, EgoError> {
   info_log_add("app_version_approve");
-  let app_version = EgoDevService::app_version_approve(&app_id)?;
+  let app_version = EgoDevService::app_version_approve(&caller(), &app_id, None, "".to_string(), None, None)?;
   Ok(app_version)
 }
 
@@ -224,10 +519,29 @@ pub fn app_version_approve(app_id: AppId) -> Result<AppVersion, EgoError> {
 #[candid_method(update, rename = "app_version_reject")]
 pub fn app_version_reject(app_id: AppId) -> Result<AppVersion, EgoError> {
   info_log_add("app_version_reject");
-  let app_version = EgoDevService::app_version_reject(&app_id)?;
+  let app_version = EgoDevService::app_version_reject(&caller(), &app_id, None, "".to_string(), None, None)?;
   Ok(app_version)
 }
 
+// 审核当前版本, 附带审核意见和审核报告
+#[update(name = "app_version_review", guard = "auditor_guard")]
+#[candid_method(update, rename = "app_version_review")]
+pub fn app_version_review(request: AppVersionReviewRequest) -> Result<AppVersion, EgoError> {
+  info_log_add("app_version_review");
+  let app_version = match request.approve {
+    true => EgoDevService::app_version_approve(&caller(), &request.app_id, request.version, request.comment, request.report, request.reproduced)?,
+    false => EgoDevService::app_version_reject(&caller(), &request.app_id, request.version, request.comment, request.report, request.reproduced)?,
+  };
+  Ok(app_version)
+}
+
+#[query(name = "audit_policy_list")]
+#[candid_method(query, rename = "audit_policy_list")]
+pub fn audit_policy_list() -> Result<Vec<AuditPolicy>, EgoError> {
+  info_log_add("audit_policy_list");
+  Ok(AuditPolicy::list(0, AuditPolicy::len() as usize))
+}
+
 /********************  manager  ********************/
 #[query(name = "user_main_list", guard = "manager_guard")]
 #[candid_method(query, rename = "user_main_list")]
@@ -246,6 +560,28 @@ pub fn user_role_set(request: UserRoleSetRequest) -> Result<bool, EgoError> {
   Ok(ret)
 }
 
+// 开发者认证标识, 会同步到 ego_store
+#[update(name = "user_verified_set", guard = "manager_guard")]
+#[candid_method(update, rename = "user_verified_set")]
+pub fn user_verified_set(request: UserVerifiedSetRequest) -> Result<bool, EgoError> {
+  info_log_add("user_verified_set");
+
+  let ego_store_id = canister_get_one("ego_store").unwrap();
+  let ego_store = EgoStore::new(ego_store_id);
+  EgoDevService::user_verified_set(&request.user_id, request.verified, ego_store)
+}
+
+// 隐藏违规评价
+#[update(name = "app_review_hide", guard = "manager_guard")]
+#[candid_method(update, rename = "app_review_hide")]
+pub fn app_review_hide(request: AppReviewHideRequest) -> Result<(), EgoError> {
+  info_log_add("app_review_hide");
+
+  let ego_store_id = canister_get_one("ego_store").unwrap();
+  let ego_store = EgoStore::new(ego_store_id);
+  EgoDevService::app_review_hide(&request.app_id, &request.wallet_id, request.hidden, ego_store)
+}
+
 /********************  ego_store  ********************/
 // TODO: developer_cycle_recharge
 
@@ -293,19 +629,25 @@ pub async fn admin_app_create(
   )
     .await?;
 
-  info_log_add("5. app_version_submit");
-  EgoDevService::app_version_submit(&caller, &request.app_id, &request.version).expect("app_version_submit should success");
-
-  info_log_add("6. app_version_approve");
-  EgoDevService::app_version_approve(&request.app_id).expect("app_version_approve should success");
+  info_log_add("5. app_version_approve");
+  EgoDevService::admin_app_version_approve(&caller, &request.app_id, &request.version, "built by the platform").expect("app_version_approve should success");
 
-  info_log_add("7. app_version_release");
+  info_log_add("6. app_version_release");
   let ego_store_id = canister_get_one("ego_store").unwrap();
   let ego_store = EgoStore::new(ego_store_id);
 
-  EgoDevService::app_version_release(&caller, &request.app_id, &request.version, ego_store)
+  EgoDevService::app_version_release(&caller, &request.app_id, &request.version, false, ego_store)
 }
 
+#[update(name = "admin_audit_policy_set", guard = "owner_guard")]
+#[candid_method(update, rename = "admin_audit_policy_set")]
+pub fn admin_audit_policy_set(request: AuditPolicySetRequest) -> Result<AuditPolicy, EgoError> {
+  info_log_add("admin_audit_policy_set");
+  EgoDevService::admin_audit_policy_set(&request.category, request.quorum, request.auditors, request.reproduction_required)
+}
+
+// the platform owner takes an app over right away, e.g. when its developer is gone for good.
+// it is exempt from the request and accept flow of app_transfer_request, a pending request is dropped
 #[update(name = "admin_app_transfer", guard = "owner_guard")]
 #[candid_method(update, rename = "admin_app_transfer")]
 pub async fn admin_app_transfer(app_id: AppId) -> Result<(), EgoError> {
@@ -332,6 +674,10 @@ pub async fn admin_app_transfer(app_id: AppId) -> Result<(), EgoError> {
       previous_developer.created_apps.retain(|exists_app_id| app_id != *exists_app_id);
       previous_developer.save();
 
+      if let Some(app_transfer) = AppTransfer::get(&app_id) {
+        app_transfer.remove();
+      }
+
       info_log_add("4. add app to current developer");
       let mut curr_developer = Developer::get(&caller).expect("developer not exists");
       curr_developer.created_apps.push(app_id);
@@ -340,6 +686,7 @@ pub async fn admin_app_transfer(app_id: AppId) -> Result<(), EgoError> {
       info_log_add("5. update ego_dev_app's developer id to current developer");
       ego_dev_app.developer_id = caller;
       ego_dev_app.save();
+
       Ok(())
     }
   }
@@ -396,6 +743,15 @@ pub fn developer_guard() -> Result<(), String> {
   }
 }
 
+// 开发者或未过期的被授权身份, 具体应用的权限在 service 中检查
+pub fn developer_or_delegate_guard() -> Result<(), String> {
+  if Developer::get(&caller()).is_some() || Delegate::get(&caller()).map_or(false, |delegate| delegate.is_active()) {
+    Ok(())
+  } else {
+    trap(&format!("{} unauthorized", api::caller()));
+  }
+}
+
 /********************  methods for ego_cycle_threshold_get   ********************/
 pub fn cycle_threshold_get() -> u128 {
   1_000_000_000_000
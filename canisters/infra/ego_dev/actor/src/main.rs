@@ -10,7 +10,12 @@ fn main() {
   use ego_dev_mod::types::ego_dev_app::EgoDevApp;
   use ego_dev_mod::types::developer::*;
   use ego_dev_mod::types::app_version::AppVersion;
+  use ego_dev_mod::types::app_version_transition::AppVersionTransition;
+  use ego_dev_mod::types::app_metadata::EgoDevAppMetadata;
+  use ego_dev_mod::types::audit_policy::AuditPolicy;
+  use ego_dev_mod::types::audit_review::AuditReview;
   use ego_dev_mod::types::*;
+  use ego_dev_mod::c2c::c2c_types::*;
   use ego_types::app::*;
   use ego_types::cycle_info::*;
   use candid::Principal;
@@ -6,11 +6,21 @@ use ic_cdk::trap;
 use ego_utils::util::get_md5;
 
 use crate::state::{backup_info_pre_upgrade, cycle_info_pre_upgrade, info_log_add, registry_pre_upgrade, seq_pre_upgrade, users_pre_upgrade};
+use crate::types::app_collaborator::AppCollaborator;
+use crate::types::app_metadata::EgoDevAppMetadata;
+use crate::types::app_transfer::AppTransfer;
 use crate::types::app_version::AppVersion;
+use crate::types::app_version_transition::AppVersionTransition;
+use crate::types::audit_policy::AuditPolicy;
+use crate::types::audit_review::AuditReview;
+use crate::types::delegate::Delegate;
+use crate::types::delegate_action::DelegateAction;
 use crate::types::developer::Developer;
+use crate::types::developer_profile::DeveloperProfile;
 use crate::types::ego_dev_app::EgoDevApp;
 use crate::types::file::File;
 use crate::types::stable_state::StableState;
+use crate::types::team::Team;
 
 pub fn job_list() -> Vec<BackupJob> {
   let mut jobs = vec![];
@@ -40,6 +50,56 @@ pub fn job_list() -> Vec<BackupJob> {
     amount: AppVersion::len() as usize,
   });
 
+  jobs.push(BackupJob {
+    name: "app_metadatas".to_string(),
+    amount: EgoDevAppMetadata::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "audit_policies".to_string(),
+    amount: AuditPolicy::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "audit_reviews".to_string(),
+    amount: AuditReview::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "app_version_transitions".to_string(),
+    amount: AppVersionTransition::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "teams".to_string(),
+    amount: Team::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "app_collaborators".to_string(),
+    amount: AppCollaborator::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "delegates".to_string(),
+    amount: Delegate::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "delegate_actions".to_string(),
+    amount: DelegateAction::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "developer_profiles".to_string(),
+    amount: DeveloperProfile::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "app_transfers".to_string(),
+    amount: AppTransfer::len() as usize,
+  });
+
   jobs
 }
 
@@ -74,6 +134,46 @@ pub fn record_export(name: String, start: usize, end: usize, last_update: u64) -
       let records = AppVersion::by_last_update(start, end, last_update);
       get_json_result(&records)
     }
+    "app_metadatas" => {
+      let records = EgoDevAppMetadata::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "audit_policies" => {
+      let records = AuditPolicy::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "audit_reviews" => {
+      let records = AuditReview::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "app_version_transitions" => {
+      let records = AppVersionTransition::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "teams" => {
+      let records = Team::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "app_collaborators" => {
+      let records = AppCollaborator::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "delegates" => {
+      let records = Delegate::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "delegate_actions" => {
+      let records = DelegateAction::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "developer_profiles" => {
+      let records = DeveloperProfile::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "app_transfers" => {
+      let records = AppTransfer::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
     _ => trap("no job matched")
   };
 
@@ -119,6 +219,46 @@ pub fn data_backup(name: String, start: usize, end: usize) -> Option<ByteReadRes
       let records = AppVersion::list(start, end);
       get_bin_result(&records)
     }
+    "app_metadatas" => {
+      let records = EgoDevAppMetadata::list(start, end);
+      get_bin_result(&records)
+    }
+    "audit_policies" => {
+      let records = AuditPolicy::list(start, end);
+      get_bin_result(&records)
+    }
+    "audit_reviews" => {
+      let records = AuditReview::list(start, end);
+      get_bin_result(&records)
+    }
+    "app_version_transitions" => {
+      let records = AppVersionTransition::list(start, end);
+      get_bin_result(&records)
+    }
+    "teams" => {
+      let records = Team::list(start, end);
+      get_bin_result(&records)
+    }
+    "app_collaborators" => {
+      let records = AppCollaborator::list(start, end);
+      get_bin_result(&records)
+    }
+    "delegates" => {
+      let records = Delegate::list(start, end);
+      get_bin_result(&records)
+    }
+    "delegate_actions" => {
+      let records = DelegateAction::list(start, end);
+      get_bin_result(&records)
+    }
+    "developer_profiles" => {
+      let records = DeveloperProfile::list(start, end);
+      get_bin_result(&records)
+    }
+    "app_transfers" => {
+      let records = AppTransfer::list(start, end);
+      get_bin_result(&records)
+    }
     _ => trap("no job matched")
   };
 
@@ -168,6 +308,76 @@ pub fn data_restore(name: String, data: Vec<u8>) -> Result<(), String> {
         record.save();
       })
     }
+    "app_metadatas" => {
+      let mut records: Vec<EgoDevAppMetadata> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "audit_policies" => {
+      let mut records: Vec<AuditPolicy> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "audit_reviews" => {
+      let mut records: Vec<AuditReview> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "app_version_transitions" => {
+      let mut records: Vec<AppVersionTransition> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "teams" => {
+      let mut records: Vec<Team> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "app_collaborators" => {
+      let mut records: Vec<AppCollaborator> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "delegates" => {
+      let mut records: Vec<Delegate> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "delegate_actions" => {
+      let mut records: Vec<DelegateAction> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "developer_profiles" => {
+      let mut records: Vec<DeveloperProfile> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "app_transfers" => {
+      let mut records: Vec<AppTransfer> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
     _ => trap("no job matched")
   };
 
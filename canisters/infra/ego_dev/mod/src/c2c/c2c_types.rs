@@ -1,7 +1,7 @@
 use candid::{CandidType, Deserialize};
 use serde::Serialize;
 
-use ego_types::app::{App, Wasm};
+use ego_types::app::{App, AppId, Version, Wasm};
 
 // type for ego_store
 
@@ -10,4 +10,50 @@ pub struct EgoStoreApp {
   pub app: App,
   pub wasm: Wasm,
   pub last_update: u64,
+  pub pre_release: Option<String>,
+  pub developer_verified: Option<bool>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppLineRelease {
+  pub app_id: AppId,
+  pub version: Version,
+  pub pre_release: Option<String>,
+  pub wasm: Wasm,
+  pub last_update: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppStat {
+  pub app_id: AppId,
+  pub day: u64,
+  pub installs: u64,
+  pub uninstalls: u64,
+  pub upgrades: u64,
+  pub last_update: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppVersionCount {
+  pub version: Version,
+  pub pre_release: Option<String>,
+  pub count: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppStatsRequest {
+  pub app_id: AppId,
+  pub from_day: u64,
+  pub to_day: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppStatsResponse {
+  pub app_id: AppId,
+  pub daily: Vec<AppStat>,
+  pub installs: u64,
+  pub uninstalls: u64,
+  pub upgrades: u64,
+  pub active_installs: u64,
+  pub versions: Vec<AppVersionCount>,
 }
@@ -17,6 +17,8 @@ pub trait TEgoFile {
     hash: String,
     data: Vec<u8>,
   ) -> Result<bool, EgoError>;
+
+  async fn file_main_delete(&self, canister_id: Principal, fid: FileId) -> Result<bool, EgoError>;
 }
 
 pub struct EgoFile {}
@@ -53,4 +55,26 @@ impl TEgoFile for EgoFile {
       }
     }
   }
+
+  async fn file_main_delete(&self, canister_id: Principal, fid: FileId) -> Result<bool, EgoError> {
+    let call_result = api::call::call(canister_id, "file_main_delete", (fid, )).await
+      as Result<(Result<bool, EgoError>, ), (RejectionCode, String)>;
+
+    match call_result {
+      Ok(resp) => match resp.0 {
+        Ok(resp) => Ok(resp),
+        Err(e) => {
+          error_log_add(format!("Error calling file_main_delete code: {}, msg: {}", e.code, e.msg).as_str());
+          Err(e)
+        }
+      },
+      Err((code, msg)) => {
+        let code = code as u16;
+        error_log_add(
+          format!("Error calling file_main_delete code: {}, msg: {}", code, msg).as_str(),
+        );
+        Err(EgoError { code, msg })
+      }
+    }
+  }
 }
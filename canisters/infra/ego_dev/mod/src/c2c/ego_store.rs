@@ -1,14 +1,23 @@
 use async_trait::async_trait;
 use candid::Principal;
 use ic_cdk::api;
+use ic_cdk::api::call::RejectionCode;
 
-use ego_types::app::{App, Wasm};
+use ego_types::app::{App, AppAuditReview, AppId, AppMetadata, EgoError, Version, Wasm};
 
-use crate::c2c::c2c_types::EgoStoreApp;
+use crate::c2c::c2c_types::{AppLineRelease, AppStatsRequest, AppStatsResponse, EgoStoreApp};
+use crate::state::error_log_add;
 
 #[async_trait]
 pub trait TEgoStore {
-  fn app_main_release(&self, app: App, wasm: Wasm);
+  fn app_main_release(&self, app: App, wasm: Wasm, pre_release: Option<String>, developer_verified: bool);
+  fn app_line_release(&self, app_id: AppId, version: Version, pre_release: Option<String>, wasm: Wasm);
+  fn app_main_metadata_set(&self, app_id: AppId, metadata: AppMetadata);
+  fn app_review_reply(&self, app_id: AppId, wallet_id: Principal, reply: String);
+  fn app_review_hide(&self, app_id: AppId, wallet_id: Principal, hidden: bool);
+  fn app_main_audit_set(&self, app_id: AppId, version: Version, reviews: Vec<AppAuditReview>);
+  fn app_main_developer_verified_set(&self, app_ids: Vec<AppId>, verified: bool);
+  async fn app_main_stats(&self, request: AppStatsRequest) -> Result<AppStatsResponse, EgoError>;
 }
 
 pub struct EgoStore {
@@ -23,8 +32,8 @@ impl EgoStore {
 
 #[async_trait]
 impl TEgoStore for EgoStore {
-  fn app_main_release(&self, app: App, wasm: Wasm) {
-    let ego_store_app = EgoStoreApp { app, wasm, last_update: 0 };
+  fn app_main_release(&self, app: App, wasm: Wasm, pre_release: Option<String>, developer_verified: bool) {
+    let ego_store_app = EgoStoreApp { app, wasm, last_update: 0, pre_release, developer_verified: Some(developer_verified) };
 
     let _result = api::call::notify(self.canister_id, "app_main_release", (ego_store_app, ));
 
@@ -41,4 +50,46 @@ impl TEgoStore for EgoStore {
     //   }
     // }
   }
+
+  fn app_line_release(&self, app_id: AppId, version: Version, pre_release: Option<String>, wasm: Wasm) {
+    let app_line_release = AppLineRelease { app_id, version, pre_release, wasm, last_update: 0 };
+
+    let _result = api::call::notify(self.canister_id, "app_line_release", (app_line_release, ));
+  }
+
+  fn app_main_metadata_set(&self, app_id: AppId, metadata: AppMetadata) {
+    let _result = api::call::notify(self.canister_id, "app_main_metadata_set", (app_id, metadata, ));
+  }
+
+  fn app_review_reply(&self, app_id: AppId, wallet_id: Principal, reply: String) {
+    let _result = api::call::notify(self.canister_id, "app_review_reply", (app_id, wallet_id, reply, ));
+  }
+
+  fn app_review_hide(&self, app_id: AppId, wallet_id: Principal, hidden: bool) {
+    let _result = api::call::notify(self.canister_id, "app_review_hide", (app_id, wallet_id, hidden, ));
+  }
+
+  fn app_main_audit_set(&self, app_id: AppId, version: Version, reviews: Vec<AppAuditReview>) {
+    let _result = api::call::notify(self.canister_id, "app_main_audit_set", (app_id, version, reviews, ));
+  }
+
+  fn app_main_developer_verified_set(&self, app_ids: Vec<AppId>, verified: bool) {
+    let _result = api::call::notify(self.canister_id, "app_main_developer_verified_set", (app_ids, verified, ));
+  }
+
+  async fn app_main_stats(&self, request: AppStatsRequest) -> Result<AppStatsResponse, EgoError> {
+    let call_result = api::call::call(self.canister_id, "app_main_stats", (request, )).await
+      as Result<(Result<AppStatsResponse, EgoError>, ), (RejectionCode, String)>;
+
+    match call_result {
+      Ok(resp) => resp.0,
+      Err((code, msg)) => {
+        let code = code as u16;
+        error_log_add(
+          format!("Error calling app_main_stats code: {}, msg: {}", code, msg).as_str(),
+        );
+        Err(EgoError { code, msg })
+      }
+    }
+  }
 }
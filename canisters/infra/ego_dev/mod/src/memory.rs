@@ -2,12 +2,23 @@ use std::cell::RefCell;
 
 use ic_stable_structures::{DefaultMemoryImpl, memory_manager::{MemoryId, MemoryManager, VirtualMemory}, RestrictedMemory, StableBTreeMap, StableCell, storable::Blob};
 
+use crate::types::app_collaborator::{AppCollaborator, AppCollaboratorKey};
 use crate::types::app_key::AppKey;
+use crate::types::app_metadata::EgoDevAppMetadata;
+use crate::types::app_transfer::AppTransfer;
 use crate::types::app_version::AppVersion;
+use crate::types::app_version_transition::AppVersionTransition;
+use crate::types::audit_policy::AuditPolicy;
+use crate::types::audit_review::AuditReview;
+use crate::types::delegate::Delegate;
+use crate::types::delegate_action::DelegateAction;
 use crate::types::developer::Developer;
+use crate::types::developer_profile::DeveloperProfile;
 use crate::types::ego_dev_app::EgoDevApp;
 use crate::types::file::File;
+use crate::types::index_key::{AuditPolicyKey, AuditReviewKey};
 use crate::types::stable_state::StableState;
+use crate::types::team::Team;
 
 pub const MB: u32 = 1024 * 1024;
 
@@ -15,6 +26,17 @@ const EGO_DEV_APP_MEM_ID: MemoryId = MemoryId::new(0);
 const FILE_MEM_ID: MemoryId = MemoryId::new(1);
 const DEVELOPER_MEM_ID: MemoryId = MemoryId::new(2);
 const APP_VERSION_MEM_ID: MemoryId = MemoryId::new(3);
+const APP_METADATA_MEM_ID: MemoryId = MemoryId::new(4);
+const AUDIT_POLICY_MEM_ID: MemoryId = MemoryId::new(5);
+const AUDIT_REVIEW_MEM_ID: MemoryId = MemoryId::new(6);
+const APP_VERSION_TRANSITION_MEM_ID: MemoryId = MemoryId::new(7);
+const TEAM_MEM_ID: MemoryId = MemoryId::new(8);
+const APP_COLLABORATOR_MEM_ID: MemoryId = MemoryId::new(9);
+const DELEGATE_MEM_ID: MemoryId = MemoryId::new(10);
+const DELEGATE_ACTION_MEM_ID: MemoryId = MemoryId::new(11);
+const DEVELOPER_PROFILE_MEM_ID: MemoryId = MemoryId::new(12);
+const APP_TRANSFER_MEM_ID: MemoryId = MemoryId::new(13);
+const AUDIT_REVIEW_INDEX_MEM_ID: MemoryId = MemoryId::new(14);
 
 const METADATA_PAGES: u64 = 64;
 // 4M
@@ -49,4 +71,48 @@ thread_local! {
     pub static APP_VERSIONS: RefCell<StableBTreeMap<u64, AppVersion, VM>> = MEMORY_MANAGER.with(|mm| {
         RefCell::new(StableBTreeMap::init(mm.borrow().get(APP_VERSION_MEM_ID)))
     });
+
+    pub static APP_METADATAS: RefCell<StableBTreeMap<AppKey, EgoDevAppMetadata, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(APP_METADATA_MEM_ID)))
+    });
+
+    pub static AUDIT_POLICIES: RefCell<StableBTreeMap<AuditPolicyKey, AuditPolicy, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(AUDIT_POLICY_MEM_ID)))
+    });
+
+    pub static AUDIT_REVIEWS: RefCell<StableBTreeMap<u64, AuditReview, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(AUDIT_REVIEW_MEM_ID)))
+    });
+
+    pub static APP_VERSION_TRANSITIONS: RefCell<StableBTreeMap<u64, AppVersionTransition, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(APP_VERSION_TRANSITION_MEM_ID)))
+    });
+
+    pub static TEAMS: RefCell<StableBTreeMap<u64, Team, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(TEAM_MEM_ID)))
+    });
+
+    pub static APP_COLLABORATORS: RefCell<StableBTreeMap<AppCollaboratorKey, AppCollaborator, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(APP_COLLABORATOR_MEM_ID)))
+    });
+
+    pub static DELEGATES: RefCell<StableBTreeMap<Blob<29>, Delegate, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(DELEGATE_MEM_ID)))
+    });
+
+    pub static DELEGATE_ACTIONS: RefCell<StableBTreeMap<u64, DelegateAction, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(DELEGATE_ACTION_MEM_ID)))
+    });
+
+    pub static DEVELOPER_PROFILES: RefCell<StableBTreeMap<Blob<29>, DeveloperProfile, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(DEVELOPER_PROFILE_MEM_ID)))
+    });
+
+    pub static APP_TRANSFERS: RefCell<StableBTreeMap<AppKey, AppTransfer, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(APP_TRANSFER_MEM_ID)))
+    });
+
+    pub static AUDIT_REVIEW_INDEX: RefCell<StableBTreeMap<AuditReviewKey, u64, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(AUDIT_REVIEW_INDEX_MEM_ID)))
+    });
 }
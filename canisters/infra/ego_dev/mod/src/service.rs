@@ -1,17 +1,32 @@
+use std::str::FromStr;
+
 use candid::Principal;
 
-use ego_types::app::{AppId, Category};
+use ego_types::app::{AppAuditReview, AppId, AuditDecision, Category};
 use ego_types::app::EgoError;
 use ego_types::app::Version;
+use ego_types::semver::SemVer;
+use ego_utils::util::time;
 
+use crate::c2c::c2c_types::{AppStatsRequest, AppStatsResponse};
 use crate::c2c::ego_file::TEgoFile;
 use crate::c2c::ego_store::TEgoStore;
 use crate::state::info_log_add;
-use crate::types::app_version::{AppVersion, AppVersionStatus};
+use crate::types::app_collaborator::{AppCollaborator, AppRole};
+use crate::types::app_metadata::EgoDevAppMetadata;
+use crate::types::app_transfer::AppTransfer;
+use crate::types::app_version::{AppVersion, AppVersionSource, AppVersionStatus, wasm_sha256};
+use crate::types::app_version_transition::AppVersionTransition;
+use crate::types::audit_policy::{AUDIT_PANEL_MAX, AuditPolicy};
+use crate::types::audit_review::{AuditReview, COMMENT_MAX_LEN, REPORT_MAX_LEN};
+use crate::types::delegate::Delegate;
+use crate::types::delegate_action::DelegateAction;
 use crate::types::developer::Developer;
-use crate::types::ego_dev_app::EgoDevApp;
-use crate::types::EgoDevErr;
+use crate::types::developer_profile::DeveloperProfile;
+use crate::types::ego_dev_app::{APP_ID_MAX_LEN, EgoDevApp};
+use crate::types::{AppMetadataSetRequest, DelegateSetRequest, DeveloperProfileSetRequest, EgoDevErr};
 use crate::types::file::File;
+use crate::types::team::{Team, TEAM_NAME_MAX_LEN};
 
 pub struct EgoDevService {}
 
@@ -46,6 +61,78 @@ impl EgoDevService {
     }
   }
 
+  pub fn developer_profile_set(caller: &Principal, request: DeveloperProfileSetRequest) -> Result<DeveloperProfile, EgoError> {
+    Developer::get(caller).ok_or(EgoError::from(EgoDevErr::NotADeveloper))?;
+
+    let mut developer_profile = DeveloperProfile::get(caller).unwrap_or_else(|| DeveloperProfile::new(caller));
+    developer_profile.info_set(request.website, request.contact, request.avatar)?;
+    developer_profile.save();
+    Ok(developer_profile)
+  }
+
+  /// the app only moves once the recipient accepts, see app_transfer_accept
+  pub fn app_transfer_request(caller: &Principal, app_id: &AppId, to_id: &Principal) -> Result<AppTransfer, EgoError> {
+    let ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::OWNER)?;
+    Developer::get(to_id).ok_or(EgoError::from(EgoDevErr::NotADeveloper))?;
+    // the recipient accepts, so an owner can not send the app to themselves
+    if ego_dev_app.developer_id == *to_id || caller == to_id {
+      return Err(EgoDevErr::OperationNotPermitted.into());
+    }
+
+    let mut app_transfer = AppTransfer::new(app_id, caller, to_id);
+    app_transfer.save();
+    Ok(app_transfer)
+  }
+
+  pub fn app_transfer_accept<S: TEgoStore>(caller: &Principal, app_id: &AppId, ego_store: S) -> Result<EgoDevApp, EgoError> {
+    let app_transfer = AppTransfer::get(app_id)
+      .filter(|app_transfer| app_transfer.to_id == *caller)
+      .ok_or(EgoError::from(EgoDevErr::AppTransferNotExists))?;
+    app_transfer.remove();
+
+    if app_transfer.is_expired() {
+      return Err(EgoDevErr::AppTransferExpired.into());
+    }
+
+    let mut ego_dev_app = EgoDevApp::get(app_id).ok_or(EgoError::from(EgoDevErr::AppNotExists))?;
+    // the sender may have lost the app since the request
+    if ego_dev_app.role(&app_transfer.from_id) != Some(AppRole::OWNER) {
+      return Err(EgoDevErr::AppTransferNotExists.into());
+    }
+
+    let mut developer = Developer::get(caller).ok_or(EgoError::from(EgoDevErr::NotADeveloper))?;
+    if let Some(mut previous_developer) = Developer::get(&ego_dev_app.developer_id) {
+      previous_developer.created_apps.retain(|exists_app_id| app_id != exists_app_id);
+      previous_developer.save();
+    }
+    developer.created_apps.push(app_id.clone());
+    developer.save();
+
+    ego_dev_app.developer_id = *caller;
+    ego_dev_app.team_id = None;
+    ego_dev_app.save();
+
+    // the roles were granted by the previous owner
+    AppCollaborator::by_app_id(app_id).iter().for_each(|app_collaborator| app_collaborator.remove());
+
+    if ego_dev_app.app.current_version != Version::default() {
+      ego_store.app_main_developer_verified_set(vec![app_id.clone()], developer.is_verified());
+    }
+
+    Ok(ego_dev_app)
+  }
+
+  /// the sender withdraws or the recipient declines
+  pub fn app_transfer_cancel(caller: &Principal, app_id: &AppId) -> Result<(), EgoError> {
+    match AppTransfer::get(app_id) {
+      Some(app_transfer) if app_transfer.from_id == *caller || app_transfer.to_id == *caller => {
+        app_transfer.remove();
+        Ok(())
+      }
+      _ => Err(EgoDevErr::AppTransferNotExists.into()),
+    }
+  }
+
   pub fn developer_app_new(
     caller: &Principal,
     app_id: &AppId,
@@ -55,6 +142,10 @@ impl EgoDevService {
     category: &Category,
     price: f32,
   ) -> Result<EgoDevApp, EgoError> {
+    if app_id.is_empty() || app_id.len() > APP_ID_MAX_LEN {
+      return Err(EgoDevErr::AppIdInvalid.into());
+    }
+
     match EgoDevApp::by_developer_id_and_id(caller, app_id) {
       None => {
         match EgoDevApp::get(app_id) {
@@ -93,7 +184,16 @@ impl EgoDevService {
     app_id: &AppId,
     version: &Version,
   ) -> Result<AppVersion, EgoError> {
-    let mut ego_dev_app = EgoDevApp::by_developer_id_and_id(caller, app_id).ok_or(EgoError::from(EgoDevErr::AppNotExists))?;
+    let result = Self::do_app_version_new(caller, app_id, version);
+    Self::delegate_action_record(caller, app_id, &format!("app_version_new {}", version.to_string()), result)
+  }
+
+  fn do_app_version_new(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+  ) -> Result<AppVersion, EgoError> {
+    let mut ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::UPLOADER)?;
 
     let ego_file_canister_id = EgoDevService::ego_file_get()?;
     let app_version = ego_dev_app.version_new(&ego_file_canister_id, &version)?;
@@ -101,6 +201,30 @@ impl EgoDevService {
     Ok(app_version)
   }
 
+  pub fn app_version_new_v2(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &str,
+  ) -> Result<AppVersion, EgoError> {
+    let result = Self::do_app_version_new_v2(caller, app_id, version);
+    Self::delegate_action_record(caller, app_id, &format!("app_version_new {}", version), result)
+  }
+
+  fn do_app_version_new_v2(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &str,
+  ) -> Result<AppVersion, EgoError> {
+    let semver = SemVer::from_str(version).map_err(|_| EgoError::from(EgoDevErr::VersionInvalid))?;
+
+    let mut ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::UPLOADER)?;
+
+    let ego_file_canister_id = EgoDevService::ego_file_get()?;
+    let app_version = ego_dev_app.version_new_semver(&ego_file_canister_id, &semver)?;
+
+    Ok(app_version)
+  }
+
   pub async fn app_version_upload_wasm<F: TEgoFile>(
     ego_file: F,
     caller: &Principal,
@@ -109,18 +233,33 @@ impl EgoDevService {
     data: Vec<u8>,
     hash: String,
   ) -> Result<bool, EgoError> {
-    let ego_dev_app = EgoDevApp::by_developer_id_and_id(caller, app_id).ok_or(EgoError::from(EgoDevErr::AppNotExists))?;
+    let result = Self::do_app_version_upload_wasm(ego_file, caller, app_id, version, data, hash).await;
+    Self::delegate_action_record(caller, app_id, &format!("app_version_upload_wasm {}", version.to_string()), result)
+  }
+
+  async fn do_app_version_upload_wasm<F: TEgoFile>(
+    ego_file: F,
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+    data: Vec<u8>,
+    hash: String,
+  ) -> Result<bool, EgoError> {
+    let ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::UPLOADER)?;
 
     match ego_dev_app.version_get(version) {
       Some(mut app_version) => {
-        if app_version.status == AppVersionStatus::RELEASED {
-          Err(EgoDevErr::OperationNotPermitted.into())
-        } else {
-          app_version.backend_update();
-          ego_file
-            .file_main_write(app_version.wasm.clone().unwrap().canister_id, app_version.wasm.clone().unwrap().fid(), hash, data)
-            .await
-        }
+        let sha256 = wasm_sha256(&data);
+        app_version.wasm_upload_check(&sha256)?;
+        app_version.backend_update();
+        let ret = ego_file
+          .file_main_write(app_version.wasm.clone().unwrap().canister_id, app_version.wasm.clone().unwrap().fid(), hash, data)
+          .await?;
+
+        // the state is committed at the await, the hash and the status follow the written wasm only
+        let mut app_version = AppVersion::get_by_app_id_and_version(app_id, version).ok_or(EgoError::from(EgoDevErr::VersionNotExists))?;
+        app_version.wasm_uploaded(caller, sha256);
+        Ok(ret)
       }
       None => Err(EgoDevErr::VersionNotExists.into()),
     }
@@ -132,36 +271,140 @@ impl EgoDevService {
     version: &Version,
     canister_id: &Principal,
   ) -> Result<bool, EgoError> {
-    let ego_dev_app = EgoDevApp::by_developer_id_and_id(caller, app_id).ok_or(EgoError::from(EgoDevErr::AppNotExists))?;
+    let result = Self::do_app_version_set_frontend_address(caller, app_id, version, canister_id);
+    Self::delegate_action_record(caller, app_id, &format!("app_version_set_frontend_address {}", version.to_string()), result)
+  }
+
+  fn do_app_version_set_frontend_address(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+    canister_id: &Principal,
+  ) -> Result<bool, EgoError> {
+    let ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::UPLOADER)?;
 
     match ego_dev_app.version_get(version) {
       Some(mut app_version) => {
         app_version.frontend_update(canister_id);
+
         Ok(true)
       }
       None => Err(EgoDevErr::VersionNotExists.into()),
     }
   }
 
+  pub fn app_version_source_set(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+    source: AppVersionSource,
+  ) -> Result<AppVersion, EgoError> {
+    let result = Self::do_app_version_source_set(caller, app_id, version, source);
+    Self::delegate_action_record(caller, app_id, &format!("app_version_source_set {}", version.to_string()), result)
+  }
+
+  fn do_app_version_source_set(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+    source: AppVersionSource,
+  ) -> Result<AppVersion, EgoError> {
+    let ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::UPLOADER)?;
+    let mut app_version = ego_dev_app.version_get(version).ok_or(EgoError::from(EgoDevErr::VersionNotExists))?;
+
+    app_version.source_set(source)?;
+
+    Ok(app_version)
+  }
+
   pub fn app_version_submit(
     caller: &Principal,
     app_id: &AppId,
     version: &Version,
   ) -> Result<AppVersion, EgoError> {
-    let mut ego_dev_app = EgoDevApp::by_developer_id_and_id(caller, app_id).ok_or(EgoError::from(EgoDevErr::AppNotExists))?;
+    let result = Self::do_app_version_submit(caller, app_id, version);
+    Self::delegate_action_record(caller, app_id, &format!("app_version_submit {}", version.to_string()), result)
+  }
+
+  fn do_app_version_submit(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+  ) -> Result<AppVersion, EgoError> {
+    let mut ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::MAINTAINER)?;
+
+    let app_version = ego_dev_app.version_submit(caller, version)?;
+
+    Ok(app_version)
+  }
+
+  pub async fn app_version_delete<F: TEgoFile>(
+    ego_file: F,
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+  ) -> Result<AppVersion, EgoError> {
+    let mut ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::MAINTAINER)?;
+
+    let app_version = ego_dev_app.version_delete(caller, version)?;
+
+    // the version is gone either way, a wasm left behind in ego_file is logged by the call
+    if let Some(wasm) = &app_version.wasm {
+      let _ = ego_file.file_main_delete(wasm.canister_id, wasm.fid()).await;
+    }
 
-    let app_version = ego_dev_app.version_submit(version)?;
     Ok(app_version)
   }
 
+  pub fn app_version_promote(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+  ) -> Result<AppVersion, EgoError> {
+    let result = Self::do_app_version_promote(caller, app_id, version);
+    Self::delegate_action_record(caller, app_id, &format!("app_version_promote {}", version.to_string()), result)
+  }
+
+  fn do_app_version_promote(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+  ) -> Result<AppVersion, EgoError> {
+    let mut ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::MAINTAINER)?;
+
+    let app_version = ego_dev_app.version_promote(caller, version)?;
+
+    Ok(app_version)
+  }
+
+  pub fn app_version_transition_list(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+  ) -> Result<Vec<AppVersionTransition>, EgoError> {
+    EgoDevApp::by_role(caller, app_id, AppRole::VIEWER)?;
+
+    Ok(AppVersionTransition::by_app_id_and_version(app_id, version))
+  }
+
   pub fn app_version_revoke(
     caller: &Principal,
     app_id: &AppId,
     version: &Version,
   ) -> Result<AppVersion, EgoError> {
-    let mut ego_dev_app = EgoDevApp::by_developer_id_and_id(caller, app_id).ok_or(EgoError::from(EgoDevErr::AppNotExists))?;
+    let result = Self::do_app_version_revoke(caller, app_id, version);
+    Self::delegate_action_record(caller, app_id, &format!("app_version_revoke {}", version.to_string()), result)
+  }
+
+  fn do_app_version_revoke(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+  ) -> Result<AppVersion, EgoError> {
+    let mut ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::MAINTAINER)?;
+
+    let app_version = ego_dev_app.version_revoke(caller, version)?;
 
-    let app_version = ego_dev_app.version_revoke(version)?;
     Ok(app_version)
   }
 
@@ -169,36 +412,393 @@ impl EgoDevService {
     caller: &Principal,
     app_id: &AppId,
     version: &Version,
+    hotfix: bool,
+    ego_store: S,
+  ) -> Result<AppVersion, EgoError> {
+    let result = Self::do_app_version_release(caller, app_id, version, hotfix, ego_store);
+    Self::delegate_action_record(caller, app_id, &format!("app_version_release {}", version.to_string()), result)
+  }
+
+  fn do_app_version_release<S: TEgoStore>(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+    hotfix: bool,
     ego_store: S,
   ) -> Result<AppVersion, EgoError> {
     info_log_add("update ego_dev_app version");
-    let mut ego_dev_app = EgoDevApp::by_developer_id_and_id(caller, app_id).ok_or(EgoError::from(EgoDevErr::AppNotExists))?;
+    let mut ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::MAINTAINER)?;
 
-    let app_version = ego_dev_app.version_release(version)?;
+    let app_version = ego_dev_app.version_release(caller, version, hotfix)?;
 
     ego_dev_app.app.app_hash_update();
     ego_dev_app.save();
 
+    let audit_reviews: Vec<AppAuditReview> = ego_dev_app.version_audit_reviews(&app_version).iter().map(|audit_review| {
+      audit_review.to_app_audit_review()
+    }).collect();
+
+    let developer_verified = Developer::get(&ego_dev_app.developer_id).map_or(false, |developer| developer.is_verified());
+
+    // a hotfix below the current version only goes to its own line
+    if ego_dev_app.app.current_version != *version {
+      info_log_add("release line to ego_store");
+      ego_store.app_line_release(app_id.clone(), *version, app_version.pre_release.clone(), app_version.clone().wasm.unwrap());
+      return Ok(app_version);
+    }
+
     info_log_add("release to ego_store");
-    ego_store.app_main_release(ego_dev_app.app, app_version.clone().wasm.unwrap());
+    ego_store.app_main_release(ego_dev_app.app, app_version.clone().wasm.unwrap(), app_version.pre_release.clone(), developer_verified);
+
+    if let Some(app_metadata) = EgoDevAppMetadata::get(app_id) {
+      ego_store.app_main_metadata_set(app_id.clone(), app_metadata.metadata);
+    }
+
+    if !audit_reviews.is_empty() {
+      ego_store.app_main_audit_set(app_id.clone(), *version, audit_reviews);
+    }
 
     Ok(app_version)
   }
 
-  pub fn app_version_approve(app_id: &AppId) -> Result<AppVersion, EgoError> {
-    let mut ego_dev_app = EgoDevApp::get(app_id).ok_or(EgoError::from(EgoDevErr::AppNotExists))?;
-    let app_version = ego_dev_app.version_approve()?;
+  pub fn app_metadata_set<S: TEgoStore>(
+    caller: &Principal,
+    request: AppMetadataSetRequest,
+    ego_store: S,
+  ) -> Result<EgoDevAppMetadata, EgoError> {
+    let app_id = &request.app_id;
+    let ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::MAINTAINER)?;
 
-    Ok(app_version)
+    let mut app_metadata = EgoDevAppMetadata::get(app_id).unwrap_or_else(|| EgoDevAppMetadata::new(app_id));
+    app_metadata.info_set(request.tags, request.screenshots, request.media, request.homepage, request.support_link)?;
+    app_metadata.save();
+
+    EgoDevService::app_metadata_publish(&ego_dev_app, &app_metadata, ego_store);
+
+    Ok(app_metadata)
+  }
+
+  pub fn app_version_release_note_set<S: TEgoStore>(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+    notes: String,
+    ego_store: S,
+  ) -> Result<EgoDevAppMetadata, EgoError> {
+    let ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::MAINTAINER)?;
+    ego_dev_app.version_get(version).ok_or(EgoError::from(EgoDevErr::VersionNotExists))?;
+
+    let mut app_metadata = EgoDevAppMetadata::get(app_id).unwrap_or_else(|| EgoDevAppMetadata::new(app_id));
+    app_metadata.release_note_set(version, notes)?;
+    app_metadata.save();
+
+    EgoDevService::app_metadata_publish(&ego_dev_app, &app_metadata, ego_store);
+
+    Ok(app_metadata)
+  }
+
+  pub fn app_review_reply<S: TEgoStore>(
+    caller: &Principal,
+    app_id: &AppId,
+    wallet_id: &Principal,
+    reply: String,
+    ego_store: S,
+  ) -> Result<(), EgoError> {
+    EgoDevApp::by_role(caller, app_id, AppRole::MAINTAINER)?;
+
+    ego_store.app_review_reply(app_id.clone(), *wallet_id, reply);
+    Ok(())
+  }
+
+  pub fn app_review_hide<S: TEgoStore>(
+    app_id: &AppId,
+    wallet_id: &Principal,
+    hidden: bool,
+    ego_store: S,
+  ) -> Result<(), EgoError> {
+    EgoDevApp::get(app_id).ok_or(EgoError::from(EgoDevErr::AppNotExists))?;
+
+    ego_store.app_review_hide(app_id.clone(), *wallet_id, hidden);
+    Ok(())
+  }
+
+  pub async fn developer_app_stats<S: TEgoStore>(
+    caller: &Principal,
+    request: AppStatsRequest,
+    ego_store: S,
+  ) -> Result<AppStatsResponse, EgoError> {
+    EgoDevApp::by_role(caller, &request.app_id, AppRole::VIEWER)?;
+
+    ego_store.app_main_stats(request).await
   }
 
-  pub fn app_version_reject(app_id: &AppId) -> Result<AppVersion, EgoError> {
+  pub fn team_new(caller: &Principal, name: &str) -> Result<Team, EgoError> {
+    if name.is_empty() || name.len() > TEAM_NAME_MAX_LEN {
+      return Err(EgoDevErr::TeamInvalid.into());
+    }
+
+    let mut team = Team::new(caller, name);
+    team.save();
+    Ok(team)
+  }
+
+  pub fn team_member_invite(caller: &Principal, team_id: u64, user_id: &Principal) -> Result<Team, EgoError> {
+    let mut team = Self::team_owned(caller, team_id)?;
+    Developer::get(user_id).ok_or(EgoError::from(EgoDevErr::NotADeveloper))?;
+
+    team.member_invite(user_id)?;
+    Ok(team)
+  }
+
+  pub fn team_invitation_accept(caller: &Principal, team_id: u64) -> Result<Team, EgoError> {
+    let mut team = Team::get(&team_id).ok_or(EgoError::from(EgoDevErr::TeamNotExists))?;
+
+    team.member_join(caller)?;
+    Ok(team)
+  }
+
+  /// the owner removes a member, or a member leaves. the roles on the team apps go with it
+  pub fn team_member_remove(caller: &Principal, team_id: u64, user_id: &Principal) -> Result<Team, EgoError> {
+    let mut team = Team::get(&team_id).ok_or(EgoError::from(EgoDevErr::TeamNotExists))?;
+    if !team.is_owner(caller) && caller != user_id {
+      return Err(EgoDevErr::UnAuthorized.into());
+    }
+
+    team.member_remove(user_id)?;
+
+    AppCollaborator::by_user_id(user_id).iter().filter(|app_collaborator| {
+      EgoDevApp::get(&app_collaborator.app_id).map_or(false, |ego_dev_app| ego_dev_app.team_id == Some(team_id))
+    }).for_each(|app_collaborator| app_collaborator.remove());
+
+    Ok(team)
+  }
+
+  /// the developer of an app moves it into a team they own. the developer or the owner of the
+  /// team moves it out of its team, the app stays with its developer. handing the app to
+  /// someone else goes through app_transfer_request and app_transfer_accept
+  pub fn team_app_transfer(caller: &Principal, app_id: &AppId, team_id: Option<u64>) -> Result<EgoDevApp, EgoError> {
+    let mut ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::OWNER)?;
+
+    match team_id {
+      Some(team_id) => {
+        if ego_dev_app.developer_id != *caller {
+          return Err(EgoDevErr::UnAuthorized.into());
+        }
+        Self::team_owned(caller, team_id)?;
+      }
+      None => {
+        let team_owner = ego_dev_app.team_id.map_or(false, |team_id| Self::team_owned(caller, team_id).is_ok());
+        if ego_dev_app.developer_id != *caller && !team_owner {
+          return Err(EgoDevErr::UnAuthorized.into());
+        }
+      }
+    }
+
+    ego_dev_app.team_id = team_id;
+    ego_dev_app.save();
+    Ok(ego_dev_app)
+  }
+
+  pub fn app_collaborator_set(caller: &Principal, app_id: &AppId, user_id: &Principal, role: AppRole) -> Result<AppCollaborator, EgoError> {
+    let ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::OWNER)?;
+    Developer::get(user_id).ok_or(EgoError::from(EgoDevErr::NotADeveloper))?;
+
+    // the collaborators of a team app come from the team
+    if let Some(team_id) = ego_dev_app.team_id {
+      let team = Team::get(&team_id).ok_or(EgoError::from(EgoDevErr::TeamNotExists))?;
+      if !team.is_member(user_id) {
+        return Err(EgoDevErr::TeamMemberNotExists.into());
+      }
+    }
+
+    let mut app_collaborator = AppCollaborator::new(app_id, user_id, role);
+    app_collaborator.save();
+    Ok(app_collaborator)
+  }
+
+  pub fn app_collaborator_remove(caller: &Principal, app_id: &AppId, user_id: &Principal) -> Result<(), EgoError> {
+    EgoDevApp::by_role(caller, app_id, AppRole::OWNER)?;
+
+    let app_collaborator = AppCollaborator::get(app_id, user_id).ok_or(EgoError::from(EgoDevErr::UserNotExists))?;
+    app_collaborator.remove();
+    Ok(())
+  }
+
+  pub fn app_collaborator_list(caller: &Principal, app_id: &AppId) -> Result<Vec<AppCollaborator>, EgoError> {
+    EgoDevApp::by_role(caller, app_id, AppRole::VIEWER)?;
+
+    Ok(AppCollaborator::by_app_id(app_id))
+  }
+
+  fn team_owned(caller: &Principal, team_id: u64) -> Result<Team, EgoError> {
+    let team = Team::get(&team_id).ok_or(EgoError::from(EgoDevErr::TeamNotExists))?;
+
+    match team.is_owner(caller) {
+      true => Ok(team),
+      false => Err(EgoDevErr::UnAuthorized.into()),
+    }
+  }
+
+  /// a developer lets another principal act on some of its apps, like a CI bot
+  pub fn delegate_set(caller: &Principal, request: DelegateSetRequest) -> Result<Delegate, EgoError> {
+    let delegate_id = &request.delegate_id;
+    if delegate_id == caller || Developer::get(delegate_id).is_some() {
+      return Err(EgoDevErr::DelegateInvalid.into());
+    }
+    if Delegate::get(delegate_id).map_or(false, |delegate| delegate.developer_id != *caller) {
+      return Err(EgoDevErr::UserExists.into());
+    }
+
+    for app_id in request.app_ids.iter() {
+      EgoDevApp::by_role(caller, app_id, request.scope.role())?;
+    }
+
+    let mut delegate = Delegate::new(delegate_id, caller, request.app_ids, request.scope, request.expires_at)?;
+    delegate.save();
+    Ok(delegate)
+  }
+
+  pub fn delegate_remove(caller: &Principal, delegate_id: &Principal) -> Result<(), EgoError> {
+    match Delegate::get(delegate_id) {
+      Some(delegate) if delegate.developer_id == *caller => {
+        delegate.remove();
+        Ok(())
+      }
+      _ => Err(EgoDevErr::DelegateNotExists.into()),
+    }
+  }
+
+  /// keeps every attempt of a delegate with its outcome, along with the developer it acted for.
+  /// callers that are not delegates are skipped
+  pub fn delegate_action_record<T>(caller: &Principal, app_id: &AppId, action: &str, result: Result<T, EgoError>) -> Result<T, EgoError> {
+    if let Some(delegate) = Delegate::get(caller) {
+      let error = result.as_ref().err().cloned();
+      match &error {
+        None => info_log_add(format!("delegate {} of developer {}: {}", caller, delegate.developer_id, action).as_str()),
+        Some(e) => info_log_add(format!("delegate {} of developer {}: {} failed, {}", caller, delegate.developer_id, action, e.msg).as_str()),
+      }
+
+      let mut delegate_action = DelegateAction::new(caller, &delegate.developer_id, app_id, action, error);
+      delegate_action.save();
+    }
+    result
+  }
+
+  // apps not released yet get their metadata pushed to ego_store along with the first release
+  fn app_metadata_publish<S: TEgoStore>(ego_dev_app: &EgoDevApp, app_metadata: &EgoDevAppMetadata, ego_store: S) {
+    if ego_dev_app.app.current_version != Version::default() {
+      info_log_add("publish app metadata to ego_store");
+      ego_store.app_main_metadata_set(app_metadata.app_id.clone(), app_metadata.metadata.clone());
+    }
+  }
+
+  pub fn app_version_approve(
+    auditor_id: &Principal,
+    app_id: &AppId,
+    version: Option<Version>,
+    comment: String,
+    report: Option<String>,
+    reproduced: Option<bool>,
+  ) -> Result<AppVersion, EgoError> {
+    EgoDevService::app_version_review(app_id, version, AppAuditReview {
+      auditor_id: *auditor_id,
+      decision: AuditDecision::APPROVE,
+      comment,
+      report,
+      created_at: time(),
+      reproduced,
+    })
+  }
+
+  pub fn app_version_reject(
+    auditor_id: &Principal,
+    app_id: &AppId,
+    version: Option<Version>,
+    comment: String,
+    report: Option<String>,
+    reproduced: Option<bool>,
+  ) -> Result<AppVersion, EgoError> {
+    EgoDevService::app_version_review(app_id, version, AppAuditReview {
+      auditor_id: *auditor_id,
+      decision: AuditDecision::REJECT,
+      comment,
+      report,
+      created_at: time(),
+      reproduced,
+    })
+  }
+
+  // without a version the lowest submitted version of the app is reviewed
+  fn app_version_review(app_id: &AppId, version: Option<Version>, review: AppAuditReview) -> Result<AppVersion, EgoError> {
+    EgoDevService::audit_review_check(&review.comment, &review.report)?;
+
     let mut ego_dev_app = EgoDevApp::get(app_id).ok_or(EgoError::from(EgoDevErr::AppNotExists))?;
-    let app_version = ego_dev_app.version_reject()?;
+    let version = version.or(ego_dev_app.audit_version).ok_or(EgoError::from(EgoDevErr::OperationNotPermitted))?;
+
+    ego_dev_app.version_review(&version, &review)
+  }
+
+  pub fn app_version_audit_list() -> Vec<AppVersion> {
+    AppVersion::by_status(AppVersionStatus::SUBMITTED)
+  }
+
+  pub fn admin_app_version_approve(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+    reason: &str,
+  ) -> Result<AppVersion, EgoError> {
+    let mut ego_dev_app = EgoDevApp::by_role(caller, app_id, AppRole::OWNER)?;
 
+    let app_version = ego_dev_app.version_admin_approve(caller, version, reason)?;
     Ok(app_version)
   }
 
+  fn audit_review_check(comment: &String, report: &Option<String>) -> Result<(), EgoError> {
+    let report_valid = report.as_ref().map(|report| report.len() <= REPORT_MAX_LEN).unwrap_or(true);
+
+    match comment.len() <= COMMENT_MAX_LEN && report_valid {
+      true => Ok(()),
+      false => Err(EgoDevErr::AuditReviewInvalid.into()),
+    }
+  }
+
+  pub fn app_version_audit_review_list(
+    caller: &Principal,
+    app_id: &AppId,
+    version: &Version,
+  ) -> Result<Vec<AuditReview>, EgoError> {
+    EgoDevApp::by_role(caller, app_id, AppRole::VIEWER)?;
+
+    Ok(AuditReview::by_app_id_and_version(app_id, version))
+  }
+
+  /// a panel holds at least `quorum` distinct auditors
+  pub fn admin_audit_policy_set(
+    category: &Category,
+    quorum: u32,
+    auditors: Option<Vec<Principal>>,
+    reproduction_required: bool,
+  ) -> Result<AuditPolicy, EgoError> {
+    let auditors = auditors.map(|mut auditors| {
+      auditors.sort();
+      auditors.dedup();
+      auditors
+    });
+    let panel_valid = auditors.as_ref().map_or(true, |auditors| {
+      auditors.len() <= AUDIT_PANEL_MAX && quorum as usize <= auditors.len()
+    });
+
+    if quorum == 0 || !panel_valid {
+      return Err(EgoDevErr::OperationNotPermitted.into());
+    }
+
+    let mut audit_policy = AuditPolicy::new(category, quorum, auditors, reproduction_required);
+    audit_policy.save();
+
+    Ok(audit_policy)
+  }
+
   pub fn user_role_set(
     user_id: &Principal,
     is_app_auditer: bool,
@@ -211,6 +811,23 @@ impl EgoDevService {
     Ok(true)
   }
 
+  pub fn user_verified_set<S: TEgoStore>(user_id: &Principal, verified: bool, ego_store: S) -> Result<bool, EgoError> {
+    let mut developer = Developer::get(user_id).ok_or(EgoError::from(EgoDevErr::NotADeveloper))?;
+    developer.verified = Some(verified);
+    developer.save();
+
+    // the badge of released apps is updated on ego_store, the others get it with their first release
+    let app_ids: Vec<AppId> = EgoDevApp::by_developer_id(user_id).into_iter()
+      .filter(|ego_dev_app| ego_dev_app.app.current_version != Version::default())
+      .map(|ego_dev_app| ego_dev_app.app.app_id)
+      .collect();
+    if !app_ids.is_empty() {
+      ego_store.app_main_developer_verified_set(app_ids, verified);
+    }
+
+    Ok(true)
+  }
+
   pub fn admin_ego_file_add(file_id: &Principal) {
     let ego_file = File::new(file_id);
     ego_file.save();
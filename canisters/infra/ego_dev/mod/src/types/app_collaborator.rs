@@ -0,0 +1,148 @@
+use std::borrow::Cow;
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::AppId;
+use ego_utils::util::time;
+
+use crate::memory::APP_COLLABORATORS;
+
+/// what a collaborator may do on an app, each role includes the ones before it
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq)]
+pub enum AppRole {
+  VIEWER,
+  UPLOADER,
+  MAINTAINER,
+  OWNER,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AppCollaboratorKey {
+  pub app_id: AppId,
+  pub user_id: Principal,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppCollaborator {
+  pub app_id: AppId,
+  pub user_id: Principal,
+  pub role: AppRole,
+  pub created_at: u64,
+  pub last_update: u64,    // second
+}
+
+impl AppCollaborator {
+  pub fn new(app_id: &AppId, user_id: &Principal, role: AppRole) -> Self {
+    AppCollaborator {
+      app_id: app_id.clone(),
+      user_id: *user_id,
+      role,
+      created_at: time(),
+      last_update: 0,
+    }
+  }
+
+  pub fn len() -> u64 {
+    APP_COLLABORATORS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<AppCollaborator> {
+    Self::iter(start, end, |(_, app_collaborator)| Some(app_collaborator))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<AppCollaborator> {
+    Self::iter(start, end, |(_, app_collaborator)| {
+      match app_collaborator.last_update >= last_update {
+        true => { Some(app_collaborator) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn by_app_id(app_id: &AppId) -> Vec<AppCollaborator> {
+    Self::iter(0, Self::len() as usize, |(_, app_collaborator)| {
+      match app_collaborator.app_id == *app_id {
+        true => { Some(app_collaborator) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn by_user_id(user_id: &Principal) -> Vec<AppCollaborator> {
+    Self::iter(0, Self::len() as usize, |(_, app_collaborator)| {
+      match app_collaborator.user_id == *user_id {
+        true => { Some(app_collaborator) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn get(app_id: &AppId, user_id: &Principal) -> Option<AppCollaborator> {
+    APP_COLLABORATORS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&AppCollaboratorKey { app_id: app_id.clone(), user_id: *user_id })
+    })
+  }
+
+  pub fn save(&mut self) {
+    APP_COLLABORATORS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(AppCollaboratorKey { app_id: self.app_id.clone(), user_id: self.user_id }, self.clone());
+    });
+  }
+
+  pub fn remove(&self) {
+    APP_COLLABORATORS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      inst.remove(&AppCollaboratorKey { app_id: self.app_id.clone(), user_id: self.user_id });
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((AppCollaboratorKey, Self)) -> Option<Self>,
+  {
+    APP_COLLABORATORS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for AppCollaboratorKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for AppCollaboratorKey {
+  const MAX_SIZE: u32 = 128;
+  const IS_FIXED_SIZE: bool = false;
+}
+
+impl Storable for AppCollaborator {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for AppCollaborator {
+  const MAX_SIZE: u32 = 256;
+  const IS_FIXED_SIZE: bool = false;
+}
@@ -0,0 +1,163 @@
+use std::borrow::Cow;
+
+use candid::{CandidType, Decode, Deserialize, Encode};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::{AppId, AppMetadata, ReleaseNote};
+use ego_types::app::EgoError;
+use ego_types::app::Version;
+use ego_utils::util::time;
+
+use crate::memory::APP_METADATAS;
+use crate::types::app_key::AppKey;
+use crate::types::EgoDevErr;
+
+pub const TAG_MAX_COUNT: usize = 10;
+pub const TAG_MAX_LEN: usize = 32;
+pub const MEDIA_MAX_COUNT: usize = 10;
+pub const URL_MAX_LEN: usize = 256;
+pub const RELEASE_NOTE_MAX_LEN: usize = 2048;
+// only the newest release notes are kept
+pub const RELEASE_NOTE_MAX_COUNT: usize = 10;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct EgoDevAppMetadata {
+  pub app_id: AppId,
+  pub metadata: AppMetadata,
+  pub last_update: u64,    // second
+}
+
+impl EgoDevAppMetadata {
+  pub fn new(app_id: &AppId) -> Self {
+    EgoDevAppMetadata {
+      app_id: app_id.clone(),
+      metadata: AppMetadata::default(),
+      last_update: 0,
+    }
+  }
+
+  pub fn info_set(
+    &mut self,
+    tags: Vec<String>,
+    screenshots: Vec<String>,
+    media: Vec<String>,
+    homepage: Option<String>,
+    support_link: Option<String>,
+  ) -> Result<(), EgoError> {
+    let tags: Vec<String> = tags.iter().map(|tag| tag.trim().to_lowercase()).filter(|tag| !tag.is_empty()).collect();
+
+    if tags.len() > TAG_MAX_COUNT || tags.iter().any(|tag| tag.len() > TAG_MAX_LEN) {
+      return Err(EgoDevErr::MetadataInvalid.into());
+    }
+
+    if screenshots.len() > MEDIA_MAX_COUNT || media.len() > MEDIA_MAX_COUNT {
+      return Err(EgoDevErr::MetadataInvalid.into());
+    }
+
+    let urls_valid = screenshots.iter()
+      .chain(media.iter())
+      .chain(homepage.iter())
+      .chain(support_link.iter())
+      .all(|url| url_valid(url));
+    if !urls_valid {
+      return Err(EgoDevErr::MetadataInvalid.into());
+    }
+
+    self.metadata.tags = tags;
+    self.metadata.screenshots = screenshots;
+    self.metadata.media = media;
+    self.metadata.homepage = homepage;
+    self.metadata.support_link = support_link;
+
+    Ok(())
+  }
+
+  pub fn release_note_set(&mut self, version: &Version, notes: String) -> Result<(), EgoError> {
+    if notes.len() > RELEASE_NOTE_MAX_LEN {
+      return Err(EgoDevErr::MetadataInvalid.into());
+    }
+
+    // refused rather than stored and dropped at once for being older than the kept ones
+    let newer = self.metadata.release_notes.iter().filter(|release_note| release_note.version > *version).count();
+    if newer >= RELEASE_NOTE_MAX_COUNT {
+      return Err(EgoDevErr::MetadataInvalid.into());
+    }
+
+    let release_notes = &mut self.metadata.release_notes;
+    release_notes.retain(|release_note| release_note.version != *version);
+    release_notes.push(ReleaseNote { version: *version, notes });
+
+    // newest version first
+    release_notes.sort_by(|a, b| b.version.cmp(&a.version));
+    release_notes.truncate(RELEASE_NOTE_MAX_COUNT);
+
+    Ok(())
+  }
+
+  pub fn len() -> u64 {
+    APP_METADATAS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_metadata)| Some(app_metadata))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_metadata)| {
+      match app_metadata.last_update >= last_update {
+        true => { Some(app_metadata) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn get(app_id: &AppId) -> Option<Self> {
+    APP_METADATAS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&AppKey::new(&app_id))
+    })
+  }
+
+  pub fn save(&mut self) {
+    APP_METADATAS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(AppKey::new(&self.app_id), self.clone());
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((AppKey, Self)) -> Option<Self>,
+  {
+    APP_METADATAS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+fn url_valid(url: &String) -> bool {
+  url.len() <= URL_MAX_LEN && (url.starts_with("https://") || url.starts_with("http://"))
+}
+
+impl Storable for EgoDevAppMetadata {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for EgoDevAppMetadata {
+  const MAX_SIZE: u32 = 32 * 1024;
+  const IS_FIXED_SIZE: bool = false;
+}
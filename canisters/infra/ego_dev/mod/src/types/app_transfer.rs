@@ -0,0 +1,122 @@
+use std::borrow::Cow;
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::AppId;
+use ego_utils::util::time;
+
+use crate::memory::APP_TRANSFERS;
+use crate::types::app_key::AppKey;
+
+// 7 days
+pub const APP_TRANSFER_TTL: u64 = 7 * 24 * 3600;
+
+/// an app offered to another developer, it only moves once the recipient accepts before the deadline
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppTransfer {
+  pub app_id: AppId,
+  pub from_id: Principal,
+  pub to_id: Principal,
+  pub expires_at: u64,     // second
+  pub created_at: u64,
+  pub last_update: u64,    // second
+}
+
+impl AppTransfer {
+  pub fn new(app_id: &AppId, from_id: &Principal, to_id: &Principal) -> Self {
+    let now = time();
+    AppTransfer {
+      app_id: app_id.clone(),
+      from_id: *from_id,
+      to_id: *to_id,
+      expires_at: now + APP_TRANSFER_TTL,
+      created_at: now,
+      last_update: 0,
+    }
+  }
+
+  pub fn is_expired(&self) -> bool {
+    time() >= self.expires_at
+  }
+
+  pub fn len() -> u64 {
+    APP_TRANSFERS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_transfer)| Some(app_transfer))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_transfer)| {
+      match app_transfer.last_update >= last_update {
+        true => { Some(app_transfer) }
+        false => { None }
+      }
+    })
+  }
+
+  /// the transfers a user sent or received
+  pub fn by_user_id(user_id: &Principal) -> Vec<Self> {
+    Self::iter(0, Self::len() as usize, |(_, app_transfer)| {
+      match app_transfer.from_id == *user_id || app_transfer.to_id == *user_id {
+        true => { Some(app_transfer) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn get(app_id: &AppId) -> Option<Self> {
+    APP_TRANSFERS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&AppKey::new(app_id))
+    })
+  }
+
+  pub fn save(&mut self) {
+    APP_TRANSFERS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(AppKey::new(&self.app_id), self.clone());
+    });
+  }
+
+  pub fn remove(&self) {
+    APP_TRANSFERS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      inst.remove(&AppKey::new(&self.app_id));
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((AppKey, Self)) -> Option<Self>,
+  {
+    APP_TRANSFERS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for AppTransfer {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for AppTransfer {
+  const MAX_SIZE: u32 = 512;
+  const IS_FIXED_SIZE: bool = false;
+}
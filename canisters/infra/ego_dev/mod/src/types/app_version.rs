@@ -4,13 +4,21 @@ use std::borrow::Cow;
 use candid::{CandidType, Decode, Deserialize, Encode, Principal};
 use ic_stable_structures::{BoundedStorable, Storable};
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 
-use ego_types::app::{AppId, Version, Wasm};
+use ego_types::app::{AppId, EgoError, Version, Wasm};
 use ego_types::app::CanisterType::{ASSET, BACKEND};
+use ego_types::semver::SemVer;
 use ego_utils::util::time;
 
 use crate::memory::APP_VERSIONS;
 use crate::state::SEQ;
+use crate::types::app_version_transition::AppVersionTransition;
+use crate::types::EgoDevErr;
+
+pub const SOURCE_URL_MAX_LEN: usize = 256;
+pub const SOURCE_TOOLCHAIN_MAX_LEN: usize = 64;
+pub const SEMVER_LABEL_MAX_LEN: usize = 64;
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct AppVersion {
@@ -21,6 +29,47 @@ pub struct AppVersion {
   pub file_id: Principal,
   pub wasm: Option<Wasm>,
   pub last_update: u64,    // second
+  // increased on every submission, audit reviews are counted per round
+  pub audit_round: Option<u32>,
+  // where the wasm was built from, required before submission
+  pub source: Option<AppVersionSource>,
+  // sha256 of the uploaded backend wasm
+  pub wasm_sha256: Option<String>,
+  // semver labels, "beta.1" in 1.2.0-beta.1+build.5
+  pub pre_release: Option<String>,
+  pub build: Option<String>,
+}
+
+/// what an auditor needs to rebuild the wasm and compare the hash
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AppVersionSource {
+  pub repo_url: String,
+  pub commit: String,
+  pub toolchain: String,
+  pub sha256: String,
+}
+
+impl AppVersionSource {
+  pub fn validate(&self) -> Result<(), EgoError> {
+    let repo_url_valid = self.repo_url.len() <= SOURCE_URL_MAX_LEN
+      && (self.repo_url.starts_with("https://") || self.repo_url.starts_with("http://"));
+    let commit_valid = (7..=64).contains(&self.commit.len()) && is_hex(&self.commit);
+    let toolchain_valid = !self.toolchain.trim().is_empty() && self.toolchain.len() <= SOURCE_TOOLCHAIN_MAX_LEN;
+    let sha256_valid = self.sha256.len() == 64 && is_hex(&self.sha256);
+
+    match repo_url_valid && commit_valid && toolchain_valid && sha256_valid {
+      true => Ok(()),
+      false => Err(EgoDevErr::SourceInvalid.into()),
+    }
+  }
+}
+
+pub fn wasm_sha256(data: &[u8]) -> String {
+  format!("{:x}", Sha256::digest(data))
+}
+
+fn is_hex(value: &str) -> bool {
+  value.chars().all(|c| c.is_ascii_hexdigit())
 }
 
 #[derive(
@@ -33,6 +82,8 @@ pub enum AppVersionStatus {
   APPROVED,
   RELEASED,
   REVOKED,
+  // only seen in the transitions, the version itself is removed
+  DELETED,
 }
 
 impl PartialEq for AppVersion {
@@ -52,6 +103,88 @@ impl AppVersion {
       file_id: ego_file_canister_id.clone(),
       wasm: None,
       last_update: 0,
+      audit_round: None,
+      source: None,
+      wasm_sha256: None,
+      pre_release: None,
+      build: None,
+    }
+  }
+
+  pub fn semver(&self) -> SemVer {
+    SemVer::new(&self.version, &self.pre_release, &self.build).unwrap_or_else(|_| SemVer::from(self.version))
+  }
+
+  /// every status change goes through here so it is recorded with the actor
+  pub fn status_set(&mut self, status: AppVersionStatus, actor_id: &Principal) {
+    self.status_transit(status, actor_id, None);
+  }
+
+  /// a status set outside of the audit, the reason is kept with the transition
+  pub fn status_override(&mut self, status: AppVersionStatus, actor_id: &Principal, reason: &str) {
+    self.status_transit(status, actor_id, Some(reason.to_string()));
+  }
+
+  fn status_transit(&mut self, status: AppVersionStatus, actor_id: &Principal, reason: Option<String>) {
+    let mut transition = AppVersionTransition::new(&self.app_id, &self.version, self.status, status, actor_id);
+    transition.reason = reason;
+    transition.save();
+
+    self.status = status;
+    self.save();
+  }
+
+  /// the wasm can be replaced while the version is a draft, after a rejection or a revoke.
+  /// a rejected version takes a different wasm only
+  pub fn wasm_upload_check(&self, wasm_sha256: &str) -> Result<(), EgoError> {
+    match self.status {
+      AppVersionStatus::NEW | AppVersionStatus::REVOKED => Ok(()),
+      AppVersionStatus::REJECTED => match self.wasm_sha256.as_deref() == Some(wasm_sha256) {
+        true => Err(EgoDevErr::WasmExists.into()),
+        false => Ok(()),
+      },
+      _ => Err(EgoDevErr::OperationNotPermitted.into()),
+    }
+  }
+
+  /// records the wasm once written, a rejected version goes back to draft
+  pub fn wasm_uploaded(&mut self, actor_id: &Principal, wasm_sha256: String) {
+    self.wasm_sha256_set(wasm_sha256);
+    if self.status == AppVersionStatus::REJECTED {
+      self.status_set(AppVersionStatus::NEW, actor_id);
+    }
+  }
+
+  pub fn wasm_sha256_set(&mut self, wasm_sha256: String) {
+    self.wasm_sha256 = Some(wasm_sha256);
+    self.save();
+  }
+
+  pub fn source_set(&mut self, source: AppVersionSource) -> Result<(), EgoError> {
+    match self.status {
+      AppVersionStatus::NEW | AppVersionStatus::REJECTED | AppVersionStatus::REVOKED => {
+        source.validate()?;
+
+        self.source = Some(AppVersionSource {
+          commit: source.commit.to_lowercase(),
+          sha256: source.sha256.to_lowercase(),
+          ..source
+        });
+        self.save();
+        Ok(())
+      }
+      _ => Err(EgoDevErr::OperationNotPermitted.into()),
+    }
+  }
+
+  /// the declared source must exist and, when a wasm was uploaded, match its hash
+  pub fn source_check(&self) -> Result<(), EgoError> {
+    match &self.source {
+      None => Err(EgoDevErr::SourceMissing.into()),
+      Some(source) => match &self.wasm_sha256 {
+        Some(wasm_sha256) if *wasm_sha256 != source.sha256 => Err(EgoDevErr::SourceInvalid.into()),
+        _ => Ok(()),
+      },
     }
   }
 
@@ -99,6 +232,15 @@ impl AppVersion {
     })
   }
 
+  pub fn by_status(status: AppVersionStatus) -> Vec<AppVersion> {
+    Self::iter(0, Self::len() as usize, |(_, app_version)| match app_version.status == status {
+      true => {
+        Some(app_version)
+      }
+      false => { None }
+    })
+  }
+
   pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<AppVersion> {
     Self::iter(start, end, |(_, app_version)| match app_version.last_update >= last_update {
       true => {
@@ -129,6 +271,13 @@ impl AppVersion {
     });
   }
 
+  pub fn remove(&self) {
+    APP_VERSIONS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      inst.remove(&self.id);
+    });
+  }
+
   fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
   where
     F: Fn((u64, Self)) -> Option<Self>,
@@ -0,0 +1,124 @@
+use std::borrow::Cow;
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::{AppId, Version};
+use ego_utils::util::time;
+
+use crate::memory::APP_VERSION_TRANSITIONS;
+use crate::state::SEQ;
+use crate::types::app_version::AppVersionStatus;
+
+pub const REASON_MAX_LEN: usize = 256;
+
+/// one status change of an app version and who made it
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppVersionTransition {
+  pub id: u64,
+  pub app_id: AppId,
+  pub version: Version,
+  pub from: AppVersionStatus,
+  pub to: AppVersionStatus,
+  pub actor_id: Principal,
+  pub created_at: u64,
+  pub last_update: u64,    // second
+  // why an admin moved the version outside of the audit
+  pub reason: Option<String>,
+}
+
+impl AppVersionTransition {
+  pub fn new(
+    app_id: &AppId,
+    version: &Version,
+    from: AppVersionStatus,
+    to: AppVersionStatus,
+    actor_id: &Principal,
+  ) -> Self {
+    let next_id = SEQ.with(|cell| cell.borrow_mut().next_number("app_version_transition", 0));
+    AppVersionTransition {
+      id: next_id,
+      app_id: app_id.clone(),
+      version: *version,
+      from,
+      to,
+      actor_id: *actor_id,
+      created_at: time(),
+      last_update: 0,
+      reason: None,
+    }
+  }
+
+  pub fn len() -> u64 {
+    APP_VERSION_TRANSITIONS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<AppVersionTransition> {
+    Self::iter(start, end, |(_, transition)| Some(transition))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<AppVersionTransition> {
+    Self::iter(start, end, |(_, transition)| {
+      match transition.last_update >= last_update {
+        true => { Some(transition) }
+        false => { None }
+      }
+    })
+  }
+
+  /// transitions of the version, oldest first
+  pub fn by_app_id_and_version(app_id: &AppId, version: &Version) -> Vec<AppVersionTransition> {
+    Self::iter(0, Self::len() as usize, |(_, transition)| {
+      match transition.app_id == *app_id && transition.version == *version {
+        true => { Some(transition) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn get(id: &u64) -> Option<AppVersionTransition> {
+    APP_VERSION_TRANSITIONS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(id)
+    })
+  }
+
+  pub fn save(&mut self) {
+    APP_VERSION_TRANSITIONS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(self.id, self.clone());
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((u64, Self)) -> Option<Self>,
+  {
+    APP_VERSION_TRANSITIONS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for AppVersionTransition {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for AppVersionTransition {
+  const MAX_SIZE: u32 = 1024;
+  const IS_FIXED_SIZE: bool = false;
+}
@@ -0,0 +1,117 @@
+use std::borrow::Cow;
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::Category;
+use ego_utils::util::time;
+
+use crate::memory::AUDIT_POLICIES;
+use crate::types::index_key::AuditPolicyKey;
+
+// approvals needed by the categories without a policy
+pub const DEFAULT_QUORUM: u32 = 1;
+pub const AUDIT_PANEL_MAX: usize = 20;
+
+/// how many auditors have to approve a version of the apps in a category, N of the M auditors
+/// on the panel when the category has one, N of all the app auditors otherwise
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AuditPolicy {
+  pub category: Category,
+  pub quorum: u32,
+  pub last_update: u64,    // second
+  // approvals only count when the auditor reproduced the wasm from its source
+  pub reproduction_required: Option<bool>,
+  // the only auditors allowed to review the apps of the category
+  pub auditors: Option<Vec<Principal>>,
+}
+
+impl AuditPolicy {
+  pub fn new(category: &Category, quorum: u32, auditors: Option<Vec<Principal>>, reproduction_required: bool) -> Self {
+    AuditPolicy {
+      category: category.clone(),
+      quorum,
+      last_update: 0,
+      reproduction_required: Some(reproduction_required),
+      auditors,
+    }
+  }
+
+  pub fn quorum(category: &Category) -> u32 {
+    Self::get(category).map(|audit_policy| audit_policy.quorum).unwrap_or(DEFAULT_QUORUM)
+  }
+
+  /// whether the auditor sits on the panel of the category, any auditor does without a panel
+  pub fn is_panel_auditor(category: &Category, auditor_id: &Principal) -> bool {
+    Self::get(category).and_then(|audit_policy| audit_policy.auditors).map_or(true, |auditors| {
+      auditors.contains(auditor_id)
+    })
+  }
+
+  pub fn reproduction_required(category: &Category) -> bool {
+    Self::get(category).and_then(|audit_policy| audit_policy.reproduction_required).unwrap_or(false)
+  }
+
+  pub fn len() -> u64 {
+    AUDIT_POLICIES.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<AuditPolicy> {
+    Self::iter(start, end, |(_, audit_policy)| Some(audit_policy))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<AuditPolicy> {
+    Self::iter(start, end, |(_, audit_policy)| {
+      match audit_policy.last_update >= last_update {
+        true => { Some(audit_policy) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn get(category: &Category) -> Option<AuditPolicy> {
+    AUDIT_POLICIES.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&AuditPolicyKey::new(category))
+    })
+  }
+
+  pub fn save(&mut self) {
+    AUDIT_POLICIES.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(AuditPolicyKey::new(&self.category), self.clone());
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((AuditPolicyKey, Self)) -> Option<Self>,
+  {
+    AUDIT_POLICIES.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for AuditPolicy {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for AuditPolicy {
+  const MAX_SIZE: u32 = 1024;
+  const IS_FIXED_SIZE: bool = false;
+}
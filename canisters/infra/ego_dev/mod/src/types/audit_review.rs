@@ -0,0 +1,166 @@
+use std::borrow::Cow;
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::{AppAuditReview, AppId, AuditDecision, Version};
+use ego_utils::util::time;
+
+use crate::memory::{AUDIT_REVIEW_INDEX, AUDIT_REVIEWS};
+use crate::state::SEQ;
+use crate::types::index_key::AuditReviewKey;
+
+pub const COMMENT_MAX_LEN: usize = 1000;
+pub const REPORT_MAX_LEN: usize = 256;
+
+/// an auditor's decision on one submission (round) of an app version
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AuditReview {
+  pub id: u64,
+  pub app_id: AppId,
+  pub version: Version,
+  pub round: u32,
+  pub auditor_id: Principal,
+  pub decision: AuditDecision,
+  pub comment: String,
+  pub report: Option<String>,
+  pub created_at: u64,
+  pub last_update: u64,    // second
+  pub reproduced: Option<bool>,
+}
+
+impl AuditReview {
+  pub fn new(app_id: &AppId, version: &Version, round: u32, review: &AppAuditReview) -> Self {
+    let next_id = SEQ.with(|cell| cell.borrow_mut().next_number("audit_review", 0));
+    AuditReview {
+      id: next_id,
+      app_id: app_id.clone(),
+      version: *version,
+      round,
+      auditor_id: review.auditor_id,
+      decision: review.decision,
+      comment: review.comment.clone(),
+      report: review.report.clone(),
+      created_at: time(),
+      last_update: 0,
+      reproduced: review.reproduced,
+    }
+  }
+
+  /// one decision per auditor and round, a second one replaces the first
+  pub fn record(app_id: &AppId, version: &Version, round: u32, review: &AppAuditReview) -> AuditReview {
+    let previous = Self::by_round(app_id, version, round).into_iter().find(|audit_review| {
+      audit_review.auditor_id == review.auditor_id
+    });
+
+    let mut audit_review = match previous {
+      Some(mut audit_review) => {
+        audit_review.decision = review.decision;
+        audit_review.comment = review.comment.clone();
+        audit_review.report = review.report.clone();
+        audit_review.reproduced = review.reproduced;
+        audit_review.created_at = time();
+        audit_review
+      }
+      None => AuditReview::new(app_id, version, round, review),
+    };
+    audit_review.save();
+    audit_review
+  }
+
+  pub fn to_app_audit_review(&self) -> AppAuditReview {
+    AppAuditReview {
+      auditor_id: self.auditor_id,
+      decision: self.decision,
+      comment: self.comment.clone(),
+      report: self.report.clone(),
+      created_at: self.created_at,
+      reproduced: self.reproduced,
+    }
+  }
+
+  pub fn len() -> u64 {
+    AUDIT_REVIEWS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<AuditReview> {
+    Self::iter(start, end, |(_, audit_review)| Some(audit_review))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<AuditReview> {
+    Self::iter(start, end, |(_, audit_review)| {
+      match audit_review.last_update >= last_update {
+        true => { Some(audit_review) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn by_app_id_and_version(app_id: &AppId, version: &Version) -> Vec<AuditReview> {
+    Self::by_index(app_id, version, None)
+  }
+
+  pub fn by_round(app_id: &AppId, version: &Version, round: u32) -> Vec<AuditReview> {
+    Self::by_index(app_id, version, Some(round))
+  }
+
+  fn by_index(app_id: &AppId, version: &Version, round: Option<u32>) -> Vec<AuditReview> {
+    AUDIT_REVIEW_INDEX.with(|cell| {
+      let index = cell.borrow();
+      index.range(AuditReviewKey::range(app_id, version, round)).filter_map(|(key, _)| {
+        Self::get(&key.id())
+      }).collect()
+    })
+  }
+
+  pub fn get(id: &u64) -> Option<AuditReview> {
+    AUDIT_REVIEWS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(id)
+    })
+  }
+
+  pub fn save(&mut self) {
+    AUDIT_REVIEWS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(self.id, self.clone());
+    });
+
+    // the app, version and round of a review never change, the key is the same on every save
+    AUDIT_REVIEW_INDEX.with(|cell| {
+      cell.borrow_mut().insert(AuditReviewKey::new(&self.app_id, &self.version, self.round, self.id), self.id);
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((u64, Self)) -> Option<Self>,
+  {
+    AUDIT_REVIEWS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for AuditReview {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for AuditReview {
+  const MAX_SIZE: u32 = 2048;
+  const IS_FIXED_SIZE: bool = false;
+}
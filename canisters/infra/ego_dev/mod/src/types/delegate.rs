@@ -0,0 +1,159 @@
+use std::borrow::Cow;
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use ic_stable_structures::storable::Blob;
+use serde::Serialize;
+
+use ego_types::app::{AppId, EgoError};
+use ego_utils::util::time;
+
+use crate::memory::DELEGATES;
+use crate::types::app_collaborator::AppRole;
+use crate::types::ego_dev_app::EgoDevApp;
+use crate::types::EgoDevErr;
+
+pub const DELEGATE_APP_MAX: usize = 10;
+// 90 days
+pub const DELEGATE_TTL_MAX: u64 = 90 * 24 * 3600;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DelegateScope {
+  UPLOAD,
+  RELEASE,
+}
+
+impl DelegateScope {
+  /// upload covers creating versions and uploading wasm, release adds submit, release and revoke
+  pub fn role(&self) -> AppRole {
+    match self {
+      DelegateScope::UPLOAD => AppRole::UPLOADER,
+      DelegateScope::RELEASE => AppRole::MAINTAINER,
+    }
+  }
+}
+
+/// a principal acting for a developer on some apps until it expires, like a CI bot
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Delegate {
+  pub delegate_id: Principal,
+  pub developer_id: Principal,
+  pub app_ids: Vec<AppId>,
+  pub scope: DelegateScope,
+  pub expires_at: u64,     // second
+  pub created_at: u64,
+  pub last_update: u64,    // second
+}
+
+impl Delegate {
+  pub fn new(delegate_id: &Principal, developer_id: &Principal, app_ids: Vec<AppId>, scope: DelegateScope, expires_at: u64) -> Result<Self, EgoError> {
+    let now = time();
+    if app_ids.is_empty() || app_ids.len() > DELEGATE_APP_MAX || expires_at <= now || expires_at > now + DELEGATE_TTL_MAX {
+      return Err(EgoDevErr::DelegateInvalid.into());
+    }
+
+    Ok(Delegate {
+      delegate_id: *delegate_id,
+      developer_id: *developer_id,
+      app_ids,
+      scope,
+      expires_at,
+      created_at: now,
+      last_update: 0,
+    })
+  }
+
+  pub fn is_active(&self) -> bool {
+    time() < self.expires_at
+  }
+
+  /// never more than the scope, nor more than the developer has on the app
+  pub fn role(&self, ego_dev_app: &EgoDevApp) -> Option<AppRole> {
+    if !self.is_active() || !self.app_ids.contains(&ego_dev_app.app.app_id) {
+      return None;
+    }
+
+    ego_dev_app.role(&self.developer_id).map(|role| role.min(self.scope.role()))
+  }
+
+  pub fn len() -> u64 {
+    DELEGATES.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Delegate> {
+    Self::iter(start, end, |(_, delegate)| Some(delegate))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Delegate> {
+    Self::iter(start, end, |(_, delegate)| {
+      match delegate.last_update >= last_update {
+        true => { Some(delegate) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn by_developer_id(developer_id: &Principal) -> Vec<Delegate> {
+    Self::iter(0, Self::len() as usize, |(_, delegate)| {
+      match delegate.developer_id == *developer_id {
+        true => { Some(delegate) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn get(delegate_id: &Principal) -> Option<Delegate> {
+    DELEGATES.with(|cell| {
+      let inst = cell.borrow();
+      let key = Blob::try_from(delegate_id.as_slice()).unwrap();
+      inst.get(&key)
+    })
+  }
+
+  pub fn save(&mut self) {
+    DELEGATES.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      let key = Blob::try_from(self.delegate_id.as_slice()).unwrap();
+      self.last_update = time();
+      inst.insert(key, self.clone());
+    });
+  }
+
+  pub fn remove(&self) {
+    DELEGATES.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      let key = Blob::try_from(self.delegate_id.as_slice()).unwrap();
+      inst.remove(&key);
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((Blob<29>, Self)) -> Option<Self>,
+  {
+    DELEGATES.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for Delegate {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for Delegate {
+  const MAX_SIZE: u32 = 1024;
+  const IS_FIXED_SIZE: bool = false;
+}
@@ -0,0 +1,111 @@
+use std::borrow::Cow;
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::{AppId, EgoError};
+use ego_utils::util::time;
+
+use crate::memory::DELEGATE_ACTIONS;
+use crate::state::SEQ;
+
+/// something a delegate tried, kept with the developer it acted for. error is None when it went through
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct DelegateAction {
+  pub id: u64,
+  pub delegate_id: Principal,
+  pub developer_id: Principal,
+  pub app_id: AppId,
+  pub action: String,
+  pub error: Option<EgoError>,
+  pub created_at: u64,
+  pub last_update: u64,    // second
+}
+
+impl DelegateAction {
+  pub fn new(delegate_id: &Principal, developer_id: &Principal, app_id: &AppId, action: &str, error: Option<EgoError>) -> Self {
+    let next_id = SEQ.with(|cell| cell.borrow_mut().next_number("delegate_action", 0));
+    DelegateAction {
+      id: next_id,
+      delegate_id: *delegate_id,
+      developer_id: *developer_id,
+      app_id: app_id.clone(),
+      action: action.to_string(),
+      error,
+      created_at: time(),
+      last_update: 0,
+    }
+  }
+
+  pub fn len() -> u64 {
+    DELEGATE_ACTIONS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<DelegateAction> {
+    Self::iter(start, end, |(_, delegate_action)| Some(delegate_action))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<DelegateAction> {
+    Self::iter(start, end, |(_, delegate_action)| {
+      match delegate_action.last_update >= last_update {
+        true => { Some(delegate_action) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn by_developer_id(developer_id: &Principal) -> Vec<DelegateAction> {
+    Self::iter(0, Self::len() as usize, |(_, delegate_action)| {
+      match delegate_action.developer_id == *developer_id {
+        true => { Some(delegate_action) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn get(id: &u64) -> Option<DelegateAction> {
+    DELEGATE_ACTIONS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(id)
+    })
+  }
+
+  pub fn save(&mut self) {
+    DELEGATE_ACTIONS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(self.id, self.clone());
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((u64, Self)) -> Option<Self>,
+  {
+    DELEGATE_ACTIONS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for DelegateAction {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for DelegateAction {
+  const MAX_SIZE: u32 = 1024;
+  const IS_FIXED_SIZE: bool = false;
+}
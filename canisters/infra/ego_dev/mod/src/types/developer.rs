@@ -18,6 +18,8 @@ pub struct Developer {
   pub is_manager: bool,
   pub created_apps: Vec<AppId>,
   pub last_update: u64,    // second
+  // granted by a manager, shown with the apps on ego_store
+  pub verified: Option<bool>,
 }
 
 impl Developer {
@@ -29,9 +31,14 @@ impl Developer {
       is_manager: false,
       created_apps: vec![],
       last_update: 0,
+      verified: None,
     }
   }
 
+  pub fn is_verified(&self) -> bool {
+    self.verified.unwrap_or(false)
+  }
+
   pub fn len() -> u64 {
     DEVELOPERS.with(|cell| {
       let inst = cell.borrow();
@@ -0,0 +1,118 @@
+use std::borrow::Cow;
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use ic_stable_structures::storable::Blob;
+use serde::Serialize;
+
+use ego_types::app::EgoError;
+use ego_utils::util::time;
+
+use crate::memory::DEVELOPER_PROFILES;
+use crate::types::EgoDevErr;
+
+pub const PROFILE_FIELD_MAX_LEN: usize = 256;
+
+/// the public information of a developer, kept apart from the developer to leave its size unchanged
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct DeveloperProfile {
+  pub developer_id: Principal,
+  pub website: Option<String>,
+  pub contact: Option<String>,
+  pub avatar: Option<String>,
+  pub last_update: u64,    // second
+}
+
+impl DeveloperProfile {
+  pub fn new(developer_id: &Principal) -> Self {
+    DeveloperProfile {
+      developer_id: *developer_id,
+      website: None,
+      contact: None,
+      avatar: None,
+      last_update: 0,
+    }
+  }
+
+  pub fn info_set(&mut self, website: Option<String>, contact: Option<String>, avatar: Option<String>) -> Result<(), EgoError> {
+    let fields_valid = website.iter()
+      .chain(contact.iter())
+      .chain(avatar.iter())
+      .all(|field| !field.is_empty() && field.len() <= PROFILE_FIELD_MAX_LEN);
+    // the contact can be an email, the others are links
+    let links_valid = website.iter().chain(avatar.iter()).all(|link| link.starts_with("https://"));
+    if !fields_valid || !links_valid {
+      return Err(EgoDevErr::ProfileInvalid.into());
+    }
+
+    self.website = website;
+    self.contact = contact;
+    self.avatar = avatar;
+
+    Ok(())
+  }
+
+  pub fn len() -> u64 {
+    DEVELOPER_PROFILES.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, developer_profile)| Some(developer_profile))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, developer_profile)| {
+      match developer_profile.last_update >= last_update {
+        true => { Some(developer_profile) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn get(developer_id: &Principal) -> Option<Self> {
+    DEVELOPER_PROFILES.with(|cell| {
+      let inst = cell.borrow();
+      let key = Blob::try_from(developer_id.as_slice()).unwrap();
+      inst.get(&key)
+    })
+  }
+
+  pub fn save(&mut self) {
+    DEVELOPER_PROFILES.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      let key = Blob::try_from(self.developer_id.as_slice()).unwrap();
+      self.last_update = time();
+      inst.insert(key, self.clone());
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((Blob<29>, Self)) -> Option<Self>,
+  {
+    DEVELOPER_PROFILES.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for DeveloperProfile {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for DeveloperProfile {
+  const MAX_SIZE: u32 = 1024;
+  const IS_FIXED_SIZE: bool = false;
+}
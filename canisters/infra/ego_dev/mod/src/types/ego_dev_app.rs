@@ -1,18 +1,29 @@
 use std::borrow::Cow;
+use std::cmp::Ordering;
 
 use candid::{CandidType, Decode, Deserialize, Encode, Principal};
 use ic_stable_structures::{BoundedStorable, Storable};
 use serde::Serialize;
 
-use ego_types::app::{App, AppId, Category};
+use ego_types::app::{App, AppAuditReview, AppId, AuditDecision, Category};
 use ego_types::app::EgoError;
 use ego_types::app::Version;
+use ego_types::semver::SemVer;
 use ego_utils::util::time;
 
 use crate::memory::EGO_DEV_APPS;
+use crate::types::app_collaborator::{AppCollaborator, AppRole};
 use crate::types::app_key::AppKey;
-use crate::types::app_version::{AppVersion, AppVersionStatus};
+use crate::types::app_version::{AppVersion, AppVersionStatus, SEMVER_LABEL_MAX_LEN};
+use crate::types::app_version_transition::REASON_MAX_LEN;
+use crate::types::audit_policy::AuditPolicy;
+use crate::types::audit_review::AuditReview;
+use crate::types::delegate::Delegate;
 use crate::types::EgoDevErr;
+use crate::types::team::Team;
+
+// the longest app id the 64 bytes AppKey holds once candid encoded, in ego_dev and ego_store
+pub const APP_ID_MAX_LEN: usize = 48;
 
 /********************  app  ********************/
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -21,6 +32,8 @@ pub struct EgoDevApp {
   pub developer_id: Principal,
   pub audit_version: Option<Version>,
   pub last_update: u64,    // second
+  // the team sharing the app, its owner has the owner role on the app
+  pub team_id: Option<u64>,
 }
 
 impl EgoDevApp {
@@ -46,6 +59,30 @@ impl EgoDevApp {
       developer_id: developer_id.clone(),
       audit_version: None,
       last_update: 0,
+      team_id: None,
+    }
+  }
+
+  /// the creator and the team owner own the app, team members can view it,
+  /// collaborators get the role they were given, delegates act within their scope
+  pub fn role(&self, user_id: &Principal) -> Option<AppRole> {
+    if self.developer_id == *user_id {
+      return Some(AppRole::OWNER);
+    }
+
+    let team_role = self.team_id.and_then(|team_id| Team::get(&team_id)).and_then(|team| {
+      match (team.is_owner(user_id), team.is_member(user_id)) {
+        (true, _) => Some(AppRole::OWNER),
+        (false, true) => Some(AppRole::VIEWER),
+        _ => None,
+      }
+    });
+    let collaborator_role = AppCollaborator::get(&self.app.app_id, user_id).map(|app_collaborator| app_collaborator.role);
+
+    // the roles granted on the app come before a delegation
+    match team_role.max(collaborator_role) {
+      Some(role) => Some(role),
+      None => Delegate::get(user_id).and_then(|delegate| delegate.role(self)),
     }
   }
 
@@ -58,32 +95,92 @@ impl EgoDevApp {
     ego_file_canister_id: &Principal,
     version: &Version,
   ) -> Result<AppVersion, EgoError> {
-    match self.version_get(version) {
+    self.version_new_semver(ego_file_canister_id, &SemVer::from(*version))
+  }
+
+  /// a version number is used once, the pre-release and build labels only tag it.
+  /// a pre-release becomes the final version through version_promote
+  pub fn version_new_semver(
+    &mut self,
+    ego_file_canister_id: &Principal,
+    semver: &SemVer,
+  ) -> Result<AppVersion, EgoError> {
+    let version = semver.core();
+    let pre_release = semver.pre_release_label();
+    let build = semver.build_label();
+
+    if version == Version::min()
+      || pre_release.as_ref().map_or(false, |label| label.len() > SEMVER_LABEL_MAX_LEN)
+      || build.as_ref().map_or(false, |label| label.len() > SEMVER_LABEL_MAX_LEN) {
+      return Err(EgoDevErr::VersionInvalid.into());
+    }
+
+    match self.version_get(&version) {
       Some(_) => Err(EgoDevErr::VersionExists.into()),
       None => {
         let mut app_version =
-          AppVersion::new(&self.app.app_id, ego_file_canister_id, version);
+          AppVersion::new(&self.app.app_id, ego_file_canister_id, &version);
+        app_version.pre_release = pre_release;
+        app_version.build = build;
         app_version.save();
         Ok(app_version)
       }
     }
   }
 
-  pub fn version_submit(&mut self, version: &Version) -> Result<AppVersion, EgoError> {
-    if self.audit_version.is_some() {
-      return Err(EgoDevErr::OperationNotPermitted.into());
+  /// drafts that were never submitted can be dropped, the deletion stays in the transitions
+  pub fn version_delete(&mut self, actor_id: &Principal, version: &Version) -> Result<AppVersion, EgoError> {
+    match self.version_get(version) {
+      Some(mut app_version) => {
+        match app_version.status {
+          AppVersionStatus::NEW => {
+            app_version.status_set(AppVersionStatus::DELETED, actor_id);
+            app_version.remove();
+            Ok(app_version)
+          }
+          _ => {
+            Err(EgoDevErr::OperationNotPermitted.into())
+          }
+        }
+      }
+      None => Err(EgoDevErr::VersionNotExists.into()),
     }
+  }
 
+  /// drops the pre-release label so the final version can go through the audit and the
+  /// release again. the version turns back into a draft unless it is under audit
+  pub fn version_promote(&mut self, actor_id: &Principal, version: &Version) -> Result<AppVersion, EgoError> {
+    match self.version_get(version) {
+      Some(mut app_version) => {
+        if app_version.pre_release.is_none() || app_version.status == AppVersionStatus::SUBMITTED {
+          return Err(EgoDevErr::OperationNotPermitted.into());
+        }
+
+        app_version.pre_release = None;
+        if app_version.status == AppVersionStatus::NEW {
+          app_version.save();
+        } else {
+          app_version.status_set(AppVersionStatus::NEW, actor_id);
+        }
+        Ok(app_version)
+      }
+      None => Err(EgoDevErr::VersionNotExists.into()),
+    }
+  }
+
+  /// several versions of an app can be under audit at the same time. a rejected version is
+  /// only submitted again after a new wasm turned it back into a draft
+  pub fn version_submit(&mut self, actor_id: &Principal, version: &Version) -> Result<AppVersion, EgoError> {
     match self.version_get(version) {
       Some(mut app_version) => {
         match app_version.status {
-          AppVersionStatus::NEW | AppVersionStatus::REJECTED | AppVersionStatus::REVOKED => {
-            self.audit_version = Some(version.clone());
-            self.save();
+          AppVersionStatus::NEW | AppVersionStatus::REVOKED => {
+            app_version.source_check()?;
 
-            app_version.status = AppVersionStatus::SUBMITTED;
-            app_version.save();
+            app_version.audit_round = Some(app_version.audit_round.unwrap_or(0) + 1);
+            app_version.status_set(AppVersionStatus::SUBMITTED, actor_id);
 
+            self.audit_version_update();
             Ok(app_version)
           }
           _ => {
@@ -95,21 +192,18 @@ impl EgoDevApp {
     }
   }
 
-  pub fn version_revoke(&mut self, version: &Version) -> Result<AppVersion, EgoError> {
+  pub fn version_revoke(&mut self, actor_id: &Principal, version: &Version) -> Result<AppVersion, EgoError> {
     match self.version_get(version) {
       Some(mut app_version) => {
         match app_version.status {
           AppVersionStatus::SUBMITTED => {
-            self.audit_version = None;
-            self.save();
+            app_version.status_set(AppVersionStatus::REVOKED, actor_id);
 
-            app_version.status = AppVersionStatus::REVOKED;
-            app_version.save();
+            self.audit_version_update();
             Ok(app_version)
           }
           AppVersionStatus::RELEASED => {
-            app_version.status = AppVersionStatus::REVOKED;
-            app_version.save();
+            app_version.status_set(AppVersionStatus::REVOKED, actor_id);
             Ok(app_version)
           }
           _ => {
@@ -121,16 +215,37 @@ impl EgoDevApp {
     }
   }
 
-  pub fn version_release(&mut self, version: &Version) -> Result<AppVersion, EgoError> {
+  /// releases only move forward. a hotfix may go below the newest release,
+  /// but it still has to be greater than the releases of its own major.minor line.
+  /// such a hotfix leaves the current version alone, it is only released to its line
+  pub fn version_release(&mut self, actor_id: &Principal, version: &Version, hotfix: bool) -> Result<AppVersion, EgoError> {
     match self.version_get(version) {
       Some(mut app_version) => {
         match app_version.status {
           AppVersionStatus::APPROVED => {
-            self.app.current_version = version.clone();
-            self.save();
-
-            app_version.status = AppVersionStatus::RELEASED;
-            app_version.save();
+            let semver = app_version.semver();
+            let released_versions: Vec<AppVersion> = self.released_versions().into_iter().filter(|released| {
+              released.version != *version
+            }).collect();
+
+            let is_newest = released_versions.iter().all(|released| {
+              semver.cmp_precedence(&released.semver()) == Ordering::Greater
+            });
+            let line_increasing = released_versions.iter().filter(|released| {
+              released.version.major == version.major && released.version.minor == version.minor
+            }).all(|released| {
+              semver.cmp_precedence(&released.semver()) == Ordering::Greater
+            });
+            if !is_newest && !(hotfix && line_increasing) {
+              return Err(EgoDevErr::VersionNotIncreasing.into());
+            }
+
+            if is_newest {
+              self.app.current_version = version.clone();
+              self.save();
+            }
+
+            app_version.status_set(AppVersionStatus::RELEASED, actor_id);
             Ok(app_version)
           }
           _ => {
@@ -142,47 +257,106 @@ impl EgoDevApp {
     }
   }
 
-  pub fn version_approve(&mut self) -> Result<AppVersion, EgoError> {
-    if self.audit_version.is_none() {
+  /// an approval counts towards the quorum of the category, only the auditors on the panel of
+  /// the category may review, a single rejection sends the version back to the developer
+  pub fn version_review(&mut self, version: &Version, review: &AppAuditReview) -> Result<AppVersion, EgoError> {
+    let mut app_version = self.version_get(version).ok_or(EgoError::from(EgoDevErr::VersionNotExists))?;
+    if app_version.status != AppVersionStatus::SUBMITTED {
       return Err(EgoDevErr::OperationNotPermitted.into());
     }
 
-    match self.version_get(&self.audit_version.unwrap()) {
-      Some(mut app_version) => {
-        self.audit_version = None;
-        self.save();
+    if !AuditPolicy::is_panel_auditor(&self.app.category, &review.auditor_id) {
+      return Err(EgoDevErr::OperationNotPermitted.into());
+    }
 
-        app_version.status = AppVersionStatus::APPROVED;
-        app_version.save();
+    let round = app_version.audit_round.unwrap_or(0);
 
-        Ok(app_version)
+    match review.decision {
+      AuditDecision::APPROVE => {
+        if AuditPolicy::reproduction_required(&self.app.category) && review.reproduced != Some(true) {
+          return Err(EgoDevErr::ReproductionRequired.into());
+        }
+
+        AuditReview::record(&self.app.app_id, version, round, review);
+
+        // the approvals of auditors taken off the panel during the round no longer count
+        let approvals = AuditReview::by_round(&self.app.app_id, version, round).iter().filter(|audit_review| {
+          audit_review.decision == AuditDecision::APPROVE
+            && AuditPolicy::is_panel_auditor(&self.app.category, &audit_review.auditor_id)
+        }).count() as u32;
+
+        if approvals >= AuditPolicy::quorum(&self.app.category) {
+          app_version.status_set(AppVersionStatus::APPROVED, &review.auditor_id);
+        }
+      }
+      AuditDecision::REJECT => {
+        AuditReview::record(&self.app.app_id, version, round, review);
+
+        app_version.status_set(AppVersionStatus::REJECTED, &review.auditor_id);
       }
-      None => Err(EgoDevErr::VersionNotExists.into()),
     }
+
+    self.audit_version_update();
+    Ok(app_version)
   }
 
-  pub fn version_reject(&mut self) -> Result<AppVersion, EgoError> {
-    if self.audit_version.is_none() {
+  /// versions of apps created by the owner are built by the platform and skip the audit.
+  /// it is an override of the quorum and the reproduction checks, so it needs a reason and only
+  /// applies to versions no auditor has turned down
+  pub fn version_admin_approve(&mut self, actor_id: &Principal, version: &Version, reason: &str) -> Result<AppVersion, EgoError> {
+    if reason.trim().is_empty() || reason.len() > REASON_MAX_LEN {
       return Err(EgoDevErr::OperationNotPermitted.into());
     }
 
-    match self.version_get(&self.audit_version.unwrap()) {
+    match self.version_get(version) {
       Some(mut app_version) => {
-        self.audit_version = None;
-        self.save();
-
-        app_version.status = AppVersionStatus::REJECTED;
-        app_version.save();
-        Ok(app_version)
+        match app_version.status {
+          AppVersionStatus::NEW | AppVersionStatus::SUBMITTED => {
+            app_version.status_override(AppVersionStatus::APPROVED, actor_id, reason);
+            self.audit_version_update();
+            Ok(app_version)
+          }
+          _ => {
+            Err(EgoDevErr::OperationNotPermitted.into())
+          }
+        }
       }
       None => Err(EgoDevErr::VersionNotExists.into()),
     }
   }
 
+  /// the submitted versions, lowest version first
+  pub fn audit_versions(&self) -> Vec<AppVersion> {
+    let mut app_versions: Vec<AppVersion> = AppVersion::by_app_id(&self.app.app_id).into_iter().filter(|app_version| {
+      app_version.status == AppVersionStatus::SUBMITTED
+    }).collect();
+    app_versions.sort_by(|a, b| a.version.cmp(&b.version));
+    app_versions
+  }
+
+  // audit_version keeps pointing to the lowest submitted version for the single version apis
+  fn audit_version_update(&mut self) {
+    self.audit_version = self.audit_versions().first().map(|app_version| app_version.version);
+    self.save();
+  }
+
+  /// the reviews of the round that approved the version
+  pub fn version_audit_reviews(&self, app_version: &AppVersion) -> Vec<AuditReview> {
+    AuditReview::by_round(&self.app.app_id, &app_version.version, app_version.audit_round.unwrap_or(0))
+  }
+
   pub fn released_version(&self) -> Option<AppVersion> {
     self.version_get(&self.app.current_version)
   }
 
+  /// the released versions, plus the current one even when it was revoked since
+  pub fn released_versions(&self) -> Vec<AppVersion> {
+    AppVersion::by_app_id(&self.app.app_id).into_iter().filter(|app_version| {
+      app_version.status == AppVersionStatus::RELEASED
+        || (app_version.version == self.app.current_version && self.app.current_version != Version::min())
+    }).collect()
+  }
+
   pub fn list(start: usize, end: usize) -> Vec<EgoDevApp> {
     Self::iter(start, end, |(_, ego_dev_app)| Some(ego_dev_app))
   }
@@ -205,6 +379,26 @@ impl EgoDevApp {
     })
   }
 
+  pub fn by_user_id(user_id: &Principal) -> Vec<EgoDevApp> {
+    Self::iter(0, Self::len() as usize, |(_, ego_dev_app)| {
+      match ego_dev_app.role(user_id).is_some() {
+        true => { Some(ego_dev_app) }
+        false => { None }
+      }
+    })
+  }
+
+  /// users without any role do not see the app at all
+  pub fn by_role(user_id: &Principal, app_id: &AppId, role: AppRole) -> Result<EgoDevApp, EgoError> {
+    let ego_dev_app = EgoDevApp::get(app_id).ok_or(EgoError::from(EgoDevErr::AppNotExists))?;
+
+    match ego_dev_app.role(user_id) {
+      None => Err(EgoDevErr::AppNotExists.into()),
+      Some(user_role) if user_role < role => Err(EgoDevErr::UnAuthorized.into()),
+      Some(_) => Ok(ego_dev_app),
+    }
+  }
+
   pub fn by_developer_id_and_id(developer_id: &Principal, app_id: &AppId) -> Option<EgoDevApp> {
     match EgoDevApp::get(app_id) {
       None => { None }
@@ -0,0 +1,144 @@
+use std::borrow::Cow;
+use std::ops::Bound;
+
+use ic_stable_structures::{BoundedStorable, Storable};
+
+use ego_types::app::{AppId, Category, Version};
+
+use crate::types::ego_dev_app::APP_ID_MAX_LEN;
+
+const APP_ID_SIZE: usize = APP_ID_MAX_LEN + 1;
+
+/// length prefixed app id bytes, padded to a fixed size so the encoded keys sort like the structs.
+/// the app ids longer than APP_ID_MAX_LEN are refused when the app is created
+fn app_id_bytes(app_id: &AppId) -> [u8; APP_ID_SIZE] {
+  let bytes = app_id.as_bytes();
+  assert!(bytes.len() <= APP_ID_MAX_LEN, "app id {} too long for the index", app_id);
+  let mut fixed = [0u8; APP_ID_SIZE];
+  fixed[0] = bytes.len() as u8;
+  fixed[1..=bytes.len()].copy_from_slice(bytes);
+  fixed
+}
+
+fn category_byte(category: &Category) -> u8 {
+  match category {
+    Category::System => 0,
+    Category::Vault => 1,
+    Category::Finance => 2,
+    Category::Social => 3,
+    Category::Game => 4,
+    Category::Tool => 5,
+    Category::Media => 6,
+    Category::Other => 7,
+  }
+}
+
+fn byte_category(byte: u8) -> Category {
+  match byte {
+    0 => Category::System,
+    1 => Category::Vault,
+    2 => Category::Finance,
+    3 => Category::Social,
+    4 => Category::Game,
+    5 => Category::Tool,
+    6 => Category::Media,
+    _ => Category::Other,
+  }
+}
+
+/// key of the audit policies, one per category
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AuditPolicyKey {
+  category: u8,
+}
+
+impl AuditPolicyKey {
+  pub fn new(category: &Category) -> Self {
+    AuditPolicyKey {
+      category: category_byte(category),
+    }
+  }
+
+  pub fn category(&self) -> Category {
+    byte_category(self.category)
+  }
+}
+
+impl Storable for AuditPolicyKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(vec![self.category])
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    AuditPolicyKey { category: bytes[0] }
+  }
+}
+
+impl BoundedStorable for AuditPolicyKey {
+  const MAX_SIZE: u32 = 1;
+  const IS_FIXED_SIZE: bool = true;
+}
+
+/// index key of the audit reviews of an app version, grouped by round
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AuditReviewKey {
+  app_id: [u8; APP_ID_SIZE],
+  version: (u32, u32, u32),
+  round: u32,
+  id: u64,
+}
+
+impl AuditReviewKey {
+  pub fn new(app_id: &AppId, version: &Version, round: u32, id: u64) -> Self {
+    AuditReviewKey {
+      app_id: app_id_bytes(app_id),
+      version: (version.major, version.minor, version.patch),
+      round,
+      id,
+    }
+  }
+
+  pub fn id(&self) -> u64 {
+    self.id
+  }
+
+  /// keys of one app version, optionally limited to a single round
+  pub fn range(app_id: &AppId, version: &Version, round: Option<u32>) -> (Bound<Self>, Bound<Self>) {
+    let (start, end) = match round {
+      Some(round) => (round, round),
+      None => (0, u32::MAX),
+    };
+    (
+      Bound::Included(AuditReviewKey::new(app_id, version, start, 0)),
+      Bound::Included(AuditReviewKey::new(app_id, version, end, u64::MAX)),
+    )
+  }
+}
+
+impl Storable for AuditReviewKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    let mut bytes = Vec::with_capacity(APP_ID_SIZE + 24);
+    bytes.extend_from_slice(&self.app_id);
+    bytes.extend_from_slice(&self.version.0.to_be_bytes());
+    bytes.extend_from_slice(&self.version.1.to_be_bytes());
+    bytes.extend_from_slice(&self.version.2.to_be_bytes());
+    bytes.extend_from_slice(&self.round.to_be_bytes());
+    bytes.extend_from_slice(&self.id.to_be_bytes());
+    Cow::Owned(bytes)
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    let mut app_id = [0u8; APP_ID_SIZE];
+    app_id.copy_from_slice(&bytes[0..APP_ID_SIZE]);
+    let u32_at = |at: usize| u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap());
+    let version = (u32_at(APP_ID_SIZE), u32_at(APP_ID_SIZE + 4), u32_at(APP_ID_SIZE + 8));
+    let round = u32_at(APP_ID_SIZE + 12);
+    let id = u64::from_be_bytes(bytes[APP_ID_SIZE + 16..APP_ID_SIZE + 24].try_into().unwrap());
+    AuditReviewKey { app_id, version, round, id }
+  }
+}
+
+impl BoundedStorable for AuditReviewKey {
+  const MAX_SIZE: u32 = APP_ID_SIZE as u32 + 24;
+  const IS_FIXED_SIZE: bool = true;
+}
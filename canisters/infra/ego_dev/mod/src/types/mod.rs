@@ -6,15 +6,28 @@ use ego_types::app::{EgoError};
 use ego_types::app::{AppId, Category, FileId};
 use ego_types::app::Version;
 
-use crate::types::app_version::{AppVersion};
+use crate::types::app_collaborator::AppRole;
+use crate::types::app_version::{AppVersion, AppVersionSource};
+use crate::types::delegate::DelegateScope;
 use crate::types::ego_dev_app::EgoDevApp;
 
 pub mod stable_state;
 pub mod file;
 pub mod app_key;
+pub mod index_key;
 pub mod ego_dev_app;
 pub mod developer;
 pub mod app_version;
+pub mod app_version_transition;
+pub mod app_metadata;
+pub mod audit_policy;
+pub mod audit_review;
+pub mod team;
+pub mod app_collaborator;
+pub mod delegate;
+pub mod delegate_action;
+pub mod developer_profile;
+pub mod app_transfer;
 
 #[derive(CandidType, Deserialize, Serialize)]
 pub enum EgoDevErr {
@@ -30,6 +43,22 @@ pub enum EgoDevErr {
   UserNotExists,
   OperationNotPermitted,
   EgoFileAlreadyAdded,
+  MetadataInvalid,
+  AuditReviewInvalid,
+  SourceInvalid,
+  SourceMissing,
+  ReproductionRequired,
+  VersionInvalid,
+  VersionNotIncreasing,
+  TeamNotExists,
+  TeamMemberNotExists,
+  TeamInvalid,
+  DelegateInvalid,
+  DelegateNotExists,
+  ProfileInvalid,
+  AppTransferNotExists,
+  AppTransferExpired,
+  AppIdInvalid,
   SystemError(String),
 }
 
@@ -53,6 +82,26 @@ impl From<EgoDevErr> for EgoError {
       EgoDevErr::EgoFileAlreadyAdded => {
         EgoError::new(1014, "ego-dev: ego file canister already added")
       }
+      EgoDevErr::MetadataInvalid => EgoError::new(1015, "ego-dev: app metadata invalid"),
+      EgoDevErr::AuditReviewInvalid => EgoError::new(1016, "ego-dev: audit review invalid"),
+      EgoDevErr::SourceInvalid => EgoError::new(1017, "ego-dev: app version source invalid"),
+      EgoDevErr::SourceMissing => EgoError::new(1018, "ego-dev: app version source missing"),
+      EgoDevErr::ReproductionRequired => {
+        EgoError::new(1019, "ego-dev: reproduced build attestation required")
+      }
+      EgoDevErr::VersionInvalid => EgoError::new(1020, "ego-dev: version invalid"),
+      EgoDevErr::VersionNotIncreasing => {
+        EgoError::new(1021, "ego-dev: version must be greater than the released ones")
+      }
+      EgoDevErr::TeamNotExists => EgoError::new(1022, "ego-dev: team not exists"),
+      EgoDevErr::TeamMemberNotExists => EgoError::new(1023, "ego-dev: team member not exists"),
+      EgoDevErr::TeamInvalid => EgoError::new(1024, "ego-dev: team invalid"),
+      EgoDevErr::DelegateInvalid => EgoError::new(1025, "ego-dev: delegate invalid"),
+      EgoDevErr::DelegateNotExists => EgoError::new(1026, "ego-dev: delegate not exists"),
+      EgoDevErr::ProfileInvalid => EgoError::new(1027, "ego-dev: developer profile invalid"),
+      EgoDevErr::AppTransferNotExists => EgoError::new(1028, "ego-dev: app transfer not exists"),
+      EgoDevErr::AppTransferExpired => EgoError::new(1029, "ego-dev: app transfer expired"),
+      EgoDevErr::AppIdInvalid => EgoError::new(1030, "ego-dev: app id empty or too long"),
       EgoDevErr::SystemError(msg) => msg.into(),
     }
   }
@@ -87,6 +136,15 @@ pub struct AppVersionSetFrontendAddressRequest {
 pub struct AppVersionReleaseRequest {
   pub app_id: AppId,
   pub version: Version,
+  // a fix on an older major.minor line, it only has to be greater than the releases of that line
+  pub hotfix: bool,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppVersionNewRequest {
+  pub app_id: AppId,
+  // full semver, like 1.2.0-beta.1+build.5
+  pub version: String,
 }
 
 #[derive(CandidType, Deserialize, Serialize)]
@@ -94,12 +152,71 @@ pub struct AppVersionReleaseResponse {
   pub app_version: AppVersion,
 }
 
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppMetadataSetRequest {
+  pub app_id: AppId,
+  pub tags: Vec<String>,
+  pub screenshots: Vec<String>,
+  pub media: Vec<String>,
+  pub homepage: Option<String>,
+  pub support_link: Option<String>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppVersionReleaseNoteSetRequest {
+  pub app_id: AppId,
+  pub version: Version,
+  pub notes: String,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppReviewReplyRequest {
+  pub app_id: AppId,
+  pub wallet_id: Principal,
+  pub reply: String,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppReviewHideRequest {
+  pub app_id: AppId,
+  pub wallet_id: Principal,
+  pub hidden: bool,
+}
+
 #[derive(CandidType, Deserialize, Serialize)]
 pub struct AppVersionApproveRequest {
   pub app_id: AppId,
   pub version: Version,
 }
 
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppVersionReviewRequest {
+  pub app_id: AppId,
+  // the lowest submitted version when not given
+  pub version: Option<Version>,
+  pub approve: bool,
+  pub comment: String,
+  pub report: Option<String>,
+  // whether the auditor rebuilt the wasm from the declared source and got the same sha256
+  pub reproduced: Option<bool>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AuditPolicySetRequest {
+  pub category: Category,
+  pub quorum: u32,
+  // the panel of auditors of the category, the quorum is counted among them
+  pub auditors: Option<Vec<Principal>>,
+  pub reproduction_required: bool,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppVersionSourceSetRequest {
+  pub app_id: AppId,
+  pub version: Version,
+  pub source: AppVersionSource,
+}
+
 #[derive(CandidType, Deserialize, Serialize)]
 pub struct AppVersionApproveResponse {
   pub app_version: AppVersion,
@@ -123,6 +240,25 @@ pub struct DeveloperMainGetResponse {
   pub developer: Developer,
 }
 
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct UserVerifiedSetRequest {
+  pub user_id: Principal,
+  pub verified: bool,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct DeveloperProfileSetRequest {
+  pub website: Option<String>,
+  pub contact: Option<String>,
+  pub avatar: Option<String>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppTransferRequest {
+  pub app_id: AppId,
+  pub to_id: Principal,
+}
+
 #[derive(CandidType, Deserialize, Serialize)]
 pub struct UserRoleSetRequest {
   pub user_id: Principal,
@@ -151,3 +287,37 @@ pub struct AdminAppCreateBackendRequest {
   pub backend_data: Vec<u8>,
   pub backend_data_hash: String,
 }
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct TeamMemberRequest {
+  pub team_id: u64,
+  pub user_id: Principal,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct TeamAppTransferRequest {
+  pub app_id: AppId,
+  // None moves the app out of its team, to the caller
+  pub team_id: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppCollaboratorSetRequest {
+  pub app_id: AppId,
+  pub user_id: Principal,
+  pub role: AppRole,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppCollaboratorRemoveRequest {
+  pub app_id: AppId,
+  pub user_id: Principal,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct DelegateSetRequest {
+  pub delegate_id: Principal,
+  pub app_ids: Vec<AppId>,
+  pub scope: DelegateScope,
+  pub expires_at: u64,
+}
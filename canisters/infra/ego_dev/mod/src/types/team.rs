@@ -0,0 +1,175 @@
+use std::borrow::Cow;
+
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::EgoError;
+use ego_utils::util::time;
+
+use crate::memory::TEAMS;
+use crate::state::SEQ;
+use crate::types::EgoDevErr;
+
+pub const TEAM_NAME_MAX_LEN: usize = 64;
+pub const TEAM_MEMBER_MAX: usize = 50;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TeamMemberStatus {
+  INVITED,
+  JOINED,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct TeamMember {
+  pub user_id: Principal,
+  pub status: TeamMemberStatus,
+  pub created_at: u64,
+}
+
+/// a group of developers sharing apps, the owner manages members and apps
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Team {
+  pub id: u64,
+  pub name: String,
+  pub owner_id: Principal,
+  pub members: Vec<TeamMember>,
+  pub last_update: u64,    // second
+}
+
+impl Team {
+  pub fn new(owner_id: &Principal, name: &str) -> Self {
+    let next_id = SEQ.with(|cell| cell.borrow_mut().next_number("team", 0));
+    Team {
+      id: next_id,
+      name: name.to_string(),
+      owner_id: *owner_id,
+      members: vec![],
+      last_update: 0,
+    }
+  }
+
+  pub fn is_owner(&self, user_id: &Principal) -> bool {
+    self.owner_id == *user_id
+  }
+
+  /// the owner and the members who accepted the invitation
+  pub fn is_member(&self, user_id: &Principal) -> bool {
+    self.is_owner(user_id) || self.members.iter().any(|member| {
+      member.user_id == *user_id && member.status == TeamMemberStatus::JOINED
+    })
+  }
+
+  pub fn member_invite(&mut self, user_id: &Principal) -> Result<(), EgoError> {
+    if self.is_owner(user_id) || self.members.iter().any(|member| member.user_id == *user_id) {
+      return Err(EgoDevErr::UserExists.into());
+    }
+    if self.members.len() >= TEAM_MEMBER_MAX {
+      return Err(EgoDevErr::TeamInvalid.into());
+    }
+
+    self.members.push(TeamMember {
+      user_id: *user_id,
+      status: TeamMemberStatus::INVITED,
+      created_at: time(),
+    });
+    self.save();
+    Ok(())
+  }
+
+  pub fn member_join(&mut self, user_id: &Principal) -> Result<(), EgoError> {
+    match self.members.iter_mut().find(|member| {
+      member.user_id == *user_id && member.status == TeamMemberStatus::INVITED
+    }) {
+      None => Err(EgoDevErr::TeamMemberNotExists.into()),
+      Some(member) => {
+        member.status = TeamMemberStatus::JOINED;
+        self.save();
+        Ok(())
+      }
+    }
+  }
+
+  pub fn member_remove(&mut self, user_id: &Principal) -> Result<(), EgoError> {
+    match self.members.iter().position(|member| member.user_id == *user_id) {
+      None => Err(EgoDevErr::TeamMemberNotExists.into()),
+      Some(index) => {
+        self.members.remove(index);
+        self.save();
+        Ok(())
+      }
+    }
+  }
+
+  pub fn len() -> u64 {
+    TEAMS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Team> {
+    Self::iter(start, end, |(_, team)| Some(team))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Team> {
+    Self::iter(start, end, |(_, team)| {
+      match team.last_update >= last_update {
+        true => { Some(team) }
+        false => { None }
+      }
+    })
+  }
+
+  /// the teams a user owns, joined or is invited to
+  pub fn by_user_id(user_id: &Principal) -> Vec<Team> {
+    Self::iter(0, Self::len() as usize, |(_, team)| {
+      match team.is_owner(user_id) || team.members.iter().any(|member| member.user_id == *user_id) {
+        true => { Some(team) }
+        false => { None }
+      }
+    })
+  }
+
+  pub fn get(id: &u64) -> Option<Team> {
+    TEAMS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(id)
+    })
+  }
+
+  pub fn save(&mut self) {
+    TEAMS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(self.id, self.clone());
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((u64, Self)) -> Option<Self>,
+  {
+    TEAMS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for Team {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for Team {
+  const MAX_SIZE: u32 = 8192;
+  const IS_FIXED_SIZE: bool = false;
+}
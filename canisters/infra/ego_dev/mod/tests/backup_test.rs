@@ -62,7 +62,7 @@ fn test_job_list() {
   set_up();
 
   let jobs = job_list();
-  assert_eq!(5, jobs.len());
+  assert_eq!(15, jobs.len());
 
   assert_eq!("config", jobs.get(0).unwrap().name);
   assert_eq!(1, jobs.get(0).unwrap().amount);
@@ -78,6 +78,36 @@ fn test_job_list() {
 
   assert_eq!("app_versions", jobs.get(4).unwrap().name);
   assert_eq!(1, jobs.get(4).unwrap().amount);
+
+  assert_eq!("app_metadatas", jobs.get(5).unwrap().name);
+  assert_eq!(0, jobs.get(5).unwrap().amount);
+
+  assert_eq!("audit_policies", jobs.get(6).unwrap().name);
+  assert_eq!(0, jobs.get(6).unwrap().amount);
+
+  assert_eq!("audit_reviews", jobs.get(7).unwrap().name);
+  assert_eq!(0, jobs.get(7).unwrap().amount);
+
+  assert_eq!("app_version_transitions", jobs.get(8).unwrap().name);
+  assert_eq!(0, jobs.get(8).unwrap().amount);
+
+  assert_eq!("teams", jobs.get(9).unwrap().name);
+  assert_eq!(0, jobs.get(9).unwrap().amount);
+
+  assert_eq!("app_collaborators", jobs.get(10).unwrap().name);
+  assert_eq!(0, jobs.get(10).unwrap().amount);
+
+  assert_eq!("delegates", jobs.get(11).unwrap().name);
+  assert_eq!(0, jobs.get(11).unwrap().amount);
+
+  assert_eq!("delegate_actions", jobs.get(12).unwrap().name);
+  assert_eq!(0, jobs.get(12).unwrap().amount);
+
+  assert_eq!("developer_profiles", jobs.get(13).unwrap().name);
+  assert_eq!(0, jobs.get(13).unwrap().amount);
+
+  assert_eq!("app_transfers", jobs.get(14).unwrap().name);
+  assert_eq!(0, jobs.get(14).unwrap().amount);
 }
 
 #[test]
@@ -1,10 +1,14 @@
+use std::str::FromStr;
+
 use candid::Principal;
 
-use ego_dev_mod::types::app_version::AppVersion;
+use ego_dev_mod::types::app_version::{AppVersion, AppVersionSource};
+use ego_dev_mod::types::app_version_transition::AppVersionTransition;
 use ego_dev_mod::types::app_version::AppVersionStatus::{APPROVED, NEW, REJECTED, RELEASED, REVOKED, SUBMITTED};
 use ego_dev_mod::types::ego_dev_app::EgoDevApp;
 use ego_dev_mod::types::EgoDevErr;
-use ego_types::app::{Category, EgoError, Version};
+use ego_types::app::{AppAuditReview, AuditDecision, Category, EgoError, Version};
+use ego_types::semver::SemVer;
 use ego_utils::util::time;
 
 static DEVELOPER_ID1: &str = "23vqh-waaaa-aaaai-qhcya-cai";
@@ -53,6 +57,26 @@ pub fn set_up() {
   ego_dev_app.version_new(&file_id, &version).unwrap();
 }
 
+fn app_version_source() -> AppVersionSource {
+  AppVersionSource {
+    repo_url: "https://github.com/example/app".to_string(),
+    commit: "0123456".to_string(),
+    toolchain: "rust 1.68.0".to_string(),
+    sha256: "0".repeat(64),
+  }
+}
+
+fn audit_review(decision: AuditDecision) -> AppAuditReview {
+  AppAuditReview {
+    auditor_id: Principal::from_text(DEVELOPER_ID2.to_string()).unwrap(),
+    decision,
+    comment: "".to_string(),
+    report: None,
+    created_at: 0,
+    reproduced: None,
+  }
+}
+
 #[test]
 pub fn new() {
   set_up();
@@ -162,6 +186,56 @@ pub fn version_new() {
   assert_eq!(3, AppVersion::len());
 }
 
+#[test]
+pub fn version_new_semver() {
+  set_up();
+
+  let file_id = Principal::from_text(FILE_ID1.to_string()).unwrap();
+  let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+
+  // 0.0.0 is not a version
+  let result = ego_dev_app.version_new(&file_id, &Version::new(0, 0, 0));
+  assert_eq!(EgoError::from(EgoDevErr::VersionInvalid), result.unwrap_err());
+
+  // pre-release and build labels
+  let semver = SemVer::from_str("1.1.0-beta.1+build.5").unwrap();
+  let app_version = ego_dev_app.version_new_semver(&file_id, &semver).unwrap();
+  assert_eq!(Version::new(1, 1, 0), app_version.version);
+  assert_eq!(Some("beta.1".to_string()), app_version.pre_release);
+  assert_eq!(Some("build.5".to_string()), app_version.build);
+  assert_eq!("1.1.0-beta.1+build.5", app_version.semver().to_string());
+
+  // the labels do not make a new version
+  let semver = SemVer::from_str("1.1.0").unwrap();
+  let result = ego_dev_app.version_new_semver(&file_id, &semver);
+  assert_eq!(EgoError::from(EgoDevErr::VersionExists), result.unwrap_err());
+}
+
+#[test]
+pub fn version_promote() {
+  set_up();
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
+  let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+
+  // not a pre-release
+  let version = version_approved(&mut ego_dev_app, "1.0.2");
+  let result = ego_dev_app.version_promote(&developer_id, &version);
+  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), result.unwrap_err());
+
+  // the released pre-release goes back to draft as the final version
+  let version = version_approved(&mut ego_dev_app, "1.1.0-beta.1");
+  assert!(ego_dev_app.version_release(&developer_id, &version, false).is_ok());
+
+  let app_version = ego_dev_app.version_promote(&developer_id, &version).unwrap();
+  assert_eq!(None, app_version.pre_release);
+  assert_eq!(NEW, app_version.status);
+  assert_eq!("1.1.0", ego_dev_app.version_get(&version).unwrap().semver().to_string());
+
+  // and is released again after the audit
+  let version = version_approved(&mut ego_dev_app, "1.1.0");
+  assert!(ego_dev_app.version_release(&developer_id, &version, false).is_ok());
+}
+
 
 #[test]
 pub fn version_get() {
@@ -192,6 +266,7 @@ pub fn version_get() {
 #[test]
 pub fn version_submit() {
   set_up();
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
 
   // exists version
   let version = Version {
@@ -204,12 +279,18 @@ pub fn version_submit() {
   let ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
   assert_eq!(None, ego_dev_app.audit_version);
 
-  let app_version = AppVersion::get_by_app_id_and_version(&EXISTS_APP_ID.to_string(), &version).unwrap();
+  let mut app_version = AppVersion::get_by_app_id_and_version(&EXISTS_APP_ID.to_string(), &version).unwrap();
   assert_eq!(NEW, app_version.status);
 
-  // submit
+  // submit without source
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
-  let result = ego_dev_app.version_submit(&version);
+  let result = ego_dev_app.version_submit(&developer_id, &version);
+  assert_eq!(EgoError::from(EgoDevErr::SourceMissing), result.unwrap_err());
+
+  app_version.source_set(app_version_source()).unwrap();
+
+  // submit
+  let result = ego_dev_app.version_submit(&developer_id, &version);
   assert!(result.is_ok());
 
   // check data
@@ -223,6 +304,7 @@ pub fn version_submit() {
 #[test]
 pub fn version_submit_failed_with_not_exists_version() {
   set_up();
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
 
   // not exists version
   let version = Version {
@@ -240,7 +322,7 @@ pub fn version_submit_failed_with_not_exists_version() {
 
   // submit
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
-  let result = ego_dev_app.version_submit(&version);
+  let result = ego_dev_app.version_submit(&developer_id, &version);
   assert!(result.is_err());
   assert_eq!(None, ego_dev_app.audit_version);
 
@@ -253,9 +335,11 @@ pub fn version_submit_failed_with_not_exists_version() {
 }
 
 #[test]
-pub fn version_submit_failed_with_submitted_version() {
+pub fn version_submit_with_submitted_version() {
   set_up();
 
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
+
   // exists version
   let version1 = Version {
     major: 1,
@@ -272,31 +356,140 @@ pub fn version_submit_failed_with_submitted_version() {
   app_version.status = SUBMITTED;
   app_version.save();
 
-  // submit
+  // submit another version
   let version2 = Version {
     major: 1,
     minor: 0,
     patch: 2,
   };
+  let mut app_version = AppVersion::get_by_app_id_and_version(&EXISTS_APP_ID.to_string(), &version2).unwrap();
+  app_version.source_set(app_version_source()).unwrap();
+
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
-  let result = ego_dev_app.version_submit(&version2);
-  assert!(result.is_err());
+  let result = ego_dev_app.version_submit(&developer_id, &version2);
+  assert!(result.is_ok());
 
-  // check data
+  // check data, both versions are under audit
   let ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
   assert_eq!(version1, ego_dev_app.audit_version.unwrap());
+  assert_eq!(2, ego_dev_app.audit_versions().len());
 
   let app_version = AppVersion::get_by_app_id_and_version(&EXISTS_APP_ID.to_string(), &version1).unwrap();
   assert_eq!(SUBMITTED, app_version.status);
 
   let app_version = AppVersion::get_by_app_id_and_version(&EXISTS_APP_ID.to_string(), &version2).unwrap();
-  assert_eq!(NEW, app_version.status);
+  assert_eq!(SUBMITTED, app_version.status);
+
+  // rejecting the lower version moves the audit version to the next one
+  let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+  let result = ego_dev_app.version_review(&version1, &audit_review(AuditDecision::REJECT));
+  assert!(result.is_ok());
+
+  let ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+  assert_eq!(version2, ego_dev_app.audit_version.unwrap());
 }
 
+#[test]
+pub fn version_delete() {
+  set_up();
+
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
+  let version = Version {
+    major: 1,
+    minor: 0,
+    patch: 1,
+  };
+
+  let mut app_version = AppVersion::get_by_app_id_and_version(&EXISTS_APP_ID.to_string(), &version).unwrap();
+  app_version.source_set(app_version_source()).unwrap();
+
+  // submitted version can not be deleted
+  let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+  ego_dev_app.version_submit(&developer_id, &version).unwrap();
+  let result = ego_dev_app.version_delete(&developer_id, &version);
+  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), result.unwrap_err());
+
+  // draft can be deleted
+  let version2 = Version {
+    major: 1,
+    minor: 0,
+    patch: 2,
+  };
+  let result = ego_dev_app.version_delete(&developer_id, &version2);
+  assert!(result.is_ok());
+  assert!(AppVersion::get_by_app_id_and_version(&EXISTS_APP_ID.to_string(), &version2).is_none());
+
+  let result = ego_dev_app.version_delete(&developer_id, &version2);
+  assert_eq!(EgoError::from(EgoDevErr::VersionNotExists), result.unwrap_err());
+}
+
+#[test]
+pub fn version_transitions() {
+  set_up();
+
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
+  let auditor_id = Principal::from_text(DEVELOPER_ID2.to_string()).unwrap();
+  let version = Version {
+    major: 1,
+    minor: 0,
+    patch: 1,
+  };
+
+  let mut app_version = AppVersion::get_by_app_id_and_version(&EXISTS_APP_ID.to_string(), &version).unwrap();
+  app_version.source_set(app_version_source()).unwrap();
+
+  let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+  ego_dev_app.version_submit(&developer_id, &version).unwrap();
+  ego_dev_app.version_review(&version, &audit_review(AuditDecision::REJECT)).unwrap();
+
+  // a rejected version is not submitted again as is
+  let result = ego_dev_app.version_submit(&developer_id, &version);
+  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), result.unwrap_err());
+
+  // nor with the wasm that was rejected
+  let mut app_version = AppVersion::get_by_app_id_and_version(&EXISTS_APP_ID.to_string(), &version).unwrap();
+  app_version.wasm_sha256_set("1".repeat(64));
+  let result = app_version.wasm_upload_check(&"1".repeat(64));
+  assert_eq!(EgoError::from(EgoDevErr::WasmExists), result.unwrap_err());
+  assert_eq!(REJECTED, app_version.status);
+
+  // re-upload after rejection turns the version back into a draft
+  app_version.wasm_upload_check(&"2".repeat(64)).unwrap();
+  app_version.wasm_uploaded(&developer_id, "2".repeat(64));
+  assert_eq!(NEW, app_version.status);
+  assert_eq!(Some("2".repeat(64)), app_version.wasm_sha256);
+
+  let transitions = AppVersionTransition::by_app_id_and_version(&EXISTS_APP_ID.to_string(), &version);
+  assert_eq!(3, transitions.len());
+
+  assert_eq!(NEW, transitions.get(0).unwrap().from);
+  assert_eq!(SUBMITTED, transitions.get(0).unwrap().to);
+  assert_eq!(developer_id, transitions.get(0).unwrap().actor_id);
+
+  assert_eq!(SUBMITTED, transitions.get(1).unwrap().from);
+  assert_eq!(REJECTED, transitions.get(1).unwrap().to);
+  assert_eq!(auditor_id, transitions.get(1).unwrap().actor_id);
+
+  assert_eq!(REJECTED, transitions.get(2).unwrap().from);
+  assert_eq!(NEW, transitions.get(2).unwrap().to);
+
+  // the wasm of a submitted version can not be replaced
+  let mut app_version = AppVersion::get_by_app_id_and_version(&EXISTS_APP_ID.to_string(), &version).unwrap();
+  app_version.status_set(SUBMITTED, &developer_id);
+  let result = app_version.wasm_upload_check(&"3".repeat(64));
+  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), result.unwrap_err());
+
+  // the one of a revoked version can, the version stays revoked
+  app_version.status_set(REVOKED, &developer_id);
+  app_version.wasm_upload_check(&"3".repeat(64)).unwrap();
+  app_version.wasm_uploaded(&developer_id, "3".repeat(64));
+  assert_eq!(REVOKED, app_version.status);
+}
 
 #[test]
 pub fn version_revoke_submitted_version() {
   set_up();
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
 
   // exists version
   let version = Version {
@@ -316,7 +509,7 @@ pub fn version_revoke_submitted_version() {
 
   // revoke
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
-  let result = ego_dev_app.version_revoke(&version);
+  let result = ego_dev_app.version_revoke(&developer_id, &version);
   assert!(result.is_ok());
 
   // check data
@@ -330,6 +523,7 @@ pub fn version_revoke_submitted_version() {
 #[test]
 pub fn version_revoke_released_version() {
   set_up();
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
 
   // exists version
   let version1 = Version {
@@ -359,7 +553,7 @@ pub fn version_revoke_released_version() {
 
   // revoke
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
-  let result = ego_dev_app.version_revoke(&version2);
+  let result = ego_dev_app.version_revoke(&developer_id, &version2);
   assert!(result.is_ok());
 
   // check data
@@ -376,6 +570,7 @@ pub fn version_revoke_released_version() {
 #[test]
 pub fn version_revoke_failed_not_exists_version() {
   set_up();
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
 
   // not exists version
   let version = Version {
@@ -392,13 +587,14 @@ pub fn version_revoke_failed_not_exists_version() {
   assert_eq!(None, app_version);
 
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
-  let result = ego_dev_app.version_revoke(&version);
+  let result = ego_dev_app.version_revoke(&developer_id, &version);
   assert!(result.is_err());
 }
 
 #[test]
 pub fn version_revoke_failed_wrong_status() {
   set_up();
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
 
   // not exists version
   let version = Version {
@@ -413,13 +609,14 @@ pub fn version_revoke_failed_wrong_status() {
   app_version.save();
 
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
-  let result = ego_dev_app.version_revoke(&version);
+  let result = ego_dev_app.version_revoke(&developer_id, &version);
   assert!(result.is_err());
 }
 
 #[test]
 pub fn version_release() {
   set_up();
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
 
   // exists version
   let version = Version {
@@ -435,7 +632,7 @@ pub fn version_release() {
 
   // release
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
-  let result = ego_dev_app.version_release(&version);
+  let result = ego_dev_app.version_release(&developer_id, &version, false);
   assert!(result.is_ok());
 
   // check data
@@ -449,6 +646,7 @@ pub fn version_release() {
 #[test]
 pub fn version_release_failed_with_wrong_status() {
   set_up();
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
 
   // exists version
   let version = Version {
@@ -464,7 +662,7 @@ pub fn version_release_failed_with_wrong_status() {
 
   // release
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
-  let result = ego_dev_app.version_release(&version);
+  let result = ego_dev_app.version_release(&developer_id, &version, false);
   assert!(result.is_err());
 
   // check data
@@ -475,6 +673,68 @@ pub fn version_release_failed_with_wrong_status() {
   assert_eq!(REJECTED, app_version.status);
 }
 
+fn version_approved(ego_dev_app: &mut EgoDevApp, version: &str) -> Version {
+  let file_id = Principal::from_text(FILE_ID1.to_string()).unwrap();
+  let semver = SemVer::from_str(version).unwrap();
+
+  let mut app_version = match ego_dev_app.version_get(&semver.core()) {
+    Some(app_version) => app_version,
+    None => ego_dev_app.version_new_semver(&file_id, &semver).unwrap(),
+  };
+  app_version.status = APPROVED;
+  app_version.save();
+  app_version.version
+}
+
+#[test]
+pub fn version_release_only_forward() {
+  set_up();
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
+  let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+
+  let version = version_approved(&mut ego_dev_app, "1.0.2");
+  assert!(ego_dev_app.version_release(&developer_id, &version, false).is_ok());
+
+  // lower than the released one
+  let version = version_approved(&mut ego_dev_app, "1.0.1");
+  let result = ego_dev_app.version_release(&developer_id, &version, false);
+  assert_eq!(EgoError::from(EgoDevErr::VersionNotIncreasing), result.unwrap_err());
+
+  // a pre-release of a greater version
+  let version = version_approved(&mut ego_dev_app, "1.1.0-beta.1");
+  assert!(ego_dev_app.version_release(&developer_id, &version, false).is_ok());
+
+  let ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+  assert_eq!(Version::new(1, 1, 0), ego_dev_app.app.current_version);
+}
+
+#[test]
+pub fn version_release_hotfix() {
+  set_up();
+  let developer_id = Principal::from_text(DEVELOPER_ID1.to_string()).unwrap();
+  let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+
+  let version = version_approved(&mut ego_dev_app, "1.0.2");
+  assert!(ego_dev_app.version_release(&developer_id, &version, false).is_ok());
+
+  // 1.0.2 is already released on the 1.0 line
+  let version = version_approved(&mut ego_dev_app, "1.0.1");
+  let result = ego_dev_app.version_release(&developer_id, &version, true);
+  assert_eq!(EgoError::from(EgoDevErr::VersionNotIncreasing), result.unwrap_err());
+
+  // nothing is released on the 0.9 line yet
+  let version = version_approved(&mut ego_dev_app, "0.9.5");
+  let result = ego_dev_app.version_release(&developer_id, &version, false);
+  assert_eq!(EgoError::from(EgoDevErr::VersionNotIncreasing), result.unwrap_err());
+
+  assert!(ego_dev_app.version_release(&developer_id, &version, true).is_ok());
+
+  // the hotfix does not move the current version
+  let ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+  assert_eq!(Version::new(1, 0, 2), ego_dev_app.app.current_version);
+  assert_eq!(2, ego_dev_app.released_versions().len());
+}
+
 #[test]
 pub fn version_approve() {
   set_up();
@@ -497,7 +757,7 @@ pub fn version_approve() {
 
   // approve
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
-  let result = ego_dev_app.version_approve();
+  let result = ego_dev_app.version_review(&version, &audit_review(AuditDecision::APPROVE));
   assert!(result.is_ok());
 
   // check data
@@ -515,8 +775,13 @@ pub fn version_approve_failed() {
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
   assert_eq!(None, ego_dev_app.audit_version);
 
-  // not the audit version
-  let result = ego_dev_app.version_approve();
+  // not a submitted version
+  let version = Version {
+    major: 1,
+    minor: 0,
+    patch: 1,
+  };
+  let result = ego_dev_app.version_review(&version, &audit_review(AuditDecision::APPROVE));
   assert!(result.is_err());
 }
 
@@ -542,7 +807,7 @@ pub fn version_reject() {
 
   // approve
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
-  let result = ego_dev_app.version_reject();
+  let result = ego_dev_app.version_review(&version, &audit_review(AuditDecision::REJECT));
   assert!(result.is_ok());
 
   // check data
@@ -560,8 +825,13 @@ pub fn version_reject_failed() {
   let mut ego_dev_app = EgoDevApp::get(&EXISTS_APP_ID.to_string()).unwrap();
   assert_eq!(None, ego_dev_app.audit_version);
 
-  // not the audit version
-  let result = ego_dev_app.version_reject();
+  // not a submitted version
+  let version = Version {
+    major: 1,
+    minor: 0,
+    patch: 1,
+  };
+  let result = ego_dev_app.version_review(&version, &audit_review(AuditDecision::REJECT));
   assert!(result.is_err());
 }
 
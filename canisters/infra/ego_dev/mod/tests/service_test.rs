@@ -2,19 +2,26 @@ use async_trait::async_trait;
 use candid::Principal;
 use mockall::mock;
 
+use ego_dev_mod::c2c::c2c_types::{AppStatsRequest, AppStatsResponse};
 use ego_dev_mod::c2c::ego_file::TEgoFile;
 use ego_dev_mod::c2c::ego_store::TEgoStore;
 use ego_dev_mod::service::EgoDevService;
-use ego_dev_mod::types::app_version::{AppVersion, AppVersionStatus};
+use ego_dev_mod::types::app_collaborator::{AppCollaborator, AppRole};
+use ego_dev_mod::types::app_transfer::AppTransfer;
+use ego_dev_mod::types::app_version::{AppVersion, AppVersionSource, AppVersionStatus};
+use ego_dev_mod::types::delegate::DelegateScope;
+use ego_dev_mod::types::delegate_action::DelegateAction;
 use ego_dev_mod::types::developer::Developer;
-use ego_dev_mod::types::ego_dev_app::EgoDevApp;
-use ego_dev_mod::types::EgoDevErr;
+use ego_dev_mod::types::ego_dev_app::{APP_ID_MAX_LEN, EgoDevApp};
+use ego_dev_mod::types::{AppMetadataSetRequest, DelegateSetRequest, DeveloperProfileSetRequest, EgoDevErr};
+use ego_dev_mod::types::app_metadata::{EgoDevAppMetadata, RELEASE_NOTE_MAX_COUNT};
 use ego_dev_mod::types::file::File;
-use ego_types::app::{App, Wasm};
+use ego_dev_mod::types::team::Team;
+use ego_types::app::{App, AppAuditReview, AppId, AppMetadata, AuditDecision, Wasm};
 use ego_types::app::{CanisterType, Category};
 use ego_types::app::EgoError;
 use ego_types::app::Version;
-use ego_utils::util::get_md5;
+use ego_utils::util::{get_md5, time};
 
 mock! {
   File {}
@@ -22,6 +29,7 @@ mock! {
   #[async_trait]
   impl TEgoFile for File {
     async fn file_main_write(&self, canister_id: Principal, fid: String, hash: String, data: Vec<u8>) -> Result<bool, EgoError>;
+    async fn file_main_delete(&self, canister_id: Principal, fid: String) -> Result<bool, EgoError>;
   }
 }
 
@@ -33,8 +41,46 @@ mock! {
     fn app_main_release(
         &self,
         app: App,
+        wasm: Wasm,
+        pre_release: Option<String>,
+        developer_verified: bool
+    );
+    fn app_line_release(
+        &self,
+        app_id: AppId,
+        version: Version,
+        pre_release: Option<String>,
         wasm: Wasm
     );
+    fn app_main_metadata_set(
+        &self,
+        app_id: AppId,
+        metadata: AppMetadata
+    );
+    fn app_review_reply(
+        &self,
+        app_id: AppId,
+        wallet_id: Principal,
+        reply: String
+    );
+    fn app_review_hide(
+        &self,
+        app_id: AppId,
+        wallet_id: Principal,
+        hidden: bool
+    );
+    fn app_main_audit_set(
+        &self,
+        app_id: AppId,
+        version: Version,
+        reviews: Vec<AppAuditReview>
+    );
+    fn app_main_developer_verified_set(
+        &self,
+        app_ids: Vec<AppId>,
+        verified: bool
+    );
+    async fn app_main_stats(&self, request: AppStatsRequest) -> Result<AppStatsResponse, EgoError>;
   }
 }
 
@@ -258,6 +304,43 @@ fn developer_app_new_fail_with_none_register_developer() {
   );
 }
 
+#[test]
+fn developer_app_new_fail_with_long_app_id() {
+  set_up();
+
+  let caller = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let result = EgoDevService::developer_app_new(
+    &caller,
+    &"a".repeat(APP_ID_MAX_LEN + 1),
+    TEST_APP_NAME,
+    APP_LOGO,
+    APP_DESCRIPTION,
+    &Category::Vault,
+    0f32,
+  );
+  assert_eq!(1030, result.unwrap_err().code);
+}
+
+#[test]
+fn developer_app_new_with_max_app_id() {
+  set_up();
+
+  // the longest app id is saved and read back
+  let caller = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let app_id = "a".repeat(APP_ID_MAX_LEN);
+  let result = EgoDevService::developer_app_new(
+    &caller,
+    &app_id,
+    TEST_APP_NAME,
+    APP_LOGO,
+    APP_DESCRIPTION,
+    &Category::Vault,
+    0f32,
+  );
+  assert!(result.is_ok());
+  assert!(EgoDevApp::get(&app_id).is_some());
+}
+
 #[test]
 fn developer_app_new_success() {
   set_up();
@@ -344,6 +427,13 @@ fn app_version_submit_process() {
   let result = EgoDevService::app_version_new(&caller, &TEST_APP_ID.to_string(), &version);
   assert!(result.is_ok());
 
+  // the source is required
+  let result = EgoDevService::app_version_submit(&caller, &TEST_APP_ID.to_string(), &version);
+  assert_eq!(EgoError::from(EgoDevErr::SourceMissing), result.unwrap_err());
+
+  let result = EgoDevService::app_version_source_set(&caller, &TEST_APP_ID.to_string(), &version, app_version_source());
+  assert!(result.is_ok());
+
   // test submit
   let result = EgoDevService::app_version_submit(&caller, &TEST_APP_ID.to_string(), &version);
   assert!(result.is_ok());
@@ -370,6 +460,7 @@ fn app_version_submit_process() {
 #[test]
 fn app_version_approve() {
   set_up();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
 
   let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
   let version = Version::new(1, 0, 1);
@@ -384,7 +475,7 @@ fn app_version_approve() {
   assert_eq!(AppVersionStatus::SUBMITTED, app_version.status);
   assert_eq!(version, app.audit_version.unwrap());
 
-  let result = EgoDevService::app_version_approve(&EXIST_APP_ID.to_string());
+  let result = EgoDevService::app_version_approve(&auditor, &EXIST_APP_ID.to_string(), None, "".to_string(), None, None);
   assert!(result.is_ok());
 
   // check after audit
@@ -407,6 +498,7 @@ fn app_version_wait_for_audit() {
 #[test]
 fn app_version_reject() {
   set_up();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
 
   let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
   let version = Version::new(1, 0, 1);
@@ -421,7 +513,7 @@ fn app_version_reject() {
   assert_eq!(AppVersionStatus::SUBMITTED, app_version.status);
   assert_eq!(version, app.audit_version.unwrap());
 
-  let result = EgoDevService::app_version_reject(&EXIST_APP_ID.to_string());
+  let result = EgoDevService::app_version_reject(&auditor, &EXIST_APP_ID.to_string(), None, "".to_string(), None, None);
   assert!(result.is_ok());
 
   // check after audit
@@ -437,23 +529,30 @@ fn app_version_reject() {
 #[tokio::test]
 async fn app_version_release() {
   set_up();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
 
   let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
   let version = Version::new(1, 0, 1);
 
   let mut ego_store = MockStore::new();
-  ego_store.expect_app_main_release().returning(|app, _wasm| {
+  ego_store.expect_app_main_release().returning(|app, _wasm, _pre_release, _developer_verified| {
     assert_eq!("e2a24d7f694107d056b967aace21349b", app.app_hash);
     ()
   });
+  ego_store.expect_app_main_audit_set().returning(|app_id, _version, reviews| {
+    assert_eq!(EXIST_APP_ID, app_id);
+    assert_eq!(1, reviews.len());
+    assert_eq!(AuditDecision::APPROVE, reviews.get(0).unwrap().decision);
+    ()
+  });
 
   // approve version
-  let result = EgoDevService::app_version_approve(&EXIST_APP_ID.to_string());
+  let result = EgoDevService::app_version_approve(&auditor, &EXIST_APP_ID.to_string(), None, "".to_string(), None, None);
   assert!(result.is_ok());
 
   // check after audit
   let result =
-    EgoDevService::app_version_release(&developer, &EXIST_APP_ID.to_string(), &version, ego_store);
+    EgoDevService::app_version_release(&developer, &EXIST_APP_ID.to_string(), &version, false, ego_store);
   assert!(result.is_ok());
 
   let result = EgoDevApp::by_developer_id_and_id(&developer, &EXIST_APP_ID.to_string());
@@ -465,14 +564,52 @@ async fn app_version_release() {
   assert_eq!(AppVersionStatus::RELEASED, app_version.status);
 }
 
+#[tokio::test]
+async fn app_version_release_hotfix() {
+  set_up();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let file_id = Principal::from_text(FILE_CANISTER_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 1);
+
+  // 1.1.0 is already the current version
+  let mut ego_dev_app = EgoDevApp::get(&EXIST_APP_ID.to_string()).unwrap();
+  let mut released = ego_dev_app.version_new(&file_id, &Version::new(1, 1, 0)).unwrap();
+  released.status = AppVersionStatus::RELEASED;
+  released.save();
+  ego_dev_app.app.current_version = Version::new(1, 1, 0);
+  ego_dev_app.save();
+
+  let mut ego_store = MockStore::new();
+  ego_store.expect_app_main_release().times(0);
+  ego_store.expect_app_line_release().times(1).returning(move |app_id, line_version, _pre_release, _wasm| {
+    assert_eq!(EXIST_APP_ID, app_id);
+    assert_eq!(version, line_version);
+    ()
+  });
+
+  let result = EgoDevService::app_version_approve(&auditor, &EXIST_APP_ID.to_string(), None, "".to_string(), None, None);
+  assert!(result.is_ok());
+
+  let result =
+    EgoDevService::app_version_release(&developer, &EXIST_APP_ID.to_string(), &version, true, ego_store);
+  assert!(result.is_ok());
+
+  let app = EgoDevApp::get(&EXIST_APP_ID.to_string()).unwrap();
+  assert_eq!(Version::new(1, 1, 0), app.app.current_version);
+  assert_eq!(AppVersionStatus::RELEASED, app.version_get(&version).unwrap().status);
+}
+
 #[tokio::test]
 async fn app_version_upload_wasm() {
   set_up();
 
   let developer_principal = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
-  let version = Version::new(1, 0, 1);
+  let version = Version::new(1, 0, 2);
   let data = vec![1, 0, 1, 0, 1, 0, 0, 0, 1];
 
+  EgoDevService::app_version_new(&developer_principal, &EXIST_APP_ID.to_string(), &version).unwrap();
+
   let mut service = MockFile::new();
 
   service
@@ -739,7 +876,7 @@ fn app_version_submit_fail() {
     EgoDevService::app_version_submit(&caller_dev, &EXIST_APP_ID.to_string(), &new_version);
   assert!(version_not_exists.is_err());
   let version_not_exists = version_not_exists.unwrap_err();
-  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), version_not_exists);
+  assert_eq!(EgoError::from(EgoDevErr::VersionNotExists), version_not_exists);
 }
 
 #[test]
@@ -784,13 +921,14 @@ fn app_version_revoke_fail() {
 #[tokio::test]
 async fn app_version_release_fail() {
   set_up();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
   let caller_test = Principal::from_text(TEST_PRINCIPAL_ID.to_string()).unwrap();
   // let caller_dev = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
   let version = Version::new(1, 0, 1);
   // let new_version = Version::new(1, 0, 0);
 
   // approve version
-  let result = EgoDevService::app_version_approve(&EXIST_APP_ID.to_string());
+  let result = EgoDevService::app_version_approve(&auditor, &EXIST_APP_ID.to_string(), None, "".to_string(), None, None);
   assert!(result.is_ok());
 
   // app not exists
@@ -800,6 +938,7 @@ async fn app_version_release_fail() {
     &caller_test,
     &TEST_APP_ID.to_string(),
     &version,
+    false,
     ego_store,
   );
   assert!(version_release.is_err());
@@ -813,6 +952,7 @@ async fn app_version_release_fail() {
     &caller_test,
     &EXIST_APP_ID.to_string(),
     &version,
+    false,
     ego_store,
   );
   assert!(caller_unauthorized.is_err());
@@ -822,18 +962,19 @@ async fn app_version_release_fail() {
 #[test]
 fn app_version_approve_fail() {
   set_up();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
 
   let caller = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
   let get_app = EgoDevApp::by_developer_id_and_id(&caller, &EXIST_APP_ID.to_string());
   assert!(get_app.is_some());
 
   // app not exists
-  let appid_not_exists = EgoDevService::app_version_approve(&"EXIST_APP_ID".to_string());
+  let appid_not_exists = EgoDevService::app_version_approve(&auditor, &"EXIST_APP_ID".to_string(), None, "".to_string(), None, None);
   let appid_not_exists = appid_not_exists.unwrap_err();
   assert_eq!("ego-dev: app not exists", appid_not_exists.msg);
 
   // approve success
-  let approve_success = EgoDevService::app_version_approve(&EXIST_APP_ID.to_string());
+  let approve_success = EgoDevService::app_version_approve(&auditor, &EXIST_APP_ID.to_string(), None, "".to_string(), None, None);
   assert!(approve_success.is_ok());
 
   let approve_success = approve_success.unwrap();
@@ -843,10 +984,778 @@ fn app_version_approve_fail() {
 #[test]
 fn app_version_reject_fail() {
   set_up();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
 
   // app not exists
-  let app_not_exists = EgoDevService::app_version_reject(&"EXIST_APP_ID".to_string());
+  let app_not_exists = EgoDevService::app_version_reject(&auditor, &"EXIST_APP_ID".to_string(), None, "".to_string(), None, None);
   assert!(app_not_exists.is_err());
   let app_not_exists = app_not_exists.unwrap_err();
   assert_eq!("ego-dev: app not exists", app_not_exists.msg);
 }
+
+#[test]
+fn app_version_approve_quorum() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
+  let auditor_2 = Principal::from_text(TEST_PRINCIPAL_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 1);
+
+  let result = EgoDevService::admin_audit_policy_set(&Category::Vault, 0, None, false);
+  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), result.unwrap_err());
+
+  let result = EgoDevService::admin_audit_policy_set(&Category::Vault, 2, None, false);
+  assert!(result.is_ok());
+
+  // the first approval is not enough
+  let app_version = EgoDevService::app_version_approve(&auditor, &EXIST_APP_ID.to_string(), None, "".to_string(), None, None).unwrap();
+  assert_eq!(AppVersionStatus::SUBMITTED, app_version.status);
+
+  // approving twice does not count twice
+  let app_version = EgoDevService::app_version_approve(&auditor, &EXIST_APP_ID.to_string(), None, "looks good".to_string(), None, None).unwrap();
+  assert_eq!(AppVersionStatus::SUBMITTED, app_version.status);
+
+  let app_version = EgoDevService::app_version_approve(&auditor_2, &EXIST_APP_ID.to_string(), None, "".to_string(), None, None).unwrap();
+  assert_eq!(AppVersionStatus::APPROVED, app_version.status);
+
+  let reviews = EgoDevService::app_version_audit_review_list(&developer, &EXIST_APP_ID.to_string(), &version).unwrap();
+  assert_eq!(2, reviews.len());
+  assert_eq!("looks good", reviews.iter().find(|review| review.auditor_id == auditor).unwrap().comment);
+}
+
+#[test]
+fn app_version_approve_panel() {
+  set_up();
+
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
+  let auditor_2 = Principal::from_text(TEST_PRINCIPAL_ID.to_string()).unwrap();
+
+  // the quorum can not exceed the panel, duplicates are counted once
+  let result = EgoDevService::admin_audit_policy_set(&Category::Vault, 2, Some(vec![auditor_2, auditor_2]), false);
+  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), result.unwrap_err());
+
+  let audit_policy = EgoDevService::admin_audit_policy_set(&Category::Vault, 1, Some(vec![auditor_2, auditor_2]), false).unwrap();
+  assert_eq!(Some(vec![auditor_2]), audit_policy.auditors);
+
+  // auditors off the panel can not review the category
+  let result = EgoDevService::app_version_approve(&auditor, &EXIST_APP_ID.to_string(), None, "".to_string(), None, None);
+  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), result.unwrap_err());
+
+  let app_version = EgoDevService::app_version_approve(&auditor_2, &EXIST_APP_ID.to_string(), None, "".to_string(), None, None).unwrap();
+  assert_eq!(AppVersionStatus::APPROVED, app_version.status);
+}
+
+#[test]
+fn app_version_reject_with_review() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 1);
+
+  // comment too long
+  let result = EgoDevService::app_version_reject(&auditor, &EXIST_APP_ID.to_string(), None, "a".repeat(1001), None, None);
+  assert_eq!(EgoError::from(EgoDevErr::AuditReviewInvalid), result.unwrap_err());
+
+  let result = EgoDevService::app_version_reject(
+    &auditor,
+    &EXIST_APP_ID.to_string(),
+    None,
+    "missing license".to_string(),
+    Some("https://example.com/report.pdf".to_string()),
+    None,
+  );
+  assert_eq!(AppVersionStatus::REJECTED, result.unwrap().status);
+
+  let reviews = EgoDevService::app_version_audit_review_list(&developer, &EXIST_APP_ID.to_string(), &version).unwrap();
+  assert_eq!(1, reviews.len());
+  let review = reviews.get(0).unwrap();
+  assert_eq!(AuditDecision::REJECT, review.decision);
+  assert_eq!("missing license", review.comment);
+  assert_eq!(Some("https://example.com/report.pdf".to_string()), review.report);
+
+  // only the developer of the app can read the reviews
+  let result = EgoDevService::app_version_audit_review_list(&auditor, &EXIST_APP_ID.to_string(), &version);
+  assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn app_version_upload_wasm_after_reject() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 1);
+  let data = vec![1, 0, 1, 0, 1, 0, 0, 0, 1];
+
+  EgoDevService::app_version_reject(&auditor, &EXIST_APP_ID.to_string(), Some(version), "".to_string(), None, None).unwrap();
+
+  let mut ego_file = MockFile::new();
+  ego_file.expect_file_main_write().returning(|_, _, _, _| Ok(true));
+  let result = EgoDevService::app_version_upload_wasm(
+    ego_file,
+    &developer,
+    &EXIST_APP_ID.to_string(),
+    &version,
+    data.clone(),
+    get_md5(&data),
+  ).await;
+  assert!(result.is_ok());
+
+  let app_version = AppVersion::get_by_app_id_and_version(&EXIST_APP_ID.to_string(), &version).unwrap();
+  assert_eq!(AppVersionStatus::NEW, app_version.status);
+
+  let transitions = EgoDevService::app_version_transition_list(&developer, &EXIST_APP_ID.to_string(), &version).unwrap();
+  assert_eq!(2, transitions.len());
+  assert_eq!(auditor, transitions.get(0).unwrap().actor_id);
+  assert_eq!(developer, transitions.get(1).unwrap().actor_id);
+
+  // the draft can be deleted now, along with its wasm
+  let mut ego_file = MockFile::new();
+  ego_file.expect_file_main_delete().times(1).returning(|_, _| Ok(true));
+  let result = EgoDevService::app_version_delete(ego_file, &developer, &EXIST_APP_ID.to_string(), &version).await;
+  assert_eq!(AppVersionStatus::DELETED, result.unwrap().status);
+  assert!(EgoDevService::app_version_audit_list().is_empty());
+  assert!(AppVersion::get_by_app_id_and_version(&EXIST_APP_ID.to_string(), &version).is_none());
+
+  let transitions = EgoDevService::app_version_transition_list(&developer, &EXIST_APP_ID.to_string(), &version).unwrap();
+  assert_eq!(3, transitions.len());
+  assert_eq!(AppVersionStatus::NEW, transitions.get(2).unwrap().from);
+  assert_eq!(AppVersionStatus::DELETED, transitions.get(2).unwrap().to);
+}
+
+#[tokio::test]
+async fn app_version_upload_wasm_write_failed() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 1);
+  let data = vec![1, 0, 1, 0, 1, 0, 0, 0, 1];
+
+  EgoDevService::app_version_reject(&auditor, &EXIST_APP_ID.to_string(), Some(version), "".to_string(), None, None).unwrap();
+  let previous = AppVersion::get_by_app_id_and_version(&EXIST_APP_ID.to_string(), &version).unwrap();
+
+  // the wasm not written, the version stays rejected with the hash it had
+  let mut ego_file = MockFile::new();
+  ego_file.expect_file_main_write().returning(|_, _, _, _| Err(EgoError::new(255, "write failed")));
+  let result = EgoDevService::app_version_upload_wasm(
+    ego_file,
+    &developer,
+    &EXIST_APP_ID.to_string(),
+    &version,
+    data.clone(),
+    get_md5(&data),
+  ).await;
+  assert_eq!(255, result.unwrap_err().code);
+
+  let app_version = AppVersion::get_by_app_id_and_version(&EXIST_APP_ID.to_string(), &version).unwrap();
+  assert_eq!(AppVersionStatus::REJECTED, app_version.status);
+  assert_eq!(previous.wasm_sha256, app_version.wasm_sha256);
+}
+
+#[test]
+fn admin_app_version_approve() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 1);
+
+  // the override needs a reason
+  let result = EgoDevService::admin_app_version_approve(&developer, &EXIST_APP_ID.to_string(), &version, " ");
+  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), result.unwrap_err());
+
+  let app_version = EgoDevService::admin_app_version_approve(&developer, &EXIST_APP_ID.to_string(), &version, "built by the platform").unwrap();
+  assert_eq!(AppVersionStatus::APPROVED, app_version.status);
+  assert!(EgoDevService::app_version_audit_list().is_empty());
+
+  let transitions = EgoDevService::app_version_transition_list(&developer, &EXIST_APP_ID.to_string(), &version).unwrap();
+  assert_eq!(Some("built by the platform".to_string()), transitions.last().unwrap().reason);
+}
+
+#[test]
+fn admin_app_version_approve_after_reject() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 1);
+
+  // a version turned down by an auditor can not be approved over the audit
+  EgoDevService::app_version_reject(&auditor, &EXIST_APP_ID.to_string(), Some(version), "".to_string(), None, None).unwrap();
+
+  let result = EgoDevService::admin_app_version_approve(&developer, &EXIST_APP_ID.to_string(), &version, "built by the platform");
+  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), result.unwrap_err());
+}
+
+fn app_version_source() -> AppVersionSource {
+  AppVersionSource {
+    repo_url: "https://github.com/example/app".to_string(),
+    commit: "0123456789abcdef0123456789abcdef01234567".to_string(),
+    toolchain: "rust 1.68.0".to_string(),
+    sha256: "0".repeat(64),
+  }
+}
+
+#[tokio::test]
+async fn app_version_source_set() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 2);
+  let data = vec![1, 0, 1, 0, 1, 0, 0, 0, 1];
+
+  EgoDevService::app_version_new(&developer, &RELEASED_APP_ID.to_string(), &version).unwrap();
+
+  let mut ego_file = MockFile::new();
+  ego_file.expect_file_main_write().returning(|_, _, _, _| Ok(true));
+  let result = EgoDevService::app_version_upload_wasm(
+    ego_file,
+    &developer,
+    &RELEASED_APP_ID.to_string(),
+    &version,
+    data.clone(),
+    get_md5(&data),
+  ).await;
+  assert!(result.is_ok());
+
+  // invalid source
+  let mut source = app_version_source();
+  source.sha256 = "not a hash".to_string();
+  let result = EgoDevService::app_version_source_set(&developer, &RELEASED_APP_ID.to_string(), &version, source);
+  assert_eq!(EgoError::from(EgoDevErr::SourceInvalid), result.unwrap_err());
+
+  // the declared hash does not match the uploaded wasm
+  let result = EgoDevService::app_version_source_set(&developer, &RELEASED_APP_ID.to_string(), &version, app_version_source());
+  assert!(result.is_ok());
+
+  let result = EgoDevService::app_version_submit(&developer, &RELEASED_APP_ID.to_string(), &version);
+  assert_eq!(EgoError::from(EgoDevErr::SourceInvalid), result.unwrap_err());
+
+  // the declared hash matches
+  let app_version = AppVersion::get_by_app_id_and_version(&RELEASED_APP_ID.to_string(), &version).unwrap();
+  let mut source = app_version_source();
+  source.sha256 = app_version.wasm_sha256.unwrap().to_uppercase();
+  let app_version = EgoDevService::app_version_source_set(&developer, &RELEASED_APP_ID.to_string(), &version, source).unwrap();
+  assert_eq!(64, app_version.source.clone().unwrap().sha256.len());
+
+  let app_version = EgoDevService::app_version_submit(&developer, &RELEASED_APP_ID.to_string(), &version).unwrap();
+  assert_eq!(AppVersionStatus::SUBMITTED, app_version.status);
+
+  // the source can not change while under audit
+  let result = EgoDevService::app_version_source_set(&developer, &RELEASED_APP_ID.to_string(), &version, app_version_source());
+  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), result.unwrap_err());
+}
+
+#[test]
+fn app_version_approve_reproduction_required() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 1);
+
+  EgoDevService::admin_audit_policy_set(&Category::Vault, 1, None, true).unwrap();
+
+  let result = EgoDevService::app_version_approve(&auditor, &EXIST_APP_ID.to_string(), None, "".to_string(), None, None);
+  assert_eq!(EgoError::from(EgoDevErr::ReproductionRequired), result.unwrap_err());
+
+  let result = EgoDevService::app_version_approve(&auditor, &EXIST_APP_ID.to_string(), None, "".to_string(), None, Some(false));
+  assert_eq!(EgoError::from(EgoDevErr::ReproductionRequired), result.unwrap_err());
+
+  let app_version = EgoDevService::app_version_approve(&auditor, &EXIST_APP_ID.to_string(), None, "".to_string(), None, Some(true)).unwrap();
+  assert_eq!(AppVersionStatus::APPROVED, app_version.status);
+
+  let reviews = EgoDevService::app_version_audit_review_list(&developer, &EXIST_APP_ID.to_string(), &version).unwrap();
+  assert_eq!(1, reviews.len());
+  assert_eq!(Some(true), reviews.get(0).unwrap().reproduced);
+}
+
+fn app_metadata_set_request(app_id: &str) -> AppMetadataSetRequest {
+  AppMetadataSetRequest {
+    app_id: app_id.to_string(),
+    tags: vec![" Wallet ".to_string(), "defi".to_string()],
+    screenshots: vec!["https://example.com/1.png".to_string()],
+    media: vec![],
+    homepage: Some("https://example.com".to_string()),
+    support_link: None,
+  }
+}
+
+#[test]
+fn app_metadata_set_unreleased_app() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+
+  // not released yet, nothing pushed to ego_store
+  let ego_store = MockStore::new();
+
+  let result = EgoDevService::app_metadata_set(&developer, app_metadata_set_request(EXIST_APP_ID), ego_store);
+  assert!(result.is_ok());
+
+  let app_metadata = EgoDevAppMetadata::get(&EXIST_APP_ID.to_string()).unwrap();
+  assert_eq!(vec!["wallet".to_string(), "defi".to_string()], app_metadata.metadata.tags);
+  assert_eq!(Some("https://example.com".to_string()), app_metadata.metadata.homepage);
+}
+
+#[test]
+fn app_metadata_set_released_app() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+
+  let mut ego_store = MockStore::new();
+  ego_store.expect_app_main_metadata_set().times(1).returning(|app_id, metadata| {
+    assert_eq!(RELEASED_APP_ID, app_id);
+    assert_eq!(2, metadata.tags.len());
+    ()
+  });
+
+  let result = EgoDevService::app_metadata_set(&developer, app_metadata_set_request(RELEASED_APP_ID), ego_store);
+  assert!(result.is_ok());
+}
+
+#[test]
+fn app_metadata_set_fail() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+
+  // app not exists
+  let result = EgoDevService::app_metadata_set(&developer, app_metadata_set_request(TEST_APP_ID), MockStore::new());
+  assert_eq!(1002, result.unwrap_err().code);
+
+  // invalid url
+  let mut request = app_metadata_set_request(EXIST_APP_ID);
+  request.homepage = Some("javascript:alert(1)".to_string());
+  let result = EgoDevService::app_metadata_set(&developer, request, MockStore::new());
+  assert_eq!(1015, result.unwrap_err().code);
+
+  // too many tags
+  let mut request = app_metadata_set_request(EXIST_APP_ID);
+  request.tags = (0..11).map(|i| format!("tag{}", i)).collect();
+  let result = EgoDevService::app_metadata_set(&developer, request, MockStore::new());
+  assert_eq!(1015, result.unwrap_err().code);
+}
+
+#[test]
+fn app_version_release_note_set() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 1);
+
+  let result = EgoDevService::app_version_release_note_set(&developer, &EXIST_APP_ID.to_string(), &version, "first".to_string(), MockStore::new());
+  assert!(result.is_ok());
+
+  // overwrite the notes of the same version
+  let result = EgoDevService::app_version_release_note_set(&developer, &EXIST_APP_ID.to_string(), &version, "second".to_string(), MockStore::new());
+  let release_notes = result.unwrap().metadata.release_notes;
+  assert_eq!(1, release_notes.len());
+  assert_eq!("second", release_notes[0].notes);
+
+  // version not exists
+  let result = EgoDevService::app_version_release_note_set(&developer, &EXIST_APP_ID.to_string(), &Version::new(9, 9, 9), "notes".to_string(), MockStore::new());
+  assert_eq!(1004, result.unwrap_err().code);
+}
+
+#[test]
+fn app_version_release_note_set_older_than_kept() {
+  let mut app_metadata = EgoDevAppMetadata::new(&EXIST_APP_ID.to_string());
+  for patch in 1..=RELEASE_NOTE_MAX_COUNT as u32 {
+    app_metadata.release_note_set(&Version::new(1, 0, patch), "notes".to_string()).unwrap();
+  }
+
+  // older than every kept note, it would be dropped as soon as stored
+  let result = app_metadata.release_note_set(&Version::new(1, 0, 0), "notes".to_string());
+  assert_eq!(1015, result.unwrap_err().code);
+  assert_eq!(RELEASE_NOTE_MAX_COUNT, app_metadata.metadata.release_notes.len());
+
+  // a newer one pushes the oldest out
+  app_metadata.release_note_set(&Version::new(1, 1, 0), "notes".to_string()).unwrap();
+  let release_notes = &app_metadata.metadata.release_notes;
+  assert_eq!(RELEASE_NOTE_MAX_COUNT, release_notes.len());
+  assert_eq!(Version::new(1, 1, 0), release_notes[0].version);
+  assert_eq!(Version::new(1, 0, 2), release_notes[RELEASE_NOTE_MAX_COUNT - 1].version);
+}
+
+#[test]
+fn app_review_reply() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let wallet_id = Principal::from_text(TEST_CANISTER_ID.to_string()).unwrap();
+
+  let mut ego_store = MockStore::new();
+  ego_store.expect_app_review_reply().times(1).returning(|app_id, _wallet_id, reply| {
+    assert_eq!(EXIST_APP_ID, app_id);
+    assert_eq!("thanks", reply);
+    ()
+  });
+
+  let result = EgoDevService::app_review_reply(&developer, &EXIST_APP_ID.to_string(), &wallet_id, "thanks".to_string(), ego_store);
+  assert!(result.is_ok());
+
+  // only the developer of the app can reply
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
+  let result = EgoDevService::app_review_reply(&auditor, &EXIST_APP_ID.to_string(), &wallet_id, "thanks".to_string(), MockStore::new());
+  assert_eq!(1002, result.unwrap_err().code);
+}
+
+#[test]
+fn app_review_hide() {
+  set_up();
+
+  let wallet_id = Principal::from_text(TEST_CANISTER_ID.to_string()).unwrap();
+
+  let mut ego_store = MockStore::new();
+  ego_store.expect_app_review_hide().times(1).returning(|_app_id, _wallet_id, hidden| {
+    assert!(hidden);
+    ()
+  });
+
+  let result = EgoDevService::app_review_hide(&EXIST_APP_ID.to_string(), &wallet_id, true, ego_store);
+  assert!(result.is_ok());
+
+  let result = EgoDevService::app_review_hide(&TEST_APP_ID.to_string(), &wallet_id, true, MockStore::new());
+  assert_eq!(1002, result.unwrap_err().code);
+}
+
+#[tokio::test]
+async fn developer_app_stats() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+
+  let mut ego_store = MockStore::new();
+  ego_store.expect_app_main_stats().times(1).returning(|request| {
+    Ok(AppStatsResponse {
+      app_id: request.app_id,
+      daily: vec![],
+      installs: 3,
+      uninstalls: 1,
+      upgrades: 0,
+      active_installs: 2,
+      versions: vec![],
+    })
+  });
+
+  let request = AppStatsRequest { app_id: EXIST_APP_ID.to_string(), from_day: 0, to_day: 30 };
+  let resp = EgoDevService::developer_app_stats(&developer, request, ego_store).await.unwrap();
+  assert_eq!(3, resp.installs);
+  assert_eq!(2, resp.active_installs);
+
+  // not the developer of the app
+  let auditor = Principal::from_text(AUDITER_PRINCIPAL_ID.to_string()).unwrap();
+  let request = AppStatsRequest { app_id: EXIST_APP_ID.to_string(), from_day: 0, to_day: 30 };
+  let result = EgoDevService::developer_app_stats(&auditor, request, MockStore::new()).await;
+  assert_eq!(1002, result.unwrap_err().code);
+}
+
+#[test]
+fn app_collaborator_roles() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let collaborator = Principal::from_text(TEST_PRINCIPAL_ID.to_string()).unwrap();
+  EgoDevService::developer_main_register(&collaborator, "user_1").unwrap();
+
+  // no role, the app is not visible
+  let result = EgoDevService::app_version_new(&collaborator, &EXIST_APP_ID.to_string(), &Version::new(1, 0, 2));
+  assert_eq!(EgoError::from(EgoDevErr::AppNotExists), result.unwrap_err());
+
+  // an uploader can create versions, but not release or revoke them
+  EgoDevService::app_collaborator_set(&developer, &EXIST_APP_ID.to_string(), &collaborator, AppRole::UPLOADER).unwrap();
+
+  let result = EgoDevService::app_version_new(&collaborator, &EXIST_APP_ID.to_string(), &Version::new(1, 0, 2));
+  assert!(result.is_ok());
+
+  let result = EgoDevService::app_version_revoke(&collaborator, &EXIST_APP_ID.to_string(), &Version::new(1, 0, 1));
+  assert_eq!(EgoError::from(EgoDevErr::UnAuthorized), result.unwrap_err());
+
+  let result = EgoDevService::app_version_release(&collaborator, &EXIST_APP_ID.to_string(), &Version::new(1, 0, 1), false, MockStore::new());
+  assert_eq!(EgoError::from(EgoDevErr::UnAuthorized), result.unwrap_err());
+
+  // only owners manage the collaborators
+  let result = EgoDevService::app_collaborator_set(&collaborator, &EXIST_APP_ID.to_string(), &collaborator, AppRole::OWNER);
+  assert_eq!(EgoError::from(EgoDevErr::UnAuthorized), result.unwrap_err());
+
+  // a maintainer can revoke
+  EgoDevService::app_collaborator_set(&developer, &EXIST_APP_ID.to_string(), &collaborator, AppRole::MAINTAINER).unwrap();
+  let result = EgoDevService::app_version_revoke(&collaborator, &EXIST_APP_ID.to_string(), &Version::new(1, 0, 1));
+  assert!(result.is_ok());
+
+  let collaborators = EgoDevService::app_collaborator_list(&collaborator, &EXIST_APP_ID.to_string()).unwrap();
+  assert_eq!(1, collaborators.len());
+  assert_eq!(AppRole::MAINTAINER, collaborators.get(0).unwrap().role);
+
+  assert_eq!(1, EgoDevApp::by_user_id(&collaborator).len());
+
+  EgoDevService::app_collaborator_remove(&developer, &EXIST_APP_ID.to_string(), &collaborator).unwrap();
+  assert!(EgoDevApp::by_user_id(&collaborator).is_empty());
+}
+
+#[test]
+fn team_members_and_apps() {
+  set_up();
+
+  let owner = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let member = Principal::from_text(TEST_PRINCIPAL_ID.to_string()).unwrap();
+  EgoDevService::developer_main_register(&member, "user_1").unwrap();
+
+  let result = EgoDevService::team_new(&owner, "");
+  assert_eq!(EgoError::from(EgoDevErr::TeamInvalid), result.unwrap_err());
+
+  let team = EgoDevService::team_new(&owner, "team 1").unwrap();
+
+  // only the team owner invites
+  let result = EgoDevService::team_member_invite(&member, team.id, &member);
+  assert_eq!(EgoError::from(EgoDevErr::UnAuthorized), result.unwrap_err());
+
+  EgoDevService::team_member_invite(&owner, team.id, &member).unwrap();
+  assert_eq!(1, Team::by_user_id(&member).len());
+
+  // move the app into the team
+  let result = EgoDevService::team_app_transfer(&member, &EXIST_APP_ID.to_string(), Some(team.id));
+  assert_eq!(EgoError::from(EgoDevErr::AppNotExists), result.unwrap_err());
+
+  let ego_dev_app = EgoDevService::team_app_transfer(&owner, &EXIST_APP_ID.to_string(), Some(team.id)).unwrap();
+  assert_eq!(Some(team.id), ego_dev_app.team_id);
+
+  // roles on a team app need the invitation to be accepted
+  let result = EgoDevService::app_collaborator_set(&owner, &EXIST_APP_ID.to_string(), &member, AppRole::UPLOADER);
+  assert_eq!(EgoError::from(EgoDevErr::TeamMemberNotExists), result.unwrap_err());
+
+  EgoDevService::team_invitation_accept(&member, team.id).unwrap();
+
+  // members view the team apps
+  let ego_dev_app = EgoDevApp::get(&EXIST_APP_ID.to_string()).unwrap();
+  assert_eq!(Some(AppRole::VIEWER), ego_dev_app.role(&member));
+
+  let result = EgoDevService::app_version_new(&member, &EXIST_APP_ID.to_string(), &Version::new(1, 0, 2));
+  assert_eq!(EgoError::from(EgoDevErr::UnAuthorized), result.unwrap_err());
+
+  EgoDevService::app_collaborator_set(&owner, &EXIST_APP_ID.to_string(), &member, AppRole::UPLOADER).unwrap();
+  let result = EgoDevService::app_version_new(&member, &EXIST_APP_ID.to_string(), &Version::new(1, 0, 2));
+  assert!(result.is_ok());
+
+  // removing the member drops the roles on the team apps
+  EgoDevService::team_member_remove(&owner, team.id, &member).unwrap();
+  assert_eq!(None, ego_dev_app.role(&member));
+  assert!(AppCollaborator::by_app_id(&EXIST_APP_ID.to_string()).is_empty());
+
+  // move the app out of the team
+  let ego_dev_app = EgoDevService::team_app_transfer(&owner, &EXIST_APP_ID.to_string(), None).unwrap();
+  assert_eq!(None, ego_dev_app.team_id);
+  assert_eq!(owner, ego_dev_app.developer_id);
+}
+
+#[test]
+fn team_app_transfer_out() {
+  set_up();
+
+  let owner = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let member = Principal::from_text(TEST_PRINCIPAL_ID.to_string()).unwrap();
+  EgoDevService::developer_main_register(&member, "user_1").unwrap();
+
+  let team = EgoDevService::team_new(&owner, "team 1").unwrap();
+  EgoDevService::team_app_transfer(&owner, &EXIST_APP_ID.to_string(), Some(team.id)).unwrap();
+  EgoDevService::team_member_invite(&owner, team.id, &member).unwrap();
+  EgoDevService::team_invitation_accept(&member, team.id).unwrap();
+  EgoDevService::app_collaborator_set(&owner, &EXIST_APP_ID.to_string(), &member, AppRole::OWNER).unwrap();
+
+  // an owner by role neither moves the app into their own team nor takes it out of its team
+  let other_team = EgoDevService::team_new(&member, "team 2").unwrap();
+  let result = EgoDevService::team_app_transfer(&member, &EXIST_APP_ID.to_string(), Some(other_team.id));
+  assert_eq!(EgoError::from(EgoDevErr::UnAuthorized), result.unwrap_err());
+
+  let result = EgoDevService::team_app_transfer(&member, &EXIST_APP_ID.to_string(), None);
+  assert_eq!(EgoError::from(EgoDevErr::UnAuthorized), result.unwrap_err());
+
+  // nor sends it to themselves
+  let result = EgoDevService::app_transfer_request(&member, &EXIST_APP_ID.to_string(), &member);
+  assert_eq!(EgoError::from(EgoDevErr::OperationNotPermitted), result.unwrap_err());
+
+  let ego_dev_app = EgoDevApp::get(&EXIST_APP_ID.to_string()).unwrap();
+  assert_eq!(Some(team.id), ego_dev_app.team_id);
+  assert_eq!(owner, ego_dev_app.developer_id);
+}
+
+#[test]
+fn delegate_upload_scope() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let delegate_id = Principal::from_text(TEST_CANISTER_ID.to_string()).unwrap();
+
+  // the expiry must be in the future and within the max ttl
+  let result = EgoDevService::delegate_set(&developer, DelegateSetRequest {
+    delegate_id,
+    app_ids: vec![EXIST_APP_ID.to_string()],
+    scope: DelegateScope::UPLOAD,
+    expires_at: time() - 1,
+  });
+  assert_eq!(EgoError::from(EgoDevErr::DelegateInvalid), result.unwrap_err());
+
+  // apps the developer has no role on can not be delegated
+  let result = EgoDevService::delegate_set(&developer, DelegateSetRequest {
+    delegate_id,
+    app_ids: vec![TEST_APP_ID.to_string()],
+    scope: DelegateScope::UPLOAD,
+    expires_at: time() + 3600,
+  });
+  assert!(result.is_err());
+
+  EgoDevService::delegate_set(&developer, DelegateSetRequest {
+    delegate_id,
+    app_ids: vec![EXIST_APP_ID.to_string()],
+    scope: DelegateScope::UPLOAD,
+    expires_at: time() + 3600,
+  }).unwrap();
+
+  // an upload delegate creates versions but can not revoke or release
+  let result = EgoDevService::app_version_new(&delegate_id, &EXIST_APP_ID.to_string(), &Version::new(1, 0, 2));
+  assert!(result.is_ok());
+
+  let result = EgoDevService::app_version_revoke(&delegate_id, &EXIST_APP_ID.to_string(), &Version::new(1, 0, 1));
+  assert_eq!(EgoError::from(EgoDevErr::UnAuthorized), result.unwrap_err());
+
+  // apps outside the delegation are not visible
+  let result = EgoDevService::app_version_new(&delegate_id, &RELEASED_APP_ID.to_string(), &Version::new(1, 0, 2));
+  assert_eq!(EgoError::from(EgoDevErr::AppNotExists), result.unwrap_err());
+
+  // every attempt is kept with both principals and its outcome
+  let delegate_actions = DelegateAction::by_developer_id(&developer);
+  assert_eq!(3, delegate_actions.len());
+  assert_eq!(delegate_id, delegate_actions.get(0).unwrap().delegate_id);
+  assert_eq!(EXIST_APP_ID.to_string(), delegate_actions.get(0).unwrap().app_id);
+  assert_eq!(None, delegate_actions.get(0).unwrap().error);
+  assert_eq!(Some(EgoError::from(EgoDevErr::UnAuthorized)), delegate_actions.get(1).unwrap().error);
+  assert_eq!(Some(EgoError::from(EgoDevErr::AppNotExists)), delegate_actions.get(2).unwrap().error);
+  assert_eq!(RELEASED_APP_ID.to_string(), delegate_actions.get(2).unwrap().app_id);
+
+  // a role granted on the app is not narrowed by the delegation
+  EgoDevService::developer_main_register(&delegate_id, "bot").unwrap();
+  EgoDevService::app_collaborator_set(&developer, &EXIST_APP_ID.to_string(), &delegate_id, AppRole::MAINTAINER).unwrap();
+  let ego_dev_app = EgoDevApp::get(&EXIST_APP_ID.to_string()).unwrap();
+  assert_eq!(Some(AppRole::MAINTAINER), ego_dev_app.role(&delegate_id));
+  EgoDevService::app_collaborator_remove(&developer, &EXIST_APP_ID.to_string(), &delegate_id).unwrap();
+
+  // once removed the delegate has no access anymore
+  let result = EgoDevService::delegate_remove(&delegate_id, &delegate_id);
+  assert_eq!(EgoError::from(EgoDevErr::DelegateNotExists), result.unwrap_err());
+
+  EgoDevService::delegate_remove(&developer, &delegate_id).unwrap();
+  let result = EgoDevService::app_version_new(&delegate_id, &EXIST_APP_ID.to_string(), &Version::new(1, 0, 3));
+  assert_eq!(EgoError::from(EgoDevErr::AppNotExists), result.unwrap_err());
+}
+
+#[test]
+fn developer_profile_set() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+
+  let result = EgoDevService::developer_profile_set(&developer, DeveloperProfileSetRequest {
+    website: Some("http://example.com".to_string()),
+    contact: None,
+    avatar: None,
+  });
+  assert_eq!(EgoError::from(EgoDevErr::ProfileInvalid), result.unwrap_err());
+
+  let developer_profile = EgoDevService::developer_profile_set(&developer, DeveloperProfileSetRequest {
+    website: Some("https://example.com".to_string()),
+    contact: Some("dev@example.com".to_string()),
+    avatar: None,
+  }).unwrap();
+  assert_eq!(Some("dev@example.com".to_string()), developer_profile.contact);
+
+  // only developers have a profile
+  let caller = Principal::from_text(TEST_PRINCIPAL_ID.to_string()).unwrap();
+  let result = EgoDevService::developer_profile_set(&caller, DeveloperProfileSetRequest {
+    website: None,
+    contact: None,
+    avatar: None,
+  });
+  assert_eq!(EgoError::from(EgoDevErr::NotADeveloper), result.unwrap_err());
+}
+
+#[test]
+fn user_verified_set() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+
+  // only the released app is pushed to ego_store
+  let mut ego_store = MockStore::new();
+  ego_store.expect_app_main_developer_verified_set().times(1).returning(|app_ids, verified| {
+    assert_eq!(vec![RELEASED_APP_ID.to_string()], app_ids);
+    assert!(verified);
+  });
+
+  EgoDevService::user_verified_set(&developer, true, ego_store).unwrap();
+  assert!(Developer::get(&developer).unwrap().is_verified());
+}
+
+#[test]
+fn app_transfer_accept() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let receiver = Principal::from_text(TEST_PRINCIPAL_ID.to_string()).unwrap();
+
+  // the recipient must be a developer
+  let result = EgoDevService::app_transfer_request(&developer, &RELEASED_APP_ID.to_string(), &receiver);
+  assert_eq!(EgoError::from(EgoDevErr::NotADeveloper), result.unwrap_err());
+
+  EgoDevService::developer_main_register(&receiver, "user_1").unwrap();
+
+  let result = EgoDevService::app_transfer_request(&receiver, &RELEASED_APP_ID.to_string(), &receiver);
+  assert_eq!(EgoError::from(EgoDevErr::AppNotExists), result.unwrap_err());
+
+  EgoDevService::app_transfer_request(&developer, &RELEASED_APP_ID.to_string(), &receiver).unwrap();
+
+  // nothing moves before the recipient accepts
+  let ego_dev_app = EgoDevApp::get(&RELEASED_APP_ID.to_string()).unwrap();
+  assert_eq!(developer, ego_dev_app.developer_id);
+  assert_eq!(1, AppTransfer::by_user_id(&receiver).len());
+
+  let result = EgoDevService::app_transfer_accept(&developer, &RELEASED_APP_ID.to_string(), MockStore::new());
+  assert_eq!(EgoError::from(EgoDevErr::AppTransferNotExists), result.unwrap_err());
+
+  let mut ego_store = MockStore::new();
+  ego_store.expect_app_main_developer_verified_set().times(1).returning(|_app_ids, verified| {
+    assert!(!verified);
+  });
+  let ego_dev_app = EgoDevService::app_transfer_accept(&receiver, &RELEASED_APP_ID.to_string(), ego_store).unwrap();
+  assert_eq!(receiver, ego_dev_app.developer_id);
+  assert!(Developer::get(&receiver).unwrap().created_apps.contains(&RELEASED_APP_ID.to_string()));
+  assert!(AppTransfer::get(&RELEASED_APP_ID.to_string()).is_none());
+}
+
+#[test]
+fn app_transfer_expired() {
+  set_up();
+
+  let developer = Principal::from_text(DEVELOPER_PRINCIPAL_ID.to_string()).unwrap();
+  let receiver = Principal::from_text(TEST_PRINCIPAL_ID.to_string()).unwrap();
+  EgoDevService::developer_main_register(&receiver, "user_1").unwrap();
+
+  let mut app_transfer = EgoDevService::app_transfer_request(&developer, &EXIST_APP_ID.to_string(), &receiver).unwrap();
+  app_transfer.expires_at = time() - 1;
+  app_transfer.save();
+
+  let result = EgoDevService::app_transfer_accept(&receiver, &EXIST_APP_ID.to_string(), MockStore::new());
+  assert_eq!(EgoError::from(EgoDevErr::AppTransferExpired), result.unwrap_err());
+
+  let ego_dev_app = EgoDevApp::get(&EXIST_APP_ID.to_string()).unwrap();
+  assert_eq!(developer, ego_dev_app.developer_id);
+
+  // a declined transfer is gone
+  EgoDevService::app_transfer_request(&developer, &EXIST_APP_ID.to_string(), &receiver).unwrap();
+  EgoDevService::app_transfer_cancel(&receiver, &EXIST_APP_ID.to_string()).unwrap();
+  let result = EgoDevService::app_transfer_cancel(&receiver, &EXIST_APP_ID.to_string());
+  assert_eq!(EgoError::from(EgoDevErr::AppTransferNotExists), result.unwrap_err());
+}
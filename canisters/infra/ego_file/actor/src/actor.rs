@@ -67,6 +67,15 @@ fn file_main_write(fid: FileId, hash: String, data: Vec<u8>) -> Result<bool, Ego
   Ok(ret)
 }
 
+#[update(name = "file_main_delete", guard = "user_guard")]
+#[candid_method(update, rename = "file_main_delete")]
+fn file_main_delete(fid: FileId) -> Result<bool, EgoError> {
+  info_log_add("ego-file: file_main_delete");
+
+  let ret = EgoFileService::file_main_delete(&fid)?;
+  Ok(ret)
+}
+
 #[query(name = "file_main_read", guard = "user_guard")]
 #[candid_method(query, rename = "file_main_read")]
 fn file_main_read(fid: FileId) -> Result<Vec<u8>, EgoError> {
@@ -10,6 +10,10 @@ impl EgoFileService {
     STORAGE.with(|s| s.borrow_mut().file_write(fid, hash, data))
   }
 
+  pub fn file_main_delete(fid: &FileId) -> Result<bool, EgoError> {
+    STORAGE.with(|s| s.borrow_mut().file_delete(fid))
+  }
+
   pub fn file_main_read(fid: &FileId) -> Result<Vec<u8>, EgoError> {
     STORAGE.with(|s| s.borrow().file_read(fid))
   }
@@ -27,6 +27,8 @@ pub struct Storage {
   pub length: u64,
   pub capacity: u64,
   pub files: BTreeMap<FileId, File>,
+  // slots of the deleted files, reused before new ones are taken
+  pub free_file_nums: Option<Vec<u64>>,
 }
 
 impl Storage {
@@ -35,6 +37,7 @@ impl Storage {
       length: st.length,
       capacity: st.capacity,
       files: st.files,
+      free_file_nums: st.free_file_nums,
     }
   }
 
@@ -43,6 +46,7 @@ impl Storage {
       length: 0,
       capacity: 0,
       files: BTreeMap::new(),
+      free_file_nums: None,
     }
   }
 
@@ -51,6 +55,10 @@ impl Storage {
   }
 
   fn next_file_num(&mut self) -> Result<u64, EgoError> {
+    if let Some(num) = self.free_file_nums.as_mut().and_then(|free_file_nums| free_file_nums.pop()) {
+      return Ok(num);
+    }
+
     if self.capacity >= self.length {
       if self.length + 10 > DEFAULT_MAX_FILES {
         return Err(EgoFileError::StorageFull.into());
@@ -120,6 +128,21 @@ impl Storage {
     Ok(true)
   }
 
+  /// Drops the file, its slot is handed to the next new file.
+  pub fn file_delete(&mut self, fid: &FileId) -> Result<bool, EgoError> {
+    match self.files.remove(fid) {
+      Some(file) => {
+        info_log_add(format!("==> delete file fid:{}, file_num: {}", fid, file.file_num).as_str());
+        self.free_file_nums.get_or_insert_with(Vec::new).push(file.file_num);
+        Ok(true)
+      }
+      None => {
+        error_log_add(format!("error deleting file fid:{}", fid).as_str());
+        Err(EgoFileError::FidNotFound.into())
+      }
+    }
+  }
+
   /// Reads file from stable memory.
   pub fn file_read(&self, fid: &FileId) -> Result<Vec<u8>, EgoError> {
     match self.files.get(fid) {
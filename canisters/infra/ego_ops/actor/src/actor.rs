@@ -121,11 +121,13 @@ pub fn canister_relation_update(name: String) {
         );
       }
       ego_canister.ego_canister_add(ego_dev_id, "ego_store".to_string(), ego_store_id);
-      ego_canister.ego_canister_add(
-        ego_dev_id,
-        "ego_tenant".to_string(),
-        ego_tenant_id.clone(),
-      );
+      for ego_tenant_id in ego_tenant_ids.iter() {
+        ego_canister.ego_canister_add(
+          ego_dev_id,
+          "ego_tenant".to_string(),
+          ego_tenant_id.clone(),
+        );
+      }
       ego_canister.ego_canister_add(
         ego_dev_id,
         "ego_record".to_string(),
@@ -186,11 +188,13 @@ pub fn canister_relation_update(name: String) {
     }
     "ego_ledger" => {
       ego_canister.ego_canister_add(ego_ledger_id, "ego_store".to_string(), ego_store_id);
-      ego_canister.ego_canister_add(
-        ego_ledger_id,
-        "ego_tenant".to_string(),
-        ego_tenant_id.clone(),
-      );
+      for ego_tenant_id in ego_tenant_ids.iter() {
+        ego_canister.ego_canister_add(
+          ego_ledger_id,
+          "ego_tenant".to_string(),
+          ego_tenant_id.clone(),
+        );
+      }
       ego_canister.ego_canister_add(
         ego_ledger_id,
         "ego_record".to_string(),
@@ -281,6 +285,23 @@ pub fn canister_main_track(name: String) {
   }
 }
 
+/// one-off migration, the tenants report their reserve to ego_store, which refills them. drops the
+/// tracking of the tenants by the tracker tenant set up by the earlier deploys
+#[update(name = "admin_tenant_untrack", guard = "owner_guard")]
+#[candid_method(update, rename = "admin_tenant_untrack")]
+pub fn admin_tenant_untrack() {
+  info_log_add("ego-ops: admin_tenant_untrack");
+
+  let wallet_id = id();
+  let ego_tenant = EgoTenantInner::new();
+
+  let tracker_ego_tenant_id = canister_get_one("ego_tenant").unwrap();
+
+  for ego_tenant_id in canister_get_all("ego_tenant") {
+    ego_tenant.canister_main_untrack(tracker_ego_tenant_id, wallet_id, ego_tenant_id);
+  }
+}
+
 #[update(name = "admin_app_create", guard = "owner_guard")]
 #[candid_method(update, rename = "admin_app_create")]
 pub fn admin_app_create(req: AdminAppCreateRequest) -> Result<(), EgoError> {
@@ -4,21 +4,33 @@ use candid::candid_method;
 use candid::Principal;
 use ego_backup::inject_backup_api;
 use ic_cdk::{caller, id};
+use ic_cdk::api::data_certificate;
 use ic_cdk_macros::*;
 use ic_ledger_types::Memo;
 
 use ego_lib::ego_canister::EgoCanister;
 use ego_macros::{inject_cycle_info_api, inject_ego_api};
 use ego_store_mod::backup::*;
+use ego_store_mod::c2c::alert_callback::AlertCallback;
+use ego_store_mod::c2c::c2c_types::StatusRecord;
 use ego_store_mod::c2c::ego_ledger::EgoLedger;
+use ego_store_mod::certification::entry_certify;
 use ego_store_mod::c2c::ego_tenant::EgoTenant as EgoTenantInner;
+use ego_store_mod::c2c::ic_management::IcManagement;
 use ego_store_mod::service::*;
 use ego_store_mod::state::*;
 use ego_store_mod::types::*;
+use ego_store_mod::types::alert::Alert;
+use ego_store_mod::types::app_audit::EgoStoreAppAudit;
+use ego_store_mod::types::app_line_release::AppLineRelease;
+use ego_store_mod::types::charge::Charge;
 use ego_store_mod::types::ego_store_app::EgoStoreApp;
 use ego_store_mod::types::order::Order;
+use ego_store_mod::types::review::Review;
+use ego_store_mod::types::tenant_metric::TenantMetric;
 use ego_store_mod::types::wallet_provider::WalletProvider;
-use ego_types::app::{App, AppId};
+use ego_store_mod::types::withdraw::Withdraw;
+use ego_types::app::{AppAuditReview, AppId, AppMetadata, Version};
 use ego_types::app::CashFlow;
 use ego_types::app::EgoError;
 use ego_types::app::UserApp;
@@ -55,23 +67,43 @@ fn post_upgrade() {
 }
 
 /********************  methods for wallet   ********************/
-#[update(name = "app_main_list")]
-#[candid_method(update, rename = "app_main_list")]
-pub fn app_main_list() -> Result<Vec<App>, EgoError> {
-  info_log_add("app_main_list");
-  Ok(EgoStoreService::app_main_list())
-}
-
-#[update(name = "app_main_get")]
-#[candid_method(update, rename = "app_main_get")]
-pub fn app_main_get(app_id: AppId) -> Result<App, EgoError> {
-  info_log_add("app_main_get");
-  match EgoStoreService::app_main_get(&app_id) {
-    Some(ego_store_app) => Ok(ego_store_app.app),
+#[query(name = "app_main_list")]
+#[candid_method(query, rename = "app_main_list")]
+pub fn app_main_list(request: Option<AppMainListRequest>) -> Result<Vec<AppEntry>, EgoError> {
+  let certificate = data_certificate();
+
+  // without a request every app is listed, in app id order
+  let entries = match request {
+    Some(request) => EgoStoreService::app_main_search(&request),
+    None => EgoStoreService::app_main_list().into_iter().map(AppEntry::from).collect(),
+  };
+
+  Ok(entries.into_iter().map(|entry| entry_certify(entry, &certificate)).collect())
+}
+
+#[query(name = "app_main_get")]
+#[candid_method(query, rename = "app_main_get")]
+pub fn app_main_get(app_id: AppId) -> Result<AppEntry, EgoError> {
+  let certificate = data_certificate();
+
+  match EgoStoreService::app_main_catalog_get(&app_id) {
+    Some(entry) => Ok(entry_certify(entry, &certificate)),
     None => Err(EgoError::from(EgoStoreErr::AppNotExists)),
   }
 }
 
+#[query(name = "app_main_audit_get")]
+#[candid_method(query, rename = "app_main_audit_get")]
+pub fn app_main_audit_get(app_id: AppId) -> Result<EgoStoreAppAudit, EgoError> {
+  EgoStoreAppAudit::get(&app_id).ok_or(EgoError::from(EgoStoreErr::AppNotExists))
+}
+
+#[query(name = "app_review_list")]
+#[candid_method(query, rename = "app_review_list")]
+pub fn app_review_list(app_id: AppId) -> Result<Vec<Review>, EgoError> {
+  Ok(EgoStoreService::app_review_list(&app_id))
+}
+
 #[update(name = "wallet_main_register")]
 #[candid_method(update, rename = "wallet_main_register")]
 pub fn wallet_main_register(user_id: Principal) -> Result<Principal, EgoError> {
@@ -84,10 +116,19 @@ pub fn wallet_main_register(user_id: Principal) -> Result<Principal, EgoError> {
   Ok(tenant_id)
 }
 
-#[update(name = "wallet_tenant_get")]
-#[candid_method(update, rename = "wallet_tenant_get")]
+#[update(name = "wallet_main_remove")]
+#[candid_method(update, rename = "wallet_main_remove")]
+pub fn wallet_main_remove() -> Result<(), EgoError> {
+  let wallet_id = caller();
+
+  info_log_add(format!("wallet_main_remove wallet_id: {}", wallet_id).as_str());
+
+  EgoStoreService::wallet_main_remove(&wallet_id)
+}
+
+#[query(name = "wallet_tenant_get")]
+#[candid_method(query, rename = "wallet_tenant_get")]
 pub fn wallet_tenant_get() -> Result<Principal, EgoError> {
-  info_log_add("wallet_tenant_get");
   let wallet_id = caller();
   match EgoStoreService::wallet_main_get(&wallet_id) {
     Ok(wallet) => Ok(wallet.tenant_id),
@@ -95,10 +136,9 @@ pub fn wallet_tenant_get() -> Result<Principal, EgoError> {
   }
 }
 
-#[update(name = "wallet_app_list")]
-#[candid_method(update, rename = "wallet_app_list")]
+#[query(name = "wallet_app_list")]
+#[candid_method(query, rename = "wallet_app_list")]
 pub fn wallet_app_list() -> Result<Vec<UserApp>, EgoError> {
-  info_log_add("wallet_app_list");
   let wallet_id = ic_cdk::caller();
 
   let user_apps = EgoStoreService::wallet_app_list(&wallet_id).iter().map(|user_app| {
@@ -107,6 +147,22 @@ pub fn wallet_app_list() -> Result<Vec<UserApp>, EgoError> {
   Ok(user_apps)
 }
 
+#[update(name = "wallet_app_review_set")]
+#[candid_method(update, rename = "wallet_app_review_set")]
+pub fn wallet_app_review_set(req: AppReviewSetRequest) -> Result<Review, EgoError> {
+  let wallet_id = caller();
+
+  info_log_add(format!("wallet_app_review_set wallet_id: {}, app_id: {}", wallet_id, req.app_id).as_str());
+
+  EgoStoreService::app_review_set(&wallet_id, &req.app_id, req.rating, req.content)
+}
+
+#[query(name = "wallet_app_review_get")]
+#[candid_method(query, rename = "wallet_app_review_get")]
+pub fn wallet_app_review_get(app_id: AppId) -> Result<Review, EgoError> {
+  Review::get(&app_id, &caller()).ok_or(EgoError::from(EgoStoreErr::ReviewNotExists))
+}
+
 #[update(name = "wallet_app_install")]
 #[candid_method(update, rename = "wallet_app_install")]
 pub async fn wallet_app_install(app_id: AppId) -> Result<UserApp, EgoError> {
@@ -122,7 +178,7 @@ pub async fn wallet_app_install(app_id: AppId) -> Result<UserApp, EgoError> {
   let ego_canister = EgoCanister::new();
 
   let user_app =
-    EgoStoreService::wallet_app_install(ego_tenant, ego_canister, &wallet_id, &app).await?;
+    EgoStoreService::wallet_app_install(ego_tenant, ego_canister, &wallet_id, &app, &None).await?;
 
   Ok(user_app.into())
 }
@@ -144,7 +200,7 @@ pub async fn wallet_app_install_v2(req: AppInstallRequest) -> Result<UserApp, Eg
   let ego_canister = EgoCanister::new();
 
   let user_app =
-    EgoStoreService::wallet_app_install(ego_tenant, ego_canister, &wallet_id, &app).await?;
+    EgoStoreService::wallet_app_install(ego_tenant, ego_canister, &wallet_id, &app, &req.version_req).await?;
 
   Ok(user_app.into())
 }
@@ -165,7 +221,7 @@ pub async fn wallet_app_upgrade(wallet_id: Principal) -> Result<(), EgoError> {
       .as_str(),
   );
 
-  EgoStoreService::wallet_app_upgrade(ego_tenant, ego_canister, &wallet_id, &canister_id).await?;
+  EgoStoreService::wallet_app_upgrade(ego_tenant, ego_canister, &wallet_id, &canister_id, &None).await?;
   Ok(())
 }
 
@@ -185,7 +241,7 @@ pub async fn wallet_app_upgrade_v2(req: AppUpgradeRequest) -> Result<(), EgoErro
       .as_str(),
   );
 
-  EgoStoreService::wallet_app_upgrade(ego_tenant, ego_canister, &wallet_id, &canister_id).await?;
+  EgoStoreService::wallet_app_upgrade(ego_tenant, ego_canister, &wallet_id, &canister_id, &req.version_req).await?;
   Ok(())
 }
 
@@ -205,7 +261,7 @@ pub async fn wallet_app_upgrade_by_wallet(canister_id: Principal) -> Result<(),
       .as_str(),
   );
 
-  EgoStoreService::wallet_app_upgrade(ego_tenant, ego_canister, &wallet_id, &canister_id).await?;
+  EgoStoreService::wallet_app_upgrade(ego_tenant, ego_canister, &wallet_id, &canister_id, &None).await?;
   Ok(())
 }
 
@@ -229,7 +285,7 @@ pub async fn wallet_app_upgrade_by_wallet_v2(req: WalletUpgradeAppRequest) -> Re
       .as_str(),
   );
 
-  EgoStoreService::wallet_app_upgrade(ego_tenant, ego_canister, &wallet_id, &canister_id).await?;
+  EgoStoreService::wallet_app_upgrade(ego_tenant, ego_canister, &wallet_id, &canister_id, &req.version_req).await?;
   Ok(())
 }
 
@@ -296,6 +352,61 @@ pub fn wallet_canister_track_self(wallet_id: Principal) -> Result<(), EgoError>
   EgoStoreService::wallet_canister_track(ego_tenant, &wallet_id, &canister_id)
 }
 
+#[update(name = "wallet_canister_top_up_policy_set")]
+#[candid_method(update, rename = "wallet_canister_top_up_policy_set")]
+pub async fn wallet_canister_top_up_policy_set(req: WalletCanisterTopUpPolicySetRequest) -> Result<(), EgoError> {
+  info_log_add("wallet_canister_top_up_policy_set");
+
+  let ego_tenant = EgoTenantInner::new();
+  let wallet_id = caller();
+
+  EgoStoreService::wallet_canister_top_up_policy_set(ego_tenant, &wallet_id, &req.canister_id, req.policy).await
+}
+
+#[update(name = "wallet_canister_status_get")]
+#[candid_method(update, rename = "wallet_canister_status_get")]
+pub async fn wallet_canister_status_get(canister_id: Principal) -> Result<StatusRecord, EgoError> {
+  info_log_add("wallet_canister_status_get");
+
+  let ego_tenant = EgoTenantInner::new();
+  let wallet_id = caller();
+
+  EgoStoreService::wallet_canister_status_get(ego_tenant, &wallet_id, &canister_id).await
+}
+
+#[update(name = "wallet_canister_start")]
+#[candid_method(update, rename = "wallet_canister_start")]
+pub async fn wallet_canister_start(canister_id: Principal) -> Result<(), EgoError> {
+  info_log_add("wallet_canister_start");
+
+  let ego_tenant = EgoTenantInner::new();
+  let wallet_id = caller();
+
+  EgoStoreService::wallet_canister_start(ego_tenant, &wallet_id, &canister_id).await
+}
+
+#[update(name = "wallet_canister_stop")]
+#[candid_method(update, rename = "wallet_canister_stop")]
+pub async fn wallet_canister_stop(canister_id: Principal) -> Result<(), EgoError> {
+  info_log_add("wallet_canister_stop");
+
+  let ego_tenant = EgoTenantInner::new();
+  let wallet_id = caller();
+
+  EgoStoreService::wallet_canister_stop(ego_tenant, &wallet_id, &canister_id).await
+}
+
+#[update(name = "wallet_canister_settings_update")]
+#[candid_method(update, rename = "wallet_canister_settings_update")]
+pub async fn wallet_canister_settings_update(req: WalletCanisterSettingsUpdateRequest) -> Result<(), EgoError> {
+  info_log_add("wallet_canister_settings_update");
+
+  let ego_tenant = EgoTenantInner::new();
+  let wallet_id = caller();
+
+  EgoStoreService::wallet_canister_settings_update(ego_tenant, &wallet_id, &req.canister_id, req.settings).await
+}
+
 #[update(name = "wallet_canister_untrack")]
 #[candid_method(update, rename = "wallet_canister_untrack")]
 pub fn wallet_canister_untrack(canister_id: Principal) -> Result<(), EgoError> {
@@ -318,16 +429,22 @@ pub fn wallet_canister_untrack_self(wallet_id: Principal) -> Result<(), EgoError
   EgoStoreService::wallet_canister_untrack(ego_tenant, &wallet_id, &canister_id)
 }
 
-#[update(name = "wallet_order_list")]
-#[candid_method(update, rename = "wallet_order_list")]
+#[query(name = "wallet_order_list")]
+#[candid_method(query, rename = "wallet_order_list")]
 pub fn wallet_order_list() -> Result<Vec<Order>, EgoError> {
-  info_log_add("wallet_order_list");
-
   let wallet_id = caller();
 
   Ok(EgoStoreService::wallet_order_list(&wallet_id))
 }
 
+#[query(name = "wallet_order_list_v2")]
+#[candid_method(query, rename = "wallet_order_list_v2")]
+pub fn wallet_order_list_v2(req: WalletOrderListRequest) -> Result<WalletOrderListResponse, EgoError> {
+  let wallet_id = caller();
+
+  Ok(EgoStoreService::wallet_order_page(&wallet_id, &req))
+}
+
 #[update(name = "wallet_order_new")]
 #[candid_method(update, rename = "wallet_order_new")]
 pub async fn wallet_order_new(amount: f32) -> Result<Memo, EgoError> {
@@ -348,27 +465,17 @@ pub async fn wallet_order_new(amount: f32) -> Result<Memo, EgoError> {
   }
 }
 
-#[update(name = "wallet_cycle_balance")]
-#[candid_method(update, rename = "wallet_cycle_balance")]
+#[query(name = "wallet_cycle_balance")]
+#[candid_method(query, rename = "wallet_cycle_balance")]
 pub fn wallet_cycle_balance() -> Result<u128, EgoError> {
-  info_log_add("wallet_cycle_balance");
-
   let wallet_id = caller();
 
-  match EgoStoreService::wallet_cycle_balance(&wallet_id) {
-    Ok(balance) => Ok(balance),
-    Err(e) => {
-      info_log_add(&format!("wallet_cycle_list {:?}", e));
-      Err(e)
-    }
-  }
+  EgoStoreService::wallet_cycle_balance(&wallet_id)
 }
 
-#[update(name = "wallet_cycle_list")]
-#[candid_method(update, rename = "wallet_cycle_list")]
+#[query(name = "wallet_cycle_list")]
+#[candid_method(query, rename = "wallet_cycle_list")]
 pub fn wallet_cycle_list() -> Result<Vec<CashFlow>, EgoError> {
-  info_log_add("wallet_cycle_list");
-
   let wallet_id = caller();
 
   let cash_flows = EgoStoreService::wallet_cash_flow_list(&wallet_id);
@@ -376,6 +483,78 @@ pub fn wallet_cycle_list() -> Result<Vec<CashFlow>, EgoError> {
   Ok(cash_flows.iter().map(|cash_flow| cash_flow.clone().into()).collect())
 }
 
+#[update(name = "wallet_cycle_transfer")]
+#[candid_method(update, rename = "wallet_cycle_transfer")]
+pub fn wallet_cycle_transfer(req: WalletCycleTransferRequest) -> Result<(), EgoError> {
+  info_log_add("wallet_cycle_transfer");
+
+  let wallet_id = caller();
+
+  EgoStoreService::wallet_cycle_transfer(
+    &wallet_id,
+    &req.to_wallet,
+    req.amount,
+    req.same_user_only,
+    &wallet_id,
+    req.comment,
+  )
+}
+
+#[update(name = "wallet_cycle_withdraw")]
+#[candid_method(update, rename = "wallet_cycle_withdraw")]
+pub async fn wallet_cycle_withdraw(req: WalletCycleWithdrawRequest) -> Result<Withdraw, EgoError> {
+  info_log_add("wallet_cycle_withdraw");
+
+  let wallet_id = caller();
+  let ic_management = IcManagement::new();
+
+  EgoStoreService::wallet_cycle_withdraw(
+    ic_management,
+    &wallet_id,
+    &req.target_id,
+    req.amount,
+    &wallet_id,
+    req.comment,
+  )
+    .await
+}
+
+#[query(name = "wallet_cycle_list_v2")]
+#[candid_method(query, rename = "wallet_cycle_list_v2")]
+pub fn wallet_cycle_list_v2(req: WalletCashFlowListRequest) -> Result<WalletCashFlowListResponse, EgoError> {
+  let wallet_id = caller();
+
+  Ok(EgoStoreService::wallet_cash_flow_page(&wallet_id, &req))
+}
+
+#[query(name = "wallet_alert_list")]
+#[candid_method(query, rename = "wallet_alert_list")]
+pub fn wallet_alert_list(unacknowledged_only: bool) -> Result<Vec<Alert>, EgoError> {
+  let wallet_id = caller();
+
+  Ok(EgoStoreService::wallet_alert_list(&wallet_id, unacknowledged_only))
+}
+
+#[update(name = "wallet_alert_ack")]
+#[candid_method(update, rename = "wallet_alert_ack")]
+pub fn wallet_alert_ack(alert_ids: Vec<u64>) -> Result<Vec<Alert>, EgoError> {
+  info_log_add("wallet_alert_ack");
+
+  let wallet_id = caller();
+
+  EgoStoreService::wallet_alert_ack(&wallet_id, &alert_ids)
+}
+
+#[update(name = "wallet_alert_subscribe")]
+#[candid_method(update, rename = "wallet_alert_subscribe")]
+pub fn wallet_alert_subscribe(callback_id: Option<Principal>) -> Result<(), EgoError> {
+  info_log_add("wallet_alert_subscribe");
+
+  let wallet_id = caller();
+
+  EgoStoreService::wallet_alert_subscribe(&wallet_id, callback_id)
+}
+
 /********************  methods for ego_tenant  ********************/
 #[update(name = "wallet_cycle_charge", guard = "user_guard")]
 #[candid_method(update, rename = "wallet_cycle_charge")]
@@ -387,17 +566,88 @@ pub fn wallet_cycle_charge(
   // the tenant id or something else
   let operator = caller();
 
+  // final on return, nothing to commit or release
   match EgoStoreService::canister_cycle_charge(
     &request.canister_id,
     request.cycle,
     &operator,
     request.comment,
   ) {
-    Ok(_) => Ok(WalletCycleChargeResponse { ret: true }),
+    Ok(charge) => Ok(WalletCycleChargeResponse { ret: true, charge_id: charge.id }),
     Err(e) => Err(e),
   }
 }
 
+#[update(name = "wallet_cycle_reserve", guard = "user_guard")]
+#[candid_method(update, rename = "wallet_cycle_reserve")]
+pub fn wallet_cycle_reserve(
+  request: WalletCycleChargeRequest,
+) -> Result<WalletCycleReserveResponse, EgoError> {
+  info_log_add("wallet_cycle_reserve");
+
+  // the tenant topping up the canister
+  let operator = caller();
+
+  let charge = EgoStoreService::canister_cycle_reserve(
+    &request.canister_id,
+    request.cycle,
+    &operator,
+    request.comment,
+  )?;
+  Ok(WalletCycleReserveResponse { charge_id: charge.id })
+}
+
+#[update(name = "wallet_cycle_commit", guard = "user_guard")]
+#[candid_method(update, rename = "wallet_cycle_commit")]
+pub fn wallet_cycle_commit(charge_id: u64) -> Result<(), EgoError> {
+  info_log_add(format!("wallet_cycle_commit, charge_id: {}", charge_id).as_str());
+
+  EgoStoreService::charge_commit(charge_id, &caller())?;
+  Ok(())
+}
+
+#[update(name = "wallet_cycle_release", guard = "user_guard")]
+#[candid_method(update, rename = "wallet_cycle_release")]
+pub fn wallet_cycle_release(charge_id: u64) -> Result<(), EgoError> {
+  info_log_add(format!("wallet_cycle_release, charge_id: {}", charge_id).as_str());
+
+  EgoStoreService::charge_release(charge_id, &caller())?;
+  Ok(())
+}
+
+#[update(name = "canister_alert_add", guard = "user_guard")]
+#[candid_method(update, rename = "canister_alert_add")]
+pub fn canister_alert_add(req: CanisterAlertAddRequest) -> Result<(), EgoError> {
+  info_log_add(format!("canister_alert_add, canister_id: {}, kind: {:?}", req.canister_id, req.kind).as_str());
+
+  let alert_callback = AlertCallback::new();
+
+  EgoStoreService::canister_alert_add(&alert_callback, &req.canister_id, req.kind, &req.message)?;
+  Ok(())
+}
+
+#[update(name = "tenant_metric_report", guard = "user_guard")]
+#[candid_method(update, rename = "tenant_metric_report")]
+pub async fn tenant_metric_report(req: TenantMetricReportRequest) -> Result<(), EgoError> {
+  info_log_add("tenant_metric_report");
+
+  let tenant_id = caller();
+
+  EgoStoreService::tenant_metric_report(&tenant_id, req.task_count, req.dead_task_count, req.cycles, req.spend_per_day, req.reserve_threshold)?;
+
+  // the report stands, the refill and the reconciliation are retried with the next one
+  let ic_management = IcManagement::new();
+  if let Err(e) = EgoStoreService::tenant_refill(ic_management, &tenant_id).await {
+    error_log_add(format!("tenant_metric_report: refill of tenant {} failed: {}", tenant_id, e.msg).as_str());
+  }
+
+  let ego_tenant = EgoTenantInner::new();
+  if let Err(e) = EgoStoreService::charge_reconcile(ego_tenant, &tenant_id).await {
+    error_log_add(format!("tenant_metric_report: charges of tenant {} not reconciled: {}", tenant_id, e.msg).as_str());
+  }
+  Ok(())
+}
+
 /********************  methods for ego_dev  ********************/
 #[update(name = "app_main_release", guard = "user_guard")]
 #[candid_method(update, rename = "app_main_release")]
@@ -410,6 +660,63 @@ pub async fn app_main_release(mut app: EgoStoreApp) -> Result<bool, EgoError> {
   }
 }
 
+#[update(name = "app_line_release", guard = "user_guard")]
+#[candid_method(update, rename = "app_line_release")]
+pub fn app_line_release(mut app_line_release: AppLineRelease) -> Result<bool, EgoError> {
+  info_log_add(format!("app_line_release, app_id {}", app_line_release.app_id).as_str());
+
+  EgoStoreService::app_line_release(&mut app_line_release)
+}
+
+#[update(name = "app_main_metadata_set", guard = "user_guard")]
+#[candid_method(update, rename = "app_main_metadata_set")]
+pub fn app_main_metadata_set(app_id: AppId, metadata: AppMetadata) -> Result<bool, EgoError> {
+  info_log_add(format!("app_main_metadata_set, app_id {}", app_id).as_str());
+
+  EgoStoreService::app_main_metadata_set(&app_id, &metadata);
+  Ok(true)
+}
+
+#[update(name = "app_main_audit_set", guard = "user_guard")]
+#[candid_method(update, rename = "app_main_audit_set")]
+pub fn app_main_audit_set(app_id: AppId, version: Version, reviews: Vec<AppAuditReview>) -> Result<bool, EgoError> {
+  info_log_add(format!("app_main_audit_set, app_id {}, version {}", app_id, version.to_string()).as_str());
+
+  EgoStoreService::app_main_audit_set(&app_id, &version, reviews);
+  Ok(true)
+}
+
+#[update(name = "app_main_developer_verified_set", guard = "user_guard")]
+#[candid_method(update, rename = "app_main_developer_verified_set")]
+pub fn app_main_developer_verified_set(app_ids: Vec<AppId>, verified: bool) -> Result<bool, EgoError> {
+  info_log_add(format!("app_main_developer_verified_set, verified {}", verified).as_str());
+
+  EgoStoreService::app_main_developer_verified_set(&app_ids, verified);
+  Ok(true)
+}
+
+#[update(name = "app_review_reply", guard = "user_guard")]
+#[candid_method(update, rename = "app_review_reply")]
+pub fn app_review_reply(app_id: AppId, wallet_id: Principal, reply: String) -> Result<Review, EgoError> {
+  info_log_add(format!("app_review_reply, app_id {}, wallet_id {}", app_id, wallet_id).as_str());
+
+  EgoStoreService::app_review_reply(&app_id, &wallet_id, reply)
+}
+
+#[update(name = "app_review_hide", guard = "user_guard")]
+#[candid_method(update, rename = "app_review_hide")]
+pub fn app_review_hide(app_id: AppId, wallet_id: Principal, hidden: bool) -> Result<Review, EgoError> {
+  info_log_add(format!("app_review_hide, app_id {}, wallet_id {}, hidden {}", app_id, wallet_id, hidden).as_str());
+
+  EgoStoreService::app_review_hide(&app_id, &wallet_id, hidden)
+}
+
+#[query(name = "app_main_stats", guard = "user_guard")]
+#[candid_method(query, rename = "app_main_stats")]
+pub fn app_main_stats(req: AppStatsRequest) -> Result<AppStatsResponse, EgoError> {
+  EgoStoreService::app_main_stats(&req.app_id, req.from_day, req.to_day)
+}
+
 /********************  methods for ego-ledger callback  ********************/
 #[update(name = "wallet_order_notify", guard = "user_guard")]
 #[candid_method(update, rename = "wallet_order_notify")]
@@ -547,6 +854,62 @@ pub fn admin_wallet_app_transfer(wallet_id: Principal, canister_id: Principal) -
   Ok(())
 }
 
+#[update(name = "admin_tenant_list", guard = "owner_guard")]
+#[candid_method(update, rename = "admin_tenant_list")]
+pub fn admin_tenant_list() -> Result<Vec<TenantInfo>, EgoError> {
+  info_log_add("admin_tenant_list");
+
+  Ok(EgoStoreService::admin_tenant_list())
+}
+
+#[update(name = "admin_charge_pending_list", guard = "owner_guard")]
+#[candid_method(update, rename = "admin_charge_pending_list")]
+pub fn admin_charge_pending_list() -> Result<Vec<Charge>, EgoError> {
+  info_log_add("admin_charge_pending_list");
+
+  Ok(EgoStoreService::admin_charge_pending_list())
+}
+
+#[update(name = "admin_tenant_capacity_set", guard = "owner_guard")]
+#[candid_method(update, rename = "admin_tenant_capacity_set")]
+pub fn admin_tenant_capacity_set(req: AdminTenantCapacitySetRequest) -> Result<TenantMetric, EgoError> {
+  info_log_add(format!("admin_tenant_capacity_set tenant_id: {}, capacity: {}", req.tenant_id, req.capacity).as_str());
+
+  EgoStoreService::admin_tenant_capacity_set(&req.tenant_id, req.capacity)
+}
+
+#[update(name = "admin_wallet_tenant_migrate", guard = "owner_guard")]
+#[candid_method(update, rename = "admin_wallet_tenant_migrate")]
+pub async fn admin_wallet_tenant_migrate(wallet_id: Principal, tenant_id: Principal) -> Result<(), EgoError> {
+  info_log_add(format!("admin_wallet_tenant_migrate wallet_id: {}, tenant_id: {}", wallet_id, tenant_id).as_str());
+
+  let ego_tenant = EgoTenantInner::new();
+  let ego_canister = EgoCanister::new();
+
+  EgoStoreService::admin_wallet_tenant_migrate(ego_tenant, ego_canister, &wallet_id, &tenant_id).await
+}
+
+#[query(name = "admin_app_install_count", guard = "owner_guard")]
+#[candid_method(query, rename = "admin_app_install_count")]
+pub fn admin_app_install_count(app_id: AppId) -> Result<u64, EgoError> {
+  Ok(EgoStoreService::admin_app_install_count(&app_id))
+}
+
+#[query(name = "admin_app_version_distribution", guard = "owner_guard")]
+#[candid_method(query, rename = "admin_app_version_distribution")]
+pub fn admin_app_version_distribution(app_id: AppId) -> Result<Vec<AppVersionCount>, EgoError> {
+  Ok(EgoStoreService::admin_app_version_distribution(&app_id))
+}
+
+#[query(name = "admin_app_user_app_list", guard = "owner_guard")]
+#[candid_method(query, rename = "admin_app_user_app_list")]
+pub fn admin_app_user_app_list(app_id: AppId, version: Option<Version>) -> Result<Vec<UserApp>, EgoError> {
+  let user_apps = EgoStoreService::admin_app_user_app_list(&app_id, version).into_iter().map(|user_app| {
+    user_app.into()
+  }).collect();
+  Ok(user_apps)
+}
+
 
 /********************  methods for ego_cycle_threshold_get   ********************/
 pub fn cycle_threshold_get() -> u128 {
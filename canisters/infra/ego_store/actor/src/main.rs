@@ -7,14 +7,17 @@ fn main() {}
 #[allow(dead_code)]
 #[cfg(not(any(target_arch = "wasm32", test)))]
 fn main() {
+  use ego_store_mod::types::app_audit::EgoStoreAppAudit;
   use ego_store_mod::types::ego_store_app::EgoStoreApp;
   use ego_store_mod::types::wallet_provider::WalletProvider;
   use ego_store_mod::types::order::Order;
+  use ego_store_mod::types::review::Review;
+  use ego_store_mod::types::withdraw::Withdraw;
   use ego_store_mod::types::*;
   use ego_types::app::EgoError;
   use ego_types::app::UserApp;
   use ego_types::types::*;
-  use ego_types::app::{App, AppId, CashFlow};
+  use ego_types::app::{App, AppAuditReview, AppId, AppMetadata, CashFlow, Version};
   use ego_types::cycle_info::*;
   use candid::Principal;
   use std::collections::BTreeMap;
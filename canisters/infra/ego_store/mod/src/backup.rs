@@ -6,14 +6,24 @@ use ic_cdk::trap;
 use ego_utils::util::get_md5;
 
 use crate::state::{backup_info_pre_upgrade, cycle_info_pre_upgrade, info_log_add, registry_pre_upgrade, seq_pre_upgrade, users_pre_upgrade};
+use crate::types::alert::Alert;
+use crate::types::alert_subscription::AlertSubscription;
+use crate::types::app_audit::EgoStoreAppAudit;
+use crate::types::app_line_release::AppLineRelease;
+use crate::types::app_metadata::EgoStoreAppMetadata;
+use crate::types::app_stat::AppStat;
 use crate::types::cash_flow::CashFlow;
+use crate::types::charge::Charge;
 use crate::types::ego_store_app::EgoStoreApp;
 use crate::types::order::Order;
+use crate::types::review::Review;
 use crate::types::stable_state::StableState;
 use crate::types::tenant::Tenant;
+use crate::types::tenant_metric::TenantMetric;
 use crate::types::user_app::UserApp;
 use crate::types::wallet::Wallet;
 use crate::types::wallet_provider::WalletProvider;
+use crate::types::withdraw::Withdraw;
 
 pub fn job_list() -> Vec<BackupJob> {
   let mut jobs = vec![];
@@ -58,6 +68,56 @@ pub fn job_list() -> Vec<BackupJob> {
     amount: CashFlow::len() as usize,
   });
 
+  jobs.push(BackupJob {
+    name: "withdraws".to_string(),
+    amount: Withdraw::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "app_metadatas".to_string(),
+    amount: EgoStoreAppMetadata::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "reviews".to_string(),
+    amount: Review::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "app_stats".to_string(),
+    amount: AppStat::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "app_audits".to_string(),
+    amount: EgoStoreAppAudit::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "alerts".to_string(),
+    amount: Alert::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "alert_subscriptions".to_string(),
+    amount: AlertSubscription::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "tenant_metrics".to_string(),
+    amount: TenantMetric::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "charges".to_string(),
+    amount: Charge::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "app_line_releases".to_string(),
+    amount: AppLineRelease::len() as usize,
+  });
+
   jobs
 }
 
@@ -105,6 +165,46 @@ pub fn record_export(name: String, start: usize, end: usize, last_update: u64) -
 
       get_json_result(&records)
     }
+    "withdraws" => {
+      let records = Withdraw::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "app_metadatas" => {
+      let records = EgoStoreAppMetadata::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "reviews" => {
+      let records = Review::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "app_stats" => {
+      let records = AppStat::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "app_audits" => {
+      let records = EgoStoreAppAudit::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "alerts" => {
+      let records = Alert::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "alert_subscriptions" => {
+      let records = AlertSubscription::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "tenant_metrics" => {
+      let records = TenantMetric::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "charges" => {
+      let records = Charge::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "app_line_releases" => {
+      let records = AppLineRelease::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
     _ => trap("no job matched")
   };
 
@@ -164,6 +264,46 @@ pub fn data_backup(name: String, start: usize, end: usize) -> Option<ByteReadRes
 
       get_bin_result(&records)
     }
+    "withdraws" => {
+      let records = Withdraw::list(start, end);
+      get_bin_result(&records)
+    }
+    "app_metadatas" => {
+      let records = EgoStoreAppMetadata::list(start, end);
+      get_bin_result(&records)
+    }
+    "reviews" => {
+      let records = Review::list(start, end);
+      get_bin_result(&records)
+    }
+    "app_stats" => {
+      let records = AppStat::list(start, end);
+      get_bin_result(&records)
+    }
+    "app_audits" => {
+      let records = EgoStoreAppAudit::list(start, end);
+      get_bin_result(&records)
+    }
+    "alerts" => {
+      let records = Alert::list(start, end);
+      get_bin_result(&records)
+    }
+    "alert_subscriptions" => {
+      let records = AlertSubscription::list(start, end);
+      get_bin_result(&records)
+    }
+    "tenant_metrics" => {
+      let records = TenantMetric::list(start, end);
+      get_bin_result(&records)
+    }
+    "charges" => {
+      let records = Charge::list(start, end);
+      get_bin_result(&records)
+    }
+    "app_line_releases" => {
+      let records = AppLineRelease::list(start, end);
+      get_bin_result(&records)
+    }
     _ => trap("no job matched")
   };
 
@@ -234,6 +374,76 @@ pub fn data_restore(name: String, data: Vec<u8>) -> Result<(), String> {
         record.save();
       })
     }
+    "withdraws" => {
+      let mut records: Vec<Withdraw> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "app_metadatas" => {
+      let mut records: Vec<EgoStoreAppMetadata> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "reviews" => {
+      let mut records: Vec<Review> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "app_stats" => {
+      let mut records: Vec<AppStat> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "app_audits" => {
+      let mut records: Vec<EgoStoreAppAudit> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "alerts" => {
+      let mut records: Vec<Alert> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "alert_subscriptions" => {
+      let mut records: Vec<AlertSubscription> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "tenant_metrics" => {
+      let mut records: Vec<TenantMetric> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "charges" => {
+      let mut records: Vec<Charge> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "app_line_releases" => {
+      let mut records: Vec<AppLineRelease> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
     _ => trap("no job matched")
   };
 
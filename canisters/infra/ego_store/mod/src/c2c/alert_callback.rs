@@ -0,0 +1,23 @@
+use candid::Principal;
+use ic_cdk::api;
+
+use crate::types::alert::Alert;
+
+/// the canister a wallet subscribed to its alerts
+pub trait TAlertCallback {
+  fn ego_alert_notify(&self, callback_id: Principal, alert: &Alert);
+}
+
+pub struct AlertCallback {}
+
+impl AlertCallback {
+  pub fn new() -> Self {
+    AlertCallback {}
+  }
+}
+
+impl TAlertCallback for AlertCallback {
+  fn ego_alert_notify(&self, callback_id: Principal, alert: &Alert) {
+    let _result = api::call::notify(callback_id, "ego_alert_notify", (alert.clone(), ));
+  }
+}
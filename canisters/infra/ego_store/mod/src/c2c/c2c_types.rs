@@ -32,3 +32,50 @@ pub struct AppMainReInstallRequest {
   pub canister_id: Principal,
   pub wasm: Wasm,
 }
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct TopUpPolicy {
+  pub min_balance: Option<u128>,
+  pub target_balance: Option<u128>,
+  pub max_daily_top_up: Option<u128>,
+  pub alert_only: bool,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct CanisterTopUpPolicySetRequest {
+  pub canister_id: Principal,
+  pub policy: Option<TopUpPolicy>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum CanisterRunStatus {
+  RUNNING,
+  STOPPING,
+  STOPPED,
+}
+
+// canister_status as recorded by ego_tenant
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct StatusRecord {
+  pub ts: u64,
+  pub status: CanisterRunStatus,
+  pub cycles: u128,
+  pub memory_size: u64,
+  pub idle_burn_per_day: u128,
+  pub freezing_threshold: u64,
+  pub memory_allocation: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct CanisterSettingsUpdate {
+  pub compute_allocation: Option<u64>,
+  pub memory_allocation: Option<u64>,
+  pub freezing_threshold: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct CanisterSettingsUpdateRequest {
+  pub canister_id: Principal,
+  pub settings: CanisterSettingsUpdate,
+}
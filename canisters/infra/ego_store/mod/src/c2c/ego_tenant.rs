@@ -5,7 +5,7 @@ use ic_cdk::{api, trap};
 use ego_types::app::EgoError;
 use ego_types::app::Wasm;
 
-use crate::c2c::c2c_types::{AppMainInstallRequest, AppMainReInstallRequest, AppMainUpgradeRequest};
+use crate::c2c::c2c_types::{AppMainInstallRequest, AppMainReInstallRequest, AppMainUpgradeRequest, CanisterSettingsUpdate, CanisterSettingsUpdateRequest, CanisterTopUpPolicySetRequest, StatusRecord, TopUpPolicy};
 
 #[async_trait]
 pub trait TEgoTenant {
@@ -35,6 +35,32 @@ pub trait TEgoTenant {
   );
   fn canister_main_untrack(&self, ego_tenant_id: Principal, canister_id: &Principal);
   fn app_main_delete(&self, ego_tenant_id: Principal, canister_id: &Principal);
+  async fn canister_top_up_policy_set(
+    &self,
+    ego_tenant_id: Principal,
+    canister_id: Principal,
+    policy: Option<TopUpPolicy>,
+  ) -> Result<(), EgoError>;
+  async fn canister_top_up_policy_get(
+    &self,
+    ego_tenant_id: Principal,
+    canister_id: Principal,
+  ) -> Result<Option<TopUpPolicy>, EgoError>;
+  async fn canister_main_status(
+    &self,
+    ego_tenant_id: Principal,
+    canister_id: Principal,
+  ) -> Result<StatusRecord, EgoError>;
+  async fn canister_main_start(&self, ego_tenant_id: Principal, canister_id: Principal) -> Result<(), EgoError>;
+  async fn canister_main_stop(&self, ego_tenant_id: Principal, canister_id: Principal) -> Result<(), EgoError>;
+  async fn canister_settings_update(
+    &self,
+    ego_tenant_id: Principal,
+    canister_id: Principal,
+    settings: CanisterSettingsUpdate,
+  ) -> Result<(), EgoError>;
+  // the charges of the ids the tenant logged a top up for
+  async fn top_up_list(&self, ego_tenant_id: Principal, charge_ids: Vec<u64>) -> Result<Vec<u64>, EgoError>;
 }
 
 pub struct EgoTenant {}
@@ -146,4 +172,122 @@ impl TEgoTenant for EgoTenant {
   fn app_main_delete(&self, ego_tenant_id: Principal, canister_id: &Principal) {
     let _result = api::call::notify(ego_tenant_id, "app_main_delete", (canister_id, ));
   }
+
+  async fn canister_top_up_policy_set(
+    &self,
+    ego_tenant_id: Principal,
+    canister_id: Principal,
+    policy: Option<TopUpPolicy>,
+  ) -> Result<(), EgoError> {
+    let req = CanisterTopUpPolicySetRequest {
+      canister_id,
+      policy,
+    };
+
+    let call_result = api::call::call(ego_tenant_id, "canister_top_up_policy_set", (req, )).await
+      as Result<(Result<(), EgoError>, ), _>;
+
+    match call_result {
+      Ok(resp) => resp.0,
+      Err((code, msg)) => {
+        let code = code as u16;
+        Err(EgoError { code, msg })
+      }
+    }
+  }
+  async fn canister_top_up_policy_get(
+    &self,
+    ego_tenant_id: Principal,
+    canister_id: Principal,
+  ) -> Result<Option<TopUpPolicy>, EgoError> {
+    let call_result = api::call::call(ego_tenant_id, "canister_top_up_policy_get", (canister_id, )).await
+      as Result<(Result<Option<TopUpPolicy>, EgoError>, ), _>;
+
+    match call_result {
+      Ok(resp) => resp.0,
+      Err((code, msg)) => {
+        let code = code as u16;
+        Err(EgoError { code, msg })
+      }
+    }
+  }
+
+  async fn canister_main_status(
+    &self,
+    ego_tenant_id: Principal,
+    canister_id: Principal,
+  ) -> Result<StatusRecord, EgoError> {
+    let call_result = api::call::call(ego_tenant_id, "canister_main_status", (canister_id, )).await
+      as Result<(Result<StatusRecord, EgoError>, ), _>;
+
+    match call_result {
+      Ok(resp) => resp.0,
+      Err((code, msg)) => {
+        let code = code as u16;
+        Err(EgoError { code, msg })
+      }
+    }
+  }
+
+  async fn canister_main_start(&self, ego_tenant_id: Principal, canister_id: Principal) -> Result<(), EgoError> {
+    let call_result = api::call::call(ego_tenant_id, "canister_main_start", (canister_id, )).await
+      as Result<(Result<(), EgoError>, ), _>;
+
+    match call_result {
+      Ok(resp) => resp.0,
+      Err((code, msg)) => {
+        let code = code as u16;
+        Err(EgoError { code, msg })
+      }
+    }
+  }
+
+  async fn canister_main_stop(&self, ego_tenant_id: Principal, canister_id: Principal) -> Result<(), EgoError> {
+    let call_result = api::call::call(ego_tenant_id, "canister_main_stop", (canister_id, )).await
+      as Result<(Result<(), EgoError>, ), _>;
+
+    match call_result {
+      Ok(resp) => resp.0,
+      Err((code, msg)) => {
+        let code = code as u16;
+        Err(EgoError { code, msg })
+      }
+    }
+  }
+
+  async fn canister_settings_update(
+    &self,
+    ego_tenant_id: Principal,
+    canister_id: Principal,
+    settings: CanisterSettingsUpdate,
+  ) -> Result<(), EgoError> {
+    let req = CanisterSettingsUpdateRequest {
+      canister_id,
+      settings,
+    };
+
+    let call_result = api::call::call(ego_tenant_id, "canister_settings_update", (req, )).await
+      as Result<(Result<(), EgoError>, ), _>;
+
+    match call_result {
+      Ok(resp) => resp.0,
+      Err((code, msg)) => {
+        let code = code as u16;
+        Err(EgoError { code, msg })
+      }
+    }
+  }
+
+  async fn top_up_list(&self, ego_tenant_id: Principal, charge_ids: Vec<u64>) -> Result<Vec<u64>, EgoError> {
+    let call_result = api::call::call(ego_tenant_id, "top_up_list", (charge_ids, )).await
+      as Result<(Result<Vec<u64>, EgoError>, ), _>;
+
+    match call_result {
+      Ok(resp) => resp.0,
+      Err((code, msg)) => {
+        let code = code as u16;
+        Err(EgoError { code, msg })
+      }
+    }
+  }
 }
@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use candid::Principal;
+use ic_cdk::api;
+
+use ego_types::app::EgoError;
+use ego_utils::ic_management::{canister_cycle_top_up, Cycles};
+
+#[async_trait]
+pub trait TIcManagement {
+  async fn canister_cycle_top_up(
+    &self,
+    canister_id: Principal,
+    cycles_to_use: Cycles,
+  ) -> Result<(), EgoError>;
+
+  // the balance of ego_store itself
+  fn canister_balance(&self) -> u128;
+}
+
+#[derive(Clone)]
+pub struct IcManagement {}
+
+impl IcManagement {
+  pub fn new() -> Self {
+    IcManagement {}
+  }
+}
+
+#[async_trait]
+impl TIcManagement for IcManagement {
+  async fn canister_cycle_top_up(
+    &self,
+    canister_id: Principal,
+    cycles_to_use: Cycles,
+  ) -> Result<(), EgoError> {
+    canister_cycle_top_up(canister_id, cycles_to_use).await
+  }
+
+  fn canister_balance(&self) -> u128 {
+    api::canister_balance128()
+  }
+}
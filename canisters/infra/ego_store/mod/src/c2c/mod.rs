@@ -1,3 +1,5 @@
+pub mod alert_callback;
 pub mod c2c_types;
 pub mod ego_ledger;
 pub mod ego_tenant;
+pub mod ic_management;
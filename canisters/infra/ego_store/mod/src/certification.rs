@@ -0,0 +1,67 @@
+/********************  certified catalog data  ********************/
+// every released app is hashed into a merkle tree, the labeled root hash is set as the
+// canister certified data, so each app returned by the app_main_list / app_main_get queries can
+// be verified by the caller against the certificate signed by the subnet.
+//
+// leaf: sha256 of the candid encoded `App`, keyed by app_id, under the label "apps"
+use std::cell::RefCell;
+
+use candid::Encode;
+use ic_certified_map::{AsHashTree, Hash, labeled, labeled_hash, RbTree};
+use serde::Serialize;
+use sha2::Digest;
+
+use ego_types::app::{App, AppId};
+
+use crate::types::AppEntry;
+
+const APPS_LABEL: &[u8] = b"apps";
+
+thread_local! {
+    static APP_HASHES: RefCell<RbTree<Vec<u8>, Hash>> = RefCell::new(RbTree::new());
+}
+
+pub fn app_hash(app: &App) -> Hash {
+  sha2::Sha256::digest(&Encode!(app).unwrap()).into()
+}
+
+pub fn app_certify(app: &App) {
+  APP_HASHES.with(|cell| {
+    let mut tree = cell.borrow_mut();
+    tree.insert(app.app_id.as_bytes().to_vec(), app_hash(app));
+  });
+  certified_data_update();
+}
+
+pub fn root_hash() -> Hash {
+  APP_HASHES.with(|cell| labeled_hash(APPS_LABEL, &cell.borrow().root_hash()))
+}
+
+/// cbor encoded hash tree proving the leaf of one app
+pub fn app_witness(app_id: &AppId) -> Vec<u8> {
+  APP_HASHES.with(|cell| {
+    let tree = cell.borrow();
+    tree_encode(labeled(APPS_LABEL, tree.witness(app_id.as_bytes())))
+  })
+}
+
+/// the entry with the proof of its app, the certificate is only available in query calls
+pub fn entry_certify(mut entry: AppEntry, certificate: &Option<Vec<u8>>) -> AppEntry {
+  entry.witness = certificate.as_ref().map(|_| app_witness(&entry.app_id));
+  entry.certificate = certificate.clone();
+  entry
+}
+
+fn tree_encode<T: Serialize>(tree: T) -> Vec<u8> {
+  let mut serializer = serde_cbor::ser::Serializer::new(vec![]);
+  serializer.self_describe().unwrap();
+  tree.serialize(&mut serializer).unwrap();
+  serializer.into_inner()
+}
+
+fn certified_data_update() {
+  #[cfg(target_arch = "wasm32")]
+  {
+    ic_cdk::api::set_certified_data(&root_hash());
+  }
+}
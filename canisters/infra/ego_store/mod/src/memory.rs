@@ -2,25 +2,58 @@ use std::cell::RefCell;
 
 use ic_stable_structures::{DefaultMemoryImpl, memory_manager::{MemoryId, MemoryManager, VirtualMemory}, RestrictedMemory, StableBTreeMap, StableCell, storable::Blob};
 
+use crate::types::alert::Alert;
+use crate::types::alert_subscription::AlertSubscription;
+use crate::types::app_audit::EgoStoreAppAudit;
 use crate::types::app_key::AppKey;
+use crate::types::app_line_release::AppLineRelease;
+use crate::types::app_metadata::EgoStoreAppMetadata;
+use crate::types::app_rating::AppRating;
+use crate::types::app_stat::AppStat;
 use crate::types::cash_flow::CashFlow;
-use crate::types::ego_store_app::EgoStoreApp;
+use crate::types::charge::Charge;
+use crate::types::ego_store_app::{EgoStoreApp, LEGACY_EGO_STORE_APP_SIZE};
+use crate::types::history_key::HistoryKey;
+use crate::types::index_key::{AppDayKey, AppLineKey, AppVersionKey, AppWalletKey, CanisterAlertKey, WalletCanisterKey};
 use crate::types::order::Order;
+use crate::types::review::Review;
 use crate::types::stable_state::StableState;
 use crate::types::tenant::Tenant;
+use crate::types::tenant_metric::TenantMetric;
 use crate::types::user_app::UserApp;
 use crate::types::wallet::Wallet;
 use crate::types::wallet_provider::WalletProvider;
+use crate::types::withdraw::Withdraw;
 
 pub const MB: u32 = 1024 * 1024;
 
-const EGO_STORE_APP_MEM_ID: MemoryId = MemoryId::new(0);
+const EGO_STORE_APP_LEGACY_MEM_ID: MemoryId = MemoryId::new(0);
 const TENANT_MEM_ID: MemoryId = MemoryId::new(1);
 const WALLET_PROVIDER_MEM_ID: MemoryId = MemoryId::new(2);
 const WALLET_MEM_ID: MemoryId = MemoryId::new(3);
 const USER_APP_MEM_ID: MemoryId = MemoryId::new(4);
 const ORDER_MEM_ID: MemoryId = MemoryId::new(5);
 const CASH_FLOW_MEM_ID: MemoryId = MemoryId::new(6);
+const WITHDRAW_MEM_ID: MemoryId = MemoryId::new(7);
+const CASH_FLOW_INDEX_MEM_ID: MemoryId = MemoryId::new(8);
+const ORDER_INDEX_MEM_ID: MemoryId = MemoryId::new(9);
+const USER_APP_WALLET_INDEX_MEM_ID: MemoryId = MemoryId::new(10);
+const USER_APP_APP_INDEX_MEM_ID: MemoryId = MemoryId::new(11);
+const APP_METADATA_MEM_ID: MemoryId = MemoryId::new(12);
+const REVIEW_MEM_ID: MemoryId = MemoryId::new(13);
+const APP_RATING_MEM_ID: MemoryId = MemoryId::new(14);
+const APP_STAT_MEM_ID: MemoryId = MemoryId::new(15);
+const APP_AUDIT_MEM_ID: MemoryId = MemoryId::new(16);
+const ALERT_MEM_ID: MemoryId = MemoryId::new(17);
+const ALERT_SUBSCRIPTION_MEM_ID: MemoryId = MemoryId::new(18);
+const TENANT_METRIC_MEM_ID: MemoryId = MemoryId::new(19);
+const CHARGE_MEM_ID: MemoryId = MemoryId::new(20);
+const PENDING_CHARGE_MEM_ID: MemoryId = MemoryId::new(21);
+const WITHDRAW_INDEX_MEM_ID: MemoryId = MemoryId::new(22);
+const APP_LINE_RELEASE_MEM_ID: MemoryId = MemoryId::new(23);
+const EGO_STORE_APP_MEM_ID: MemoryId = MemoryId::new(24);
+const ALERT_WALLET_INDEX_MEM_ID: MemoryId = MemoryId::new(25);
+const ALERT_OPEN_INDEX_MEM_ID: MemoryId = MemoryId::new(26);
 
 const METADATA_PAGES: u64 = 64;
 // 4M
@@ -40,6 +73,11 @@ thread_local! {
         MemoryManager::init(RM::new(DefaultMemoryImpl::default(), METADATA_PAGES..MAX_PAGES))
     );
 
+    // the apps saved under the former bound, moved to EGO_STORE_APPS on upgrade
+    pub static EGO_STORE_APPS_LEGACY: RefCell<StableBTreeMap<AppKey, Blob<LEGACY_EGO_STORE_APP_SIZE>, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(EGO_STORE_APP_LEGACY_MEM_ID)))
+    });
+
     pub static EGO_STORE_APPS: RefCell<StableBTreeMap<AppKey, EgoStoreApp, VM>> = MEMORY_MANAGER.with(|mm| {
         RefCell::new(StableBTreeMap::init(mm.borrow().get(EGO_STORE_APP_MEM_ID)))
     });
@@ -67,4 +105,92 @@ thread_local! {
     pub static CASH_FLOWS: RefCell<StableBTreeMap<u64, CashFlow, VM>> = MEMORY_MANAGER.with(|mm| {
         RefCell::new(StableBTreeMap::init(mm.borrow().get(CASH_FLOW_MEM_ID)))
     });
+
+    pub static WITHDRAWS: RefCell<StableBTreeMap<u64, Withdraw, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(WITHDRAW_MEM_ID)))
+    });
+
+    // wallet_id + created_at => cash flow id
+    pub static CASH_FLOW_INDEX: RefCell<StableBTreeMap<HistoryKey, u64, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(CASH_FLOW_INDEX_MEM_ID)))
+    });
+
+    // wallet_id + last_update => order memo
+    pub static ORDER_INDEX: RefCell<StableBTreeMap<HistoryKey, u64, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(ORDER_INDEX_MEM_ID)))
+    });
+
+    // wallet_id + canister_id => user app last_update
+    pub static USER_APP_WALLET_INDEX: RefCell<StableBTreeMap<WalletCanisterKey, u64, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(USER_APP_WALLET_INDEX_MEM_ID)))
+    });
+
+    // app_id + version + canister_id => user app last_update
+    pub static USER_APP_APP_INDEX: RefCell<StableBTreeMap<AppVersionKey, u64, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(USER_APP_APP_INDEX_MEM_ID)))
+    });
+
+    pub static APP_METADATAS: RefCell<StableBTreeMap<AppKey, EgoStoreAppMetadata, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(APP_METADATA_MEM_ID)))
+    });
+
+    // app_id + wallet_id => review
+    pub static REVIEWS: RefCell<StableBTreeMap<AppWalletKey, Review, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(REVIEW_MEM_ID)))
+    });
+
+    pub static APP_RATINGS: RefCell<StableBTreeMap<AppKey, AppRating, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(APP_RATING_MEM_ID)))
+    });
+
+    // app_id + day => install counters of the day
+    pub static APP_STATS: RefCell<StableBTreeMap<AppDayKey, AppStat, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(APP_STAT_MEM_ID)))
+    });
+
+    pub static APP_AUDITS: RefCell<StableBTreeMap<AppKey, EgoStoreAppAudit, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(APP_AUDIT_MEM_ID)))
+    });
+
+    pub static ALERTS: RefCell<StableBTreeMap<u64, Alert, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(ALERT_MEM_ID)))
+    });
+
+    // wallet_id + created_at => alert id
+    pub static ALERT_WALLET_INDEX: RefCell<StableBTreeMap<HistoryKey, u64, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(ALERT_WALLET_INDEX_MEM_ID)))
+    });
+
+    // canister_id + kind => alert id, the alerts not acknowledged yet
+    pub static ALERT_OPEN_INDEX: RefCell<StableBTreeMap<CanisterAlertKey, u64, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(ALERT_OPEN_INDEX_MEM_ID)))
+    });
+
+    // wallet_id => callback canister
+    pub static ALERT_SUBSCRIPTIONS: RefCell<StableBTreeMap<Blob<29>, AlertSubscription, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(ALERT_SUBSCRIPTION_MEM_ID)))
+    });
+
+    // tenant_id => capacity and health
+    pub static TENANT_METRICS: RefCell<StableBTreeMap<Blob<29>, TenantMetric, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(TENANT_METRIC_MEM_ID)))
+    });
+
+    pub static CHARGES: RefCell<StableBTreeMap<u64, Charge, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(CHARGE_MEM_ID)))
+    });
+
+    // charge id => tenant_id, the charges not settled yet
+    pub static PENDING_CHARGES: RefCell<StableBTreeMap<u64, Blob<29>, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(PENDING_CHARGE_MEM_ID)))
+    });
+
+    // wallet_id => the last withdraw not failed
+    pub static WITHDRAW_INDEX: RefCell<StableBTreeMap<Blob<29>, u64, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(WITHDRAW_INDEX_MEM_ID)))
+    });
+
+    pub static APP_LINE_RELEASES: RefCell<StableBTreeMap<AppLineKey, AppLineRelease, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(APP_LINE_RELEASE_MEM_ID)))
+    });
 }
@@ -3,4 +3,5 @@ pub mod service;
 pub mod state;
 pub mod types;
 pub mod memory;
-pub mod backup;
\ No newline at end of file
+pub mod backup;
+pub mod certification;
\ No newline at end of file
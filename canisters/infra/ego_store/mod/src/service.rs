@@ -1,20 +1,57 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
 use candid::Principal;
 use ic_ledger_types::Memo;
 
 use ego_lib::ego_canister::TEgoCanister;
-use ego_types::app::{App, AppId, Canister};
+use ego_types::app::{AlertKind, App, AppAuditReview, AppId, AppMetadata, Canister, Version};
 use ego_types::app::EgoError;
+use ego_types::semver::VersionReq;
+use ego_utils::util::time;
 
+use crate::c2c::alert_callback::TAlertCallback;
+use crate::c2c::c2c_types::{CanisterSettingsUpdate, StatusRecord, TopUpPolicy};
 use crate::c2c::ego_ledger::TEgoLedger;
 use crate::c2c::ego_tenant::TEgoTenant;
+use crate::c2c::ic_management::TIcManagement;
 use crate::state::{error_log_add, info_log_add};
+use crate::types::alert::Alert;
+use crate::types::alert_subscription::AlertSubscription;
+use crate::types::app_audit::EgoStoreAppAudit;
+use crate::types::app_line_release::AppLineRelease;
+use crate::types::app_metadata::EgoStoreAppMetadata;
+use crate::types::app_rating::AppRating;
+use crate::types::app_stat::{AppStat, AppStatEvent};
 use crate::types::cash_flow::CashFlow;
+use crate::types::charge::{Charge, ChargeStatus};
 use crate::types::ego_store_app::EgoStoreApp;
-use crate::types::EgoStoreErr;
+use crate::types::{AppEntry, AppMainListRequest, AppSortBy, AppStatsResponse, AppVersionCount, EgoStoreErr, TenantInfo, WalletCashFlowListRequest, WalletCashFlowListResponse, WalletOrderListRequest, WalletOrderListResponse};
+use crate::types::history_key::HistoryKey;
+use crate::types::index_key::AppVersionKey;
 use crate::types::order::{Order, OrderStatus};
+use crate::types::review::{RATING_MAX, RATING_MIN, Review, REVIEW_CONTENT_MAX_LEN, REVIEW_REPLY_MAX_LEN};
 use crate::types::tenant::Tenant;
+use crate::types::tenant_metric::TenantMetric;
 use crate::types::user_app::UserApp;
 use crate::types::wallet::Wallet;
+use crate::types::withdraw::{Withdraw, WithdrawStatus};
+
+pub const HISTORY_PAGE_MAX: u32 = 100;
+pub const CATALOG_PAGE_MAX: u32 = 100;
+pub const APP_STATS_DAYS_MAX: u64 = 366;
+
+pub const WITHDRAW_MIN_CYCLES: u128 = 1_000_000_000_000;
+pub const WITHDRAW_FEE_CYCLES: u128 = 100_000_000_000;
+// one withdraw per wallet in this duration, in seconds
+pub const WITHDRAW_INTERVAL: u64 = 24 * 60 * 60;
+// kept by ego_store for itself when refilling tenants, twice its cycle threshold
+pub const STORE_CYCLES_RESERVE: u128 = 2_000_000_000_000;
+// a charge is settled by the tenant within seconds, the ones pending longer are reconciled
+pub const CHARGE_RECONCILE_DELAY: u64 = 60 * 60;
+// as long as the tenants keep their top up log (TOP_UP_LOG_DURATION of ego_tenant), older charges are left to the admin
+pub const CHARGE_RECONCILE_WINDOW: u64 = 30 * 24 * 60 * 60;
+pub const CHARGE_RECONCILE_BATCH: usize = 100;
 
 pub struct EgoStoreService {}
 
@@ -27,6 +64,139 @@ impl EgoStoreService {
     EgoStoreApp::get(app_id)
   }
 
+  /// one page of the apps matching the request, there is no total, the callers keep paging
+  /// until a page comes back shorter than the limit
+  pub fn app_main_search(request: &AppMainListRequest) -> Vec<AppEntry> {
+    let keyword = request.keyword.as_ref().map(|keyword| keyword.trim().to_lowercase()).filter(|keyword| !keyword.is_empty());
+    let tags: Vec<String> = request.tags.iter().map(|tag| tag.trim().to_lowercase()).collect();
+
+    let mut entries: Vec<AppEntry> = EgoStoreApp::list(0, EgoStoreApp::len() as usize).into_iter().filter_map(|ego_store_app| {
+      if request.category.is_some() && request.category.as_ref() != Some(&ego_store_app.app.category) {
+        return None;
+      }
+
+      let entry = EgoStoreService::catalog_entry(ego_store_app);
+
+      let entry_tags = entry.metadata.as_ref().map(|metadata| metadata.tags.as_slice()).unwrap_or_default();
+      if !tags.iter().all(|tag| entry_tags.contains(tag)) {
+        return None;
+      }
+
+      match &keyword {
+        Some(keyword) => {
+          let matched = entry.app_id.to_lowercase().contains(keyword)
+            || entry.name.to_lowercase().contains(keyword)
+            || entry.description.to_lowercase().contains(keyword)
+            || entry_tags.iter().any(|tag| tag.contains(keyword));
+          matched.then_some(entry)
+        }
+        None => Some(entry)
+      }
+    }).collect();
+
+    // without sort_by the apps keep the app id order of the store
+    match request.sort_by {
+      Some(AppSortBy::NAME) => entries.sort_by_key(|entry| entry.name.to_lowercase()),
+      Some(AppSortBy::PRICE) => entries.sort_by(|a, b| a.price.total_cmp(&b.price)),
+      Some(AppSortBy::LAST_UPDATE) => entries.sort_by_key(|entry| entry.last_update),
+      None => {}
+    }
+    if request.descending {
+      entries.reverse();
+    }
+
+    let limit = request.limit.min(CATALOG_PAGE_MAX) as usize;
+    entries.into_iter().skip(request.offset as usize).take(limit).collect()
+  }
+
+  pub fn app_main_catalog_get(app_id: &AppId) -> Option<AppEntry> {
+    EgoStoreApp::get(app_id).map(EgoStoreService::catalog_entry)
+  }
+
+  pub fn app_main_metadata_set(app_id: &AppId, metadata: &AppMetadata) {
+    let mut app_metadata = EgoStoreAppMetadata::new(app_id, metadata);
+    app_metadata.save();
+  }
+
+  pub fn app_main_audit_set(app_id: &AppId, version: &Version, reviews: Vec<AppAuditReview>) {
+    let mut app_audit = EgoStoreAppAudit::new(app_id, version, reviews);
+    app_audit.save();
+  }
+
+  pub fn app_main_developer_verified_set(app_ids: &[AppId], verified: bool) {
+    app_ids.iter().filter_map(EgoStoreApp::get).for_each(|mut ego_store_app| {
+      ego_store_app.developer_verified = Some(verified);
+      ego_store_app.save();
+    });
+  }
+
+  fn catalog_entry(ego_store_app: EgoStoreApp) -> AppEntry {
+    let app_id = &ego_store_app.app.app_id;
+    let metadata = EgoStoreAppMetadata::get(app_id).map(|app_metadata| app_metadata.metadata).unwrap_or_default();
+    let app_rating = AppRating::get(app_id).unwrap_or_else(|| AppRating::new(app_id));
+
+    let mut entry = AppEntry::from(ego_store_app.app);
+    entry.metadata = Some(metadata);
+    entry.rating_count = Some(app_rating.count);
+    entry.rating_average = Some(app_rating.average());
+    entry.last_update = Some(ego_store_app.last_update);
+    entry.developer_verified = Some(ego_store_app.developer_verified.unwrap_or(false));
+    entry
+  }
+
+  pub fn app_review_set(
+    wallet_id: &Principal,
+    app_id: &AppId,
+    rating: u8,
+    content: String,
+  ) -> Result<Review, EgoError> {
+    EgoStoreApp::get(app_id).ok_or(EgoError::from(EgoStoreErr::AppNotExists))?;
+
+    let installed = UserApp::by_wallet_id(wallet_id).iter().any(|user_app| user_app.app.app_id == *app_id);
+    if !installed {
+      return Err(EgoStoreErr::ReviewNotAllowed.into());
+    }
+
+    if !(RATING_MIN..=RATING_MAX).contains(&rating) || content.len() > REVIEW_CONTENT_MAX_LEN {
+      return Err(EgoStoreErr::ReviewInvalid.into());
+    }
+
+    let mut review = Review::get(app_id, wallet_id).unwrap_or_else(|| Review::new(app_id, wallet_id));
+    review.rating = rating;
+    review.content = content;
+    review.save();
+
+    Ok(review)
+  }
+
+  /// reviews shown in the store, hidden ones are left out
+  pub fn app_review_list(app_id: &AppId) -> Vec<Review> {
+    Review::by_app_id(app_id).into_iter().filter(|review| !review.hidden).collect()
+  }
+
+  pub fn app_review_reply(app_id: &AppId, wallet_id: &Principal, reply: String) -> Result<Review, EgoError> {
+    if reply.len() > REVIEW_REPLY_MAX_LEN {
+      return Err(EgoStoreErr::ReviewInvalid.into());
+    }
+
+    let mut review = Review::get(app_id, wallet_id).ok_or(EgoError::from(EgoStoreErr::ReviewNotExists))?;
+    review.reply = match reply.is_empty() {
+      true => None,
+      false => Some(reply),
+    };
+    review.save();
+
+    Ok(review)
+  }
+
+  pub fn app_review_hide(app_id: &AppId, wallet_id: &Principal, hidden: bool) -> Result<Review, EgoError> {
+    let mut review = Review::get(app_id, wallet_id).ok_or(EgoError::from(EgoStoreErr::ReviewNotExists))?;
+    review.hidden = hidden;
+    review.save();
+
+    Ok(review)
+  }
+
   pub fn wallet_main_get(
     wallet_id: &Principal,
   ) -> Result<Wallet, EgoError> {
@@ -39,11 +209,32 @@ impl EgoStoreService {
   ) -> Result<Principal, EgoError> {
     let ego_tenant_id = EgoStoreService::tenant_get()?;
 
-    let mut wallet = Wallet::new(&ego_tenant_id, wallet_id, user_id);
-    wallet.save();
+    EgoStoreService::wallet_main_place(&ego_tenant_id, wallet_id, user_id);
     Ok(ego_tenant_id)
   }
 
+  // registering again moves the wallet off its former tenant
+  fn wallet_main_place(ego_tenant_id: &Principal, wallet_id: &Principal, user_id: &Principal) {
+    if let Some(wallet) = Wallet::get(wallet_id) {
+      EgoStoreService::tenant_release(&wallet.tenant_id);
+    }
+
+    let mut wallet = Wallet::new(ego_tenant_id, wallet_id, user_id);
+    wallet.save();
+  }
+
+  /// a wallet without apps and cycles leaves the store, its place on the tenant is given back
+  pub fn wallet_main_remove(wallet_id: &Principal) -> Result<(), EgoError> {
+    let wallet = EgoStoreService::wallet_main_get(wallet_id)?;
+    if wallet.cycles > 0 || !UserApp::by_wallet_id(wallet_id).is_empty() {
+      return Err(EgoStoreErr::WalletNotEmpty.into());
+    }
+
+    Wallet::remove(wallet_id);
+    EgoStoreService::tenant_release(&wallet.tenant_id);
+    Ok(())
+  }
+
   pub fn wallet_app_list(wallet_id: &Principal) -> Vec<UserApp> {
     UserApp::by_wallet_id(wallet_id)
   }
@@ -66,12 +257,18 @@ impl EgoStoreService {
     ego_canister: EC,
     wallet_id: &Principal,
     ego_store_app: &EgoStoreApp,
+    version_req: &Option<String>,
   ) -> Result<UserApp, EgoError> {
+    let ego_store_app = &Self::app_release_resolve(ego_store_app, version_req)?;
+
     info_log_add("3 get wallet");
     let wallet = EgoStoreService::wallet_main_get(wallet_id)?;
 
     info_log_add("4 get ego_tenant_id relative to wallet");
     let ego_tenant_id = wallet.tenant_id;
+    if TenantMetric::get(&ego_tenant_id).map_or(false, |metric| !metric.install_allowed()) {
+      return Err(EgoStoreErr::TenantReserveLow.into());
+    }
 
     info_log_add("5 call ego tenant to install wasm");
 
@@ -89,7 +286,10 @@ impl EgoStoreService {
       &Canister::new(canister_id, ego_store_app.wasm.canister_type.clone()),
       Some(wallet_id.clone()),
     );
+    user_app.pre_release = ego_store_app.pre_release.clone();
     user_app.save();
+    TenantMetric::app_count_add(&ego_tenant_id, 1);
+    AppStat::record(&ego_store_app.app.app_id, AppStatEvent::INSTALL);
 
     info_log_add("6 track canister");
     ego_tenant.canister_main_track(ego_tenant_id, &canister_id);
@@ -110,12 +310,14 @@ impl EgoStoreService {
     ego_canister: EC,
     wallet_id: &Principal,
     canister_id: &Principal,
+    version_req: &Option<String>,
   ) -> Result<(), EgoError> {
     info_log_add("1 get user_app to be upgrade");
     let mut user_app = Self::wallet_app_get(wallet_id, canister_id)?;
 
     info_log_add("2 get app to be upgrade");
     let ego_store_app = Self::ego_store_app_get(&user_app.app.app_id)?;
+    let ego_store_app = Self::app_release_resolve(&ego_store_app, version_req)?;
 
     info_log_add(
       format!(
@@ -139,7 +341,9 @@ impl EgoStoreService {
       .await?;
 
     user_app.app.current_version = ego_store_app.app.current_version.clone();
+    user_app.pre_release = ego_store_app.pre_release.clone();
     user_app.save();
+    AppStat::record(&ego_store_app.app.app_id, AppStatEvent::UPGRADE);
 
     info_log_add("6 set app info");
     ego_canister.ego_app_info_update(
@@ -188,6 +392,7 @@ impl EgoStoreService {
       .await?;
 
     user_app.app.current_version = ego_store_app.app.current_version.clone();
+    user_app.pre_release = ego_store_app.pre_release.clone();
     user_app.save();
 
     info_log_add("6 set app info");
@@ -219,6 +424,8 @@ impl EgoStoreService {
 
     info_log_add("4 remove the user app from wallet");
     UserApp::remove(&user_app.canister.canister_id);
+    TenantMetric::app_count_sub(&ego_tenant_id, 1);
+    AppStat::record(&user_app.app.app_id, AppStatEvent::UNINSTALL);
 
     Ok(())
   }
@@ -241,6 +448,73 @@ impl EgoStoreService {
     Ok(())
   }
 
+  /// the policy is kept and enforced by the tenant tracking the canister
+  pub async fn wallet_canister_top_up_policy_set<T: TEgoTenant>(
+    ego_tenant: T,
+    wallet_id: &Principal,
+    canister_id: &Principal,
+    policy: Option<TopUpPolicy>,
+  ) -> Result<(), EgoError> {
+    info_log_add("1 get ego tenant id");
+    let wallet = EgoStoreService::wallet_main_get(wallet_id)?;
+
+    info_log_add("2 get user app");
+    // only the canisters of the wallet
+    let _ = Self::wallet_app_get(wallet_id, canister_id)?;
+
+    info_log_add("3 set top up policy");
+    ego_tenant.canister_top_up_policy_set(wallet.tenant_id, *canister_id, policy).await
+  }
+
+  /// the lifecycle controls below are run by the tenant of the wallet, while it is a controller of the canister
+  pub async fn wallet_canister_status_get<T: TEgoTenant>(
+    ego_tenant: T,
+    wallet_id: &Principal,
+    canister_id: &Principal,
+  ) -> Result<StatusRecord, EgoError> {
+    let wallet = EgoStoreService::wallet_main_get(wallet_id)?;
+    let _ = Self::wallet_app_get(wallet_id, canister_id)?;
+
+    ego_tenant.canister_main_status(wallet.tenant_id, *canister_id).await
+  }
+
+  pub async fn wallet_canister_start<T: TEgoTenant>(
+    ego_tenant: T,
+    wallet_id: &Principal,
+    canister_id: &Principal,
+  ) -> Result<(), EgoError> {
+    let wallet = EgoStoreService::wallet_main_get(wallet_id)?;
+    let _ = Self::wallet_app_get(wallet_id, canister_id)?;
+
+    info_log_add(format!("start canister {}", canister_id).as_str());
+    ego_tenant.canister_main_start(wallet.tenant_id, *canister_id).await
+  }
+
+  pub async fn wallet_canister_stop<T: TEgoTenant>(
+    ego_tenant: T,
+    wallet_id: &Principal,
+    canister_id: &Principal,
+  ) -> Result<(), EgoError> {
+    let wallet = EgoStoreService::wallet_main_get(wallet_id)?;
+    let _ = Self::wallet_app_get(wallet_id, canister_id)?;
+
+    info_log_add(format!("stop canister {}", canister_id).as_str());
+    ego_tenant.canister_main_stop(wallet.tenant_id, *canister_id).await
+  }
+
+  pub async fn wallet_canister_settings_update<T: TEgoTenant>(
+    ego_tenant: T,
+    wallet_id: &Principal,
+    canister_id: &Principal,
+    settings: CanisterSettingsUpdate,
+  ) -> Result<(), EgoError> {
+    let wallet = EgoStoreService::wallet_main_get(wallet_id)?;
+    let _ = Self::wallet_app_get(wallet_id, canister_id)?;
+
+    info_log_add(format!("update settings of canister {}", canister_id).as_str());
+    ego_tenant.canister_settings_update(wallet.tenant_id, *canister_id, settings).await
+  }
+
   pub fn wallet_canister_untrack<T: TEgoTenant>(
     ego_tenant: T,
     wallet_id: &Principal,
@@ -265,6 +539,49 @@ impl EgoStoreService {
     Order::by_wallet_id(wallet_id)
   }
 
+  /// one page of the orders of the wallet, reads no further than the page past the cursor
+  pub fn wallet_order_page(
+    wallet_id: &Principal,
+    req: &WalletOrderListRequest,
+  ) -> WalletOrderListResponse {
+    let limit = req.limit.min(HISTORY_PAGE_MAX) as usize;
+    let from_ts = req.from_ts.unwrap_or(0);
+    let to_ts = req.to_ts.unwrap_or(u64::MAX);
+    let matched = |order: &Order| req.status.map_or(true, |status| status == order.status);
+
+    let mut resp = WalletOrderListResponse::default();
+    let mut last_key: Option<HistoryKey> = None;
+
+    Order::iter_by_wallet_id(wallet_id, from_ts, to_ts, req.cursor, |key, order| {
+      if !matched(&order) {
+        return true;
+      }
+      if resp.orders.len() == limit {
+        resp.next_cursor = last_key.as_ref().map(HistoryKey::cursor);
+        return false;
+      }
+
+      resp.orders.push(order);
+      last_key = Some(key);
+      true
+    });
+
+    if req.with_totals.unwrap_or(false) {
+      let mut count = 0;
+      let mut total_amount = 0f32;
+      Order::iter_by_wallet_id(wallet_id, from_ts, to_ts, None, |_, order| {
+        if matched(&order) {
+          count += 1;
+          total_amount += order.amount;
+        }
+        true
+      });
+      resp.count = Some(count);
+      resp.total_amount = Some(total_amount);
+    }
+    resp
+  }
+
   pub fn wallet_order_new<L: TEgoLedger>(
     ego_ledger: L,
     wallet_id: &Principal,
@@ -283,6 +600,56 @@ impl EgoStoreService {
     CashFlow::by_wallet_id(wallet_id)
   }
 
+  /// one page of the cash flows of the wallet, reads no further than the page past the cursor
+  pub fn wallet_cash_flow_page(
+    wallet_id: &Principal,
+    req: &WalletCashFlowListRequest,
+  ) -> WalletCashFlowListResponse {
+    let limit = req.limit.min(HISTORY_PAGE_MAX) as usize;
+    let from_ts = req.from_ts.unwrap_or(0);
+    let to_ts = req.to_ts.unwrap_or(u64::MAX);
+    let matched = |cash_flow: &CashFlow| {
+      req.cash_flow_type.as_ref().map_or(true, |cash_flow_type| *cash_flow_type == cash_flow.cash_flow_type)
+    };
+
+    let mut resp = WalletCashFlowListResponse::default();
+    let mut last_key: Option<HistoryKey> = None;
+
+    CashFlow::iter_by_wallet_id(wallet_id, from_ts, to_ts, req.cursor, |key, cash_flow| {
+      if !matched(&cash_flow) {
+        return true;
+      }
+      if resp.cash_flows.len() == limit {
+        resp.next_cursor = last_key.as_ref().map(HistoryKey::cursor);
+        return false;
+      }
+
+      resp.cash_flows.push(cash_flow.into());
+      last_key = Some(key);
+      true
+    });
+
+    if req.with_totals.unwrap_or(false) {
+      let mut count = 0;
+      let mut total_in = 0;
+      let mut total_out = 0;
+      CashFlow::iter_by_wallet_id(wallet_id, from_ts, to_ts, None, |_, cash_flow| {
+        if matched(&cash_flow) {
+          count += 1;
+          match cash_flow.is_inflow() {
+            true => total_in += cash_flow.cycles,
+            false => total_out += cash_flow.cycles,
+          }
+        }
+        true
+      });
+      resp.count = Some(count);
+      resp.total_in = Some(total_in);
+      resp.total_out = Some(total_out);
+    }
+    resp
+  }
+
   pub fn wallet_cycle_balance(wallet_id: &Principal) -> Result<u128, EgoError> {
     let wallet = EgoStoreService::wallet_main_get(wallet_id)?;
     Ok(wallet.cycles)
@@ -316,37 +683,279 @@ impl EgoStoreService {
     }
   }
 
-  pub fn canister_cycle_charge(canister_id: &Principal,
-                               cycle: u128,
-                               operator: &Principal,
-                               comment: String) -> Result<(), EgoError> {
+  /// the one call charge, final once it returns. the operator logs no top up for it, so it is recorded
+  /// as reconciled and never left pending
+  pub fn canister_cycle_charge(
+    canister_id: &Principal,
+    cycle: u128,
+    operator: &Principal,
+    comment: String,
+  ) -> Result<Charge, EgoError> {
+    let mut charge = EgoStoreService::canister_cycle_reserve(canister_id, cycle, operator, comment)?;
+    EgoStoreService::charge_settle(&mut charge, ChargeStatus::RECONCILED)?;
+    Ok(charge)
+  }
+
+  /// takes the cycles of a top up off the wallet of the canister, committed once the top up went through,
+  /// released when it failed
+  pub fn canister_cycle_reserve(
+    canister_id: &Principal,
+    cycle: u128,
+    operator: &Principal,
+    comment: String,
+  ) -> Result<Charge, EgoError> {
     let user_app = UserApp::get(canister_id).ok_or(EgoError::from(EgoStoreErr::AppNotExists))?;
-    match user_app.wallet_id {
-      None => {
-        Err(EgoError::from(EgoStoreErr::WalletNotExists))
+    let wallet_id = user_app.wallet_id.ok_or(EgoError::from(EgoStoreErr::WalletNotExists))?;
+    if cycle == 0 {
+      return Err(EgoStoreErr::CyclesNotEnouth.into());
+    }
+
+    let mut wallet = EgoStoreService::wallet_main_get(&wallet_id)?;
+    wallet.cycle_reserve(cycle)?;
+
+    let mut charge = Charge::new(&wallet_id, canister_id, operator, cycle, comment);
+    charge.save();
+    Ok(charge)
+  }
+
+  pub fn charge_commit(charge_id: u64, operator: &Principal) -> Result<Charge, EgoError> {
+    let mut charge = EgoStoreService::charge_reserved_get(charge_id, operator)?;
+    EgoStoreService::charge_settle(&mut charge, ChargeStatus::COMMITTED)?;
+    Ok(charge)
+  }
+
+  pub fn charge_release(charge_id: u64, operator: &Principal) -> Result<Charge, EgoError> {
+    let mut charge = EgoStoreService::charge_reserved_get(charge_id, operator)?;
+    EgoStoreService::charge_settle(&mut charge, ChargeStatus::RELEASED)?;
+    Ok(charge)
+  }
+
+  /// settles the charges of the tenant left pending against its top up log. the ones it has no top up of
+  /// are credited back to the wallets, returns the charges settled
+  pub async fn charge_reconcile<T: TEgoTenant>(
+    ego_tenant: T,
+    tenant_id: &Principal,
+  ) -> Result<Vec<Charge>, EgoError> {
+    let now = time();
+    let charges = Charge::pending_by_operator(
+      tenant_id,
+      now.saturating_sub(CHARGE_RECONCILE_WINDOW),
+      now.saturating_sub(CHARGE_RECONCILE_DELAY),
+      CHARGE_RECONCILE_BATCH,
+    );
+    if charges.is_empty() {
+      return Ok(vec![]);
+    }
+
+    let charge_ids = charges.iter().map(|charge| charge.id).collect();
+    let topped_up = ego_tenant.top_up_list(*tenant_id, charge_ids).await?;
+
+    let mut settled = vec![];
+    for charge in charges {
+      // the tenant may have settled it meanwhile
+      let mut charge = match Charge::get(charge.id).filter(|charge| charge.is_pending()) {
+        None => continue,
+        Some(charge) => charge,
+      };
+
+      let status = match (charge.status, topped_up.contains(&charge.id)) {
+        (ChargeStatus::COMMITTED, false) => ChargeStatus::REFUNDED,
+        (ChargeStatus::RESERVED, false) => ChargeStatus::RELEASED,
+        _ => ChargeStatus::RECONCILED,
+      };
+      match EgoStoreService::charge_settle(&mut charge, status) {
+        Ok(_) => {
+          info_log_add(format!("charge_reconcile: charge {} of wallet {} {:?}", charge.id, charge.wallet_id, charge.status).as_str());
+          settled.push(charge);
+        }
+        Err(e) => error_log_add(format!("charge_reconcile: charge {} not settled: {}", charge.id, e.msg).as_str()),
+      }
+    }
+
+    Ok(settled)
+  }
+
+  pub fn admin_charge_pending_list() -> Vec<Charge> {
+    Charge::pending_list()
+  }
+
+  fn charge_reserved_get(charge_id: u64, operator: &Principal) -> Result<Charge, EgoError> {
+    let charge = Charge::get(charge_id)
+      .filter(|charge| charge.operator == *operator)
+      .ok_or(EgoError::from(EgoStoreErr::ChargeNotExists))?;
+    match charge.status {
+      ChargeStatus::RESERVED => Ok(charge),
+      _ => Err(EgoStoreErr::ChargeSettled.into()),
+    }
+  }
+
+  /// moves a pending charge to the status, recording or refunding the cycles on the wallet
+  fn charge_settle(charge: &mut Charge, status: ChargeStatus) -> Result<(), EgoError> {
+    let mut wallet = EgoStoreService::wallet_main_get(&charge.wallet_id)?;
+    match (charge.status, status) {
+      (ChargeStatus::RESERVED, ChargeStatus::COMMITTED | ChargeStatus::RECONCILED) => {
+        let cash_flow = wallet.cycle_reserve_commit(charge.cycles, &charge.operator, charge.comment.clone());
+        charge.cash_flow_id = Some(cash_flow.id);
       }
-      Some(wallet_id) => {
-        EgoStoreService::wallet_cycle_charge(
-          &wallet_id,
-          cycle,
-          &operator,
-          comment,
-        )
+      (ChargeStatus::RESERVED, ChargeStatus::RELEASED) => wallet.cycle_reserve_release(charge.cycles),
+      (ChargeStatus::COMMITTED, ChargeStatus::REFUNDED) => wallet.cycle_recharge(
+        charge.cycles,
+        &charge.operator,
+        format!("refund of charge {}, no top up logged by the tenant", charge.id),
+      )?,
+      (ChargeStatus::COMMITTED, ChargeStatus::RECONCILED) => {}
+      _ => return Err(EgoStoreErr::ChargeSettled.into()),
+    }
+
+    charge.status = status;
+    charge.save();
+    Ok(())
+  }
+
+  /// an alert raised again before acknowledged is counted on the open one, not notified again
+  pub fn canister_alert_add<C: TAlertCallback>(
+    alert_callback: &C,
+    canister_id: &Principal,
+    kind: AlertKind,
+    message: &str,
+  ) -> Result<Alert, EgoError> {
+    let user_app = UserApp::get(canister_id).ok_or(EgoError::from(EgoStoreErr::AppNotExists))?;
+    let wallet_id = user_app.wallet_id.ok_or(EgoError::from(EgoStoreErr::WalletNotExists))?;
+
+    match Alert::unacknowledged(canister_id, kind) {
+      Some(mut alert) => {
+        alert.repeat(message);
+        alert.save();
+        Ok(alert)
+      }
+      None => {
+        let mut alert = Alert::new(&wallet_id, canister_id, kind, message);
+        alert.save();
+
+        if let Some(subscription) = AlertSubscription::get(&wallet_id) {
+          info_log_add(format!("notify alert {} to {}", alert.id, subscription.callback_id).as_str());
+          alert_callback.ego_alert_notify(subscription.callback_id, &alert);
+        }
+        Ok(alert)
       }
     }
   }
 
-  pub fn wallet_cycle_charge(
+  pub fn wallet_alert_list(wallet_id: &Principal, unacknowledged_only: bool) -> Vec<Alert> {
+    Alert::by_wallet_id(wallet_id)
+      .into_iter()
+      .filter(|alert| !unacknowledged_only || alert.acknowledged_at.is_none())
+      .collect()
+  }
+
+  pub fn wallet_alert_ack(wallet_id: &Principal, alert_ids: &[u64]) -> Result<Vec<Alert>, EgoError> {
+    let mut alerts = alert_ids.iter().map(|alert_id| {
+      Alert::get(*alert_id)
+        .filter(|alert| alert.wallet_id == *wallet_id)
+        .ok_or(EgoError::from(EgoStoreErr::AlertNotExists))
+    }).collect::<Result<Vec<Alert>, EgoError>>()?;
+
+    alerts.iter_mut().for_each(|alert| {
+      alert.acknowledge();
+      alert.save();
+    });
+    Ok(alerts)
+  }
+
+  /// None stops the notifications
+  pub fn wallet_alert_subscribe(wallet_id: &Principal, callback_id: Option<Principal>) -> Result<(), EgoError> {
+    let _ = EgoStoreService::wallet_main_get(wallet_id)?;
+
+    match callback_id {
+      None => AlertSubscription::remove(wallet_id),
+      Some(callback_id) => AlertSubscription::new(wallet_id, &callback_id).save(),
+    }
+    Ok(())
+  }
+
+  pub fn wallet_cycle_transfer(
     wallet_id: &Principal,
+    to_wallet_id: &Principal,
     cycle: u128,
+    same_user_only: bool,
     operator: &Principal,
     comment: String,
   ) -> Result<(), EgoError> {
-    if cycle > 0 {
-      let mut wallet = EgoStoreService::wallet_main_get(&wallet_id)?;
-      wallet.cycle_charge(cycle, operator, comment)
-    } else {
-      Err(EgoStoreErr::CyclesNotEnouth.into())
+    info_log_add(
+      format!(
+        "wallet_cycle_transfer from:{}, to:{}, cycle:{}",
+        wallet_id, to_wallet_id, cycle
+      )
+        .as_str(),
+    );
+
+    let mut wallet = EgoStoreService::wallet_main_get(wallet_id)?;
+    let mut to_wallet = EgoStoreService::wallet_main_get(to_wallet_id)?;
+
+    if same_user_only && wallet.user_id != to_wallet.user_id {
+      return Err(EgoStoreErr::WalletNotSameUser.into());
+    }
+
+    wallet.cycle_transfer(&mut to_wallet, cycle, operator, comment)
+  }
+
+  pub async fn wallet_cycle_withdraw<M: TIcManagement>(
+    ic_management: M,
+    wallet_id: &Principal,
+    target_id: &Principal,
+    cycle: u128,
+    operator: &Principal,
+    comment: String,
+  ) -> Result<Withdraw, EgoError> {
+    info_log_add(
+      format!(
+        "wallet_cycle_withdraw wallet:{}, target:{}, cycle:{}",
+        wallet_id, target_id, cycle
+      )
+        .as_str(),
+    );
+
+    if cycle < WITHDRAW_MIN_CYCLES {
+      return Err(EgoStoreErr::WithdrawAmountTooSmall.into());
+    }
+
+    let now = time();
+    let recent = Withdraw::last_by_wallet_id(wallet_id).map_or(false, |withdraw| {
+      withdraw.created_at + WITHDRAW_INTERVAL > now
+    });
+    if recent {
+      return Err(EgoStoreErr::WithdrawTooFrequent.into());
+    }
+
+    info_log_add("1 debit wallet");
+    let mut wallet = EgoStoreService::wallet_main_get(wallet_id)?;
+    wallet.cycle_withdraw(cycle, WITHDRAW_FEE_CYCLES, operator, comment)?;
+
+    let mut withdraw = Withdraw::new(wallet_id, target_id, cycle, WITHDRAW_FEE_CYCLES);
+    withdraw.save();
+
+    info_log_add("2 deposit cycles to target");
+    match ic_management.canister_cycle_top_up(*target_id, cycle).await {
+      Ok(_) => {
+        withdraw.status = WithdrawStatus::SUCCESS;
+        withdraw.save();
+        Ok(withdraw)
+      }
+      Err(e) => {
+        error_log_add(format!("wallet_cycle_withdraw deposit failed: {:?}", e).as_str());
+
+        info_log_add("3 refund wallet");
+        let mut wallet = EgoStoreService::wallet_main_get(wallet_id)?;
+        wallet.cycle_recharge(
+          cycle + WITHDRAW_FEE_CYCLES,
+          operator,
+          format!("wallet cycle withdraw refund, withdraw id {}", withdraw.id),
+        )?;
+
+        withdraw.status = WithdrawStatus::FAILED;
+        withdraw.save();
+        Err(e)
+      }
     }
   }
 
@@ -371,11 +980,55 @@ impl EgoStoreService {
     }
   }
 
+  /// the release a requirement installs: the current version when it matches, otherwise the
+  /// highest matching hotfix of the older lines. a pre-release only matches a requirement asking
+  /// for a pre-release of the same version
+  pub fn app_release_resolve(ego_store_app: &EgoStoreApp, version_req: &Option<String>) -> Result<EgoStoreApp, EgoError> {
+    let version_req = match version_req {
+      None => return Ok(ego_store_app.clone()),
+      Some(version_req) => VersionReq::from_str(version_req).map_err(|_| EgoError::from(EgoStoreErr::VersionReqInvalid))?,
+    };
+
+    if version_req.matches(&ego_store_app.semver()) {
+      return Ok(ego_store_app.clone());
+    }
+
+    AppLineRelease::by_app_id(&ego_store_app.app.app_id).into_iter().filter(|app_line_release| {
+      version_req.matches(&app_line_release.semver())
+    }).max_by(|a, b| a.semver().cmp_precedence(&b.semver()))
+      .map(|app_line_release| app_line_release.to_ego_store_app(ego_store_app))
+      .ok_or(EgoStoreErr::VersionNotMatched.into())
+  }
+
   pub fn app_main_release(ego_store_app: &mut EgoStoreApp) -> Result<bool, EgoError> {
+    if !AppVersionKey::fits(&ego_store_app.app.app_id, &ego_store_app.semver()) {
+      return Err(EgoStoreErr::AppKeyInvalid.into());
+    }
     ego_store_app.save();
     Ok(true)
   }
 
+  /// a hotfix of an older line, the current version and wasm of the app stay as they are
+  pub fn app_line_release(app_line_release: &mut AppLineRelease) -> Result<bool, EgoError> {
+    let ego_store_app = EgoStoreApp::get(&app_line_release.app_id).ok_or(EgoError::from(EgoStoreErr::AppNotExists))?;
+
+    if !AppVersionKey::fits(&app_line_release.app_id, &app_line_release.semver()) {
+      return Err(EgoStoreErr::AppKeyInvalid.into());
+    }
+
+    let current = ego_store_app.semver();
+    let below_current = (app_line_release.version.major, app_line_release.version.minor) < (current.major, current.minor);
+    let above_line = AppLineRelease::get(&app_line_release.app_id, &app_line_release.version).map_or(true, |released| {
+      app_line_release.semver().cmp_precedence(&released.semver()) == Ordering::Greater
+    });
+    if !below_current || !above_line {
+      return Err(EgoStoreErr::LineReleaseInvalid.into());
+    }
+
+    app_line_release.save();
+    Ok(true)
+  }
+
   pub async fn wallet_controller_install<T: TEgoTenant, EC: TEgoCanister>(
     ego_tenant: T,
     ego_canister: EC,
@@ -395,13 +1048,17 @@ impl EgoStoreService {
       .await?;
 
     info_log_add(format!("5 register wallet {}, to ego_store", canister_id).as_str());
-    let _result = EgoStoreService::wallet_main_register(&canister_id, &user_id);
+    // on the tenant which installed it, counted once
+    EgoStoreService::wallet_main_place(&ego_tenant_id, &canister_id, &user_id);
 
     let mut user_app = UserApp::new(
       &ego_store_app.app,
       &Canister::new(canister_id, ego_store_app.wasm.canister_type), Some(canister_id),
     );
+    user_app.pre_release = ego_store_app.pre_release.clone();
     user_app.save();
+    TenantMetric::app_count_add(&ego_tenant_id, 1);
+    AppStat::record(&ego_store_app.app.app_id, AppStatEvent::INSTALL);
 
     info_log_add("7 track canister");
     ego_tenant.canister_main_track(ego_tenant_id, &canister_id);
@@ -453,17 +1110,201 @@ impl EgoStoreService {
     }
   }
 
+  pub fn admin_app_install_count(app_id: &AppId) -> u64 {
+    UserApp::count_by_app_id(app_id)
+  }
+
+  pub fn admin_app_version_distribution(app_id: &AppId) -> Vec<AppVersionCount> {
+    UserApp::version_distribution(app_id).into_iter().map(|(version, pre_release, count)| {
+      AppVersionCount { version, pre_release, count }
+    }).collect()
+  }
+
+  pub fn app_main_stats(app_id: &AppId, from_day: u64, to_day: u64) -> Result<AppStatsResponse, EgoError> {
+    if to_day < from_day || to_day - from_day >= APP_STATS_DAYS_MAX {
+      return Err(EgoStoreErr::SystemError("invalid day range".to_string()).into());
+    }
+
+    let daily = AppStat::by_app_id(app_id, from_day, to_day);
+
+    Ok(AppStatsResponse {
+      app_id: app_id.clone(),
+      installs: daily.iter().map(|app_stat| app_stat.installs).sum(),
+      uninstalls: daily.iter().map(|app_stat| app_stat.uninstalls).sum(),
+      upgrades: daily.iter().map(|app_stat| app_stat.upgrades).sum(),
+      daily,
+      active_installs: UserApp::count_by_app_id(app_id),
+      versions: EgoStoreService::admin_app_version_distribution(app_id),
+    })
+  }
+
+  pub fn admin_app_user_app_list(app_id: &AppId, version: Option<Version>) -> Vec<UserApp> {
+    UserApp::by_app_id(app_id, version)
+  }
+
+  /// places a wallet on the healthy tenant with room, the least loaded for its capacity
   pub fn tenant_get() -> Result<Principal, EgoError> {
-    let tenants = Tenant::list(0, Tenant::len() as usize);
+    let now = time();
+    let tenants = Tenant::list(0, Tenant::len() as usize).into_iter().map(|tenant| {
+      let metric = TenantMetric::get(&tenant.canister_id).unwrap_or_else(|| TenantMetric::new(&tenant.canister_id));
+      (tenant, metric)
+    });
 
-    if tenants.len() == 0 {
-      error_log_add("tenant_get: no tenant");
-      Err(EgoStoreErr::NoTenant.into())
-    } else {
-      let mut tenant = tenants.iter().min().unwrap().clone();
-      tenant.wallet_count += 1;
+    let placed = tenants
+      .filter(|(tenant, metric)| metric.is_healthy(now) && metric.install_allowed() && tenant.wallet_count < metric.capacity)
+      .min_by(|(a, a_metric), (b, b_metric)| {
+        // a.wallet_count / a.capacity against b.wallet_count / b.capacity
+        (a.wallet_count as u64 * b_metric.capacity as u64).cmp(&(b.wallet_count as u64 * a_metric.capacity as u64))
+      });
+
+    match placed {
+      None => {
+        error_log_add("tenant_get: no tenant");
+        Err(EgoStoreErr::NoTenant.into())
+      }
+      Some((mut tenant, _)) => {
+        tenant.wallet_count += 1;
+        tenant.save();
+        Ok(tenant.canister_id)
+      }
+    }
+  }
+
+  pub fn tenant_release(tenant_id: &Principal) {
+    if let Some(mut tenant) = Tenant::get(tenant_id) {
+      tenant.wallet_count = tenant.wallet_count.saturating_sub(1);
       tenant.save();
-      Ok(tenant.canister_id)
     }
   }
+
+  pub fn tenant_metric_report(
+    tenant_id: &Principal,
+    task_count: u64,
+    dead_task_count: u64,
+    cycles: u128,
+    spend_per_day: Option<u128>,
+    reserve_threshold: Option<u128>,
+  ) -> Result<(), EgoError> {
+    let _ = Tenant::get(tenant_id).ok_or(EgoError::from(EgoStoreErr::TenantNotExists))?;
+
+    let mut metric = TenantMetric::get(tenant_id).unwrap_or_else(|| TenantMetric::new(tenant_id));
+    metric.report(task_count, dead_task_count, cycles, spend_per_day, reserve_threshold, time());
+    metric.save();
+    Ok(())
+  }
+
+  /// refills a tenant under its reserve threshold from the cycles charged off the wallets,
+  /// returns the cycles sent
+  pub async fn tenant_refill<M: TIcManagement>(
+    ic_management: M,
+    tenant_id: &Principal,
+  ) -> Result<u128, EgoError> {
+    let now = time();
+    let mut metric = TenantMetric::get(tenant_id).ok_or(EgoError::from(EgoStoreErr::TenantNotExists))?;
+
+    let required = metric.refill_required(now);
+    if required == 0 {
+      return Ok(0);
+    }
+
+    let cycles = required.min(ic_management.canister_balance().saturating_sub(STORE_CYCLES_RESERVE));
+    if cycles == 0 {
+      error_log_add(format!("tenant_refill: no cycles left to refill tenant {}, {} required", tenant_id, required).as_str());
+      return Err(EgoStoreErr::CyclesNotEnouth.into());
+    }
+
+    // one refill in flight, a failed one is retried after the interval
+    metric.refilled_at = Some(now);
+    metric.save();
+
+    info_log_add(format!("tenant_refill: refill tenant {} with {} cycles", tenant_id, cycles).as_str());
+    ic_management.canister_cycle_top_up(*tenant_id, cycles).await?;
+
+    let mut metric = TenantMetric::get(tenant_id).unwrap_or_else(|| TenantMetric::new(tenant_id));
+    metric.refill_record(cycles);
+    metric.save();
+    Ok(cycles)
+  }
+
+  pub fn admin_tenant_list() -> Vec<TenantInfo> {
+    let now = time();
+    Tenant::list(0, Tenant::len() as usize).into_iter().map(|tenant| {
+      let metric = TenantMetric::get(&tenant.canister_id).unwrap_or_else(|| TenantMetric::new(&tenant.canister_id));
+      let healthy = metric.is_healthy(now);
+      TenantInfo { tenant, metric, healthy }
+    }).collect()
+  }
+
+  /// 0 drains the tenant, the wallets already on it stay until migrated
+  pub fn admin_tenant_capacity_set(tenant_id: &Principal, capacity: u16) -> Result<TenantMetric, EgoError> {
+    let _ = Tenant::get(tenant_id).ok_or(EgoError::from(EgoStoreErr::TenantNotExists))?;
+
+    let mut metric = TenantMetric::get(tenant_id).unwrap_or_else(|| TenantMetric::new(tenant_id));
+    metric.capacity = capacity;
+    metric.save();
+    Ok(metric)
+  }
+
+  /// moves a wallet and the tracking of its apps to another tenant. the top up policies go along,
+  /// and so does the controller right of the former tenant where it still holds one
+  pub async fn admin_wallet_tenant_migrate<T: TEgoTenant, EC: TEgoCanister>(
+    ego_tenant: T,
+    ego_canister: EC,
+    wallet_id: &Principal,
+    tenant_id: &Principal,
+  ) -> Result<(), EgoError> {
+    let mut wallet = EgoStoreService::wallet_main_get(wallet_id)?;
+    let mut tenant = Tenant::get(tenant_id).ok_or(EgoError::from(EgoStoreErr::TenantNotExists))?;
+
+    let from_tenant_id = wallet.tenant_id;
+    if from_tenant_id == *tenant_id {
+      return Ok(());
+    }
+
+    info_log_add(format!("1 place wallet {} on tenant {}", wallet_id, tenant_id).as_str());
+    // new installs go to the new tenant while the apps are moved
+    wallet.tenant_id = *tenant_id;
+    wallet.save();
+    EgoStoreService::tenant_release(&from_tenant_id);
+    tenant.wallet_count += 1;
+    tenant.save();
+
+    let user_apps = EgoStoreService::wallet_app_list(wallet_id);
+    TenantMetric::app_count_sub(&from_tenant_id, user_apps.len() as u64);
+    TenantMetric::app_count_add(tenant_id, user_apps.len() as u64);
+
+    for user_app in user_apps.iter() {
+      let canister_id = user_app.canister.canister_id;
+      info_log_add(format!("2 move canister {}", canister_id).as_str());
+
+      // read before the former tenant untracks it
+      let policy = ego_tenant.canister_top_up_policy_get(from_tenant_id, canister_id).await.unwrap_or_else(|e| {
+        error_log_add(format!("top up policy of {} not moved: {}", canister_id, e.msg).as_str());
+        None
+      });
+      // canister_status answers controllers only
+      let is_controller = ego_tenant.canister_main_status(from_tenant_id, canister_id).await.is_ok();
+
+      ego_tenant.canister_main_track(*tenant_id, &canister_id);
+      if policy.is_some() {
+        if let Err(e) = ego_tenant.canister_top_up_policy_set(*tenant_id, canister_id, policy).await {
+          error_log_add(format!("top up policy of {} not moved: {}", canister_id, e.msg).as_str());
+        }
+      }
+      ego_tenant.canister_main_untrack(from_tenant_id, &canister_id);
+
+      // where the canister sends its cycle check answers
+      ego_canister.ego_canister_remove(canister_id, "ego_tenant".to_string(), from_tenant_id);
+      ego_canister.ego_canister_add(canister_id, "ego_tenant".to_string(), *tenant_id);
+      ego_canister.ego_op_remove(canister_id, from_tenant_id);
+      ego_canister.ego_op_add(canister_id, *tenant_id);
+
+      if is_controller {
+        ego_canister.ego_controller_add(canister_id, *tenant_id).await;
+        ego_canister.ego_controller_remove(canister_id, from_tenant_id);
+      }
+    }
+
+    Ok(())
+  }
 }
@@ -5,6 +5,11 @@ use ego_backup::inject_backup_data;
 use ego_macros::{inject_cycle_info, inject_ego_data, inject_seq_info};
 
 use crate::memory::CONFIG;
+use crate::types::alert::Alert;
+use crate::types::cash_flow::CashFlow;
+use crate::types::ego_store_app::EgoStoreApp;
+use crate::types::order::Order;
+use crate::types::user_app::UserApp;
 use crate::types::stable_state::StableState;
 use crate::types::tenant::Tenant;
 
@@ -46,4 +51,11 @@ pub fn post_upgrade() {
 
     StableState::restore(state.to_owned());
   });
+
+  Alert::index_rebuild();
+  CashFlow::index_rebuild();
+  Order::index_rebuild();
+  UserApp::index_rebuild();
+  EgoStoreApp::migrate();
+  EgoStoreApp::certify_all();
 }
\ No newline at end of file
@@ -0,0 +1,167 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::AlertKind;
+use ego_utils::util::time;
+
+use crate::memory::{ALERT_OPEN_INDEX, ALERT_WALLET_INDEX, ALERTS};
+use crate::state::SEQ;
+use crate::types::history_key::HistoryKey;
+use crate::types::index_key::CanisterAlertKey;
+
+pub const ALERT_MESSAGE_MAX_LEN: usize = 256;
+
+/// raised by ego_tenant for a canister of the wallet
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Alert {
+  pub id: u64,
+  pub wallet_id: Principal,
+  pub canister_id: Principal,
+  pub kind: AlertKind,
+  pub message: String,
+  // how many times the alert was raised before acknowledged
+  pub count: u32,
+  pub created_at: u64,
+  pub acknowledged_at: Option<u64>,
+  pub last_update: u64, // second
+}
+
+impl Alert {
+  pub fn new(wallet_id: &Principal, canister_id: &Principal, kind: AlertKind, message: &str) -> Self {
+    let next_id = SEQ.with(|cell| cell.borrow_mut().next_number("alert", 0));
+    Self {
+      id: next_id,
+      wallet_id: *wallet_id,
+      canister_id: *canister_id,
+      kind,
+      message: message.chars().take(ALERT_MESSAGE_MAX_LEN).collect(),
+      count: 1,
+      created_at: time(),
+      acknowledged_at: None,
+      last_update: 0,
+    }
+  }
+
+  /// raised again before acknowledged
+  pub fn repeat(&mut self, message: &str) {
+    self.message = message.chars().take(ALERT_MESSAGE_MAX_LEN).collect();
+    self.count += 1;
+  }
+
+  pub fn acknowledge(&mut self) {
+    if self.acknowledged_at.is_none() {
+      self.acknowledged_at = Some(time());
+    }
+  }
+
+  pub fn len() -> u64 {
+    ALERTS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, alert)| Some(alert))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, alert)| match alert.last_update >= last_update {
+      true => { Some(alert) }
+      false => { None }
+    })
+  }
+
+  pub fn by_wallet_id(wallet_id: &Principal) -> Vec<Self> {
+    let mut alerts: Vec<Self> = ALERT_WALLET_INDEX.with(|cell| {
+      let index = cell.borrow();
+      index.range(HistoryKey::range(wallet_id, 0, u64::MAX)).filter_map(|(_, id)| Self::get(id)).collect()
+    });
+    alerts.sort_by_key(|alert| alert.id);
+    alerts
+  }
+
+  /// the alert of the canister still waiting for the wallet
+  pub fn unacknowledged(canister_id: &Principal, kind: AlertKind) -> Option<Self> {
+    let id = ALERT_OPEN_INDEX.with(|cell| cell.borrow().get(&CanisterAlertKey::new(canister_id, kind)))?;
+    Self::get(id)
+  }
+
+  pub fn get(id: u64) -> Option<Self> {
+    ALERTS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&id)
+    })
+  }
+
+  pub fn save(&mut self) {
+    ALERTS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(self.id, self.clone());
+    });
+
+    self.index_insert();
+  }
+
+  /// build the indexes for the alerts saved before the indexes existed
+  pub fn index_rebuild() {
+    let index_len = ALERT_WALLET_INDEX.with(|cell| cell.borrow().len());
+    if index_len == Self::len() {
+      return;
+    }
+
+    Self::list(0, Self::len() as usize).iter().for_each(|alert| alert.index_insert());
+  }
+
+  /// the wallet index keeps every alert, the open index the unacknowledged ones
+  fn index_insert(&self) {
+    ALERT_WALLET_INDEX.with(|cell| {
+      cell.borrow_mut().insert(HistoryKey::new(&self.wallet_id, self.created_at, self.id), self.id);
+    });
+
+    ALERT_OPEN_INDEX.with(|cell| {
+      let mut index = cell.borrow_mut();
+      let key = CanisterAlertKey::new(&self.canister_id, self.kind);
+      match self.acknowledged_at {
+        None => { index.insert(key, self.id); }
+        Some(_) => {
+          if index.get(&key) == Some(self.id) {
+            index.remove(&key);
+          }
+        }
+      }
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((u64, Self)) -> Option<Self>,
+  {
+    ALERTS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for Alert {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for Alert {
+  const MAX_SIZE: u32 = 1024;
+  const IS_FIXED_SIZE: bool = false;
+}
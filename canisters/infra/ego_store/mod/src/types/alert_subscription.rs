@@ -0,0 +1,99 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use ic_stable_structures::storable::Blob;
+use serde::Serialize;
+
+use ego_utils::util::time;
+
+use crate::memory::ALERT_SUBSCRIPTIONS;
+
+/// the canister notified with every new alert of the wallet
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AlertSubscription {
+  pub wallet_id: Principal,
+  pub callback_id: Principal,
+  pub last_update: u64, // second
+}
+
+impl AlertSubscription {
+  pub fn new(wallet_id: &Principal, callback_id: &Principal) -> Self {
+    Self {
+      wallet_id: *wallet_id,
+      callback_id: *callback_id,
+      last_update: 0,
+    }
+  }
+
+  pub fn len() -> u64 {
+    ALERT_SUBSCRIPTIONS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, subscription)| Some(subscription))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, subscription)| match subscription.last_update >= last_update {
+      true => { Some(subscription) }
+      false => { None }
+    })
+  }
+
+  pub fn get(wallet_id: &Principal) -> Option<Self> {
+    ALERT_SUBSCRIPTIONS.with(|cell| {
+      let inst = cell.borrow();
+      let key = Blob::try_from(wallet_id.as_slice()).unwrap();
+      inst.get(&key)
+    })
+  }
+
+  pub fn save(&mut self) {
+    ALERT_SUBSCRIPTIONS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      let key = Blob::try_from(self.wallet_id.as_slice()).unwrap();
+      self.last_update = time();
+      inst.insert(key, self.clone());
+    });
+  }
+
+  pub fn remove(wallet_id: &Principal) {
+    ALERT_SUBSCRIPTIONS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      let key = Blob::try_from(wallet_id.as_slice()).unwrap();
+      inst.remove(&key);
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((Blob<29>, Self)) -> Option<Self>,
+  {
+    ALERT_SUBSCRIPTIONS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for AlertSubscription {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for AlertSubscription {
+  const MAX_SIZE: u32 = 128;
+  const IS_FIXED_SIZE: bool = false;
+}
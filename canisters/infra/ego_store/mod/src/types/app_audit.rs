@@ -0,0 +1,87 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::{AppAuditReview, AppId, Version};
+use ego_utils::util::time;
+
+use crate::memory::APP_AUDITS;
+use crate::types::app_key::AppKey;
+
+// audit record of the released version of an app, pushed by ego_dev on release
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct EgoStoreAppAudit {
+  pub app_id: AppId,
+  pub version: Version,
+  pub reviews: Vec<AppAuditReview>,
+  pub last_update: u64, // second
+}
+
+impl EgoStoreAppAudit {
+  pub fn new(app_id: &AppId, version: &Version, reviews: Vec<AppAuditReview>) -> Self {
+    Self { app_id: app_id.clone(), version: *version, reviews, last_update: 0 }
+  }
+
+  pub fn len() -> u64 {
+    APP_AUDITS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_audit)| Some(app_audit))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_audit)| match app_audit.last_update >= last_update {
+      true => { Some(app_audit) }
+      false => { None }
+    })
+  }
+
+  pub fn get(app_id: &AppId) -> Option<Self> {
+    APP_AUDITS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&AppKey::new(app_id))
+    })
+  }
+
+  pub fn save(&mut self) {
+    APP_AUDITS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(AppKey::new(&self.app_id), self.clone());
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((AppKey, Self)) -> Option<Self>,
+  {
+    APP_AUDITS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for EgoStoreAppAudit {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for EgoStoreAppAudit {
+  const MAX_SIZE: u32 = 32 * 1024;
+  const IS_FIXED_SIZE: bool = false;
+}
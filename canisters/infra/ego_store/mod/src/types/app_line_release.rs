@@ -0,0 +1,119 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::{AppId, Version, Wasm};
+use ego_types::semver::SemVer;
+use ego_utils::util::time;
+
+use crate::memory::APP_LINE_RELEASES;
+use crate::types::ego_store_app::EgoStoreApp;
+use crate::types::index_key::AppLineKey;
+
+// the app id, the version labels and the wasm encode to about 400 bytes at most
+pub const APP_LINE_RELEASE_SIZE: u32 = 1024;
+
+// a hotfix released by ego_dev below the current version of the app, the latest one of each
+// major.minor line. only the installs and upgrades asking for that line resolve to it
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppLineRelease {
+  pub app_id: AppId,
+  pub version: Version,
+  pub pre_release: Option<String>,
+  pub wasm: Wasm,
+  pub last_update: u64, // second
+}
+
+impl AppLineRelease {
+  pub fn new(app_id: &AppId, version: &Version, pre_release: &Option<String>, wasm: &Wasm) -> Self {
+    Self { app_id: app_id.clone(), version: *version, pre_release: pre_release.clone(), wasm: wasm.clone(), last_update: 0 }
+  }
+
+  pub fn semver(&self) -> SemVer {
+    SemVer::new(&self.version, &self.pre_release, &None).unwrap_or_else(|_| SemVer::from(self.version))
+  }
+
+  /// the app as installed from this release
+  pub fn to_ego_store_app(&self, ego_store_app: &EgoStoreApp) -> EgoStoreApp {
+    let mut app = ego_store_app.app.clone();
+    app.current_version = self.version;
+    EgoStoreApp {
+      app,
+      wasm: self.wasm.clone(),
+      last_update: ego_store_app.last_update,
+      pre_release: self.pre_release.clone(),
+      developer_verified: ego_store_app.developer_verified,
+    }
+  }
+
+  pub fn len() -> u64 {
+    APP_LINE_RELEASES.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_line_release)| Some(app_line_release))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_line_release)| match app_line_release.last_update >= last_update {
+      true => { Some(app_line_release) }
+      false => { None }
+    })
+  }
+
+  /// the line releases of an app, lowest line first
+  pub fn by_app_id(app_id: &AppId) -> Vec<Self> {
+    APP_LINE_RELEASES.with(|cell| {
+      let inst = cell.borrow();
+      inst.range(AppLineKey::range(app_id)).map(|(_, app_line_release)| app_line_release).collect()
+    })
+  }
+
+  pub fn get(app_id: &AppId, version: &Version) -> Option<Self> {
+    APP_LINE_RELEASES.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&AppLineKey::new(app_id, version))
+    })
+  }
+
+  pub fn save(&mut self) {
+    APP_LINE_RELEASES.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(AppLineKey::new(&self.app_id, &self.version), self.clone());
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((AppLineKey, Self)) -> Option<Self>,
+  {
+    APP_LINE_RELEASES.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for AppLineRelease {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for AppLineRelease {
+  const MAX_SIZE: u32 = APP_LINE_RELEASE_SIZE;
+  const IS_FIXED_SIZE: bool = false;
+}
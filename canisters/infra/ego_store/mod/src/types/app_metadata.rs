@@ -0,0 +1,87 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::{AppId, AppMetadata};
+use ego_utils::util::time;
+
+use crate::memory::APP_METADATAS;
+use crate::types::app_key::AppKey;
+
+// catalog information of an app, validated and pushed by ego_dev
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct EgoStoreAppMetadata {
+  pub app_id: AppId,
+  pub metadata: AppMetadata,
+  pub last_update: u64, // second
+}
+
+impl EgoStoreAppMetadata {
+  pub fn new(app_id: &AppId, metadata: &AppMetadata) -> Self {
+    Self { app_id: app_id.clone(), metadata: metadata.clone(), last_update: 0 }
+  }
+
+  pub fn len() -> u64 {
+    APP_METADATAS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_metadata)| Some(app_metadata))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_metadata)| match app_metadata.last_update >= last_update {
+      true => { Some(app_metadata) }
+      false => { None }
+    })
+  }
+
+  pub fn get(app_id: &AppId) -> Option<Self> {
+    APP_METADATAS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&AppKey::new(&app_id))
+    })
+  }
+
+  pub fn save(&mut self) {
+    APP_METADATAS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(AppKey::new(&self.app_id), self.clone());
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((AppKey, Self)) -> Option<Self>,
+  {
+    APP_METADATAS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for EgoStoreAppMetadata {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for EgoStoreAppMetadata {
+  // same bound as the ego_dev side
+  const MAX_SIZE: u32 = 32 * 1024;
+  const IS_FIXED_SIZE: bool = false;
+}
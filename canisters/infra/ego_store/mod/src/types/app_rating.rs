@@ -0,0 +1,71 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::AppId;
+
+use crate::memory::APP_RATINGS;
+use crate::types::app_key::AppKey;
+
+/// aggregated ratings of the visible reviews of an app, maintained by Review::save
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppRating {
+  pub app_id: AppId,
+  pub count: u64,
+  pub total: u64,
+}
+
+impl AppRating {
+  pub fn new(app_id: &AppId) -> Self {
+    Self { app_id: app_id.clone(), count: 0, total: 0 }
+  }
+
+  pub fn add(&mut self, rating: u8) {
+    self.count += 1;
+    self.total += rating as u64;
+  }
+
+  pub fn remove(&mut self, rating: u8) {
+    self.count = self.count.saturating_sub(1);
+    self.total = self.total.saturating_sub(rating as u64);
+  }
+
+  pub fn average(&self) -> f32 {
+    match self.count {
+      0 => 0f32,
+      count => self.total as f32 / count as f32
+    }
+  }
+
+  pub fn get(app_id: &AppId) -> Option<Self> {
+    APP_RATINGS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&AppKey::new(app_id))
+    })
+  }
+
+  pub fn save(&self) {
+    APP_RATINGS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      inst.insert(AppKey::new(&self.app_id), self.clone());
+    });
+  }
+}
+
+impl Storable for AppRating {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for AppRating {
+  const MAX_SIZE: u32 = 128;
+  const IS_FIXED_SIZE: bool = false;
+}
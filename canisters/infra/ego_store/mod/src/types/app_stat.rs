@@ -0,0 +1,130 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::AppId;
+use ego_utils::util::time;
+
+use crate::memory::APP_STATS;
+use crate::types::index_key::AppDayKey;
+
+pub const DAY_SECONDS: u64 = 24 * 60 * 60;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AppStatEvent {
+  INSTALL,
+  UNINSTALL,
+  UPGRADE,
+}
+
+/// install counters of an app in one day, day is the number of days since the unix epoch
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppStat {
+  pub app_id: AppId,
+  pub day: u64,
+  pub installs: u64,
+  pub uninstalls: u64,
+  pub upgrades: u64,
+  pub last_update: u64, // second
+}
+
+impl AppStat {
+  pub fn new(app_id: &AppId, day: u64) -> Self {
+    Self {
+      app_id: app_id.clone(),
+      day,
+      installs: 0,
+      uninstalls: 0,
+      upgrades: 0,
+      last_update: 0,
+    }
+  }
+
+  pub fn today() -> u64 {
+    time() / DAY_SECONDS
+  }
+
+  pub fn record(app_id: &AppId, event: AppStatEvent) {
+    let day = Self::today();
+    let mut app_stat = Self::get(app_id, day).unwrap_or_else(|| Self::new(app_id, day));
+
+    match event {
+      AppStatEvent::INSTALL => app_stat.installs += 1,
+      AppStatEvent::UNINSTALL => app_stat.uninstalls += 1,
+      AppStatEvent::UPGRADE => app_stat.upgrades += 1,
+    }
+
+    app_stat.save();
+  }
+
+  pub fn len() -> u64 {
+    APP_STATS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_stat)| Some(app_stat))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, app_stat)| match app_stat.last_update >= last_update {
+      true => { Some(app_stat) }
+      false => { None }
+    })
+  }
+
+  /// daily records of the app between the two days, both included
+  pub fn by_app_id(app_id: &AppId, from_day: u64, to_day: u64) -> Vec<Self> {
+    APP_STATS.with(|cell| {
+      let inst = cell.borrow();
+      inst.range(AppDayKey::range(app_id, from_day, to_day)).map(|(_, app_stat)| app_stat).collect()
+    })
+  }
+
+  pub fn get(app_id: &AppId, day: u64) -> Option<Self> {
+    APP_STATS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&AppDayKey::new(app_id, day))
+    })
+  }
+
+  pub fn save(&mut self) {
+    APP_STATS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(AppDayKey::new(&self.app_id, self.day), self.clone());
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((AppDayKey, Self)) -> Option<Self>,
+  {
+    APP_STATS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for AppStat {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for AppStat {
+  const MAX_SIZE: u32 = 256;
+  const IS_FIXED_SIZE: bool = false;
+}
@@ -8,7 +8,8 @@ use serde::Serialize;
 use ego_types::app::CashFlowType;
 use ego_utils::util::time;
 
-use crate::memory::CASH_FLOWS;
+use crate::memory::{CASH_FLOW_INDEX, CASH_FLOWS};
+use crate::types::history_key::{HistoryCursor, HistoryKey};
 use crate::state::SEQ;
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -68,14 +69,45 @@ impl CashFlow {
   }
 
   pub fn by_wallet_id(wallet_id: &Principal) -> Vec<Self> {
-    Self::iter(0, Self::len() as usize, |(_, cash_flow)| match cash_flow.wallet_id == *wallet_id {
-      true => {
-        Some(cash_flow)
+    let mut cash_flows = vec![];
+    Self::iter_by_wallet_id(wallet_id, 0, u64::MAX, None, |_, cash_flow| {
+      cash_flows.push(cash_flow);
+      true
+    });
+    cash_flows.sort_by_key(|cash_flow| cash_flow.id);
+    cash_flows
+  }
+
+  /// walk through the cash flows of the wallet created between from_ts and to_ts after the cursor,
+  /// newest first, until f returns false
+  pub fn iter_by_wallet_id<F>(wallet_id: &Principal, from_ts: u64, to_ts: u64, cursor: Option<HistoryCursor>, mut f: F)
+  where
+    F: FnMut(HistoryKey, Self) -> bool,
+  {
+    CASH_FLOW_INDEX.with(|cell| {
+      let index = cell.borrow();
+      for (key, id) in index.range(HistoryKey::page(wallet_id, from_ts, to_ts, cursor)) {
+        if let Some(cash_flow) = Self::get(id) {
+          if !f(key, cash_flow) {
+            break;
+          }
+        }
       }
-      false => { None }
+    });
+  }
+
+  pub fn get(id: u64) -> Option<Self> {
+    CASH_FLOWS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&id)
     })
   }
 
+  /// whether the cash flow adds cycles to the wallet
+  pub fn is_inflow(&self) -> bool {
+    matches!(self.cash_flow_type, CashFlowType::RECHARGE | CashFlowType::TRANSFER_IN)
+  }
+
   pub fn save(&mut self) {
     CASH_FLOWS.with(|cell| {
       let mut inst = cell.borrow_mut();
@@ -84,6 +116,29 @@ impl CashFlow {
       }
       inst.insert(self.id, self.clone());
     });
+
+    CASH_FLOW_INDEX.with(|cell| {
+      let mut index = cell.borrow_mut();
+      index.insert(HistoryKey::new(&self.wallet_id, self.created_at, self.id), self.id);
+    });
+  }
+
+  /// build the wallet index for the cash flows saved before the index existed
+  pub fn index_rebuild() {
+    let index_len = CASH_FLOW_INDEX.with(|cell| cell.borrow().len());
+    if index_len == Self::len() {
+      return;
+    }
+
+    CASH_FLOWS.with(|cell| {
+      let inst = cell.borrow();
+      CASH_FLOW_INDEX.with(|index_cell| {
+        let mut index = index_cell.borrow_mut();
+        inst.iter().for_each(|(id, cash_flow)| {
+          index.insert(HistoryKey::new(&cash_flow.wallet_id, cash_flow.created_at, id), id);
+        });
+      });
+    });
   }
 
   fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
@@ -0,0 +1,157 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use ic_stable_structures::storable::Blob;
+use serde::Serialize;
+
+use ego_utils::util::time;
+
+use crate::memory::{CHARGES, PENDING_CHARGES};
+use crate::state::SEQ;
+
+#[derive(
+  CandidType, Serialize, Deserialize, Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq,
+)]
+pub enum ChargeStatus {
+  // debited from the wallet, the top up not confirmed yet
+  RESERVED,
+  // the top up confirmed by the tenant, recorded as a CHARGE cash flow
+  COMMITTED,
+  // the top up failed, credited back to the wallet
+  RELEASED,
+  // the top up found in the log of the tenant
+  RECONCILED,
+  // committed, but the top up not found in the log of the tenant
+  REFUNDED,
+}
+
+/// a wallet charge for topping up one of its canisters, settled in two phases
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Charge {
+  pub id: u64,
+  pub wallet_id: Principal,
+  pub canister_id: Principal,
+  // the tenant topping up the canister
+  pub operator: Principal,
+  pub cycles: u128,
+  pub comment: String,
+  pub status: ChargeStatus,
+  // the CHARGE cash flow written on commit
+  pub cash_flow_id: Option<u64>,
+  pub created_at: u64,
+  pub last_update: u64, // second
+}
+
+impl Charge {
+  pub fn new(wallet_id: &Principal, canister_id: &Principal, operator: &Principal, cycles: u128, comment: String) -> Self {
+    let next_id = SEQ.with(|cell| cell.borrow_mut().next_number("charge", 0));
+    Self {
+      id: next_id,
+      wallet_id: *wallet_id,
+      canister_id: *canister_id,
+      operator: *operator,
+      cycles,
+      comment,
+      status: ChargeStatus::RESERVED,
+      cash_flow_id: None,
+      created_at: time(),
+      last_update: 0,
+    }
+  }
+
+  /// whether the charge is still to be reconciled against the log of the tenant
+  pub fn is_pending(&self) -> bool {
+    matches!(self.status, ChargeStatus::RESERVED | ChargeStatus::COMMITTED)
+  }
+
+  pub fn len() -> u64 {
+    CHARGES.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, charge)| Some(charge))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, charge)| match charge.last_update >= last_update {
+      true => { Some(charge) }
+      false => { None }
+    })
+  }
+
+  /// the pending charges of the tenant created between from_ts and to_ts, the oldest first
+  pub fn pending_by_operator(operator: &Principal, from_ts: u64, to_ts: u64, limit: usize) -> Vec<Self> {
+    let key = Blob::try_from(operator.as_slice()).unwrap();
+    PENDING_CHARGES.with(|cell| {
+      let index = cell.borrow();
+      index.iter()
+        .filter(|(_, tenant)| *tenant == key)
+        .filter_map(|(id, _)| Self::get(id))
+        .filter(|charge| charge.created_at >= from_ts && charge.created_at < to_ts)
+        .take(limit)
+        .collect()
+    })
+  }
+
+  pub fn pending_list() -> Vec<Self> {
+    PENDING_CHARGES.with(|cell| {
+      let index = cell.borrow();
+      index.iter().filter_map(|(id, _)| Self::get(id)).collect()
+    })
+  }
+
+  pub fn get(id: u64) -> Option<Self> {
+    CHARGES.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&id)
+    })
+  }
+
+  pub fn save(&mut self) {
+    CHARGES.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(self.id, self.clone());
+    });
+
+    PENDING_CHARGES.with(|cell| {
+      let mut index = cell.borrow_mut();
+      match self.is_pending() {
+        true => { index.insert(self.id, Blob::try_from(self.operator.as_slice()).unwrap()); }
+        false => { index.remove(&self.id); }
+      }
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((u64, Self)) -> Option<Self>,
+  {
+    CHARGES.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for Charge {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for Charge {
+  const MAX_SIZE: u32 = 1024;
+  const IS_FIXED_SIZE: bool = false;
+}
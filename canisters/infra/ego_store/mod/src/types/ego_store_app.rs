@@ -3,24 +3,40 @@ use std::borrow::Cow;
 use candid::{Decode, Encode};
 use candid::{CandidType, Deserialize};
 use ic_stable_structures::{BoundedStorable, Storable};
+use ic_stable_structures::storable::Blob;
 use serde::Serialize;
 
 use ego_types::app::{App, AppId, Wasm};
+use ego_types::semver::SemVer;
 use ego_utils::util::time;
 
-use crate::memory::EGO_STORE_APPS;
+use crate::certification::app_certify;
+use crate::memory::{EGO_STORE_APPS, EGO_STORE_APPS_LEGACY};
 use crate::types::app_key::AppKey;
 
+// the app fits the 2048 bytes ego_dev keeps it in, the wasm and the labels add less than 512
+pub const EGO_STORE_APP_SIZE: u32 = 4096;
+// the bound of the apps saved before the pre-release and verified fields
+pub const LEGACY_EGO_STORE_APP_SIZE: usize = 512;
+
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct EgoStoreApp {
   pub app: App,
   pub wasm: Wasm,
   pub last_update: u64, // second
+  // the semver pre-release label of the current version, like "beta.1"
+  pub pre_release: Option<String>,
+  // the developer of the app holds the verified badge of ego_dev
+  pub developer_verified: Option<bool>,
 }
 
 impl EgoStoreApp {
   pub fn new(app: &App, wasm: &Wasm) -> Self {
-    Self { app: app.clone(), wasm: wasm.clone(), last_update: 0 }
+    Self { app: app.clone(), wasm: wasm.clone(), last_update: 0, pre_release: None, developer_verified: None }
+  }
+
+  pub fn semver(&self) -> SemVer {
+    SemVer::new(&self.app.current_version, &self.pre_release, &None).unwrap_or_else(|_| SemVer::from(self.app.current_version))
   }
 
   pub fn len() -> u64 {
@@ -54,6 +70,25 @@ impl EgoStoreApp {
       self.last_update = time();
       inst.insert(AppKey::new(&self.app.app_id), self.clone());
     });
+    app_certify(&self.app);
+  }
+
+  /// moves the apps saved under the legacy bound to the apps map, keeping them as they were
+  pub fn migrate() {
+    let legacy: Vec<(AppKey, Blob<LEGACY_EGO_STORE_APP_SIZE>)> = EGO_STORE_APPS_LEGACY.with(|cell| cell.borrow().iter().collect());
+
+    legacy.into_iter().for_each(|(key, value)| {
+      let ego_store_app = EgoStoreApp::from_bytes(value.to_bytes());
+      EGO_STORE_APPS_LEGACY.with(|cell| cell.borrow_mut().remove(&key));
+      EGO_STORE_APPS.with(|cell| cell.borrow_mut().insert(key, ego_store_app));
+    });
+  }
+
+  /// rebuild the certified catalog tree, which lives on the heap
+  pub fn certify_all() {
+    Self::list(0, Self::len() as usize).iter().for_each(|ego_store_app| {
+      app_certify(&ego_store_app.app)
+    });
   }
 
   fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
@@ -80,6 +115,6 @@ impl Storable for EgoStoreApp {
 }
 
 impl BoundedStorable for EgoStoreApp {
-  const MAX_SIZE: u32 = 512;
+  const MAX_SIZE: u32 = EGO_STORE_APP_SIZE;
   const IS_FIXED_SIZE: bool = false;
 }
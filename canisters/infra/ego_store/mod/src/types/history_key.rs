@@ -0,0 +1,104 @@
+use std::borrow::Cow;
+use std::ops::Bound;
+
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use crate::types::index_key::{PRINCIPAL_SIZE, principal_bytes};
+
+const WALLET_SIZE: usize = PRINCIPAL_SIZE;
+const KEY_SIZE: usize = WALLET_SIZE + 8 + 8;
+
+/// position of the last record returned by a paginated history query
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HistoryCursor {
+  pub ts: u64,
+  pub id: u64,
+}
+
+/// secondary index key for wallet histories, ordered by wallet, then newest record first.
+/// the timestamp and id are stored inverted so an ascending scan returns the latest records first
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HistoryKey {
+  wallet: [u8; WALLET_SIZE],
+  rev_ts: u64,
+  rev_id: u64,
+}
+
+impl HistoryKey {
+  pub fn new(wallet_id: &Principal, ts: u64, id: u64) -> Self {
+    HistoryKey {
+      wallet: principal_bytes(wallet_id),
+      rev_ts: u64::MAX - ts,
+      rev_id: u64::MAX - id,
+    }
+  }
+
+  pub fn ts(&self) -> u64 {
+    u64::MAX - self.rev_ts
+  }
+
+  pub fn id(&self) -> u64 {
+    u64::MAX - self.rev_id
+  }
+
+  pub fn cursor(&self) -> HistoryCursor {
+    HistoryCursor {
+      ts: self.ts(),
+      id: self.id(),
+    }
+  }
+
+  /// keys of one wallet created between from_ts and to_ts (both inclusive), newest first
+  pub fn range(wallet_id: &Principal, from_ts: u64, to_ts: u64) -> (Bound<Self>, Bound<Self>) {
+    (
+      Bound::Included(HistoryKey::new(wallet_id, to_ts, u64::MAX)),
+      Bound::Included(HistoryKey::new(wallet_id, from_ts, 0)),
+    )
+  }
+
+  /// keys of one wallet created between from_ts and to_ts following the cursor, newest first
+  pub fn page(wallet_id: &Principal, from_ts: u64, to_ts: u64, cursor: Option<HistoryCursor>) -> (Bound<Self>, Bound<Self>) {
+    let (start, end) = HistoryKey::range(wallet_id, from_ts, to_ts);
+    let start = match (start, cursor) {
+      (Bound::Included(first), Some(cursor)) => {
+        let after = HistoryKey::new(wallet_id, cursor.ts, cursor.id);
+        match after >= first {
+          true => Bound::Excluded(after),
+          false => Bound::Included(first),
+        }
+      }
+      (start, _) => start,
+    };
+    (start, end)
+  }
+}
+
+impl Storable for HistoryKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    let mut bytes = Vec::with_capacity(KEY_SIZE);
+    bytes.extend_from_slice(&self.wallet);
+    bytes.extend_from_slice(&self.rev_ts.to_be_bytes());
+    bytes.extend_from_slice(&self.rev_id.to_be_bytes());
+    Cow::Owned(bytes)
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    let mut wallet = [0u8; WALLET_SIZE];
+    wallet.copy_from_slice(&bytes[0..WALLET_SIZE]);
+    let rev_ts = u64::from_be_bytes(bytes[WALLET_SIZE..WALLET_SIZE + 8].try_into().unwrap());
+    let rev_id = u64::from_be_bytes(bytes[WALLET_SIZE + 8..KEY_SIZE].try_into().unwrap());
+
+    HistoryKey {
+      wallet,
+      rev_ts,
+      rev_id,
+    }
+  }
+}
+
+impl BoundedStorable for HistoryKey {
+  const MAX_SIZE: u32 = KEY_SIZE as u32;
+  const IS_FIXED_SIZE: bool = true;
+}
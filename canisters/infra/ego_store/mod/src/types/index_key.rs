@@ -0,0 +1,403 @@
+use std::borrow::Cow;
+use std::ops::Bound;
+
+use candid::Principal;
+use ic_stable_structures::{BoundedStorable, Storable};
+
+use ego_types::app::{AlertKind, AppId, Version};
+use ego_types::semver::SemVer;
+
+pub(crate) const PRINCIPAL_SIZE: usize = 30;
+// the longest app id the 64 bytes AppKey holds once candid encoded
+pub const APP_ID_MAX_LEN: usize = 48;
+const APP_ID_SIZE: usize = APP_ID_MAX_LEN + 1;
+const PRE_RELEASE_SIZE: usize = 32;
+
+/// length prefixed principal bytes, padded to a fixed size so the encoded keys sort like the structs
+pub(crate) fn principal_bytes(principal: &Principal) -> [u8; PRINCIPAL_SIZE] {
+  let bytes = principal.as_slice();
+  let mut fixed = [0u8; PRINCIPAL_SIZE];
+  fixed[0] = bytes.len() as u8;
+  fixed[1..=bytes.len()].copy_from_slice(bytes);
+  fixed
+}
+
+pub(crate) fn bytes_principal(fixed: &[u8]) -> Principal {
+  let len = fixed[0] as usize;
+  Principal::from_slice(&fixed[1..=len])
+}
+
+/// length prefixed app id bytes, the app ids longer than APP_ID_MAX_LEN are refused on release
+fn app_id_bytes(app_id: &AppId) -> [u8; APP_ID_SIZE] {
+  let bytes = app_id.as_bytes();
+  assert!(bytes.len() <= APP_ID_MAX_LEN, "app id {} too long for the index", app_id);
+  let mut fixed = [0u8; APP_ID_SIZE];
+  fixed[0] = bytes.len() as u8;
+  fixed[1..=bytes.len()].copy_from_slice(bytes);
+  fixed
+}
+
+/// pre-release identifiers encoded so the bytes sort by semver precedence: a release sorts after
+/// its pre-releases, numeric identifiers sort by value and before the alphanumeric ones, and a
+/// shorter list sorts before a longer one sharing its prefix
+fn pre_release_bytes(pre_release: &[String]) -> Option<[u8; PRE_RELEASE_SIZE]> {
+  let mut fixed = [0u8; PRE_RELEASE_SIZE];
+  if pre_release.is_empty() {
+    fixed[0] = 1;
+    return Some(fixed);
+  }
+
+  let mut bytes = vec![0u8];
+  for identifier in pre_release {
+    match identifier.parse::<u64>() {
+      Ok(number) => {
+        bytes.push(1);
+        bytes.extend_from_slice(&number.to_be_bytes());
+      }
+      Err(_) => {
+        bytes.push(2);
+        bytes.extend_from_slice(identifier.as_bytes());
+        bytes.push(0);
+      }
+    }
+  }
+  if bytes.len() > PRE_RELEASE_SIZE {
+    return None;
+  }
+  fixed[0..bytes.len()].copy_from_slice(&bytes);
+  Some(fixed)
+}
+
+fn bytes_pre_release(fixed: &[u8]) -> Option<String> {
+  if fixed[0] == 1 {
+    return None;
+  }
+
+  let mut identifiers = vec![];
+  let mut at = 1;
+  while at < PRE_RELEASE_SIZE {
+    match fixed[at] {
+      1 => {
+        identifiers.push(u64::from_be_bytes(fixed[at + 1..at + 9].try_into().unwrap()).to_string());
+        at += 9;
+      }
+      2 => {
+        let end = at + 1 + fixed[at + 1..].iter().position(|byte| *byte == 0).unwrap();
+        identifiers.push(String::from_utf8_lossy(&fixed[at + 1..end]).to_string());
+        at = end + 1;
+      }
+      _ => break,
+    }
+  }
+  Some(identifiers.join("."))
+}
+
+/// index key of the user apps owned by a wallet
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WalletCanisterKey {
+  wallet: [u8; PRINCIPAL_SIZE],
+  canister: [u8; PRINCIPAL_SIZE],
+}
+
+impl WalletCanisterKey {
+  pub fn new(wallet_id: &Principal, canister_id: &Principal) -> Self {
+    WalletCanisterKey {
+      wallet: principal_bytes(wallet_id),
+      canister: principal_bytes(canister_id),
+    }
+  }
+
+  pub fn canister_id(&self) -> Principal {
+    bytes_principal(&self.canister)
+  }
+
+  pub fn range(wallet_id: &Principal) -> (Bound<Self>, Bound<Self>) {
+    let wallet = principal_bytes(wallet_id);
+    (
+      Bound::Included(WalletCanisterKey { wallet, canister: [0u8; PRINCIPAL_SIZE] }),
+      Bound::Included(WalletCanisterKey { wallet, canister: [u8::MAX; PRINCIPAL_SIZE] }),
+    )
+  }
+}
+
+impl Storable for WalletCanisterKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    let mut bytes = Vec::with_capacity(2 * PRINCIPAL_SIZE);
+    bytes.extend_from_slice(&self.wallet);
+    bytes.extend_from_slice(&self.canister);
+    Cow::Owned(bytes)
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    let mut wallet = [0u8; PRINCIPAL_SIZE];
+    wallet.copy_from_slice(&bytes[0..PRINCIPAL_SIZE]);
+    let mut canister = [0u8; PRINCIPAL_SIZE];
+    canister.copy_from_slice(&bytes[PRINCIPAL_SIZE..2 * PRINCIPAL_SIZE]);
+    WalletCanisterKey { wallet, canister }
+  }
+}
+
+impl BoundedStorable for WalletCanisterKey {
+  const MAX_SIZE: u32 = 2 * PRINCIPAL_SIZE as u32;
+  const IS_FIXED_SIZE: bool = true;
+}
+
+/// index key of the alerts waiting for the wallet, one per canister and kind
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CanisterAlertKey {
+  canister: [u8; PRINCIPAL_SIZE],
+  kind: u8,
+}
+
+impl CanisterAlertKey {
+  pub fn new(canister_id: &Principal, kind: AlertKind) -> Self {
+    CanisterAlertKey {
+      canister: principal_bytes(canister_id),
+      kind: kind as u8,
+    }
+  }
+}
+
+impl Storable for CanisterAlertKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    let mut bytes = Vec::with_capacity(PRINCIPAL_SIZE + 1);
+    bytes.extend_from_slice(&self.canister);
+    bytes.push(self.kind);
+    Cow::Owned(bytes)
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    let mut canister = [0u8; PRINCIPAL_SIZE];
+    canister.copy_from_slice(&bytes[0..PRINCIPAL_SIZE]);
+    CanisterAlertKey { canister, kind: bytes[PRINCIPAL_SIZE] }
+  }
+}
+
+impl BoundedStorable for CanisterAlertKey {
+  const MAX_SIZE: u32 = PRINCIPAL_SIZE as u32 + 1;
+  const IS_FIXED_SIZE: bool = true;
+}
+
+/// index key of the user apps installed from an app, grouped by version in semver precedence
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AppVersionKey {
+  app_id: [u8; APP_ID_SIZE],
+  version: (u32, u32, u32),
+  pre_release: [u8; PRE_RELEASE_SIZE],
+  canister: [u8; PRINCIPAL_SIZE],
+}
+
+impl AppVersionKey {
+  pub fn new(app_id: &AppId, version: &SemVer, canister_id: &Principal) -> Self {
+    AppVersionKey {
+      app_id: app_id_bytes(app_id),
+      version: (version.major, version.minor, version.patch),
+      pre_release: pre_release_bytes(&version.pre_release).expect("pre-release too long for the index"),
+      canister: principal_bytes(canister_id),
+    }
+  }
+
+  /// whether the app id and the version fit in the key, checked before an app is released
+  pub fn fits(app_id: &AppId, version: &SemVer) -> bool {
+    app_id.len() <= APP_ID_MAX_LEN && pre_release_bytes(&version.pre_release).is_some()
+  }
+
+  pub fn version(&self) -> Version {
+    Version::new(self.version.0, self.version.1, self.version.2)
+  }
+
+  pub fn pre_release(&self) -> Option<String> {
+    bytes_pre_release(&self.pre_release)
+  }
+
+  pub fn canister_id(&self) -> Principal {
+    bytes_principal(&self.canister)
+  }
+
+  /// keys of one app, optionally limited to a single version and its pre-releases
+  pub fn range(app_id: &AppId, version: Option<Version>) -> (Bound<Self>, Bound<Self>) {
+    let app_id = app_id_bytes(app_id);
+    let (start, end) = match version {
+      Some(v) => ((v.major, v.minor, v.patch), (v.major, v.minor, v.patch)),
+      None => ((0, 0, 0), (u32::MAX, u32::MAX, u32::MAX)),
+    };
+    (
+      Bound::Included(AppVersionKey { app_id, version: start, pre_release: [0u8; PRE_RELEASE_SIZE], canister: [0u8; PRINCIPAL_SIZE] }),
+      Bound::Included(AppVersionKey { app_id, version: end, pre_release: [u8::MAX; PRE_RELEASE_SIZE], canister: [u8::MAX; PRINCIPAL_SIZE] }),
+    )
+  }
+}
+
+impl Storable for AppVersionKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    let mut bytes = Vec::with_capacity(APP_ID_SIZE + 12 + PRE_RELEASE_SIZE + PRINCIPAL_SIZE);
+    bytes.extend_from_slice(&self.app_id);
+    bytes.extend_from_slice(&self.version.0.to_be_bytes());
+    bytes.extend_from_slice(&self.version.1.to_be_bytes());
+    bytes.extend_from_slice(&self.version.2.to_be_bytes());
+    bytes.extend_from_slice(&self.pre_release);
+    bytes.extend_from_slice(&self.canister);
+    Cow::Owned(bytes)
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    let mut app_id = [0u8; APP_ID_SIZE];
+    app_id.copy_from_slice(&bytes[0..APP_ID_SIZE]);
+    let at = |offset: usize| {
+      u32::from_be_bytes(bytes[APP_ID_SIZE + offset..APP_ID_SIZE + offset + 4].try_into().unwrap())
+    };
+    let version = (at(0), at(4), at(8));
+    let mut pre_release = [0u8; PRE_RELEASE_SIZE];
+    pre_release.copy_from_slice(&bytes[APP_ID_SIZE + 12..APP_ID_SIZE + 12 + PRE_RELEASE_SIZE]);
+    let mut canister = [0u8; PRINCIPAL_SIZE];
+    canister.copy_from_slice(&bytes[APP_ID_SIZE + 12 + PRE_RELEASE_SIZE..APP_ID_SIZE + 12 + PRE_RELEASE_SIZE + PRINCIPAL_SIZE]);
+    AppVersionKey { app_id, version, pre_release, canister }
+  }
+}
+
+impl BoundedStorable for AppVersionKey {
+  const MAX_SIZE: u32 = (APP_ID_SIZE + 12 + PRE_RELEASE_SIZE + PRINCIPAL_SIZE) as u32;
+  const IS_FIXED_SIZE: bool = true;
+}
+
+/// key of the data one wallet keeps per app, like reviews
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AppWalletKey {
+  app_id: [u8; APP_ID_SIZE],
+  wallet: [u8; PRINCIPAL_SIZE],
+}
+
+impl AppWalletKey {
+  pub fn new(app_id: &AppId, wallet_id: &Principal) -> Self {
+    AppWalletKey {
+      app_id: app_id_bytes(app_id),
+      wallet: principal_bytes(wallet_id),
+    }
+  }
+
+  pub fn wallet_id(&self) -> Principal {
+    bytes_principal(&self.wallet)
+  }
+
+  pub fn range(app_id: &AppId) -> (Bound<Self>, Bound<Self>) {
+    let app_id = app_id_bytes(app_id);
+    (
+      Bound::Included(AppWalletKey { app_id, wallet: [0u8; PRINCIPAL_SIZE] }),
+      Bound::Included(AppWalletKey { app_id, wallet: [u8::MAX; PRINCIPAL_SIZE] }),
+    )
+  }
+}
+
+impl Storable for AppWalletKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    let mut bytes = Vec::with_capacity(APP_ID_SIZE + PRINCIPAL_SIZE);
+    bytes.extend_from_slice(&self.app_id);
+    bytes.extend_from_slice(&self.wallet);
+    Cow::Owned(bytes)
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    let mut app_id = [0u8; APP_ID_SIZE];
+    app_id.copy_from_slice(&bytes[0..APP_ID_SIZE]);
+    let mut wallet = [0u8; PRINCIPAL_SIZE];
+    wallet.copy_from_slice(&bytes[APP_ID_SIZE..APP_ID_SIZE + PRINCIPAL_SIZE]);
+    AppWalletKey { app_id, wallet }
+  }
+}
+
+impl BoundedStorable for AppWalletKey {
+  const MAX_SIZE: u32 = (APP_ID_SIZE + PRINCIPAL_SIZE) as u32;
+  const IS_FIXED_SIZE: bool = true;
+}
+
+/// key of the daily records of an app
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AppDayKey {
+  app_id: [u8; APP_ID_SIZE],
+  day: u64,
+}
+
+impl AppDayKey {
+  pub fn new(app_id: &AppId, day: u64) -> Self {
+    AppDayKey {
+      app_id: app_id_bytes(app_id),
+      day,
+    }
+  }
+
+  pub fn range(app_id: &AppId, from_day: u64, to_day: u64) -> (Bound<Self>, Bound<Self>) {
+    let app_id = app_id_bytes(app_id);
+    (
+      Bound::Included(AppDayKey { app_id, day: from_day }),
+      Bound::Included(AppDayKey { app_id, day: to_day }),
+    )
+  }
+}
+
+impl Storable for AppDayKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    let mut bytes = Vec::with_capacity(APP_ID_SIZE + 8);
+    bytes.extend_from_slice(&self.app_id);
+    bytes.extend_from_slice(&self.day.to_be_bytes());
+    Cow::Owned(bytes)
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    let mut app_id = [0u8; APP_ID_SIZE];
+    app_id.copy_from_slice(&bytes[0..APP_ID_SIZE]);
+    let day = u64::from_be_bytes(bytes[APP_ID_SIZE..APP_ID_SIZE + 8].try_into().unwrap());
+    AppDayKey { app_id, day }
+  }
+}
+
+impl BoundedStorable for AppDayKey {
+  const MAX_SIZE: u32 = (APP_ID_SIZE + 8) as u32;
+  const IS_FIXED_SIZE: bool = true;
+}
+
+/// key of the release kept for each major.minor line of an app
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AppLineKey {
+  app_id: [u8; APP_ID_SIZE],
+  line: (u32, u32),
+}
+
+impl AppLineKey {
+  pub fn new(app_id: &AppId, version: &Version) -> Self {
+    AppLineKey {
+      app_id: app_id_bytes(app_id),
+      line: (version.major, version.minor),
+    }
+  }
+
+  pub fn range(app_id: &AppId) -> (Bound<Self>, Bound<Self>) {
+    let app_id = app_id_bytes(app_id);
+    (
+      Bound::Included(AppLineKey { app_id, line: (0, 0) }),
+      Bound::Included(AppLineKey { app_id, line: (u32::MAX, u32::MAX) }),
+    )
+  }
+}
+
+impl Storable for AppLineKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    let mut bytes = Vec::with_capacity(APP_ID_SIZE + 8);
+    bytes.extend_from_slice(&self.app_id);
+    bytes.extend_from_slice(&self.line.0.to_be_bytes());
+    bytes.extend_from_slice(&self.line.1.to_be_bytes());
+    Cow::Owned(bytes)
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    let mut app_id = [0u8; APP_ID_SIZE];
+    app_id.copy_from_slice(&bytes[0..APP_ID_SIZE]);
+    let major = u32::from_be_bytes(bytes[APP_ID_SIZE..APP_ID_SIZE + 4].try_into().unwrap());
+    let minor = u32::from_be_bytes(bytes[APP_ID_SIZE + 4..APP_ID_SIZE + 8].try_into().unwrap());
+    AppLineKey { app_id, line: (major, minor) }
+  }
+}
+
+impl BoundedStorable for AppLineKey {
+  const MAX_SIZE: u32 = (APP_ID_SIZE + 8) as u32;
+  const IS_FIXED_SIZE: bool = true;
+}
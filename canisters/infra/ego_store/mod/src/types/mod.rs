@@ -1,17 +1,37 @@
 use candid::{CandidType, Deserialize, Principal};
 use serde::Serialize;
 
-use ego_types::app::{AppId, EgoError};
+use ego_types::app::{AlertKind, App, AppId, AppMetadata, CashFlow, CashFlowType, Category, EgoError, Version};
 
+use crate::c2c::c2c_types::{CanisterSettingsUpdate, TopUpPolicy};
+use crate::types::app_stat::AppStat;
+use crate::types::history_key::HistoryCursor;
+use crate::types::order::{Order, OrderStatus};
+use crate::types::tenant::Tenant;
+use crate::types::tenant_metric::TenantMetric;
+
+pub mod alert;
+pub mod alert_subscription;
+pub mod app_audit;
 pub mod app_key;
+pub mod app_line_release;
+pub mod app_metadata;
+pub mod app_rating;
+pub mod app_stat;
 pub mod cash_flow;
+pub mod charge;
 pub mod ego_store_app;
+pub mod history_key;
+pub mod index_key;
 pub mod order;
+pub mod review;
 pub mod stable_state;
 pub mod tenant;
+pub mod tenant_metric;
 pub mod user_app;
 pub mod wallet;
 pub mod wallet_provider;
+pub mod withdraw;
 
 #[derive(Clone, Debug, CandidType, Deserialize, Serialize)]
 pub enum EgoStoreErr {
@@ -29,6 +49,23 @@ pub enum EgoStoreErr {
   WalletProviderExists,
   WalletProviderNotExists,
   CyclesNotEnouth,
+  WalletTransferInvalid,
+  WalletNotSameUser,
+  WithdrawAmountTooSmall,
+  WithdrawTooFrequent,
+  ReviewNotAllowed,
+  ReviewInvalid,
+  ReviewNotExists,
+  VersionReqInvalid,
+  VersionNotMatched,
+  AlertNotExists,
+  TenantNotExists,
+  TenantReserveLow,
+  ChargeNotExists,
+  ChargeSettled,
+  AppKeyInvalid,
+  LineReleaseInvalid,
+  WalletNotEmpty,
 }
 
 impl From<EgoStoreErr> for EgoError {
@@ -53,6 +90,43 @@ impl From<EgoStoreErr> for EgoError {
         EgoError::new(3012, "ego-store: wallet provider not exists")
       }
       EgoStoreErr::CyclesNotEnouth => EgoError::new(3003, "ego-store: cycles not enough"),
+      EgoStoreErr::WalletTransferInvalid => {
+        EgoError::new(3013, "ego-store: invalid wallet transfer")
+      }
+      EgoStoreErr::WalletNotSameUser => {
+        EgoError::new(3014, "ego-store: wallet not belongs to the same user")
+      }
+      EgoStoreErr::WithdrawAmountTooSmall => {
+        EgoError::new(3015, "ego-store: withdraw amount too small")
+      }
+      EgoStoreErr::WithdrawTooFrequent => {
+        EgoError::new(3016, "ego-store: withdraw too frequent")
+      }
+      EgoStoreErr::ReviewNotAllowed => {
+        EgoError::new(3017, "ego-store: only wallets installed the app can review it")
+      }
+      EgoStoreErr::ReviewInvalid => EgoError::new(3018, "ego-store: review invalid"),
+      EgoStoreErr::ReviewNotExists => EgoError::new(3019, "ego-store: review not exists"),
+      EgoStoreErr::VersionReqInvalid => EgoError::new(3020, "ego-store: version requirement invalid"),
+      EgoStoreErr::VersionNotMatched => {
+        EgoError::new(3021, "ego-store: app version does not match the requirement")
+      }
+      EgoStoreErr::AlertNotExists => EgoError::new(3022, "ego-store: alert not exists"),
+      EgoStoreErr::TenantNotExists => EgoError::new(3023, "ego-store: tenant not exists"),
+      EgoStoreErr::TenantReserveLow => {
+        EgoError::new(3024, "ego-store: the tenant of the wallet is short of cycles, retry after it is refilled")
+      }
+      EgoStoreErr::ChargeNotExists => EgoError::new(3025, "ego-store: charge not exists"),
+      EgoStoreErr::ChargeSettled => EgoError::new(3026, "ego-store: charge already settled"),
+      EgoStoreErr::AppKeyInvalid => {
+        EgoError::new(3027, "ego-store: app id or pre-release label too long")
+      }
+      EgoStoreErr::LineReleaseInvalid => {
+        EgoError::new(3028, "ego-store: a line release must be below the current version of the app")
+      }
+      EgoStoreErr::WalletNotEmpty => {
+        EgoError::new(3029, "ego-store: the wallet still has apps or cycles")
+      }
       EgoStoreErr::SystemError(msg) => msg.into(),
     }
   }
@@ -74,6 +148,36 @@ pub struct WalletCycleChargeRequest {
 #[derive(CandidType, Deserialize, Serialize)]
 pub struct WalletCycleChargeResponse {
   pub ret: bool,
+  // the charge, settled at once
+  pub charge_id: u64,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct WalletCycleReserveResponse {
+  pub charge_id: u64,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct CanisterAlertAddRequest {
+  pub canister_id: Principal,
+  pub kind: AlertKind,
+  pub message: String,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct WalletCycleTransferRequest {
+  pub to_wallet: Principal,
+  pub amount: u128,
+  pub comment: String,
+  // reject the transfer when the receiving wallet belongs to another user
+  pub same_user_only: bool,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct WalletCycleWithdrawRequest {
+  pub target_id: Principal,
+  pub amount: u128,
+  pub comment: String,
 }
 
 #[derive(CandidType, Deserialize, Serialize)]
@@ -88,3 +192,195 @@ pub struct AdminWalletCycleRechargeRequest {
   pub cycle: u128,
   pub comment: String,
 }
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct WalletCashFlowListRequest {
+  pub cursor: Option<HistoryCursor>,
+  pub limit: u32,
+  pub from_ts: Option<u64>,
+  pub to_ts: Option<u64>,
+  pub cash_flow_type: Option<CashFlowType>,
+  // scans the whole filtered range, leave it off when paging through
+  pub with_totals: Option<bool>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Default)]
+pub struct WalletCashFlowListResponse {
+  pub cash_flows: Vec<CashFlow>,
+  pub next_cursor: Option<HistoryCursor>,
+  // totals over the whole filtered range, only when asked for
+  pub count: Option<u64>,
+  pub total_in: Option<u128>,
+  pub total_out: Option<u128>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct WalletCanisterTopUpPolicySetRequest {
+  pub canister_id: Principal,
+  // None restores the default policy of the tenant
+  pub policy: Option<TopUpPolicy>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct TenantInfo {
+  pub tenant: Tenant,
+  pub metric: TenantMetric,
+  pub healthy: bool,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct TenantMetricReportRequest {
+  pub task_count: u64,
+  pub dead_task_count: u64,
+  pub cycles: u128,
+  pub spend_per_day: Option<u128>,
+  pub reserve_threshold: Option<u128>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AdminTenantCapacitySetRequest {
+  pub tenant_id: Principal,
+  pub capacity: u16,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct WalletCanisterSettingsUpdateRequest {
+  pub canister_id: Principal,
+  // None keeps the current value
+  pub settings: CanisterSettingsUpdate,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct WalletOrderListRequest {
+  pub cursor: Option<HistoryCursor>,
+  pub limit: u32,
+  pub from_ts: Option<u64>,
+  pub to_ts: Option<u64>,
+  pub status: Option<OrderStatus>,
+  // scans the whole filtered range, leave it off when paging through
+  pub with_totals: Option<bool>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Default)]
+pub struct WalletOrderListResponse {
+  pub orders: Vec<Order>,
+  pub next_cursor: Option<HistoryCursor>,
+  // totals over the whole filtered range, only when asked for
+  pub count: Option<u64>,
+  pub total_amount: Option<f32>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct AppVersionCount {
+  pub version: Version,
+  pub pre_release: Option<String>,
+  pub count: u64,
+}
+
+/// an `App` as returned by app_main_list / app_main_get. it keeps every field of `App`, so the
+/// clients decoding an `App` still can, and adds the proof needed to verify a query response
+/// and the catalog details of the app. only the `App` part is covered by the proof
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct AppEntry {
+  pub app_id: AppId,
+  pub name: String,
+  pub category: Category,
+  pub logo: String,
+  pub description: String,
+  pub current_version: Version,
+  pub price: f32,
+  pub app_hash: String,
+  // the subnet signed certificate of the canister certified data, query calls only
+  pub certificate: Option<Vec<u8>>,
+  // the cbor encoded hash tree containing the sha256 of the candid encoded `App`
+  pub witness: Option<Vec<u8>>,
+  pub metadata: Option<AppMetadata>,
+  pub rating_count: Option<u64>,
+  pub rating_average: Option<f32>,
+  pub last_update: Option<u64>,
+  pub developer_verified: Option<bool>,
+}
+
+impl AppEntry {
+  pub fn app(&self) -> App {
+    App {
+      app_id: self.app_id.clone(),
+      name: self.name.clone(),
+      category: self.category.clone(),
+      logo: self.logo.clone(),
+      description: self.description.clone(),
+      current_version: self.current_version,
+      price: self.price,
+      app_hash: self.app_hash.clone(),
+    }
+  }
+}
+
+impl From<App> for AppEntry {
+  fn from(app: App) -> Self {
+    AppEntry {
+      app_id: app.app_id,
+      name: app.name,
+      category: app.category,
+      logo: app.logo,
+      description: app.description,
+      current_version: app.current_version,
+      price: app.price,
+      app_hash: app.app_hash,
+      certificate: None,
+      witness: None,
+      metadata: None,
+      rating_count: None,
+      rating_average: None,
+      last_update: None,
+      developer_verified: None,
+    }
+  }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum AppSortBy {
+  NAME,
+  PRICE,
+  LAST_UPDATE,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppMainListRequest {
+  // matched against app id, name, description and tags, case insensitive
+  pub keyword: Option<String>,
+  pub category: Option<Category>,
+  // an app must carry all of the tags
+  pub tags: Vec<String>,
+  pub sort_by: Option<AppSortBy>,
+  pub descending: bool,
+  pub offset: u64,
+  pub limit: u32,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppReviewSetRequest {
+  pub app_id: AppId,
+  pub rating: u8,
+  pub content: String,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppStatsRequest {
+  pub app_id: AppId,
+  // days since the unix epoch, both included
+  pub from_day: u64,
+  pub to_day: u64,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct AppStatsResponse {
+  pub app_id: AppId,
+  pub daily: Vec<AppStat>,
+  pub installs: u64,
+  pub uninstalls: u64,
+  pub upgrades: u64,
+  pub active_installs: u64,
+  pub versions: Vec<AppVersionCount>,
+}
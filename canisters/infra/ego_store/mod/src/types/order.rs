@@ -8,7 +8,8 @@ use serde::Serialize;
 
 use ego_utils::util::time;
 
-use crate::memory::ORDERS;
+use crate::memory::{ORDER_INDEX, ORDERS};
+use crate::types::history_key::{HistoryCursor, HistoryKey};
 use crate::state::SEQ;
 
 #[derive(
@@ -67,10 +68,31 @@ impl Order {
   }
 
   pub fn by_wallet_id(wallet_id: &Principal) -> Vec<Self> {
-    Self::iter(0, Self::len() as usize, |(_, order)| match order.wallet_id == *wallet_id {
-      true => { Some(order) }
-      false => { None }
-    })
+    let mut orders = vec![];
+    Self::iter_by_wallet_id(wallet_id, 0, u64::MAX, None, |_, order| {
+      orders.push(order);
+      true
+    });
+    orders.sort_by_key(|order| order.memo.0);
+    orders
+  }
+
+  /// walk through the orders of the wallet updated between from_ts and to_ts after the cursor,
+  /// latest first, until f returns false
+  pub fn iter_by_wallet_id<F>(wallet_id: &Principal, from_ts: u64, to_ts: u64, cursor: Option<HistoryCursor>, mut f: F)
+  where
+    F: FnMut(HistoryKey, Self) -> bool,
+  {
+    ORDER_INDEX.with(|cell| {
+      let index = cell.borrow();
+      for (key, memo) in index.range(HistoryKey::page(wallet_id, from_ts, to_ts, cursor)) {
+        if let Some(order) = Self::get(Memo(memo)) {
+          if !f(key, order) {
+            break;
+          }
+        }
+      }
+    });
   }
 
   pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
@@ -88,11 +110,37 @@ impl Order {
   }
 
   pub fn save(&mut self) {
-    ORDERS.with(|cell| {
+    let previous = ORDERS.with(|cell| {
       let mut inst = cell.borrow_mut();
       self.last_update = time();
 
-      inst.insert(self.memo.0, self.clone());
+      inst.insert(self.memo.0, self.clone())
+    });
+
+    ORDER_INDEX.with(|cell| {
+      let mut index = cell.borrow_mut();
+      if let Some(previous) = previous {
+        index.remove(&HistoryKey::new(&previous.wallet_id, previous.last_update, previous.memo.0));
+      }
+      index.insert(HistoryKey::new(&self.wallet_id, self.last_update, self.memo.0), self.memo.0);
+    });
+  }
+
+  /// build the wallet index for the orders saved before the index existed
+  pub fn index_rebuild() {
+    let index_len = ORDER_INDEX.with(|cell| cell.borrow().len());
+    if index_len == Self::len() {
+      return;
+    }
+
+    ORDERS.with(|cell| {
+      let inst = cell.borrow();
+      ORDER_INDEX.with(|index_cell| {
+        let mut index = index_cell.borrow_mut();
+        inst.iter().for_each(|(memo, order)| {
+          index.insert(HistoryKey::new(&order.wallet_id, order.last_update, memo), memo);
+        });
+      });
     });
   }
 
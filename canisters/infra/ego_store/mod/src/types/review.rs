@@ -0,0 +1,123 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_types::app::AppId;
+use ego_utils::util::time;
+
+use crate::memory::REVIEWS;
+use crate::types::app_rating::AppRating;
+use crate::types::index_key::AppWalletKey;
+
+pub const RATING_MIN: u8 = 1;
+pub const RATING_MAX: u8 = 5;
+pub const REVIEW_CONTENT_MAX_LEN: usize = 1000;
+pub const REVIEW_REPLY_MAX_LEN: usize = 1000;
+
+/// one review per wallet and app
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Review {
+  pub app_id: AppId,
+  pub wallet_id: Principal,
+  pub rating: u8,
+  pub content: String,
+  pub reply: Option<String>,
+  pub hidden: bool,
+  pub created_at: u64,
+  pub last_update: u64, // second
+}
+
+impl Review {
+  pub fn new(app_id: &AppId, wallet_id: &Principal) -> Self {
+    Self {
+      app_id: app_id.clone(),
+      wallet_id: *wallet_id,
+      rating: RATING_MIN,
+      content: "".to_string(),
+      reply: None,
+      hidden: false,
+      created_at: time(),
+      last_update: 0,
+    }
+  }
+
+  pub fn len() -> u64 {
+    REVIEWS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, review)| Some(review))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, review)| match review.last_update >= last_update {
+      true => { Some(review) }
+      false => { None }
+    })
+  }
+
+  pub fn by_app_id(app_id: &AppId) -> Vec<Self> {
+    REVIEWS.with(|cell| {
+      let inst = cell.borrow();
+      inst.range(AppWalletKey::range(app_id)).map(|(_, review)| review).collect()
+    })
+  }
+
+  pub fn get(app_id: &AppId, wallet_id: &Principal) -> Option<Self> {
+    REVIEWS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&AppWalletKey::new(app_id, wallet_id))
+    })
+  }
+
+  pub fn save(&mut self) {
+    let previous = REVIEWS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(AppWalletKey::new(&self.app_id, &self.wallet_id), self.clone())
+    });
+
+    // keep the aggregated rating of the app in step, hidden reviews don't count
+    let mut app_rating = AppRating::get(&self.app_id).unwrap_or_else(|| AppRating::new(&self.app_id));
+    if let Some(previous) = previous.filter(|review| !review.hidden) {
+      app_rating.remove(previous.rating);
+    }
+    if !self.hidden {
+      app_rating.add(self.rating);
+    }
+    app_rating.save();
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((AppWalletKey, Self)) -> Option<Self>,
+  {
+    REVIEWS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for Review {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for Review {
+  const MAX_SIZE: u32 = 4096;
+  const IS_FIXED_SIZE: bool = false;
+}
@@ -0,0 +1,177 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use ic_stable_structures::storable::Blob;
+use serde::Serialize;
+
+use ego_utils::util::time;
+
+use crate::memory::TENANT_METRICS;
+
+// no limit until the admin sets a capacity
+pub const DEFAULT_TENANT_CAPACITY: u16 = u16::MAX;
+// 3 report intervals of the tenant
+pub const TENANT_REPORT_TIMEOUT: u64 = 30 * 60;
+// the tenant pays the top ups before charging the wallets
+pub const TENANT_CYCLES_MIN: u128 = 1_000_000_000_000;
+// what a tenant pays to create a canister
+pub const TENANT_INSTALL_CYCLES: u128 = 200_000_000_000;
+// the report interval of the tenant, a refill shows in the next report
+pub const TENANT_REFILL_INTERVAL: u64 = 10 * 60;
+
+/// the capacity set by the admin, and the health reported by the tenant itself
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct TenantMetric {
+  pub tenant_id: Principal,
+  // wallets placed on the tenant at most, 0 stops new placements
+  pub capacity: u16,
+  // user apps of the wallets on the tenant
+  pub app_count: u64,
+  pub task_count: u64,
+  pub dead_task_count: u64,
+  pub cycles: u128,
+  // second, None before the first report
+  pub reported_at: Option<u64>,
+  // what the tenant spends a day on creating and topping up canisters
+  pub spend_per_day: Option<u128>,
+  // the balance the tenant wants to hold
+  pub reserve_threshold: Option<u128>,
+  // the cycles ego_store refilled the tenant with in total
+  pub refilled: Option<u128>,
+  // second
+  pub refilled_at: Option<u64>,
+  pub last_update: u64, // second
+}
+
+impl TenantMetric {
+  pub fn new(tenant_id: &Principal) -> Self {
+    Self {
+      tenant_id: *tenant_id,
+      capacity: DEFAULT_TENANT_CAPACITY,
+      app_count: 0,
+      task_count: 0,
+      dead_task_count: 0,
+      cycles: 0,
+      reported_at: None,
+      spend_per_day: None,
+      reserve_threshold: None,
+      refilled: None,
+      refilled_at: None,
+      last_update: 0,
+    }
+  }
+
+  /// a tenant never reported is trusted, one stopped reporting or running out of cycles is not
+  pub fn is_healthy(&self, now: u64) -> bool {
+    self.reported_at.map_or(true, |reported_at| {
+      now.saturating_sub(reported_at) <= TENANT_REPORT_TIMEOUT && self.cycles >= TENANT_CYCLES_MIN
+    })
+  }
+
+  /// a tenant never reported may install, the tenant refuses itself once short
+  pub fn install_allowed(&self) -> bool {
+    self.reported_at.is_none() || self.cycles >= TENANT_CYCLES_MIN + TENANT_INSTALL_CYCLES
+  }
+
+  pub fn report(&mut self, task_count: u64, dead_task_count: u64, cycles: u128, spend_per_day: Option<u128>, reserve_threshold: Option<u128>, now: u64) {
+    self.task_count = task_count;
+    self.dead_task_count = dead_task_count;
+    self.cycles = cycles;
+    self.spend_per_day = spend_per_day;
+    self.reserve_threshold = reserve_threshold;
+    self.reported_at = Some(now);
+  }
+
+  /// the cycles to bring a tenant under its reserve threshold back to twice the threshold, 0 for none.
+  /// tenants reporting no threshold are held at twice the min cycles
+  pub fn refill_required(&self, now: u64) -> u128 {
+    let threshold = self.reserve_threshold.unwrap_or(TENANT_CYCLES_MIN * 2);
+    let refilled_recently = self.refilled_at.map_or(false, |refilled_at| now.saturating_sub(refilled_at) < TENANT_REFILL_INTERVAL);
+    if self.reported_at.is_none() || refilled_recently || self.cycles >= threshold {
+      return 0;
+    }
+    threshold * 2 - self.cycles
+  }
+
+  pub fn refill_record(&mut self, cycles: u128) {
+    self.cycles += cycles;
+    self.refilled = Some(self.refilled.unwrap_or(0) + cycles);
+  }
+
+  /// moves the app count of a wallet between tenants, 1 for a single app
+  pub fn app_count_add(tenant_id: &Principal, count: u64) {
+    let mut metric = Self::get(tenant_id).unwrap_or_else(|| Self::new(tenant_id));
+    metric.app_count += count;
+    metric.save();
+  }
+
+  pub fn app_count_sub(tenant_id: &Principal, count: u64) {
+    let mut metric = Self::get(tenant_id).unwrap_or_else(|| Self::new(tenant_id));
+    metric.app_count = metric.app_count.saturating_sub(count);
+    metric.save();
+  }
+
+  pub fn len() -> u64 {
+    TENANT_METRICS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, metric)| Some(metric))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, metric)| match metric.last_update >= last_update {
+      true => { Some(metric) }
+      false => { None }
+    })
+  }
+
+  pub fn get(tenant_id: &Principal) -> Option<Self> {
+    TENANT_METRICS.with(|cell| {
+      let inst = cell.borrow();
+      let key = Blob::try_from(tenant_id.as_slice()).unwrap();
+      inst.get(&key)
+    })
+  }
+
+  pub fn save(&mut self) {
+    TENANT_METRICS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      let key = Blob::try_from(self.tenant_id.as_slice()).unwrap();
+      self.last_update = time();
+      inst.insert(key, self.clone());
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((Blob<29>, Self)) -> Option<Self>,
+  {
+    TENANT_METRICS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for TenantMetric {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for TenantMetric {
+  const MAX_SIZE: u32 = 256;
+  const IS_FIXED_SIZE: bool = false;
+}
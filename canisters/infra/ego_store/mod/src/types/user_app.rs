@@ -6,11 +6,13 @@ use ic_stable_structures::{BoundedStorable, Storable};
 use ic_stable_structures::storable::Blob;
 use serde::Serialize;
 
-use ego_types::app::{App, Canister};
+use ego_types::app::{App, AppId, Canister, Version};
+use ego_types::semver::SemVer;
 use ego_utils::util::time;
 
-use crate::memory::USER_APPS;
+use crate::memory::{USER_APP_APP_INDEX, USER_APP_WALLET_INDEX, USER_APPS};
 use crate::types::ego_store_app::EgoStoreApp;
+use crate::types::index_key::{AppVersionKey, WalletCanisterKey};
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct UserApp {
@@ -18,6 +20,8 @@ pub struct UserApp {
   pub canister: Canister,
   pub wallet_id: Option<Principal>,
   pub last_update: u64, // second
+  // the semver pre-release label of the installed version
+  pub pre_release: Option<String>,
 }
 
 impl UserApp {
@@ -27,9 +31,14 @@ impl UserApp {
       canister: canister.clone(),
       wallet_id,
       last_update: 0,
+      pre_release: None,
     }
   }
 
+  pub fn semver(&self) -> SemVer {
+    SemVer::new(&self.app.current_version, &self.pre_release, &None).unwrap_or_else(|_| SemVer::from(self.app.current_version))
+  }
+
   pub fn len() -> u64 {
     USER_APPS.with(|cell| {
       let inst = cell.borrow();
@@ -55,13 +64,45 @@ impl UserApp {
   }
 
   pub fn by_wallet_id(wallet_id: &Principal) -> Vec<Self> {
-    Self::iter(0, Self::len() as usize, |(_, user_app)| match user_app.wallet_id.is_some() && user_app.wallet_id.unwrap() == *wallet_id {
-      true => {
-        Some(user_app)
-      }
-      false => {
-        None
-      }
+    USER_APP_WALLET_INDEX.with(|cell| {
+      let index = cell.borrow();
+      index.range(WalletCanisterKey::range(wallet_id)).filter_map(|(key, _)| {
+        Self::get(&key.canister_id())
+      }).collect()
+    })
+  }
+
+  /// user apps installed from the app, optionally limited to a single version
+  pub fn by_app_id(app_id: &AppId, version: Option<Version>) -> Vec<Self> {
+    USER_APP_APP_INDEX.with(|cell| {
+      let index = cell.borrow();
+      index.range(AppVersionKey::range(app_id, version)).filter_map(|(key, _)| {
+        Self::get(&key.canister_id())
+      }).collect()
+    })
+  }
+
+  pub fn count_by_app_id(app_id: &AppId) -> u64 {
+    USER_APP_APP_INDEX.with(|cell| {
+      let index = cell.borrow();
+      index.range(AppVersionKey::range(app_id, None)).count() as u64
+    })
+  }
+
+  /// install count of each version of the app with its pre-release label, in semver precedence
+  pub fn version_distribution(app_id: &AppId) -> Vec<(Version, Option<String>, u64)> {
+    USER_APP_APP_INDEX.with(|cell| {
+      let index = cell.borrow();
+      let mut distribution: Vec<(Version, Option<String>, u64)> = vec![];
+      index.range(AppVersionKey::range(app_id, None)).for_each(|(key, _)| {
+        let version = key.version();
+        let pre_release = key.pre_release();
+        match distribution.last_mut() {
+          Some((last, last_pre_release, count)) if *last == version && *last_pre_release == pre_release => *count += 1,
+          _ => distribution.push((version, pre_release, 1)),
+        }
+      });
+      distribution
     })
   }
 
@@ -90,19 +131,68 @@ impl UserApp {
   }
 
   pub fn save(&mut self) {
-    USER_APPS.with(|cell| {
+    let previous = USER_APPS.with(|cell| {
       let mut inst = cell.borrow_mut();
       let key = Blob::try_from(self.canister.canister_id.as_slice()).unwrap();
       self.last_update = time();
-      inst.insert(key, self.clone());
+      inst.insert(key, self.clone())
     });
+
+    if let Some(previous) = previous {
+      previous.index_remove();
+    }
+    self.index_insert();
   }
 
   pub fn remove(canister_id: &Principal) {
-    USER_APPS.with(|cell| {
+    let removed = USER_APPS.with(|cell| {
       let mut inst = cell.borrow_mut();
       let key = Blob::try_from(canister_id.as_slice()).unwrap();
-      inst.remove(&key);
+      inst.remove(&key)
+    });
+
+    if let Some(removed) = removed {
+      removed.index_remove();
+    }
+  }
+
+  /// build the wallet and app indexes for the user apps saved before the indexes existed
+  pub fn index_rebuild() {
+    let index_len = USER_APP_APP_INDEX.with(|cell| cell.borrow().len());
+    if index_len == Self::len() {
+      return;
+    }
+
+    Self::list(0, Self::len() as usize).iter().for_each(|user_app| user_app.index_insert());
+  }
+
+  fn index_insert(&self) {
+    let canister_id = self.canister.canister_id;
+
+    if let Some(wallet_id) = self.wallet_id {
+      USER_APP_WALLET_INDEX.with(|cell| {
+        cell.borrow_mut().insert(WalletCanisterKey::new(&wallet_id, &canister_id), self.last_update);
+      });
+    }
+
+    USER_APP_APP_INDEX.with(|cell| {
+      let key = AppVersionKey::new(&self.app.app_id, &self.semver(), &canister_id);
+      cell.borrow_mut().insert(key, self.last_update);
+    });
+  }
+
+  fn index_remove(&self) {
+    let canister_id = self.canister.canister_id;
+
+    if let Some(wallet_id) = self.wallet_id {
+      USER_APP_WALLET_INDEX.with(|cell| {
+        cell.borrow_mut().remove(&WalletCanisterKey::new(&wallet_id, &canister_id));
+      });
+    }
+
+    USER_APP_APP_INDEX.with(|cell| {
+      let key = AppVersionKey::new(&self.app.app_id, &self.semver(), &canister_id);
+      cell.borrow_mut().remove(&key);
     });
   }
 
@@ -33,32 +33,40 @@ impl Wallet {
     }
   }
 
-  pub fn cycle_charge(
-    &mut self,
-    cycle: u128,
-    operator: &Principal,
-    comment: String,
-  ) -> Result<(), EgoError> {
+  /// takes the cycles of a charge off the balance, recorded once the charge is committed
+  pub fn cycle_reserve(&mut self, cycle: u128) -> Result<(), EgoError> {
     if self.cycles > cycle {
       self.cycles -= cycle;
       self.save();
-
-      let mut cash_flow = CashFlow::new(
-        &self.wallet_id,
-        CashFlowType::CHARGE,
-        cycle,
-        self.cycles,
-        operator,
-        comment,
-      );
-      cash_flow.save();
-
       Ok(())
     } else {
       Err(EgoStoreErr::CyclesNotEnouth.into())
     }
   }
 
+  pub fn cycle_reserve_commit(
+    &self,
+    cycle: u128,
+    operator: &Principal,
+    comment: String,
+  ) -> CashFlow {
+    let mut cash_flow = CashFlow::new(
+      &self.wallet_id,
+      CashFlowType::CHARGE,
+      cycle,
+      self.cycles,
+      operator,
+      comment,
+    );
+    cash_flow.save();
+    cash_flow
+  }
+
+  pub fn cycle_reserve_release(&mut self, cycle: u128) {
+    self.cycles += cycle;
+    self.save();
+  }
+
   pub fn cycle_recharge(
     &mut self,
     cycle: u128,
@@ -80,6 +88,89 @@ impl Wallet {
     Ok(())
   }
 
+  pub fn cycle_withdraw(
+    &mut self,
+    cycle: u128,
+    fee: u128,
+    operator: &Principal,
+    comment: String,
+  ) -> Result<(), EgoError> {
+    if self.cycles < cycle + fee {
+      return Err(EgoStoreErr::CyclesNotEnouth.into());
+    }
+
+    self.cycles -= cycle + fee;
+    self.save();
+
+    let mut cash_flow = CashFlow::new(
+      &self.wallet_id,
+      CashFlowType::WITHDRAW,
+      cycle,
+      self.cycles + fee,
+      operator,
+      comment,
+    );
+    cash_flow.save();
+
+    if fee > 0 {
+      let mut fee_cash_flow = CashFlow::new(
+        &self.wallet_id,
+        CashFlowType::WITHDRAW_FEE,
+        fee,
+        self.cycles,
+        operator,
+        "withdraw fee".to_string(),
+      );
+      fee_cash_flow.save();
+    }
+
+    Ok(())
+  }
+
+  pub fn cycle_transfer(
+    &mut self,
+    to_wallet: &mut Wallet,
+    cycle: u128,
+    operator: &Principal,
+    comment: String,
+  ) -> Result<(), EgoError> {
+    if self.wallet_id == to_wallet.wallet_id || cycle == 0 {
+      return Err(EgoStoreErr::WalletTransferInvalid.into());
+    }
+
+    if self.cycles < cycle {
+      return Err(EgoStoreErr::CyclesNotEnouth.into());
+    }
+
+    self.cycles -= cycle;
+    self.save();
+
+    to_wallet.cycles += cycle;
+    to_wallet.save();
+
+    let mut cash_flow_out = CashFlow::new(
+      &self.wallet_id,
+      CashFlowType::TRANSFER_OUT,
+      cycle,
+      self.cycles,
+      operator,
+      comment.clone(),
+    );
+    cash_flow_out.save();
+
+    let mut cash_flow_in = CashFlow::new(
+      &to_wallet.wallet_id,
+      CashFlowType::TRANSFER_IN,
+      cycle,
+      to_wallet.cycles,
+      operator,
+      comment,
+    );
+    cash_flow_in.save();
+
+    Ok(())
+  }
+
   pub fn len() -> u64 {
     WALLETS.with(|cell| {
       let inst = cell.borrow();
@@ -115,6 +206,14 @@ impl Wallet {
     });
   }
 
+  pub fn remove(wallet_id: &Principal) {
+    WALLETS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      let key = Blob::try_from(wallet_id.as_slice()).unwrap();
+      inst.remove(&key)
+    });
+  }
+
   fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
   where
     F: Fn((Blob<29>, Self)) -> Option<Self>,
@@ -0,0 +1,144 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use ic_stable_structures::storable::Blob;
+use serde::Serialize;
+
+use ego_utils::util::time;
+
+use crate::memory::{WITHDRAW_INDEX, WITHDRAWS};
+use crate::state::SEQ;
+
+#[derive(
+  CandidType, Serialize, Deserialize, Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq,
+)]
+pub enum WithdrawStatus {
+  PENDING,
+  SUCCESS,
+  FAILED,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Withdraw {
+  pub id: u64,
+  pub wallet_id: Principal,
+  // the canister or cycles wallet receiving the cycles
+  pub target_id: Principal,
+  pub cycles: u128,
+  pub fee: u128,
+  pub status: WithdrawStatus,
+  pub created_at: u64,
+  // second
+  pub last_update: u64, // second
+}
+
+impl Withdraw {
+  pub fn new(wallet_id: &Principal, target_id: &Principal, cycles: u128, fee: u128) -> Self {
+    let next_id = SEQ.with(|cell| cell.borrow_mut().next_number("withdraw", 0));
+    Self {
+      id: next_id,
+      wallet_id: *wallet_id,
+      target_id: *target_id,
+      cycles,
+      fee,
+      status: WithdrawStatus::PENDING,
+      created_at: time(),
+      last_update: 0,
+    }
+  }
+
+  pub fn len() -> u64 {
+    WITHDRAWS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, withdraw)| Some(withdraw))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, withdraw)| match withdraw.last_update >= last_update {
+      true => { Some(withdraw) }
+      false => { None }
+    })
+  }
+
+  pub fn by_wallet_id(wallet_id: &Principal) -> Vec<Self> {
+    Self::iter(0, Self::len() as usize, |(_, withdraw)| match withdraw.wallet_id == *wallet_id {
+      true => { Some(withdraw) }
+      false => { None }
+    })
+  }
+
+  /// the last withdraw of the wallet which did not fail
+  pub fn last_by_wallet_id(wallet_id: &Principal) -> Option<Self> {
+    let key = Blob::try_from(wallet_id.as_slice()).unwrap();
+    WITHDRAW_INDEX.with(|cell| {
+      let index = cell.borrow();
+      index.get(&key)
+    }).and_then(Self::get)
+  }
+
+  pub fn get(id: u64) -> Option<Self> {
+    WITHDRAWS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&id)
+    })
+  }
+
+  pub fn save(&mut self) {
+    WITHDRAWS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(self.id, self.clone());
+    });
+
+    WITHDRAW_INDEX.with(|cell| {
+      let mut index = cell.borrow_mut();
+      let key = Blob::try_from(self.wallet_id.as_slice()).unwrap();
+      match self.status {
+        WithdrawStatus::FAILED => {
+          if index.get(&key) == Some(self.id) {
+            index.remove(&key);
+          }
+        }
+        _ => {
+          if index.get(&key).map_or(true, |id| id <= self.id) {
+            index.insert(key, self.id);
+          }
+        }
+      }
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((u64, Self)) -> Option<Self>,
+  {
+    WITHDRAWS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for Withdraw {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for Withdraw {
+  const MAX_SIZE: u32 = 256;
+  const IS_FIXED_SIZE: bool = false;
+}
@@ -98,7 +98,7 @@ fn test_job_list() {
   set_up();
 
   let jobs = job_list();
-  assert_eq!(8, jobs.len());
+  assert_eq!(18, jobs.len());
 
   assert_eq!("config", jobs.get(0).unwrap().name);
   assert_eq!(1, jobs.get(0).unwrap().amount);
@@ -123,6 +123,30 @@ fn test_job_list() {
 
   assert_eq!("cash_flows", jobs.get(7).unwrap().name);
   assert_eq!(1, jobs.get(7).unwrap().amount);
+
+  assert_eq!("withdraws", jobs.get(8).unwrap().name);
+  assert_eq!(0, jobs.get(8).unwrap().amount);
+
+  assert_eq!("app_metadatas", jobs.get(9).unwrap().name);
+  assert_eq!(0, jobs.get(9).unwrap().amount);
+
+  assert_eq!("reviews", jobs.get(10).unwrap().name);
+  assert_eq!(0, jobs.get(10).unwrap().amount);
+
+  assert_eq!("app_stats", jobs.get(11).unwrap().name);
+  assert_eq!(0, jobs.get(11).unwrap().amount);
+
+  assert_eq!("app_audits", jobs.get(12).unwrap().name);
+  assert_eq!(0, jobs.get(12).unwrap().amount);
+
+  assert_eq!("alerts", jobs.get(13).unwrap().name);
+  assert_eq!(0, jobs.get(13).unwrap().amount);
+
+  assert_eq!("alert_subscriptions", jobs.get(14).unwrap().name);
+  assert_eq!(0, jobs.get(14).unwrap().amount);
+
+  assert_eq!("tenant_metrics", jobs.get(15).unwrap().name);
+  assert_eq!(0, jobs.get(15).unwrap().amount);
 }
 
 #[test]
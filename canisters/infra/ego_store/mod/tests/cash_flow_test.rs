@@ -61,7 +61,11 @@ pub fn by_wallet_id() {
 
   let wallet1 = Principal::from_text(CANISTER_ID1.to_string()).unwrap();
 
-  assert_eq!(2, CashFlow::by_wallet_id(&wallet1).len());
+  let cash_flows = CashFlow::by_wallet_id(&wallet1);
+  assert_eq!(2, cash_flows.len());
+  // in the order they were made
+  assert_eq!(CashFlowType::RECHARGE, cash_flows[0].cash_flow_type);
+  assert_eq!(CashFlowType::CHARGE, cash_flows[1].cash_flow_type);
 }
 
 #[test]
@@ -1,8 +1,10 @@
 use candid::Principal;
 
 use ego_store_mod::service::EgoStoreService;
+use ego_store_mod::types::{AppMainListRequest, AppSortBy};
+use ego_store_mod::types::app_audit::EgoStoreAppAudit;
 use ego_store_mod::types::ego_store_app::EgoStoreApp;
-use ego_types::app::{App, Category, Wasm};
+use ego_types::app::{App, AppAuditReview, AppMetadata, AuditDecision, Category, Wasm};
 use ego_types::app::CanisterType::BACKEND;
 use ego_types::app::Version;
 
@@ -113,3 +115,149 @@ fn app_main_release_new_app_version() {
   assert_eq!(EXISTS_APP_NAME.to_string(), app.app.name);
   assert_eq!(version, app.app.current_version);
 }
+
+fn app_release(app_id: &str, name: &str, category: Category, price: f32) {
+  let file_canister = Principal::from_text(FILE_CANISTER_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 0);
+
+  let wasm = Wasm::new(app_id.to_string(), version, BACKEND, file_canister);
+  let app = App {
+    app_id: app_id.to_string(),
+    name: name.to_string(),
+    category,
+    logo: NEW_APP_LOGO.to_string(),
+    description: NEW_APP_DESCRIPTION.to_string(),
+    current_version: version,
+    price,
+    app_hash: "".to_string(),
+  };
+
+  let mut ego_store_app = EgoStoreApp::new(&app, &wasm);
+  EgoStoreService::app_main_release(&mut ego_store_app).unwrap();
+}
+
+fn app_list_request() -> AppMainListRequest {
+  AppMainListRequest {
+    keyword: None,
+    category: None,
+    tags: vec![],
+    sort_by: None,
+    descending: false,
+    offset: 0,
+    limit: 10,
+  }
+}
+
+#[test]
+fn app_main_search() {
+  set_up();
+
+  app_release("swap", "Swap", Category::Finance, 2.0);
+  app_release("chat", "chat room", Category::Social, 1.0);
+
+  let metadata = AppMetadata {
+    tags: vec!["defi".to_string(), "dex".to_string()],
+    ..Default::default()
+  };
+  EgoStoreService::app_main_metadata_set(&"swap".to_string(), &metadata);
+
+  // all apps, in app id order
+  let apps = EgoStoreService::app_main_search(&app_list_request());
+  assert_eq!(3, apps.len());
+  assert_eq!("app_test", apps[0].app_id);
+  assert_eq!("chat", apps[1].app_id);
+
+  // category
+  let mut request = app_list_request();
+  request.category = Some(Category::Social);
+  let apps = EgoStoreService::app_main_search(&request);
+  assert_eq!(1, apps.len());
+  assert_eq!("chat", apps[0].app_id);
+
+  // keyword matches the tags
+  let mut request = app_list_request();
+  request.keyword = Some("DEF".to_string());
+  let apps = EgoStoreService::app_main_search(&request);
+  assert_eq!(1, apps.len());
+  assert_eq!("swap", apps[0].app_id);
+  assert_eq!(2, apps[0].metadata.as_ref().unwrap().tags.len());
+
+  // tags
+  let mut request = app_list_request();
+  request.tags = vec!["dex".to_string(), "nft".to_string()];
+  assert!(EgoStoreService::app_main_search(&request).is_empty());
+
+  // sort and page
+  let mut request = app_list_request();
+  request.sort_by = Some(AppSortBy::PRICE);
+  request.descending = true;
+  request.offset = 1;
+  request.limit = 1;
+  let apps = EgoStoreService::app_main_search(&request);
+  assert_eq!(1, apps.len());
+  assert_eq!("chat", apps[0].app_id);
+
+  // a short page is the last one
+  request.offset = 2;
+  request.limit = 2;
+  assert_eq!(1, EgoStoreService::app_main_search(&request).len());
+}
+
+#[test]
+fn app_main_catalog_get() {
+  set_up();
+
+  let entry = EgoStoreService::app_main_catalog_get(&EXISTS_APP_ID.to_string()).unwrap();
+  assert_eq!(EXISTS_APP_NAME, entry.name);
+  assert!(entry.metadata.unwrap().tags.is_empty());
+
+  assert!(EgoStoreService::app_main_catalog_get(&NEW_APP_ID.to_string()).is_none());
+}
+
+#[test]
+fn app_main_audit_set() {
+  set_up();
+
+  let auditor_id = Principal::from_text(FILE_CANISTER_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 0);
+
+  assert!(EgoStoreAppAudit::get(&EXISTS_APP_ID.to_string()).is_none());
+
+  let review = AppAuditReview {
+    auditor_id,
+    decision: AuditDecision::APPROVE,
+    comment: "looks good".to_string(),
+    report: None,
+    created_at: 0,
+    reproduced: Some(true),
+  };
+  EgoStoreService::app_main_audit_set(&EXISTS_APP_ID.to_string(), &version, vec![review]);
+
+  let app_audit = EgoStoreAppAudit::get(&EXISTS_APP_ID.to_string()).unwrap();
+  assert_eq!(version, app_audit.version);
+  assert_eq!(1, app_audit.reviews.len());
+  assert_eq!(AuditDecision::APPROVE, app_audit.reviews.get(0).unwrap().decision);
+
+  // a new release replaces the record
+  let new_version = Version::new(1, 0, 1);
+  EgoStoreService::app_main_audit_set(&EXISTS_APP_ID.to_string(), &new_version, vec![]);
+
+  let app_audit = EgoStoreAppAudit::get(&EXISTS_APP_ID.to_string()).unwrap();
+  assert_eq!(new_version, app_audit.version);
+  assert!(app_audit.reviews.is_empty());
+}
+
+#[test]
+fn app_main_developer_verified_set() {
+  set_up();
+
+  let entry = EgoStoreService::app_main_catalog_get(&EXISTS_APP_ID.to_string()).unwrap();
+  assert_eq!(Some(false), entry.developer_verified);
+
+  // apps not on the store are skipped
+  EgoStoreService::app_main_developer_verified_set(&[EXISTS_APP_ID.to_string(), NEW_APP_ID.to_string()], true);
+
+  let entry = EgoStoreService::app_main_catalog_get(&EXISTS_APP_ID.to_string()).unwrap();
+  assert_eq!(Some(true), entry.developer_verified);
+  assert!(EgoStoreApp::get(&NEW_APP_ID.to_string()).is_none());
+}
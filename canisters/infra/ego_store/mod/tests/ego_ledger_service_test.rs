@@ -4,16 +4,21 @@ use mockall::mock;
 
 use ego_store_mod::c2c::ego_ledger::TEgoLedger;
 use ego_store_mod::service::EgoStoreService;
+use ego_store_mod::types::charge::ChargeStatus;
 use ego_store_mod::types::order::{Order, OrderStatus};
+use ego_store_mod::types::user_app::UserApp;
 use ego_store_mod::types::wallet::Wallet;
+use ego_types::app::{App, Canister, CanisterType, Category, Version};
 
 static LEDGER_ID: &str = "22k5f-nqaaa-aaaad-qaigq-cai";
 static STORE_ID: &str = "22cl3-kqaaa-aaaaf-add7q-cai";
-static TEST_OPERATOR: &str = "c5jhr-faaaa-aaaaf-acebq-cai";
 static EXISTS_WALLET_ID: &str = "amybd-zyaaa-aaaah-qc4hq-cai";
 static EXISTS_USER_ID: &str = "225da-yaaaa-aaaah-qahrq-cai";
 static EXISTS_TENANT_ID: &str = "22ayq-aiaaa-aaaai-qgmma-cai";
 static TEST_WALLET_ID: &str = "5vreg-2yaaa-aaaaf-ajkdq-cai";
+static TEST_OPERATOR: &str = "c5jhr-faaaa-aaaaf-acebq-cai";
+static EXISTS_USER_APP_BACKEND: &str = "224jh-lqaaa-aaaad-qaxda-cai";
+static TEST_USER_APP_BACKEND: &str = "223vg-sqaaa-aaaak-abtmq-cai";
 
 mock! {
   Ledger{}
@@ -37,6 +42,23 @@ pub fn set_up() {
   // add order
   let mut order = Order::new(&wallet_principal, &store_principal, 1.2f32);
   order.save();
+
+  // add user apps, of the wallet and of a wallet not exists
+  let app = App {
+    app_id: "app_exists".to_string(),
+    name: "app1".to_string(),
+    category: Category::Vault,
+    logo: "logo".to_string(),
+    description: "test is app description".to_string(),
+    current_version: Version::new(1, 0, 1),
+    price: 0.0,
+    app_hash: "".to_string(),
+  };
+  let backend_principal = Principal::from_text(EXISTS_USER_APP_BACKEND).unwrap();
+  UserApp::new(&app, &Canister::new(backend_principal, CanisterType::BACKEND), Some(wallet_principal)).save();
+  let test_backend_principal = Principal::from_text(TEST_USER_APP_BACKEND).unwrap();
+  let test_wallet_principal = Principal::from_text(TEST_WALLET_ID).unwrap();
+  UserApp::new(&app, &Canister::new(test_backend_principal, CanisterType::BACKEND), Some(test_wallet_principal)).save();
 }
 
 #[test]
@@ -146,12 +168,12 @@ fn wallet_order_new_wallet_not_exists() {
 #[test]
 fn wallet_cycle_charge_wallet_not_exists() {
   set_up();
-  let wallet_id = Principal::from_text(TEST_WALLET_ID).unwrap();
+  let canister_id = Principal::from_text(TEST_USER_APP_BACKEND).unwrap();
   let ledger_id = Principal::from_text(TEST_OPERATOR).unwrap();
 
   // wallet not exists
-  let result = EgoStoreService::wallet_cycle_charge(
-    &wallet_id,
+  let result = EgoStoreService::canister_cycle_charge(
+    &canister_id,
     128,
     &ledger_id,
     "charge cycle".to_string(),
@@ -168,20 +190,24 @@ fn wallet_cycle_charge_wallet_not_exists() {
 fn wallet_cycle_charge() {
   set_up();
   let wallet_id = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+  let canister_id = Principal::from_text(EXISTS_USER_APP_BACKEND).unwrap();
   let ledger_id = Principal::from_text(LEDGER_ID).unwrap();
 
   let wallet = Wallet::get(&wallet_id).unwrap();
   assert_eq!(256, wallet.cycles);
 
   // wallet charge cycle
-  let result = EgoStoreService::wallet_cycle_charge(
-    &wallet_id,
+  let result = EgoStoreService::canister_cycle_charge(
+    &canister_id,
     128,
     &ledger_id,
     "charge cycle".to_string(),
   );
   println!("{:?}", result);
   assert!(result.is_ok());
+  let charge = result.unwrap();
+  assert_eq!(wallet_id, charge.wallet_id);
+  assert_eq!(ChargeStatus::RECONCILED, charge.status);
   let wallet = Wallet::get(&wallet_id).unwrap();
   assert_eq!(128, wallet.cycles);
 }
@@ -1,8 +1,17 @@
 use candid::Principal;
-
-use ego_store_mod::types::ego_store_app::EgoStoreApp;
+use ic_stable_structures::{BoundedStorable, Storable};
+use ic_stable_structures::storable::Blob;
+
+use ego_store_mod::certification::{app_hash, app_witness, entry_certify, root_hash};
+use ego_store_mod::memory::EGO_STORE_APPS_LEGACY;
+use ego_store_mod::types::AppEntry;
+use ego_store_mod::types::app_key::AppKey;
+use ego_store_mod::types::app_line_release::{APP_LINE_RELEASE_SIZE, AppLineRelease};
+use ego_store_mod::types::ego_store_app::{EGO_STORE_APP_SIZE, EgoStoreApp};
+use ego_store_mod::types::index_key::{APP_ID_MAX_LEN, AppVersionKey};
 use ego_types::app::{App, Category, Version, Wasm};
 use ego_types::app::CanisterType::BACKEND;
+use ego_types::semver::SemVer;
 use ego_utils::util::time;
 
 static FILE_CANISTER_ID: &str = "amybd-zyaaa-aaaah-qc4hq-cai";
@@ -101,3 +110,113 @@ pub fn get() {
   assert!(app.is_none());
 }
 
+
+#[test]
+pub fn save_certify() {
+  set_up();
+
+  let previous_root_hash = root_hash();
+
+  let mut ego_store_app = EgoStoreApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+  ego_store_app.app.current_version = Version::new(1, 0, 2);
+  ego_store_app.save();
+
+  // the certified root follows the catalog changes
+  assert_ne!(previous_root_hash, root_hash());
+  assert!(!app_witness(&EXISTS_APP_ID.to_string()).is_empty());
+}
+
+#[test]
+pub fn app_entry_certified() {
+  set_up();
+
+  let ego_store_app = EgoStoreApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+
+  // update calls have no certificate to hand out
+  let entry = entry_certify(AppEntry::from(ego_store_app.app.clone()), &None);
+  assert!(entry.witness.is_none());
+
+  let entry = entry_certify(AppEntry::from(ego_store_app.app.clone()), &Some(vec![1, 2, 3]));
+  assert_eq!(Some(vec![1, 2, 3]), entry.certificate);
+  assert_eq!(Some(app_witness(&EXISTS_APP_ID.to_string())), entry.witness);
+  assert_eq!(app_hash(&ego_store_app.app), app_hash(&entry.app()));
+}
+
+#[test]
+pub fn max_size() {
+  let file_canister = Principal::from_text(FILE_CANISTER_ID.to_string()).unwrap();
+  let app_id = "a".repeat(48);
+  let version = Version::new(u32::MAX, u32::MAX, u32::MAX);
+  let pre_release = Some("b".repeat(64));
+  let wasm = Wasm::new(app_id.clone(), version, BACKEND, file_canister);
+
+  // about the largest app ego_dev can keep
+  let app = App {
+    app_id: app_id.clone(),
+    name: "n".repeat(64),
+    category: Category::Vault,
+    logo: "l".repeat(256),
+    description: "d".repeat(1536),
+    current_version: version,
+    price: f32::MAX,
+    app_hash: "h".repeat(64),
+  };
+
+  let mut ego_store_app = EgoStoreApp::new(&app, &wasm);
+  ego_store_app.pre_release = pre_release.clone();
+  ego_store_app.developer_verified = Some(true);
+  assert!(ego_store_app.to_bytes().len() <= EGO_STORE_APP_SIZE as usize);
+  ego_store_app.save();
+  assert_eq!(1536, EgoStoreApp::get(&app_id).unwrap().app.description.len());
+
+  let mut app_line_release = AppLineRelease::new(&app_id, &version, &pre_release, &wasm);
+  assert!(app_line_release.to_bytes().len() <= APP_LINE_RELEASE_SIZE as usize);
+  app_line_release.save();
+  assert!(AppLineRelease::get(&app_id, &version).is_some());
+}
+
+#[test]
+pub fn migrate() {
+  set_up();
+
+  let file_canister = Principal::from_text(FILE_CANISTER_ID.to_string()).unwrap();
+  let version = Version::new(1, 0, 1);
+  let wasm = Wasm::new(TEST_APP_ID.to_string(), version, BACKEND, file_canister);
+  let app = App {
+    app_id: TEST_APP_ID.to_string(),
+    name: APP_NAME.to_string(),
+    category: Category::Vault,
+    logo: APP_LOGO.to_string(),
+    description: APP_DESCRIPTION.to_string(),
+    current_version: version,
+    price: 0.0,
+    app_hash: "".to_string(),
+  };
+  let mut ego_store_app = EgoStoreApp::new(&app, &wasm);
+  ego_store_app.last_update = 20;
+
+  EGO_STORE_APPS_LEGACY.with(|cell| {
+    cell.borrow_mut().insert(AppKey::new(&TEST_APP_ID.to_string()), Blob::try_from(ego_store_app.to_bytes().as_ref()).unwrap());
+  });
+
+  EgoStoreApp::migrate();
+
+  let migrated = EgoStoreApp::get(&TEST_APP_ID.to_string()).unwrap();
+  assert_eq!(APP_DESCRIPTION, migrated.app.description);
+  assert_eq!(20, migrated.last_update);
+  assert_eq!(2, EgoStoreApp::len());
+  assert_eq!(0, EGO_STORE_APPS_LEGACY.with(|cell| cell.borrow().len()));
+}
+
+#[test]
+pub fn app_id_max_len() {
+  let version = SemVer::from(Version::new(1, 0, 1));
+
+  // the longest app id fits the app key and the indexes, one more byte does not
+  let app_id = "a".repeat(APP_ID_MAX_LEN);
+  assert!(AppKey::new(&app_id).to_bytes().len() <= AppKey::MAX_SIZE as usize);
+  assert!(AppVersionKey::fits(&app_id, &version));
+
+  let app_id = "a".repeat(APP_ID_MAX_LEN + 1);
+  assert!(!AppVersionKey::fits(&app_id, &version));
+}
@@ -1,9 +1,13 @@
 use candid::Principal;
+use mockall::mock;
 
+use ego_store_mod::c2c::alert_callback::TAlertCallback;
 use ego_store_mod::service::EgoStoreService;
+use ego_store_mod::types::alert::Alert;
+use ego_store_mod::types::charge::ChargeStatus;
 use ego_store_mod::types::user_app::UserApp;
 use ego_store_mod::types::wallet::Wallet;
-use ego_types::app::{App, Canister, CanisterType, Category, Version};
+use ego_types::app::{AlertKind, App, Canister, CanisterType, Category, Version};
 
 static EXISTS_APP_ID: &str = "app_exists";
 static APP_NAME: &str = "app1";
@@ -15,6 +19,15 @@ static EXISTS_USER_ID: &str = "225da-yaaaa-aaaah-qahrq-cai";
 static EXISTS_USER_APP_BACKEND: &str = "224jh-lqaaa-aaaad-qaxda-cai";
 static FAKE_USER_APP_BACKEND: &str = "223vg-sqaaa-aaaak-abtmq-cai";
 static EXISTS_TENANT_ID: &str = "22ayq-aiaaa-aaaai-qgmma-cai";
+static CALLBACK_ID: &str = "227wz-liaaa-aaaaa-qaara-cai";
+
+mock! {
+  Callback {}
+
+  impl TAlertCallback for Callback {
+    fn ego_alert_notify(&self, callback_id: Principal, alert: &Alert);
+  }
+}
 
 pub fn set_up() {
   let tenant_principal = Principal::from_text(EXISTS_TENANT_ID.to_string()).unwrap();
@@ -57,10 +70,15 @@ fn canister_charge_success() {
   let wallet = Wallet::get(&wallet_principal).unwrap();
   assert_eq!(1000, wallet.cycles);
 
-  EgoStoreService::canister_cycle_charge(&backend_principal, 100, &tenant_principal, "cycle charge".to_string()).unwrap();
+  let charge = EgoStoreService::canister_cycle_charge(&backend_principal, 100, &tenant_principal, "cycle charge".to_string()).unwrap();
 
   let wallet = Wallet::get(&wallet_principal).unwrap();
   assert_eq!(900, wallet.cycles);
+
+  // final at once, nothing left to the reconciliation
+  assert_eq!(ChargeStatus::RECONCILED, charge.status);
+  assert!(charge.cash_flow_id.is_some());
+  assert!(EgoStoreService::admin_charge_pending_list().is_empty());
 }
 
 #[test]
@@ -73,3 +91,96 @@ fn canister_charge_failed() {
 
   EgoStoreService::canister_cycle_charge(&fake_backend_principal, 100, &tenant_principal, "cycle charge".to_string()).unwrap();
 }
+
+#[test]
+fn canister_alert_add() {
+  set_up();
+
+  let backend_principal = Principal::from_text(EXISTS_USER_APP_BACKEND.to_string()).unwrap();
+  let wallet_principal = Principal::from_text(EXISTS_WALLET_ID.to_string()).unwrap();
+  let callback_principal = Principal::from_text(CALLBACK_ID.to_string()).unwrap();
+
+  // no subscription, nothing to notify
+  let mut alert_callback = MockCallback::new();
+  alert_callback.expect_ego_alert_notify().times(0);
+  let alert = EgoStoreService::canister_alert_add(&alert_callback, &backend_principal, AlertKind::LOW_BALANCE, "low balance").unwrap();
+  assert_eq!(wallet_principal, alert.wallet_id);
+  assert_eq!(1, alert.count);
+
+  EgoStoreService::wallet_alert_subscribe(&wallet_principal, Some(callback_principal)).unwrap();
+
+  // raised again before acknowledged
+  let mut alert_callback = MockCallback::new();
+  alert_callback.expect_ego_alert_notify().times(0);
+  let repeated = EgoStoreService::canister_alert_add(&alert_callback, &backend_principal, AlertKind::LOW_BALANCE, "lower balance").unwrap();
+  assert_eq!(alert.id, repeated.id);
+  assert_eq!(2, repeated.count);
+  assert_eq!("lower balance", repeated.message);
+
+  let mut alert_callback = MockCallback::new();
+  alert_callback
+    .expect_ego_alert_notify()
+    .times(1)
+    .returning(move |callback_id, alert| {
+      assert_eq!(callback_principal, callback_id);
+      assert_eq!(AlertKind::TOP_UP_FAILED, alert.kind);
+    });
+  let top_up_failed = EgoStoreService::canister_alert_add(&alert_callback, &backend_principal, AlertKind::TOP_UP_FAILED, "top up failed").unwrap();
+
+  assert_eq!(2, EgoStoreService::wallet_alert_list(&wallet_principal, true).len());
+
+  // acknowledged alerts are raised as new ones
+  EgoStoreService::wallet_alert_ack(&wallet_principal, &[alert.id]).unwrap();
+  assert_eq!(1, EgoStoreService::wallet_alert_list(&wallet_principal, true).len());
+
+  let mut alert_callback = MockCallback::new();
+  alert_callback.expect_ego_alert_notify().times(1).returning(|_, _| ());
+  let raised = EgoStoreService::canister_alert_add(&alert_callback, &backend_principal, AlertKind::LOW_BALANCE, "low balance").unwrap();
+  assert_ne!(alert.id, raised.id);
+  assert_eq!(3, EgoStoreService::wallet_alert_list(&wallet_principal, false).len());
+
+  // looked up through the indexes, only the open alert of the canister and kind
+  assert_eq!(Some(raised.id), Alert::unacknowledged(&backend_principal, AlertKind::LOW_BALANCE).map(|alert| alert.id));
+  assert!(Alert::unacknowledged(&backend_principal, AlertKind::MEMORY_LIMIT).is_none());
+  assert!(Alert::by_wallet_id(&callback_principal).is_empty());
+  Alert::index_rebuild();
+  assert_eq!(vec![alert.id, top_up_failed.id, raised.id], Alert::by_wallet_id(&wallet_principal).iter().map(|alert| alert.id).collect::<Vec<u64>>());
+
+  // unsubscribed
+  EgoStoreService::wallet_alert_subscribe(&wallet_principal, None).unwrap();
+  let mut alert_callback = MockCallback::new();
+  alert_callback.expect_ego_alert_notify().times(0);
+  EgoStoreService::canister_alert_add(&alert_callback, &backend_principal, AlertKind::CANISTER_FROZEN, "frozen").unwrap();
+}
+
+#[test]
+fn canister_alert_add_failed() {
+  set_up();
+
+  let fake_backend_principal = Principal::from_text(FAKE_USER_APP_BACKEND.to_string()).unwrap();
+
+  let mut alert_callback = MockCallback::new();
+  alert_callback.expect_ego_alert_notify().times(0);
+  let result = EgoStoreService::canister_alert_add(&alert_callback, &fake_backend_principal, AlertKind::LOW_BALANCE, "low balance");
+  assert_eq!(3002, result.unwrap_err().code);
+}
+
+#[test]
+fn wallet_alert_ack_other_wallet() {
+  set_up();
+
+  let backend_principal = Principal::from_text(EXISTS_USER_APP_BACKEND.to_string()).unwrap();
+  let other_wallet_principal = Principal::from_text(CALLBACK_ID.to_string()).unwrap();
+
+  let mut alert_callback = MockCallback::new();
+  alert_callback.expect_ego_alert_notify().times(0);
+  let alert = EgoStoreService::canister_alert_add(&alert_callback, &backend_principal, AlertKind::LOW_BALANCE, "low balance").unwrap();
+
+  let result = EgoStoreService::wallet_alert_ack(&other_wallet_principal, &[alert.id]);
+  assert_eq!(3022, result.unwrap_err().code);
+  assert!(Alert::get(alert.id).unwrap().acknowledged_at.is_none());
+
+  // the subscription belongs to a registered wallet
+  let result = EgoStoreService::wallet_alert_subscribe(&other_wallet_principal, Some(backend_principal));
+  assert_eq!(3006, result.unwrap_err().code);
+}
@@ -4,13 +4,23 @@ use mockall::mock;
 
 use ego_lib::ego_canister::TEgoCanister;
 use ego_lib::inject_mock_ego_canister;
+use ego_store_mod::c2c::c2c_types::{CanisterRunStatus, CanisterSettingsUpdate, StatusRecord, TopUpPolicy};
 use ego_store_mod::c2c::ego_tenant::TEgoTenant;
-use ego_store_mod::service::EgoStoreService;
+use ego_store_mod::c2c::ic_management::TIcManagement;
+use ego_store_mod::service::{CHARGE_RECONCILE_DELAY, EgoStoreService, STORE_CYCLES_RESERVE, WITHDRAW_FEE_CYCLES};
+use ego_store_mod::types::app_line_release::AppLineRelease;
+use ego_store_mod::types::app_stat::{AppStat, AppStatEvent};
+use ego_store_mod::types::cash_flow::CashFlow;
+use ego_store_mod::types::charge::{Charge, ChargeStatus};
 use ego_store_mod::types::ego_store_app::EgoStoreApp;
+use ego_store_mod::types::{WalletCashFlowListRequest, WalletOrderListRequest};
+use ego_store_mod::types::order::Order;
 use ego_store_mod::types::tenant::Tenant;
+use ego_store_mod::types::tenant_metric::{TENANT_CYCLES_MIN, TenantMetric};
 use ego_store_mod::types::user_app::UserApp;
 use ego_store_mod::types::wallet::Wallet;
-use ego_types::app::{App, AppId, Canister, CanisterType, Category, Wasm};
+use ego_store_mod::types::withdraw::{Withdraw, WithdrawStatus};
+use ego_types::app::{App, AppId, Canister, CanisterType, CashFlowType, Category, Wasm};
 use ego_types::app::CanisterType::BACKEND;
 use ego_types::app::EgoError;
 use ego_types::app::Version;
@@ -24,6 +34,7 @@ static EXISTS_USER_APP_BACKEND: &str = "224jh-lqaaa-aaaad-qaxda-cai";
 static FAKE_USER_APP_BACKEND: &str = "223vg-sqaaa-aaaak-abtmq-cai";
 
 static EXISTS_TENANT_ID: &str = "22ayq-aiaaa-aaaai-qgmma-cai";
+static OTHER_TENANT_ID: &str = "22fyd-yaaaa-aaaaf-aml4q-cai";
 
 static EXISTS_APP_ID: &str = "app_exists";
 static APP_NAME: &str = "app1";
@@ -70,6 +81,47 @@ mock! {
     );
     fn canister_main_untrack(&self, ego_tenant_id: Principal, canister_id: &Principal);
     fn app_main_delete(&self, ego_tenant_id: Principal, canister_id: &Principal);
+    async fn canister_top_up_policy_set(
+        &self,
+        ego_tenant_id: Principal,
+        canister_id: Principal,
+        policy: Option<TopUpPolicy>,
+    ) -> Result<(), EgoError>;
+    async fn canister_top_up_policy_get(
+        &self,
+        ego_tenant_id: Principal,
+        canister_id: Principal,
+    ) -> Result<Option<TopUpPolicy>, EgoError>;
+    async fn canister_main_status(
+        &self,
+        ego_tenant_id: Principal,
+        canister_id: Principal,
+    ) -> Result<StatusRecord, EgoError>;
+    async fn canister_main_start(&self, ego_tenant_id: Principal, canister_id: Principal) -> Result<(), EgoError>;
+    async fn canister_main_stop(&self, ego_tenant_id: Principal, canister_id: Principal) -> Result<(), EgoError>;
+    async fn canister_settings_update(
+        &self,
+        ego_tenant_id: Principal,
+        canister_id: Principal,
+        settings: CanisterSettingsUpdate,
+    ) -> Result<(), EgoError>;
+
+    async fn top_up_list(&self, ego_tenant_id: Principal, charge_ids: Vec<u64>) -> Result<Vec<u64>, EgoError>;
+  }
+}
+
+mock! {
+  Management {}
+
+  #[async_trait]
+  impl TIcManagement for Management {
+    async fn canister_cycle_top_up(
+        &self,
+        canister_id: Principal,
+        cycles_to_use: u128,
+    ) -> Result<(), EgoError>;
+
+    fn canister_balance(&self) -> u128;
   }
 }
 
@@ -177,6 +229,40 @@ fn wallet_main_register() {
   assert_eq!(EXISTS_TENANT_ID, tenant_id.to_string())
 }
 
+#[test]
+fn wallet_main_remove() {
+  set_up();
+
+  let tenant_id = Principal::from_text(EXISTS_TENANT_ID).unwrap();
+  let wallet_principal = Principal::from_text(TEST_WALLET_ID).unwrap();
+  let user_principal = Principal::from_text(TEST_USER_ID).unwrap();
+  let wallet_count = Tenant::get(&tenant_id).unwrap().wallet_count;
+
+  EgoStoreService::wallet_main_register(&wallet_principal, &user_principal).unwrap();
+  assert_eq!(wallet_count + 1, Tenant::get(&tenant_id).unwrap().wallet_count);
+
+  // cycles left
+  let mut wallet = Wallet::get(&wallet_principal).unwrap();
+  wallet.cycles = 100;
+  wallet.save();
+  assert_eq!(3029, EgoStoreService::wallet_main_remove(&wallet_principal).unwrap_err().code);
+
+  wallet.cycles = 0;
+  wallet.save();
+  EgoStoreService::wallet_main_remove(&wallet_principal).unwrap();
+  assert!(Wallet::get(&wallet_principal).is_none());
+  assert_eq!(wallet_count, Tenant::get(&tenant_id).unwrap().wallet_count);
+
+  assert_eq!(3006, EgoStoreService::wallet_main_remove(&wallet_principal).unwrap_err().code);
+
+  // a wallet with apps stays
+  let exists_wallet = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+  let mut wallet = Wallet::get(&exists_wallet).unwrap();
+  wallet.cycles = 0;
+  wallet.save();
+  assert_eq!(3029, EgoStoreService::wallet_main_remove(&exists_wallet).unwrap_err().code);
+}
+
 #[test]
 fn wallet_main_register_failed() {
   let wallet_principal = Principal::from_text(TEST_WALLET_ID).unwrap();
@@ -268,6 +354,7 @@ async fn wallet_app_install_success() {
     ego_canister,
     &wallet_principal,
     &ego_store_app,
+    &None,
   )
     .await;
   assert!(result.is_ok());
@@ -296,6 +383,7 @@ async fn wallet_app_upgrade_not_exists_wallet() {
     ego_canister,
     &wallet_id,
     &backend_principal,
+    &None,
   )
     .await;
   assert!(result.is_err());
@@ -322,6 +410,7 @@ async fn wallet_app_upgrade_not_installed_app() {
     ego_canister,
     &wallet_principal,
     &backend_principal,
+    &None,
   )
     .await;
   assert!(result.is_err());
@@ -363,6 +452,7 @@ async fn wallet_app_upgrade_success() {
     ego_canister,
     &exist_wallet_id,
     &backend_principal,
+    &None,
   )
     .await;
   assert!(result.is_ok());
@@ -373,6 +463,113 @@ async fn wallet_app_upgrade_success() {
   assert_eq!(latest_version, user_app.app.current_version);
 }
 
+#[tokio::test]
+async fn wallet_app_upgrade_version_not_matched() {
+  set_up();
+
+  let exist_wallet_id = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+  let backend_principal = Principal::from_text(EXISTS_USER_APP_BACKEND.to_string()).unwrap();
+
+  // a new major version is released
+  let mut ego_store_app = EgoStoreApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+  ego_store_app.app.current_version = Version::new(2, 0, 0);
+  ego_store_app.save();
+
+  let result = EgoStoreService::wallet_app_upgrade(
+    MockTenant::new(),
+    MockCanister::new(),
+    &exist_wallet_id,
+    &backend_principal,
+    &Some("^1.0".to_string()),
+  )
+    .await;
+  assert_eq!(3021, result.unwrap_err().code);
+
+  let result = EgoStoreService::wallet_app_upgrade(
+    MockTenant::new(),
+    MockCanister::new(),
+    &exist_wallet_id,
+    &backend_principal,
+    &Some("latest".to_string()),
+  )
+    .await;
+  assert_eq!(3020, result.unwrap_err().code);
+
+  // the user app is not touched
+  let user_apps = EgoStoreService::wallet_app_list(&exist_wallet_id);
+  assert_eq!(Version::new(1, 0, 1), user_apps.get(0).unwrap().app.current_version);
+}
+
+#[test]
+fn app_release_resolve() {
+  set_up();
+
+  let mut ego_store_app = EgoStoreApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+  ego_store_app.app.current_version = Version::new(1, 3, 0);
+
+  assert!(EgoStoreService::app_release_resolve(&ego_store_app, &None).is_ok());
+  assert!(EgoStoreService::app_release_resolve(&ego_store_app, &Some("^1.2".to_string())).is_ok());
+  assert!(EgoStoreService::app_release_resolve(&ego_store_app, &Some(">=1.0.0, <1.3.0".to_string())).is_err());
+
+  // pre-releases have to be asked for
+  ego_store_app.pre_release = Some("beta.1".to_string());
+  assert_eq!(3021, EgoStoreService::app_release_resolve(&ego_store_app, &Some("^1.2".to_string())).unwrap_err().code);
+  assert!(EgoStoreService::app_release_resolve(&ego_store_app, &Some("^1.3.0-beta".to_string())).is_ok());
+}
+
+#[test]
+fn app_line_release() {
+  set_up();
+
+  let app_id = EXISTS_APP_ID.to_string();
+  let mut ego_store_app = EgoStoreApp::get(&app_id).unwrap();
+  ego_store_app.app.current_version = Version::new(1, 2, 0);
+  EgoStoreService::app_main_release(&mut ego_store_app).unwrap();
+  let wasm = ego_store_app.wasm.clone();
+
+  // the line of the current version is released as the current version
+  let mut app_line_release = AppLineRelease::new(&app_id, &Version::new(1, 2, 1), &None, &wasm);
+  assert_eq!(3028, EgoStoreService::app_line_release(&mut app_line_release).unwrap_err().code);
+
+  let mut app_line_release = AppLineRelease::new(&app_id, &Version::new(1, 1, 5), &None, &wasm);
+  assert!(EgoStoreService::app_line_release(&mut app_line_release).is_ok());
+  assert_eq!(Version::new(1, 2, 0), EgoStoreApp::get(&app_id).unwrap().app.current_version);
+
+  // a line only moves forward
+  let mut app_line_release = AppLineRelease::new(&app_id, &Version::new(1, 1, 4), &None, &wasm);
+  assert_eq!(3028, EgoStoreService::app_line_release(&mut app_line_release).unwrap_err().code);
+
+  // only the requirements excluding the current version resolve to the hotfix
+  let resolved = EgoStoreService::app_release_resolve(&ego_store_app, &Some("~1.1".to_string())).unwrap();
+  assert_eq!(Version::new(1, 1, 5), resolved.app.current_version);
+
+  let resolved = EgoStoreService::app_release_resolve(&ego_store_app, &Some("^1.1".to_string())).unwrap();
+  assert_eq!(Version::new(1, 2, 0), resolved.app.current_version);
+
+  let resolved = EgoStoreService::app_release_resolve(&ego_store_app, &None).unwrap();
+  assert_eq!(Version::new(1, 2, 0), resolved.app.current_version);
+
+  assert_eq!(3021, EgoStoreService::app_release_resolve(&ego_store_app, &Some("~1.0".to_string())).unwrap_err().code);
+}
+
+#[test]
+fn app_main_release_key_invalid() {
+  set_up();
+
+  let mut ego_store_app = EgoStoreApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+
+  // refused rather than cut down to a prefix another app id could share
+  ego_store_app.app.app_id = "a".repeat(65);
+  assert_eq!(3027, EgoStoreService::app_main_release(&mut ego_store_app).unwrap_err().code);
+
+  ego_store_app.app.app_id = "a".repeat(64);
+  ego_store_app.pre_release = Some("beta.1".to_string());
+  assert!(EgoStoreService::app_main_release(&mut ego_store_app).is_ok());
+
+  ego_store_app.pre_release = Some("a-very-long-pre-release.label.1".to_string());
+  assert_eq!(3027, EgoStoreService::app_main_release(&mut ego_store_app).unwrap_err().code);
+}
+
 #[tokio::test]
 async fn wallet_app_reinstall_success() {
   set_up();
@@ -484,6 +681,12 @@ fn wallet_app_remove_success() {
   // get app list after upgrade
   let apps = EgoStoreService::wallet_app_list(&exist_wallet_id);
   assert_eq!(0, apps.len());
+
+  // the uninstall is counted
+  let today = AppStat::today();
+  let stats = EgoStoreService::app_main_stats(&EXISTS_APP_ID.to_string(), today, today).unwrap();
+  assert_eq!(1, stats.uninstalls);
+  assert_eq!(0, stats.active_installs);
 }
 
 #[test]
@@ -679,4 +882,831 @@ fn admin_wallet_app_transfer_wrong_canister() {
     format!("user app {} not exists", backend_principal),
     result.err().unwrap().msg
   );
-}
\ No newline at end of file
+}
+#[test]
+fn wallet_cycle_transfer() {
+  set_up();
+
+  let wallet_principal = Principal::from_text(EXISTS_WALLET_ID.to_string()).unwrap();
+  let new_wallet_principal = Principal::from_text(NEW_WALLET_ID.to_string()).unwrap();
+
+  let mut wallet = Wallet::get(&wallet_principal).unwrap();
+  let _ = wallet.cycle_recharge(1000, &wallet_principal, "recharge".to_string());
+
+  let result = EgoStoreService::wallet_cycle_transfer(
+    &wallet_principal,
+    &new_wallet_principal,
+    400,
+    true,
+    &wallet_principal,
+    "transfer".to_string(),
+  );
+  assert!(result.is_ok());
+
+  assert_eq!(600, Wallet::get(&wallet_principal).unwrap().cycles);
+  assert_eq!(400, Wallet::get(&new_wallet_principal).unwrap().cycles);
+
+  let cash_flows = EgoStoreService::wallet_cash_flow_list(&new_wallet_principal);
+  assert_eq!(1, cash_flows.len());
+  assert_eq!(CashFlowType::TRANSFER_IN, cash_flows.get(0).unwrap().cash_flow_type);
+}
+
+#[test]
+fn wallet_cycle_transfer_not_enough() {
+  set_up();
+
+  let wallet_principal = Principal::from_text(EXISTS_WALLET_ID.to_string()).unwrap();
+  let new_wallet_principal = Principal::from_text(NEW_WALLET_ID.to_string()).unwrap();
+
+  let result = EgoStoreService::wallet_cycle_transfer(
+    &wallet_principal,
+    &new_wallet_principal,
+    400,
+    false,
+    &wallet_principal,
+    "transfer".to_string(),
+  );
+  assert!(result.is_err());
+  assert_eq!(0, Wallet::get(&new_wallet_principal).unwrap().cycles);
+}
+
+#[test]
+fn wallet_cycle_transfer_not_same_user() {
+  set_up();
+
+  let wallet_principal = Principal::from_text(EXISTS_WALLET_ID.to_string()).unwrap();
+  let test_wallet_principal = Principal::from_text(TEST_WALLET_ID).unwrap();
+  let test_user_principal = Principal::from_text(TEST_USER_ID).unwrap();
+  let _ = EgoStoreService::wallet_main_register(&test_wallet_principal, &test_user_principal);
+
+  let mut wallet = Wallet::get(&wallet_principal).unwrap();
+  let _ = wallet.cycle_recharge(1000, &wallet_principal, "recharge".to_string());
+
+  let result = EgoStoreService::wallet_cycle_transfer(
+    &wallet_principal,
+    &test_wallet_principal,
+    400,
+    true,
+    &wallet_principal,
+    "transfer".to_string(),
+  );
+  assert!(result.is_err());
+  assert_eq!(3014, result.unwrap_err().code);
+  assert_eq!(1000, Wallet::get(&wallet_principal).unwrap().cycles);
+}
+
+#[tokio::test]
+async fn wallet_cycle_withdraw() {
+  set_up();
+
+  let wallet_principal = Principal::from_text(EXISTS_WALLET_ID.to_string()).unwrap();
+  let target_principal = Principal::from_text(TEST_USER_APP_BACKEND.to_string()).unwrap();
+
+  let mut wallet = Wallet::get(&wallet_principal).unwrap();
+  let _ = wallet.cycle_recharge(5_000_000_000_000, &wallet_principal, "recharge".to_string());
+
+  let mut ic_management = MockManagement::new();
+  ic_management
+    .expect_canister_cycle_top_up()
+    .returning(move |canister_id, cycles| {
+      assert_eq!(target_principal, canister_id);
+      assert_eq!(2_000_000_000_000, cycles);
+      Ok(())
+    });
+
+  let result = EgoStoreService::wallet_cycle_withdraw(
+    ic_management,
+    &wallet_principal,
+    &target_principal,
+    2_000_000_000_000,
+    &wallet_principal,
+    "withdraw".to_string(),
+  )
+    .await;
+  assert!(result.is_ok());
+  assert_eq!(WithdrawStatus::SUCCESS, result.unwrap().status);
+
+  assert_eq!(
+    3_000_000_000_000 - WITHDRAW_FEE_CYCLES,
+    Wallet::get(&wallet_principal).unwrap().cycles
+  );
+
+  // the fee is kept apart from the cycle charges
+  let fee_cash_flow = CashFlow::by_wallet_id(&wallet_principal)
+    .into_iter()
+    .find(|cash_flow| cash_flow.cash_flow_type == CashFlowType::WITHDRAW_FEE)
+    .unwrap();
+  assert_eq!(WITHDRAW_FEE_CYCLES, fee_cash_flow.cycles);
+
+  // a second withdraw in the same interval is rejected
+  let ic_management = MockManagement::new();
+  let result = EgoStoreService::wallet_cycle_withdraw(
+    ic_management,
+    &wallet_principal,
+    &target_principal,
+    1_000_000_000_000,
+    &wallet_principal,
+    "withdraw".to_string(),
+  )
+    .await;
+  assert!(result.is_err());
+  assert_eq!(3016, result.unwrap_err().code);
+}
+
+#[tokio::test]
+async fn wallet_cycle_withdraw_too_small() {
+  set_up();
+
+  let wallet_principal = Principal::from_text(EXISTS_WALLET_ID.to_string()).unwrap();
+  let target_principal = Principal::from_text(TEST_USER_APP_BACKEND.to_string()).unwrap();
+
+  let ic_management = MockManagement::new();
+  let result = EgoStoreService::wallet_cycle_withdraw(
+    ic_management,
+    &wallet_principal,
+    &target_principal,
+    100,
+    &wallet_principal,
+    "withdraw".to_string(),
+  )
+    .await;
+  assert!(result.is_err());
+  assert_eq!(3015, result.unwrap_err().code);
+}
+
+#[tokio::test]
+async fn wallet_cycle_withdraw_deposit_failed() {
+  set_up();
+
+  let wallet_principal = Principal::from_text(EXISTS_WALLET_ID.to_string()).unwrap();
+  let target_principal = Principal::from_text(TEST_USER_APP_BACKEND.to_string()).unwrap();
+
+  let mut wallet = Wallet::get(&wallet_principal).unwrap();
+  let _ = wallet.cycle_recharge(5_000_000_000_000, &wallet_principal, "recharge".to_string());
+
+  let mut ic_management = MockManagement::new();
+  ic_management
+    .expect_canister_cycle_top_up()
+    .returning(|_, _| Err(EgoError::new(255, "deposit failed")));
+
+  let result = EgoStoreService::wallet_cycle_withdraw(
+    ic_management,
+    &wallet_principal,
+    &target_principal,
+    2_000_000_000_000,
+    &wallet_principal,
+    "withdraw".to_string(),
+  )
+    .await;
+  assert!(result.is_err());
+
+  // the wallet is refunded
+  assert_eq!(5_000_000_000_000, Wallet::get(&wallet_principal).unwrap().cycles);
+
+  let withdraws = Withdraw::by_wallet_id(&wallet_principal);
+  assert_eq!(1, withdraws.len());
+  assert_eq!(WithdrawStatus::FAILED, withdraws.get(0).unwrap().status);
+
+  // a failed withdraw does not hold the next one back
+  assert!(Withdraw::last_by_wallet_id(&wallet_principal).is_none());
+
+  let mut ic_management = MockManagement::new();
+  ic_management
+    .expect_canister_cycle_top_up()
+    .returning(|_, _| Ok(()));
+  let result = EgoStoreService::wallet_cycle_withdraw(
+    ic_management,
+    &wallet_principal,
+    &target_principal,
+    2_000_000_000_000,
+    &wallet_principal,
+    "withdraw".to_string(),
+  )
+    .await;
+  assert_eq!(WithdrawStatus::SUCCESS, result.unwrap().status);
+}
+
+#[test]
+fn wallet_cash_flow_page() {
+  set_up();
+
+  let wallet_principal = Principal::from_text(EXISTS_WALLET_ID.to_string()).unwrap();
+
+  let mut wallet = Wallet::get(&wallet_principal).unwrap();
+  for _ in 0..3 {
+    let _ = wallet.cycle_recharge(100, &wallet_principal, "recharge".to_string());
+  }
+  for _ in 0..2 {
+    let _ = wallet.cycle_reserve(10);
+    let _ = wallet.cycle_reserve_commit(10, &wallet_principal, "charge".to_string());
+  }
+
+  let mut req = WalletCashFlowListRequest {
+    cursor: None,
+    limit: 2,
+    from_ts: None,
+    to_ts: None,
+    cash_flow_type: None,
+    with_totals: Some(true),
+  };
+
+  let resp = EgoStoreService::wallet_cash_flow_page(&wallet_principal, &req);
+  assert_eq!(2, resp.cash_flows.len());
+  assert_eq!(Some(5), resp.count);
+  assert_eq!(Some(300), resp.total_in);
+  assert_eq!(Some(20), resp.total_out);
+  // newest first
+  assert_eq!(CashFlowType::CHARGE, resp.cash_flows.get(0).unwrap().cash_flow_type);
+  assert_eq!(280, resp.cash_flows.get(0).unwrap().balance);
+  assert!(resp.next_cursor.is_some());
+
+  // the next pages go without the totals
+  req.cursor = resp.next_cursor;
+  req.with_totals = None;
+  let resp = EgoStoreService::wallet_cash_flow_page(&wallet_principal, &req);
+  assert_eq!(2, resp.cash_flows.len());
+  assert_eq!(300, resp.cash_flows.get(0).unwrap().balance);
+  assert!(resp.count.is_none());
+
+  req.cursor = resp.next_cursor;
+  let resp = EgoStoreService::wallet_cash_flow_page(&wallet_principal, &req);
+  assert_eq!(1, resp.cash_flows.len());
+  assert!(resp.next_cursor.is_none());
+
+  // filter by type
+  let req = WalletCashFlowListRequest {
+    cursor: None,
+    limit: 10,
+    from_ts: None,
+    to_ts: None,
+    cash_flow_type: Some(CashFlowType::CHARGE),
+    with_totals: Some(true),
+  };
+  let resp = EgoStoreService::wallet_cash_flow_page(&wallet_principal, &req);
+  assert_eq!(2, resp.cash_flows.len());
+  assert!(resp.next_cursor.is_none());
+  assert_eq!(Some(2), resp.count);
+  assert_eq!(Some(0), resp.total_in);
+
+  // filter by date range
+  let req = WalletCashFlowListRequest {
+    cursor: None,
+    limit: 10,
+    from_ts: Some(0),
+    to_ts: Some(1),
+    cash_flow_type: None,
+    with_totals: Some(true),
+  };
+  let resp = EgoStoreService::wallet_cash_flow_page(&wallet_principal, &req);
+  assert_eq!(Some(0), resp.count);
+}
+
+#[test]
+fn wallet_order_page() {
+  set_up();
+
+  let wallet_principal = Principal::from_text(EXISTS_WALLET_ID.to_string()).unwrap();
+  let store_principal = Principal::from_text(FILE_CANISTER_ID.to_string()).unwrap();
+
+  for amount in 1..4 {
+    let mut order = Order::new(&wallet_principal, &store_principal, amount as f32);
+    order.save();
+  }
+
+  let req = WalletOrderListRequest {
+    cursor: None,
+    limit: 2,
+    from_ts: None,
+    to_ts: None,
+    status: None,
+    with_totals: Some(true),
+  };
+
+  let resp = EgoStoreService::wallet_order_page(&wallet_principal, &req);
+  assert_eq!(2, resp.orders.len());
+  assert_eq!(Some(3), resp.count);
+  assert_eq!(Some(6.0), resp.total_amount);
+  assert_eq!(3.0, resp.orders.get(0).unwrap().amount);
+  assert!(resp.next_cursor.is_some());
+
+  let next = WalletOrderListRequest {
+    cursor: resp.next_cursor,
+    limit: 2,
+    from_ts: None,
+    to_ts: None,
+    status: None,
+    with_totals: None,
+  };
+  let next = EgoStoreService::wallet_order_page(&wallet_principal, &next);
+  assert_eq!(1, next.orders.len());
+  assert_eq!(1.0, next.orders.get(0).unwrap().amount);
+  assert!(next.next_cursor.is_none());
+
+  // the unpaged list keeps the order the orders were made in
+  let orders = EgoStoreService::wallet_order_list(&wallet_principal);
+  assert_eq!(vec![1.0, 2.0, 3.0], orders.iter().map(|order| order.amount).collect::<Vec<_>>());
+
+  // updating an order keeps a single index entry
+  let _ = EgoStoreService::wallet_order_notify(resp.orders.get(0).unwrap().memo, &wallet_principal);
+  assert_eq!(3, EgoStoreService::wallet_order_list(&wallet_principal).len());
+}
+
+#[test]
+fn app_review_set() {
+  set_up();
+
+  let wallet_id = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+  let new_wallet_id = Principal::from_text(NEW_WALLET_ID).unwrap();
+
+  // the wallet didn't install the app
+  let result = EgoStoreService::app_review_set(&new_wallet_id, &EXISTS_APP_ID.to_string(), 5, "good".to_string());
+  assert_eq!(3017, result.unwrap_err().code);
+
+  // rating out of range
+  let result = EgoStoreService::app_review_set(&wallet_id, &EXISTS_APP_ID.to_string(), 6, "good".to_string());
+  assert_eq!(3018, result.unwrap_err().code);
+
+  let result = EgoStoreService::app_review_set(&wallet_id, &EXISTS_APP_ID.to_string(), 4, "good".to_string());
+  assert!(result.is_ok());
+
+  // edit the review
+  let result = EgoStoreService::app_review_set(&wallet_id, &EXISTS_APP_ID.to_string(), 2, "bad".to_string());
+  assert!(result.is_ok());
+
+  let reviews = EgoStoreService::app_review_list(&EXISTS_APP_ID.to_string());
+  assert_eq!(1, reviews.len());
+  assert_eq!("bad", reviews[0].content);
+
+  // app_main_get returns the app along with its rating
+  let entry = EgoStoreService::app_main_catalog_get(&EXISTS_APP_ID.to_string()).unwrap();
+  assert_eq!(EXISTS_APP_ID, entry.app_id);
+  assert_eq!(Some(1), entry.rating_count);
+  assert_eq!(Some(2f32), entry.rating_average);
+}
+
+#[test]
+fn app_review_reply_and_hide() {
+  set_up();
+
+  let wallet_id = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+
+  let result = EgoStoreService::app_review_reply(&EXISTS_APP_ID.to_string(), &wallet_id, "thanks".to_string());
+  assert_eq!(3019, result.unwrap_err().code);
+
+  EgoStoreService::app_review_set(&wallet_id, &EXISTS_APP_ID.to_string(), 5, "good".to_string()).unwrap();
+
+  let review = EgoStoreService::app_review_reply(&EXISTS_APP_ID.to_string(), &wallet_id, "thanks".to_string()).unwrap();
+  assert_eq!(Some("thanks".to_string()), review.reply);
+
+  // hidden reviews are not listed and not rated
+  EgoStoreService::app_review_hide(&EXISTS_APP_ID.to_string(), &wallet_id, true).unwrap();
+  assert!(EgoStoreService::app_review_list(&EXISTS_APP_ID.to_string()).is_empty());
+
+  let entry = EgoStoreService::app_main_catalog_get(&EXISTS_APP_ID.to_string()).unwrap();
+  assert_eq!(Some(0), entry.rating_count);
+
+  EgoStoreService::app_review_hide(&EXISTS_APP_ID.to_string(), &wallet_id, false).unwrap();
+  let entry = EgoStoreService::app_main_catalog_get(&EXISTS_APP_ID.to_string()).unwrap();
+  assert_eq!(Some(1), entry.rating_count);
+  assert_eq!(Some(5f32), entry.rating_average);
+}
+
+#[test]
+fn app_main_stats() {
+  set_up();
+
+  let app_id = EXISTS_APP_ID.to_string();
+  AppStat::record(&app_id, AppStatEvent::INSTALL);
+  AppStat::record(&app_id, AppStatEvent::INSTALL);
+  AppStat::record(&app_id, AppStatEvent::UPGRADE);
+
+  let today = AppStat::today();
+  let stats = EgoStoreService::app_main_stats(&app_id, today - 7, today).unwrap();
+  assert_eq!(1, stats.daily.len());
+  assert_eq!(2, stats.installs);
+  assert_eq!(1, stats.upgrades);
+  assert_eq!(1, stats.active_installs);
+  assert_eq!(1, stats.versions.len());
+
+  // no records for other apps
+  let stats = EgoStoreService::app_main_stats(&TEST_APP_ID.to_string(), today - 7, today).unwrap();
+  assert!(stats.daily.is_empty());
+
+  // invalid range
+  assert!(EgoStoreService::app_main_stats(&app_id, today, today - 1).is_err());
+  assert!(EgoStoreService::app_main_stats(&app_id, 0, today).is_err());
+}
+
+#[tokio::test]
+async fn wallet_canister_top_up_policy_set() {
+  set_up();
+  let wallet_id = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+  let backend_principal = Principal::from_text(EXISTS_USER_APP_BACKEND.to_string()).unwrap();
+  let fake_principal = Principal::from_text(FAKE_USER_APP_BACKEND.to_string()).unwrap();
+
+  let policy = TopUpPolicy {
+    min_balance: Some(1_000_000_000_000),
+    target_balance: Some(2_000_000_000_000),
+    max_daily_top_up: None,
+    alert_only: false,
+  };
+
+  // only canisters installed by the wallet
+  let mut ego_tenant = MockTenant::new();
+  ego_tenant.expect_canister_top_up_policy_set().times(0);
+  let result = EgoStoreService::wallet_canister_top_up_policy_set(ego_tenant, &wallet_id, &fake_principal, Some(policy.clone())).await;
+  assert_eq!(3002, result.unwrap_err().code);
+
+  let mut ego_tenant = MockTenant::new();
+  let expected = policy.clone();
+  ego_tenant
+    .expect_canister_top_up_policy_set()
+    .times(1)
+    .returning(move |_, canister_id, policy| {
+      assert_eq!(backend_principal, canister_id);
+      assert_eq!(Some(expected.clone()), policy);
+      Ok(())
+    });
+  let result = EgoStoreService::wallet_canister_top_up_policy_set(ego_tenant, &wallet_id, &backend_principal, Some(policy)).await;
+  assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn wallet_canister_stop() {
+  set_up();
+  let wallet_id = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+  let tenant_id = Principal::from_text(EXISTS_TENANT_ID).unwrap();
+  let backend_principal = Principal::from_text(EXISTS_USER_APP_BACKEND.to_string()).unwrap();
+  let fake_principal = Principal::from_text(FAKE_USER_APP_BACKEND.to_string()).unwrap();
+
+  // only canisters installed by the wallet
+  let mut ego_tenant = MockTenant::new();
+  ego_tenant.expect_canister_main_stop().times(0);
+  let result = EgoStoreService::wallet_canister_stop(ego_tenant, &wallet_id, &fake_principal).await;
+  assert_eq!(3002, result.unwrap_err().code);
+
+  // routed to the tenant of the wallet
+  let mut ego_tenant = MockTenant::new();
+  ego_tenant
+    .expect_canister_main_stop()
+    .times(1)
+    .returning(move |ego_tenant_id, canister_id| {
+      assert_eq!(tenant_id, ego_tenant_id);
+      assert_eq!(backend_principal, canister_id);
+      Ok(())
+    });
+  let result = EgoStoreService::wallet_canister_stop(ego_tenant, &wallet_id, &backend_principal).await;
+  assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn wallet_canister_status_and_settings() {
+  set_up();
+  let wallet_id = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+  let backend_principal = Principal::from_text(EXISTS_USER_APP_BACKEND.to_string()).unwrap();
+
+  let mut ego_tenant = MockTenant::new();
+  ego_tenant
+    .expect_canister_main_status()
+    .times(1)
+    .returning(|_, _| Ok(StatusRecord {
+      ts: 10,
+      status: CanisterRunStatus::STOPPED,
+      cycles: 1_000_000,
+      memory_size: 1024,
+      idle_burn_per_day: 1_000,
+      freezing_threshold: 2_592_000,
+      memory_allocation: None,
+    }));
+  let status = EgoStoreService::wallet_canister_status_get(ego_tenant, &wallet_id, &backend_principal).await.unwrap();
+  assert_eq!(CanisterRunStatus::STOPPED, status.status);
+
+  let settings = CanisterSettingsUpdate { memory_allocation: Some(1024 * 1024 * 1024), ..CanisterSettingsUpdate::default() };
+  let expected = settings.clone();
+  let mut ego_tenant = MockTenant::new();
+  ego_tenant
+    .expect_canister_settings_update()
+    .times(1)
+    .returning(move |_, canister_id, settings| {
+      assert_eq!(backend_principal, canister_id);
+      assert_eq!(expected, settings);
+      Ok(())
+    });
+  let result = EgoStoreService::wallet_canister_settings_update(ego_tenant, &wallet_id, &backend_principal, settings).await;
+  assert!(result.is_ok());
+}
+
+#[test]
+fn tenant_get_weighted() {
+  set_up();
+  let tenant_id = Principal::from_text(EXISTS_TENANT_ID).unwrap();
+  let other_tenant_id = Principal::from_text(OTHER_TENANT_ID).unwrap();
+  Tenant::new(&other_tenant_id).save();
+
+  // twice the capacity takes twice the wallets
+  EgoStoreService::admin_tenant_capacity_set(&tenant_id, 100).unwrap();
+  EgoStoreService::admin_tenant_capacity_set(&other_tenant_id, 200).unwrap();
+  for _ in 0..3 {
+    EgoStoreService::tenant_get().unwrap();
+  }
+  assert_eq!(1, Tenant::get(&tenant_id).unwrap().wallet_count);
+  assert_eq!(2, Tenant::get(&other_tenant_id).unwrap().wallet_count);
+
+  // a tenant short of cycles takes no wallet
+  EgoStoreService::tenant_metric_report(&tenant_id, 10, 0, TENANT_CYCLES_MIN - 1, None, None).unwrap();
+  assert_eq!(other_tenant_id, EgoStoreService::tenant_get().unwrap());
+
+  // nor a full one
+  EgoStoreService::admin_tenant_capacity_set(&other_tenant_id, 3).unwrap();
+  assert_eq!(3003, EgoStoreService::tenant_get().unwrap_err().code);
+
+  let tenants = EgoStoreService::admin_tenant_list();
+  assert_eq!(2, tenants.len());
+  assert!(tenants.iter().any(|info| info.tenant.canister_id == tenant_id && !info.healthy));
+}
+
+#[test]
+fn tenant_metric_report_not_exists() {
+  set_up();
+  let other_tenant_id = Principal::from_text(OTHER_TENANT_ID).unwrap();
+
+  let result = EgoStoreService::tenant_metric_report(&other_tenant_id, 0, 0, TENANT_CYCLES_MIN, None, None);
+  assert_eq!(3023, result.unwrap_err().code);
+}
+
+#[test]
+fn wallet_cycle_reserve_commit_release() {
+  set_up();
+  let wallet_id = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+  let tenant_id = Principal::from_text(EXISTS_TENANT_ID).unwrap();
+  let other_tenant_id = Principal::from_text(OTHER_TENANT_ID).unwrap();
+  let backend_principal = Principal::from_text(EXISTS_USER_APP_BACKEND).unwrap();
+
+  let mut wallet = Wallet::get(&wallet_id).unwrap();
+  wallet.cycle_recharge(1_000_000, &wallet_id, "recharge".to_string()).unwrap();
+
+  // taken off the balance on reserve, written to the cash flows on commit only
+  let charge = EgoStoreService::canister_cycle_reserve(&backend_principal, 300_000, &tenant_id, "top up".to_string()).unwrap();
+  assert_eq!(ChargeStatus::RESERVED, charge.status);
+  assert_eq!(700_000, Wallet::get(&wallet_id).unwrap().cycles);
+  assert_eq!(1, CashFlow::by_wallet_id(&wallet_id).len());
+
+  // committed by the tenant which reserved it, and only once
+  assert_eq!(3025, EgoStoreService::charge_commit(charge.id, &other_tenant_id).unwrap_err().code);
+  let committed = EgoStoreService::charge_commit(charge.id, &tenant_id).unwrap();
+  assert_eq!(ChargeStatus::COMMITTED, committed.status);
+  let cash_flows = CashFlow::by_wallet_id(&wallet_id);
+  assert_eq!(2, cash_flows.len());
+  let cash_flow = cash_flows.iter().find(|cash_flow| cash_flow.cash_flow_type == CashFlowType::CHARGE).unwrap();
+  assert_eq!(300_000, cash_flow.cycles);
+  assert_eq!(Some(cash_flow.id), committed.cash_flow_id);
+  assert_eq!(3026, EgoStoreService::charge_release(charge.id, &tenant_id).unwrap_err().code);
+
+  // back to the wallet on release
+  let charge = EgoStoreService::canister_cycle_reserve(&backend_principal, 300_000, &tenant_id, "top up".to_string()).unwrap();
+  assert_eq!(400_000, Wallet::get(&wallet_id).unwrap().cycles);
+  EgoStoreService::charge_release(charge.id, &tenant_id).unwrap();
+  assert_eq!(700_000, Wallet::get(&wallet_id).unwrap().cycles);
+  assert_eq!(2, CashFlow::by_wallet_id(&wallet_id).len());
+
+  // the committed one waits for the reconciliation
+  assert_eq!(1, EgoStoreService::admin_charge_pending_list().len());
+
+  let result = EgoStoreService::canister_cycle_reserve(&backend_principal, 700_000, &tenant_id, "top up".to_string());
+  assert_eq!(3003, result.unwrap_err().code);
+}
+
+#[tokio::test]
+async fn charge_reconcile() {
+  set_up();
+  let wallet_id = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+  let tenant_id = Principal::from_text(EXISTS_TENANT_ID).unwrap();
+  let backend_principal = Principal::from_text(EXISTS_USER_APP_BACKEND).unwrap();
+
+  let mut wallet = Wallet::get(&wallet_id).unwrap();
+  wallet.cycle_recharge(1_000_000, &wallet_id, "recharge".to_string()).unwrap();
+
+  let reserve = |cycles: u128, committed: bool, age: u64| {
+    let mut charge = EgoStoreService::canister_cycle_reserve(&backend_principal, cycles, &tenant_id, "top up".to_string()).unwrap();
+    if committed {
+      charge = EgoStoreService::charge_commit(charge.id, &tenant_id).unwrap();
+    }
+    charge.created_at -= age;
+    charge.save();
+    charge
+  };
+  // committed and topped up, committed but no top up, the commit lost, the release lost
+  let reconciled = reserve(100_000, true, CHARGE_RECONCILE_DELAY + 60);
+  let refunded = reserve(100_000, true, CHARGE_RECONCILE_DELAY + 60);
+  let lost_commit = reserve(100_000, false, CHARGE_RECONCILE_DELAY + 60);
+  let lost_release = reserve(100_000, false, CHARGE_RECONCILE_DELAY + 60);
+  // too recent, still being settled by the tenant
+  let recent = reserve(100_000, false, 0);
+  assert_eq!(500_000, Wallet::get(&wallet_id).unwrap().cycles);
+
+  let mut ego_tenant = MockTenant::new();
+  ego_tenant
+    .expect_top_up_list()
+    .times(1)
+    .returning(move |ego_tenant_id, charge_ids| {
+      assert_eq!(tenant_id, ego_tenant_id);
+      assert_eq!(vec![reconciled.id, refunded.id, lost_commit.id, lost_release.id], charge_ids);
+      Ok(vec![reconciled.id, lost_commit.id])
+    });
+
+  let settled = EgoStoreService::charge_reconcile(ego_tenant, &tenant_id).await.unwrap();
+  assert_eq!(4, settled.len());
+
+  assert_eq!(ChargeStatus::RECONCILED, Charge::get(reconciled.id).unwrap().status);
+  assert_eq!(ChargeStatus::REFUNDED, Charge::get(refunded.id).unwrap().status);
+  let lost_commit = Charge::get(lost_commit.id).unwrap();
+  assert_eq!(ChargeStatus::RECONCILED, lost_commit.status);
+  assert!(lost_commit.cash_flow_id.is_some());
+  assert_eq!(ChargeStatus::RELEASED, Charge::get(lost_release.id).unwrap().status);
+
+  // the refund and the release credited back, the recent one still pending
+  assert_eq!(700_000, Wallet::get(&wallet_id).unwrap().cycles);
+  let pending = EgoStoreService::admin_charge_pending_list();
+  assert_eq!(1, pending.len());
+  assert_eq!(recent.id, pending[0].id);
+}
+
+#[tokio::test]
+async fn charge_reconcile_one_call() {
+  set_up();
+  let wallet_id = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+  let tenant_id = Principal::from_text(EXISTS_TENANT_ID).unwrap();
+  let backend_principal = Principal::from_text(EXISTS_USER_APP_BACKEND).unwrap();
+
+  let mut wallet = Wallet::get(&wallet_id).unwrap();
+  wallet.cycle_recharge(1_000_000, &wallet_id, "recharge".to_string()).unwrap();
+
+  // no top up logged for it, still not refunded
+  let mut charge = EgoStoreService::canister_cycle_charge(&backend_principal, 100_000, &tenant_id, "top up".to_string()).unwrap();
+  charge.created_at -= CHARGE_RECONCILE_DELAY + 60;
+  charge.save();
+
+  let mut ego_tenant = MockTenant::new();
+  ego_tenant.expect_top_up_list().times(0);
+
+  let settled = EgoStoreService::charge_reconcile(ego_tenant, &tenant_id).await.unwrap();
+  assert!(settled.is_empty());
+  assert_eq!(ChargeStatus::RECONCILED, Charge::get(charge.id).unwrap().status);
+  assert_eq!(900_000, Wallet::get(&wallet_id).unwrap().cycles);
+  assert!(EgoStoreService::admin_charge_pending_list().is_empty());
+}
+
+#[tokio::test]
+async fn wallet_app_install_tenant_reserve_low() {
+  set_up();
+
+  let wallet_principal = Principal::from_text(TEST_WALLET_ID).unwrap();
+  let user_principal = Principal::from_text(TEST_USER_ID).unwrap();
+  EgoStoreService::wallet_main_register(&wallet_principal, &user_principal).unwrap();
+  let tenant_id = Wallet::get(&wallet_principal).unwrap().tenant_id;
+
+  // enough to stay healthy, not to pay another canister
+  EgoStoreService::tenant_metric_report(&tenant_id, 10, 0, TENANT_CYCLES_MIN, None, None).unwrap();
+
+  let mut ego_tenant = MockTenant::new();
+  ego_tenant.expect_app_main_install().times(0);
+
+  let ego_store_app = EgoStoreService::app_main_get(&TEST_APP_ID.to_string()).unwrap();
+  let result = EgoStoreService::wallet_app_install(ego_tenant, MockCanister::new(), &wallet_principal, &ego_store_app, &None).await;
+  assert_eq!(3024, result.unwrap_err().code);
+}
+
+#[tokio::test]
+async fn tenant_refill() {
+  set_up();
+  let tenant_id = Principal::from_text(EXISTS_TENANT_ID).unwrap();
+  let threshold = 3_000_000_000_000;
+
+  // above the threshold
+  EgoStoreService::tenant_metric_report(&tenant_id, 10, 0, threshold, Some(100), Some(threshold)).unwrap();
+  let result = EgoStoreService::tenant_refill(MockManagement::new(), &tenant_id).await;
+  assert_eq!(0, result.unwrap());
+
+  // under it, back to twice the threshold
+  EgoStoreService::tenant_metric_report(&tenant_id, 10, 0, threshold - 1, Some(100), Some(threshold)).unwrap();
+  let mut ic_management = MockManagement::new();
+  ic_management.expect_canister_balance().returning(|| 100_000_000_000_000);
+  ic_management
+    .expect_canister_cycle_top_up()
+    .times(1)
+    .returning(move |canister_id, cycles| {
+      assert_eq!(tenant_id, canister_id);
+      assert_eq!(threshold + 1, cycles);
+      Ok(())
+    });
+  let result = EgoStoreService::tenant_refill(ic_management, &tenant_id).await;
+  assert_eq!(threshold + 1, result.unwrap());
+
+  let metric = TenantMetric::get(&tenant_id).unwrap();
+  assert_eq!(threshold * 2, metric.cycles);
+  assert_eq!(Some(threshold + 1), metric.refilled);
+
+  // a stale report in the same interval is not refilled twice
+  EgoStoreService::tenant_metric_report(&tenant_id, 10, 0, threshold - 1, Some(100), Some(threshold)).unwrap();
+  let result = EgoStoreService::tenant_refill(MockManagement::new(), &tenant_id).await;
+  assert_eq!(0, result.unwrap());
+}
+
+#[tokio::test]
+async fn tenant_refill_store_reserve() {
+  set_up();
+  let tenant_id = Principal::from_text(EXISTS_TENANT_ID).unwrap();
+
+  EgoStoreService::tenant_metric_report(&tenant_id, 10, 0, 0, None, None).unwrap();
+
+  // ego_store keeps its own reserve
+  let mut ic_management = MockManagement::new();
+  ic_management.expect_canister_balance().returning(|| STORE_CYCLES_RESERVE);
+  ic_management.expect_canister_cycle_top_up().times(0);
+  let result = EgoStoreService::tenant_refill(ic_management, &tenant_id).await;
+  assert_eq!(3003, result.unwrap_err().code);
+
+  // and refills what is left above it
+  let mut ic_management = MockManagement::new();
+  ic_management.expect_canister_balance().returning(|| STORE_CYCLES_RESERVE + 1_000);
+  ic_management
+    .expect_canister_cycle_top_up()
+    .times(1)
+    .returning(|_, cycles| {
+      assert_eq!(1_000, cycles);
+      Ok(())
+    });
+  let result = EgoStoreService::tenant_refill(ic_management, &tenant_id).await;
+  assert_eq!(1_000, result.unwrap());
+}
+
+#[tokio::test]
+async fn admin_wallet_tenant_migrate() {
+  set_up();
+  let wallet_id = Principal::from_text(EXISTS_WALLET_ID).unwrap();
+  let tenant_id = Principal::from_text(EXISTS_TENANT_ID).unwrap();
+  let other_tenant_id = Principal::from_text(OTHER_TENANT_ID).unwrap();
+  let backend_principal = Principal::from_text(EXISTS_USER_APP_BACKEND.to_string()).unwrap();
+  Tenant::new(&other_tenant_id).save();
+
+  let policy = TopUpPolicy {
+    min_balance: Some(1_000_000_000_000),
+    target_balance: None,
+    max_daily_top_up: None,
+    alert_only: false,
+  };
+  let expected = policy.clone();
+
+  let mut ego_tenant = MockTenant::new();
+  ego_tenant
+    .expect_canister_top_up_policy_get()
+    .times(1)
+    .returning(move |ego_tenant_id, _| {
+      assert_eq!(tenant_id, ego_tenant_id);
+      Ok(Some(policy.clone()))
+    });
+  // the former tenant is no controller of the canister
+  ego_tenant
+    .expect_canister_main_status()
+    .times(1)
+    .returning(|_, _| Err(EgoError::new(403, "only the controllers of the canister")));
+  ego_tenant
+    .expect_canister_main_track()
+    .times(1)
+    .returning(move |ego_tenant_id, canister_id| {
+      assert_eq!(other_tenant_id, ego_tenant_id);
+      assert_eq!(backend_principal, *canister_id);
+    });
+  ego_tenant
+    .expect_canister_top_up_policy_set()
+    .times(1)
+    .returning(move |ego_tenant_id, _, policy| {
+      assert_eq!(other_tenant_id, ego_tenant_id);
+      assert_eq!(Some(expected.clone()), policy);
+      Ok(())
+    });
+  ego_tenant
+    .expect_canister_main_untrack()
+    .times(1)
+    .returning(move |ego_tenant_id, _| assert_eq!(tenant_id, ego_tenant_id));
+
+  let mut ego_canister = MockCanister::new();
+  ego_canister
+    .expect_ego_canister_remove()
+    .times(1)
+    .returning(move |_, _, principal| assert_eq!(tenant_id, principal));
+  ego_canister
+    .expect_ego_canister_add()
+    .times(1)
+    .returning(move |_, _, principal| assert_eq!(other_tenant_id, principal));
+  ego_canister.expect_ego_op_remove().times(1).returning(|_, _| ());
+  ego_canister.expect_ego_op_add().times(1).returning(|_, _| ());
+  ego_canister.expect_ego_controller_add().times(0);
+  ego_canister.expect_ego_controller_remove().times(0);
+
+  EgoStoreService::admin_wallet_tenant_migrate(ego_tenant, ego_canister, &wallet_id, &other_tenant_id).await.unwrap();
+
+  assert_eq!(other_tenant_id, Wallet::get(&wallet_id).unwrap().tenant_id);
+  assert_eq!(1, Tenant::get(&other_tenant_id).unwrap().wallet_count);
+  assert_eq!(1, TenantMetric::get(&other_tenant_id).unwrap().app_count);
+}
@@ -1,6 +1,7 @@
 use candid::Principal;
 
 use ego_store_mod::types::tenant::Tenant;
+use ego_store_mod::types::tenant_metric::{TENANT_CYCLES_MIN, TENANT_REPORT_TIMEOUT, TenantMetric};
 
 static TENANT_ID1: &str = "22fyd-yaaaa-aaaaf-aml4q-cai";
 static TENANT_ID2: &str = "223xb-saaaa-aaaaf-arlqa-cai";
@@ -53,4 +54,23 @@ pub fn get() {
   let tenant_id2 = Principal::from_text(TENANT_ID2.to_string()).unwrap();
   let tenant2 = Tenant::get(&tenant_id2);
   assert!(tenant2.is_none());
-}
\ No newline at end of file
+}
+
+#[test]
+pub fn metric_healthy() {
+  let tenant_id1 = Principal::from_text(TENANT_ID1.to_string()).unwrap();
+  let mut metric = TenantMetric::new(&tenant_id1);
+
+  // not reported yet
+  assert!(metric.is_healthy(1000));
+  assert!(metric.install_allowed());
+
+  metric.report(10, 1, TENANT_CYCLES_MIN, None, None, 1000);
+  assert!(metric.is_healthy(1000 + TENANT_REPORT_TIMEOUT));
+  // healthy, but short of a canister creation
+  assert!(!metric.install_allowed());
+  assert!(!metric.is_healthy(1000 + TENANT_REPORT_TIMEOUT + 1));
+
+  metric.report(10, 1, TENANT_CYCLES_MIN - 1, None, None, 1000);
+  assert!(!metric.is_healthy(1000));
+}
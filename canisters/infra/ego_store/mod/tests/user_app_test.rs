@@ -150,3 +150,55 @@ pub fn into() {
   assert_eq!(user_app.app.app_id, u_a.app.app_id);
   assert_eq!(ego_store_app.app.current_version, u_a.latest_version);
 }
+
+#[test]
+pub fn by_app_id() {
+  set_up();
+
+  let ego_store_app = EgoStoreApp::get(&EXISTS_APP_ID.to_string()).unwrap();
+
+  let mut app = ego_store_app.app.clone();
+  app.current_version = Version::new(1, 0, 2);
+
+  let wallet_id2 = Principal::from_text(WALLET_ID2.to_string()).unwrap();
+  let user_app_canister_id2 = Principal::from_text(USER_APP_CANISTER_ID2.to_string()).unwrap();
+  let canister = Canister::new(user_app_canister_id2, BACKEND);
+  let mut user_app = UserApp::new(&app, &canister, Some(wallet_id2));
+  user_app.save();
+
+  assert_eq!(2, UserApp::count_by_app_id(&EXISTS_APP_ID.to_string()));
+  assert_eq!(1, UserApp::by_app_id(&EXISTS_APP_ID.to_string(), Some(Version::new(1, 0, 2))).len());
+  assert_eq!(0, UserApp::by_app_id(&"not_exists".to_string(), None).len());
+
+  let distribution = UserApp::version_distribution(&EXISTS_APP_ID.to_string());
+  assert_eq!(vec![(Version::new(1, 0, 1), None, 1), (Version::new(1, 0, 2), None, 1)], distribution);
+
+  // upgrade the first user app, the index follows the version
+  let user_app_canister_id1 = Principal::from_text(USER_APP_CANISTER_ID1.to_string()).unwrap();
+  let mut user_app = UserApp::get(&user_app_canister_id1).unwrap();
+  user_app.app.current_version = Version::new(1, 0, 2);
+  user_app.save();
+
+  let distribution = UserApp::version_distribution(&EXISTS_APP_ID.to_string());
+  assert_eq!(vec![(Version::new(1, 0, 2), None, 2)], distribution);
+
+  // the pre-releases of a version are kept apart from it, and sort before it by precedence
+  user_app.pre_release = Some("beta.10".to_string());
+  user_app.save();
+  let mut beta = UserApp::get(&user_app_canister_id2).unwrap();
+  beta.pre_release = Some("beta.2".to_string());
+  beta.save();
+
+  let distribution = UserApp::version_distribution(&EXISTS_APP_ID.to_string());
+  assert_eq!(vec![
+    (Version::new(1, 0, 2), Some("beta.2".to_string()), 1),
+    (Version::new(1, 0, 2), Some("beta.10".to_string()), 1),
+  ], distribution);
+  assert_eq!(2, UserApp::by_app_id(&EXISTS_APP_ID.to_string(), Some(Version::new(1, 0, 2))).len());
+
+  // removed user apps leave the indexes
+  UserApp::remove(&user_app_canister_id1);
+  assert_eq!(1, UserApp::count_by_app_id(&EXISTS_APP_ID.to_string()));
+  let wallet_id1 = Principal::from_text(WALLET_ID1.to_string()).unwrap();
+  assert_eq!(0, UserApp::by_wallet_id(&wallet_id1).len());
+}
@@ -4,6 +4,7 @@ use mockall::mock;
 
 use ego_lib::ego_canister::TEgoCanister;
 use ego_lib::inject_mock_ego_canister;
+use ego_store_mod::c2c::c2c_types::{CanisterSettingsUpdate, StatusRecord, TopUpPolicy};
 use ego_store_mod::c2c::ego_tenant::TEgoTenant;
 use ego_store_mod::service::EgoStoreService;
 use ego_store_mod::types::cash_flow::CashFlow;
@@ -49,6 +50,32 @@ mock! {
     );
     fn canister_main_untrack(&self, ego_tenant_id: Principal, canister_id: &Principal);
     fn app_main_delete(&self, ego_tenant_id: Principal, canister_id: &Principal);
+    async fn canister_top_up_policy_set(
+        &self,
+        ego_tenant_id: Principal,
+        canister_id: Principal,
+        policy: Option<TopUpPolicy>,
+    ) -> Result<(), EgoError>;
+    async fn canister_top_up_policy_get(
+        &self,
+        ego_tenant_id: Principal,
+        canister_id: Principal,
+    ) -> Result<Option<TopUpPolicy>, EgoError>;
+    async fn canister_main_status(
+        &self,
+        ego_tenant_id: Principal,
+        canister_id: Principal,
+    ) -> Result<StatusRecord, EgoError>;
+    async fn canister_main_start(&self, ego_tenant_id: Principal, canister_id: Principal) -> Result<(), EgoError>;
+    async fn canister_main_stop(&self, ego_tenant_id: Principal, canister_id: Principal) -> Result<(), EgoError>;
+    async fn canister_settings_update(
+        &self,
+        ego_tenant_id: Principal,
+        canister_id: Principal,
+        settings: CanisterSettingsUpdate,
+    ) -> Result<(), EgoError>;
+
+    async fn top_up_list(&self, ego_tenant_id: Principal, charge_ids: Vec<u64>) -> Result<Vec<u64>, EgoError>;
   }
 }
 
@@ -0,0 +1,69 @@
+use candid::Principal;
+
+use ego_store_mod::types::withdraw::{Withdraw, WithdrawStatus};
+use ego_utils::util::time;
+
+static WALLET1: &str = "22fyd-yaaaa-aaaaf-aml4q-cai";
+static WALLET2: &str = "223xb-saaaa-aaaaf-arlqa-cai";
+
+static TARGET: &str = "225da-yaaaa-aaaah-qahrq-cai";
+
+pub fn set_up() {
+  let target = Principal::from_text(TARGET.to_string()).unwrap();
+
+  let wallet1 = Principal::from_text(WALLET1.to_string()).unwrap();
+  let mut withdraw1 = Withdraw::new(&wallet1, &target, 100, 1);
+  withdraw1.save();
+
+  let wallet2 = Principal::from_text(WALLET2.to_string()).unwrap();
+  let mut withdraw2 = Withdraw::new(&wallet2, &target, 200, 1);
+  withdraw2.save();
+}
+
+#[test]
+pub fn new() {
+  set_up();
+
+  assert_eq!(2, Withdraw::len());
+
+  let withdraw = Withdraw::get(1).unwrap();
+  assert_eq!(WithdrawStatus::PENDING, withdraw.status);
+  assert_eq!(100, withdraw.cycles);
+}
+
+#[test]
+pub fn by_last_update() {
+  set_up();
+
+  let now = time();
+
+  assert_eq!(2, Withdraw::by_last_update(0, 100, now).len());
+}
+
+#[test]
+pub fn by_wallet_id() {
+  set_up();
+
+  let wallet1 = Principal::from_text(WALLET1.to_string()).unwrap();
+
+  assert_eq!(1, Withdraw::by_wallet_id(&wallet1).len());
+}
+
+#[test]
+pub fn last_by_wallet_id() {
+  set_up();
+
+  let wallet1 = Principal::from_text(WALLET1.to_string()).unwrap();
+  let target = Principal::from_text(TARGET.to_string()).unwrap();
+
+  assert_eq!(1, Withdraw::last_by_wallet_id(&wallet1).unwrap().id);
+
+  let mut withdraw = Withdraw::new(&wallet1, &target, 300, 1);
+  withdraw.save();
+  assert_eq!(withdraw.id, Withdraw::last_by_wallet_id(&wallet1).unwrap().id);
+
+  // the failed one is dropped, not replaced by the earlier one
+  withdraw.status = WithdrawStatus::FAILED;
+  withdraw.save();
+  assert!(Withdraw::last_by_wallet_id(&wallet1).is_none());
+}
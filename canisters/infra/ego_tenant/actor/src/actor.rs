@@ -15,10 +15,13 @@ use ego_tenant_mod::c2c::ego_store::EgoStore;
 use ego_tenant_mod::c2c::ic_management::IcManagement;
 use ego_tenant_mod::service::{EgoTenantService, NEXT_CHECK_DURATION};
 use ego_tenant_mod::state::*;
-use ego_tenant_mod::types::{AppMainInstallRequest, AppMainReInstallRequest, AppMainUpgradeRequest, DataExport, task};
+use ego_tenant_mod::types::{AppMainInstallRequest, AppMainReInstallRequest, AppMainUpgradeRequest, CanisterSettingsUpdateRequest, CanisterTopUpPolicySetRequest, DataExport, task};
+use ego_tenant_mod::types::cycle_reserve::CycleReserve;
 use ego_tenant_mod::types::EgoTenantErr::CanisterNotFounded;
 use ego_tenant_mod::types::stable_state::StableState;
-use ego_tenant_mod::types::task::{MAX_TRY_COUNT, Task};
+use ego_tenant_mod::types::status_history::{StatusHistory, StatusRecord};
+use ego_tenant_mod::types::task::{Task, TopUpPolicy};
+use ego_tenant_mod::types::top_up_log::{TOP_UP_LOG_DURATION, TopUpLog};
 use ego_types::app::EgoError;
 use ego_utils::util::time;
 
@@ -27,6 +30,7 @@ inject_cycle_info_api!();
 inject_backup_api!();
 
 pub const CHECK_DURATION: u64 = 600; // 每 10 分钟，检查有没有需要检查的Canister
+pub const TASK_RUN_BATCH: usize = 100; // 每次最多检查的Canister数量
 
 
 #[init]
@@ -89,15 +93,11 @@ async fn app_main_upgrade(req: AppMainUpgradeRequest) -> Result<bool, EgoError>
   let management = IcManagement::new();
   let ego_file = EgoFile::new();
 
-  let ego_canister = EgoCanister::new();
-
   let ret = EgoTenantService::app_main_upgrade(
     ego_file,
     management,
-    ego_canister,
     req.canister_id,
     req.wasm,
-    id(),
   )
     .await?;
   Ok(ret)
@@ -144,6 +144,68 @@ fn canister_main_track(canister_id: Principal) -> Result<(), EgoError> {
   Ok(())
 }
 
+#[update(name = "canister_top_up_policy_set", guard = "user_guard")]
+#[candid_method(update, rename = "canister_top_up_policy_set")]
+fn canister_top_up_policy_set(req: CanisterTopUpPolicySetRequest) -> Result<(), EgoError> {
+  info_log_add(format!("canister_top_up_policy_set, canister_id: {}", req.canister_id).as_str());
+
+  EgoTenantService::canister_top_up_policy_set(&req.canister_id, req.policy)?;
+  Ok(())
+}
+
+#[update(name = "canister_top_up_policy_get", guard = "user_guard")]
+#[candid_method(update, rename = "canister_top_up_policy_get")]
+fn canister_top_up_policy_get(canister_id: Principal) -> Result<Option<TopUpPolicy>, EgoError> {
+  info_log_add(format!("canister_top_up_policy_get, canister_id: {}", canister_id).as_str());
+
+  EgoTenantService::canister_top_up_policy_get(&canister_id)
+}
+
+#[update(name = "top_up_list", guard = "user_guard")]
+#[candid_method(update, rename = "top_up_list")]
+fn top_up_list(charge_ids: Vec<u64>) -> Result<Vec<u64>, EgoError> {
+  info_log_add(format!("top_up_list, {} charges", charge_ids.len()).as_str());
+
+  Ok(EgoTenantService::top_up_list(&charge_ids))
+}
+
+// 以下操作需要 ego_tenant 仍是 canister 的 controller, app_main_install 安装时已将 ego_tenant 设为 controller
+#[update(name = "canister_main_status", guard = "user_guard")]
+#[candid_method(update, rename = "canister_main_status")]
+async fn canister_main_status(canister_id: Principal) -> Result<StatusRecord, EgoError> {
+  info_log_add(format!("canister_main_status, canister_id: {}", canister_id).as_str());
+
+  let management = IcManagement::new();
+  EgoTenantService::canister_main_status(management, &canister_id).await
+}
+
+#[update(name = "canister_main_start", guard = "user_guard")]
+#[candid_method(update, rename = "canister_main_start")]
+async fn canister_main_start(canister_id: Principal) -> Result<(), EgoError> {
+  info_log_add(format!("canister_main_start, canister_id: {}", canister_id).as_str());
+
+  let management = IcManagement::new();
+  EgoTenantService::canister_main_start(management, &canister_id, time()).await
+}
+
+#[update(name = "canister_main_stop", guard = "user_guard")]
+#[candid_method(update, rename = "canister_main_stop")]
+async fn canister_main_stop(canister_id: Principal) -> Result<(), EgoError> {
+  info_log_add(format!("canister_main_stop, canister_id: {}", canister_id).as_str());
+
+  let management = IcManagement::new();
+  EgoTenantService::canister_main_stop(management, &canister_id).await
+}
+
+#[update(name = "canister_settings_update", guard = "user_guard")]
+#[candid_method(update, rename = "canister_settings_update")]
+async fn canister_settings_update(req: CanisterSettingsUpdateRequest) -> Result<(), EgoError> {
+  info_log_add(format!("canister_settings_update, canister_id: {}", req.canister_id).as_str());
+
+  let management = IcManagement::new();
+  EgoTenantService::canister_settings_update(management, &req.canister_id, req.settings).await
+}
+
 #[update(name = "canister_main_untrack", guard = "user_guard")]
 #[candid_method(update, rename = "canister_main_untrack")]
 fn canister_main_untrack(canister_id: Principal) -> Result<(), EgoError> {
@@ -212,7 +274,7 @@ async fn wallet_cycle_recharge(cycles: u128) -> Result<(), EgoError> {
   let ego_store = EgoStore::new(ego_store_id);
 
   info_log_add("1. get task by canister_id");
-  let task = match Task::get(&canister_id) {
+  let mut task = match Task::get(&canister_id) {
     None => {
       info_log_add("ego_tenant error, can not find task");
       Err(EgoError::from(CanisterNotFounded))
@@ -220,7 +282,7 @@ async fn wallet_cycle_recharge(cycles: u128) -> Result<(), EgoError> {
     Some(task) => Ok(task.clone()),
   }?;
 
-  EgoTenantService::wallet_cycle_recharge(management, ego_store, &task, cycles).await?;
+  EgoTenantService::wallet_cycle_recharge(management, &ego_store, &mut task, cycles).await?;
   Ok(())
 }
 
@@ -233,20 +295,36 @@ pub fn admin_task_list(last_update: u64) -> Result<Vec<Task>, EgoError> {
   Ok(Task::by_last_update(0, Task::len() as usize, last_update))
 }
 
-#[update(name = "reset_next_check_time", guard = "owner_guard")]
-#[candid_method(update, rename = "reset_next_check_time")]
-pub fn reset_next_check_time() {
-  info_log_add("reset_next_check_time");
+#[update(name = "admin_task_dead_list", guard = "owner_guard")]
+#[candid_method(update, rename = "admin_task_dead_list")]
+pub fn admin_task_dead_list() -> Result<Vec<Task>, EgoError> {
+  info_log_add("admin_task_dead_list");
 
-  let now = time() as i64;
+  Ok(Task::dead_list())
+}
 
-  for task in Task::list(0, Task::len() as usize).iter_mut() {
-    if (now - task.next_check_time as i64).abs() > NEXT_CHECK_DURATION as i64 {
-      task.next_check_time = 0;
-      task.try_count = MAX_TRY_COUNT - 1;
-      task.save()
-    }
-  }
+#[update(name = "admin_task_status_get", guard = "owner_guard")]
+#[candid_method(update, rename = "admin_task_status_get")]
+pub fn admin_task_status_get(canister_id: Principal) -> Result<Option<StatusHistory>, EgoError> {
+  info_log_add("admin_task_status_get");
+
+  Ok(StatusHistory::get(&canister_id))
+}
+
+#[update(name = "admin_task_unresponsive_list", guard = "owner_guard")]
+#[candid_method(update, rename = "admin_task_unresponsive_list")]
+pub fn admin_task_unresponsive_list() -> Result<Vec<StatusHistory>, EgoError> {
+  info_log_add("admin_task_unresponsive_list");
+
+  Ok(StatusHistory::unresponsive())
+}
+
+#[update(name = "admin_task_requeue", guard = "owner_guard")]
+#[candid_method(update, rename = "admin_task_requeue")]
+pub fn admin_task_requeue(canister_id: Principal) -> Result<Task, EgoError> {
+  info_log_add(format!("admin_task_requeue, canister_id: {}", canister_id).as_str());
+
+  EgoTenantService::task_requeue(&canister_id, time())
 }
 
 #[update(name = "admin_task_check", guard = "owner_guard")]
@@ -262,16 +340,30 @@ pub fn admin_task_check(canister_id: Principal) {
 fn task_run() {
   info_log_add("task_run");
 
+  ic_cdk::spawn(task_run_batch());
+}
+
+async fn task_run_batch() {
   let sentinel = time(); // convert to second
-  let tasks = Task::by_sentinel(sentinel);
 
-  for mut task in tasks {
-    let ego_canister = EgoCanister::new();
-    info_log_add(format!("calling ego_cycle_check on {}", task.canister_id).as_str());
+  let ego_store_id = canister_get_one("ego_store").unwrap();
+  let ego_store = EgoStore::new(ego_store_id);
+  let ego_canister = EgoCanister::new();
+
+  EgoTenantService::tenant_metric_report(&ego_store, ic_cdk::api::canister_balance128(), sentinel);
+  TopUpLog::prune(sentinel.saturating_sub(TOP_UP_LOG_DURATION));
 
-    ego_canister.ego_cycle_check(task.canister_id);
-    task.try_count += 1;
-    task.save();
+  // the earliest due first, the rest wait for the next run. they are rescheduled before the
+  // first await, so an overlapping run does not top them up a second time
+  let tasks = EgoTenantService::task_batch_check(&ego_store, &ego_canister, sentinel, TASK_RUN_BATCH);
+
+  // canister_status as well, so a canister not answering ego_cycle_check is still watched
+  for (canister_id, unanswered) in tasks {
+    let management = IcManagement::new();
+
+    if let Err(e) = EgoTenantService::canister_status_check(management, &ego_store, &canister_id, unanswered, sentinel).await {
+      info_log_add(format!("canister_status of {} not available: {}", canister_id, e.msg).as_str());
+    }
   }
 }
 
@@ -332,9 +424,9 @@ pub async fn delegate_controller_remove(target_canister: Principal, principal: P
 
 /********************  methods for ego_cycle_threshold_get   ********************/
 pub fn cycle_threshold_get() -> u128 {
-  10_000_000_000_000
+  CycleReserve::get().threshold(time())
 }
 
 pub fn runtime_cycle_threshold_get() -> u128 {
-  10_000_000_000_000
+  CycleReserve::get().threshold(time())
 }
\ No newline at end of file
@@ -5,9 +5,11 @@ use ic_cdk::trap;
 
 use ego_utils::util::get_md5;
 
-use crate::state::{backup_info_pre_upgrade, cycle_info_pre_upgrade, info_log_add, registry_pre_upgrade, users_pre_upgrade};
+use crate::state::{backup_info_pre_upgrade, cycle_info_pre_upgrade, info_log_add, registry_pre_upgrade, reserve_pre_upgrade, users_pre_upgrade};
 use crate::types::stable_state::StableState;
+use crate::types::status_history::StatusHistory;
 use crate::types::task::Task;
+use crate::types::top_up_log::TopUpLog;
 
 pub fn job_list() -> Vec<BackupJob> {
   let mut jobs = vec![];
@@ -22,6 +24,16 @@ pub fn job_list() -> Vec<BackupJob> {
     amount: Task::len() as usize,
   });
 
+  jobs.push(BackupJob {
+    name: "status_histories".to_string(),
+    amount: StatusHistory::len() as usize,
+  });
+
+  jobs.push(BackupJob {
+    name: "top_up_logs".to_string(),
+    amount: TopUpLog::len() as usize,
+  });
+
   jobs
 }
 
@@ -35,6 +47,7 @@ pub fn record_export(name: String, start: usize, end: usize, last_update: u64) -
         registry: Some(registry_pre_upgrade()),
         cycle_info: Some(cycle_info_pre_upgrade()),
         backup_info: Some(backup_info_pre_upgrade()),
+        reserve: Some(reserve_pre_upgrade()),
       };
       let data = serde_json::to_vec(&records).unwrap();
 
@@ -44,6 +57,14 @@ pub fn record_export(name: String, start: usize, end: usize, last_update: u64) -
       let records = Task::by_last_update(start, end, last_update);
       get_json_result(&records)
     }
+    "status_histories" => {
+      let records = StatusHistory::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
+    "top_up_logs" => {
+      let records = TopUpLog::by_last_update(start, end, last_update);
+      get_json_result(&records)
+    }
     _ => trap("no job matched")
   };
 
@@ -67,6 +88,7 @@ pub fn data_backup(name: String, start: usize, end: usize) -> Option<ByteReadRes
         registry: Some(registry_pre_upgrade()),
         cycle_info: Some(cycle_info_pre_upgrade()),
         backup_info: Some(backup_info_pre_upgrade()),
+        reserve: Some(reserve_pre_upgrade()),
       };
       let data = candid::encode_one(&records).unwrap();
       (data, 1)
@@ -75,6 +97,14 @@ pub fn data_backup(name: String, start: usize, end: usize) -> Option<ByteReadRes
       let records = Task::list(start, end);
       get_bin_result(&records)
     }
+    "status_histories" => {
+      let records = StatusHistory::list(start, end);
+      get_bin_result(&records)
+    }
+    "top_up_logs" => {
+      let records = TopUpLog::list(start, end);
+      get_bin_result(&records)
+    }
     _ => trap("no job matched")
   };
 
@@ -103,6 +133,20 @@ pub fn data_restore(name: String, data: Vec<u8>) -> Result<(), String> {
         record.save();
       })
     }
+    "status_histories" => {
+      let mut records: Vec<StatusHistory> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
+    "top_up_logs" => {
+      let mut records: Vec<TopUpLog> = candid::decode_one(data.as_slice()).unwrap();
+
+      records.iter_mut().for_each(|record| {
+        record.save();
+      })
+    }
     _ => trap("no job matched")
   };
 
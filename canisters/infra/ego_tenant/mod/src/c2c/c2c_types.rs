@@ -1,6 +1,8 @@
 use candid::{CandidType, Deserialize, Principal};
 use serde::Serialize;
 
+use ego_types::app::AlertKind;
+
 // type for ego_store
 #[derive(CandidType, Deserialize, Serialize)]
 pub struct WalletCycleChargeRequest {
@@ -10,6 +12,22 @@ pub struct WalletCycleChargeRequest {
 }
 
 #[derive(CandidType, Deserialize, Serialize)]
-pub struct WalletCycleChargeResponse {
-  pub ret: bool,
+pub struct WalletCycleReserveResponse {
+  pub charge_id: u64,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct CanisterAlertAddRequest {
+  pub canister_id: Principal,
+  pub kind: AlertKind,
+  pub message: String,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct TenantMetricReportRequest {
+  pub task_count: u64,
+  pub dead_task_count: u64,
+  pub cycles: u128,
+  pub spend_per_day: Option<u128>,
+  pub reserve_threshold: Option<u128>,
 }
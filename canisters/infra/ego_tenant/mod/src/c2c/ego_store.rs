@@ -3,18 +3,27 @@ use candid::Principal;
 use ic_cdk::api;
 use ic_cdk::api::call::RejectionCode;
 
-use ego_types::app::EgoError;
+use ego_types::app::{AlertKind, EgoError};
 
-use crate::c2c::c2c_types::{WalletCycleChargeRequest, WalletCycleChargeResponse};
+use crate::c2c::c2c_types::{CanisterAlertAddRequest, TenantMetricReportRequest, WalletCycleChargeRequest, WalletCycleReserveResponse};
 
 #[async_trait]
 pub trait TEgoStore {
-  async fn wallet_cycle_charge(
+  // takes the cycles off the wallet of the canister, returns the charge id to commit or release
+  async fn wallet_cycle_reserve(
     &self,
     canister_id: Principal,
     cycle: u128,
     comment: String,
-  ) -> Result<bool, EgoError>;
+  ) -> Result<u64, EgoError>;
+
+  fn wallet_cycle_commit(&self, charge_id: u64);
+
+  fn wallet_cycle_release(&self, charge_id: u64);
+
+  fn canister_alert_add(&self, canister_id: Principal, kind: AlertKind, message: String);
+
+  fn tenant_metric_report(&self, task_count: u64, dead_task_count: u64, cycles: u128, spend_per_day: u128, reserve_threshold: u128);
 }
 
 pub struct EgoStore {
@@ -29,24 +38,24 @@ impl EgoStore {
 
 #[async_trait]
 impl TEgoStore for EgoStore {
-  async fn wallet_cycle_charge(
+  async fn wallet_cycle_reserve(
     &self,
     canister_id: Principal,
     cycle: u128,
     comment: String,
-  ) -> Result<bool, EgoError> {
+  ) -> Result<u64, EgoError> {
     let req = WalletCycleChargeRequest {
       canister_id,
       cycle,
       comment,
     };
 
-    let call_result = api::call::call(self.canister_id, "wallet_cycle_charge", (req, )).await
-      as Result<(Result<WalletCycleChargeResponse, EgoError>, ), (RejectionCode, String)>;
+    let call_result = api::call::call(self.canister_id, "wallet_cycle_reserve", (req, )).await
+      as Result<(Result<WalletCycleReserveResponse, EgoError>, ), (RejectionCode, String)>;
 
     match call_result {
       Ok(resp) => match resp.0 {
-        Ok(resp) => Ok(resp.ret),
+        Ok(resp) => Ok(resp.charge_id),
         Err(e) => Err(e),
       },
       Err((code, msg)) => {
@@ -55,4 +64,34 @@ impl TEgoStore for EgoStore {
       }
     }
   }
+
+  fn wallet_cycle_commit(&self, charge_id: u64) {
+    let _result = api::call::notify(self.canister_id, "wallet_cycle_commit", (charge_id, ));
+  }
+
+  fn wallet_cycle_release(&self, charge_id: u64) {
+    let _result = api::call::notify(self.canister_id, "wallet_cycle_release", (charge_id, ));
+  }
+
+  fn canister_alert_add(&self, canister_id: Principal, kind: AlertKind, message: String) {
+    let req = CanisterAlertAddRequest {
+      canister_id,
+      kind,
+      message,
+    };
+
+    let _result = api::call::notify(self.canister_id, "canister_alert_add", (req, ));
+  }
+
+  fn tenant_metric_report(&self, task_count: u64, dead_task_count: u64, cycles: u128, spend_per_day: u128, reserve_threshold: u128) {
+    let req = TenantMetricReportRequest {
+      task_count,
+      dead_task_count,
+      cycles,
+      spend_per_day: Some(spend_per_day),
+      reserve_threshold: Some(reserve_threshold),
+    };
+
+    let _result = api::call::notify(self.canister_id, "tenant_metric_report", (req, ));
+  }
 }
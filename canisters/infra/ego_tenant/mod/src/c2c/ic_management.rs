@@ -1,9 +1,15 @@
 use async_trait::async_trait;
-use candid::Principal;
+use candid::{Nat, Principal};
+use ic_cdk::api;
+use ic_cdk::api::management_canister::main::{CanisterSettings, CanisterStatusType};
 
-use ego_lib::ic_management::controllers_update;
+use ego_lib::ic_management::{canister_status_get, controllers_update, settings_update};
 use ego_types::app::EgoError;
-use ego_utils::ic_management::{canister_code_install, canister_code_reinstall, canister_code_upgrade, canister_cycle_top_up, canister_main_create, canister_main_delete, Cycles};
+use ego_utils::ic_management::{canister_code_install, canister_code_reinstall, canister_code_upgrade, canister_cycle_top_up, canister_main_create, canister_main_delete, canister_main_start, canister_main_stop, Cycles};
+use ego_utils::util::time;
+
+use crate::types::CanisterSettingsUpdate;
+use crate::types::status_history::{CanisterRunStatus, StatusRecord};
 
 #[async_trait]
 pub trait TIcManagement {
@@ -34,11 +40,27 @@ pub trait TIcManagement {
 
   async fn canister_main_delete(&self, canister_id: Principal) -> Result<(), EgoError>;
 
+  async fn canister_main_start(&self, canister_id: Principal) -> Result<(), EgoError>;
+
+  async fn canister_main_stop(&self, canister_id: Principal) -> Result<(), EgoError>;
+
   async fn controllers_update(
     &self,
     canister_id: Principal,
     controllers: Vec<Principal>,
   ) -> Result<(), EgoError>;
+
+  async fn canister_settings_update(
+    &self,
+    canister_id: Principal,
+    settings: CanisterSettingsUpdate,
+  ) -> Result<(), EgoError>;
+
+  // only works where the tenant is a controller of the canister
+  async fn canister_status_get(&self, canister_id: Principal) -> Result<StatusRecord, EgoError>;
+
+  // the balance of the tenant itself
+  fn canister_balance(&self) -> u128;
 }
 
 #[derive(Clone)]
@@ -91,6 +113,14 @@ impl TIcManagement for IcManagement {
     canister_main_delete(canister_id).await
   }
 
+  async fn canister_main_start(&self, canister_id: Principal) -> Result<(), EgoError> {
+    canister_main_start(canister_id).await
+  }
+
+  async fn canister_main_stop(&self, canister_id: Principal) -> Result<(), EgoError> {
+    canister_main_stop(canister_id).await
+  }
+
   async fn controllers_update(
     &self,
     canister_id: Principal,
@@ -98,4 +128,45 @@ impl TIcManagement for IcManagement {
   ) -> Result<(), EgoError> {
     controllers_update(canister_id, controllers).await
   }
+
+  async fn canister_settings_update(
+    &self,
+    canister_id: Principal,
+    settings: CanisterSettingsUpdate,
+  ) -> Result<(), EgoError> {
+    settings_update(
+      canister_id,
+      CanisterSettings {
+        controllers: None,
+        compute_allocation: settings.compute_allocation.map(Nat::from),
+        memory_allocation: settings.memory_allocation.map(Nat::from),
+        freezing_threshold: settings.freezing_threshold.map(Nat::from),
+      },
+    ).await
+  }
+
+  async fn canister_status_get(&self, canister_id: Principal) -> Result<StatusRecord, EgoError> {
+    let resp = canister_status_get(canister_id).await?;
+
+    let status = match resp.status {
+      CanisterStatusType::Running => CanisterRunStatus::RUNNING,
+      CanisterStatusType::Stopping => CanisterRunStatus::STOPPING,
+      CanisterStatusType::Stopped => CanisterRunStatus::STOPPED,
+    };
+
+    Ok(StatusRecord {
+      ts: time(),
+      status,
+      cycles: u128::try_from(resp.cycles.0).unwrap_or(u128::MAX),
+      memory_size: u64::try_from(resp.memory_size.0).unwrap_or(u64::MAX),
+      idle_burn_per_day: u128::try_from(resp.idle_cycles_burned_per_day.0).unwrap_or(u128::MAX),
+      freezing_threshold: u64::try_from(resp.settings.freezing_threshold.0).unwrap_or(u64::MAX),
+      // 0 is best effort, no reserved memory
+      memory_allocation: u64::try_from(resp.settings.memory_allocation.0).ok().filter(|allocation| *allocation > 0),
+    })
+  }
+
+  fn canister_balance(&self) -> u128 {
+    api::canister_balance128()
+  }
 }
@@ -0,0 +1,143 @@
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+
+use ego_types::cycle_info::{CycleRecord, DEFAULT_ESTIMATE};
+
+use crate::service::NEXT_CHECK_DURATION;
+
+// 10 minutes
+pub const MIN_CHECK_DURATION: u64 = 10 * 60;
+// 1 day
+pub const MAX_CHECK_DURATION: u64 = 24 * 60 * 60;
+
+// the intervals between the 12 records kept by CycleInfo
+const SAMPLE_MAX: usize = 11;
+
+// the wasm32 heap
+pub const HEAP_LIMIT: u64 = 4 * 1024 * 1024 * 1024;
+// the stable memory a canister can hold on a subnet
+pub const STABLE_LIMIT: u64 = 400 * 1024 * 1024 * 1024;
+// 30 days, alert once a memory limit is projected within it
+pub const MEMORY_ALERT_DURATION: u64 = 30 * 24 * 60 * 60;
+
+const DAY: u128 = 24 * 60 * 60;
+
+/// the burn rate of a canister estimated from its whole cycle history
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CycleForecast {
+  pub burn_per_second: u128,
+  // the intervals used, the ones with a top up are left out
+  pub samples: u8,
+  // percent, grows with the samples and drops when the intervals disagree
+  pub confidence: u8,
+}
+
+impl CycleForecast {
+  /// records come newest first, the way CycleInfo keeps them.
+  /// the burn rate of each interval is weighted by its duration and by how recent it is
+  pub fn new(records: &[CycleRecord]) -> Option<Self> {
+    let rates: Vec<(f64, f64)> = records.windows(2).enumerate().filter_map(|(index, pair)| {
+      let (newer, older) = (&pair[0], &pair[1]);
+
+      let duration = newer.ts.checked_sub(older.ts).filter(|duration| *duration > 0)?;
+      // the balance went up, a top up hides the burn of this interval
+      let burnt = older.balance.checked_sub(newer.balance)?;
+
+      let recency = (SAMPLE_MAX - index.min(SAMPLE_MAX - 1)) as f64;
+      Some((burnt as f64 / duration as f64, duration as f64 * recency))
+    }).collect();
+
+    if rates.is_empty() {
+      return None;
+    }
+
+    let weight_sum: f64 = rates.iter().map(|(_, weight)| weight).sum();
+    let mean = rates.iter().map(|(rate, weight)| rate * weight).sum::<f64>() / weight_sum;
+    let variance = rates.iter().map(|(rate, weight)| weight * (rate - mean).powi(2)).sum::<f64>() / weight_sum;
+
+    // coefficient of variation, 0 when every interval burns the same
+    let dispersion = if mean > 0.0 { variance.sqrt() / mean } else { 0.0 };
+    let coverage = rates.len().min(SAMPLE_MAX) as f64 / SAMPLE_MAX as f64;
+
+    Some(CycleForecast {
+      burn_per_second: mean.round() as u128,
+      samples: rates.len() as u8,
+      confidence: (100.0 * coverage / (1.0 + dispersion)).round() as u8,
+    })
+  }
+
+  /// seconds until the balance is used up
+  pub fn remaining(&self, balance: u128) -> u64 {
+    match self.burn_per_second {
+      0 => DEFAULT_ESTIMATE,
+      burn_per_second => (balance / burn_per_second).min(u64::MAX as u128) as u64,
+    }
+  }
+
+  /// halfway to the moment the balance falls under the threshold, sooner when the forecast is unsure
+  pub fn next_check_duration(&self, balance: u128, threshold: u128) -> u64 {
+    if self.burn_per_second == 0 {
+      return NEXT_CHECK_DURATION;
+    }
+
+    let until_threshold = (balance.saturating_sub(threshold) / self.burn_per_second).min(u64::MAX as u128) as u64;
+    let duration = until_threshold / 2 / 100 * self.confidence as u64;
+
+    duration.clamp(MIN_CHECK_DURATION, MAX_CHECK_DURATION)
+  }
+}
+
+/// the memory growth of a canister, from the records carrying memory samples
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MemoryForecast {
+  // bytes
+  pub heap_size: u64,
+  pub stable_size: u64,
+  // bytes per day, 0 when not growing
+  pub heap_growth_per_day: u64,
+  pub stable_growth_per_day: u64,
+}
+
+impl MemoryForecast {
+  /// records come newest first. the growth is taken between the newest and the oldest sample
+  pub fn new(records: &[CycleRecord]) -> Option<Self> {
+    let samples: Vec<(u64, u64, u64)> = records.iter()
+      .filter_map(|record| Some((record.ts, record.heap_size?, record.stable_size?)))
+      .collect();
+
+    let (newest_ts, heap_size, stable_size) = *samples.first()?;
+    let (oldest_ts, oldest_heap_size, oldest_stable_size) = *samples.last()?;
+
+    let growth_per_day = |size: u64, oldest_size: u64| match newest_ts.checked_sub(oldest_ts) {
+      Some(duration) if duration > 0 => {
+        (size.saturating_sub(oldest_size) as u128 * DAY / duration as u128).min(u64::MAX as u128) as u64
+      }
+      _ => 0,
+    };
+
+    Some(MemoryForecast {
+      heap_size,
+      stable_size,
+      heap_growth_per_day: growth_per_day(heap_size, oldest_heap_size),
+      stable_growth_per_day: growth_per_day(stable_size, oldest_stable_size),
+    })
+  }
+
+  /// seconds until the first limit is reached, by the heap, the stable memory or the memory allocation of the canister.
+  /// None when nothing grows toward a limit
+  pub fn remaining(&self, memory_allocation: Option<u64>) -> Option<u64> {
+    let until = |size: u64, limit: u64, growth_per_day: u64| match (limit.saturating_sub(size), growth_per_day) {
+      (0, _) => Some(0),
+      (_, 0) => None,
+      (left, growth_per_day) => Some((left as u128 * DAY / growth_per_day as u128).min(u64::MAX as u128) as u64),
+    };
+
+    [
+      until(self.heap_size, HEAP_LIMIT, self.heap_growth_per_day),
+      until(self.stable_size, STABLE_LIMIT, self.stable_growth_per_day),
+      memory_allocation.and_then(|allocation| {
+        until(self.heap_size + self.stable_size, allocation, self.heap_growth_per_day + self.stable_growth_per_day)
+      }),
+    ].into_iter().flatten().min()
+  }
+}
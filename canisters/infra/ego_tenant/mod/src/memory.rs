@@ -2,10 +2,19 @@ use std::cell::RefCell;
 
 use ic_stable_structures::{DefaultMemoryImpl, memory_manager::{MemoryId, MemoryManager, VirtualMemory}, RestrictedMemory, StableBTreeMap, StableCell, storable::Blob};
 
+use crate::types::schedule_key::{ScheduleKey, TopUpLogKey};
 use crate::types::stable_state::StableState;
-use crate::types::task::Task;
+use crate::types::status_history::StatusHistory;
+use crate::types::task::{LEGACY_TASK_SIZE, Task};
+use crate::types::top_up_log::TopUpLog;
 
-const TASK_MEM_ID: MemoryId = MemoryId::new(0);
+const TASK_LEGACY_MEM_ID: MemoryId = MemoryId::new(0);
+const TASK_SCHEDULE_MEM_ID: MemoryId = MemoryId::new(1);
+const STATUS_HISTORY_MEM_ID: MemoryId = MemoryId::new(2);
+const TOP_UP_LOG_MEM_ID: MemoryId = MemoryId::new(3);
+const TASK_MEM_ID: MemoryId = MemoryId::new(4);
+const TASK_DEAD_INDEX_MEM_ID: MemoryId = MemoryId::new(5);
+const TOP_UP_LOG_INDEX_MEM_ID: MemoryId = MemoryId::new(6);
 const METADATA_PAGES: u64 = 64;
 // 4M
 const WASM_PAGE_SIZE: u64 = 65536;
@@ -24,7 +33,36 @@ thread_local! {
         MemoryManager::init(RM::new(DefaultMemoryImpl::default(), METADATA_PAGES..MAX_PAGES))
     );
 
+    // the tasks saved under the former bound, moved to TASKS on upgrade
+    pub static TASKS_LEGACY: RefCell<StableBTreeMap<Blob<29>, Blob<LEGACY_TASK_SIZE>, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(TASK_LEGACY_MEM_ID)))
+    });
+
     pub static TASKS: RefCell<StableBTreeMap<Blob<29>, Task, VM>> = MEMORY_MANAGER.with(|mm| {
         RefCell::new(StableBTreeMap::init(mm.borrow().get(TASK_MEM_ID)))
     });
+
+    // next_check_time + canister_id => task last_update, the dead tasks are left out
+    pub static TASK_SCHEDULE: RefCell<StableBTreeMap<ScheduleKey, u64, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(TASK_SCHEDULE_MEM_ID)))
+    });
+
+    // canister_id => dead_at, the dead tasks only
+    pub static TASK_DEAD_INDEX: RefCell<StableBTreeMap<Blob<29>, u64, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(TASK_DEAD_INDEX_MEM_ID)))
+    });
+
+    pub static STATUS_HISTORIES: RefCell<StableBTreeMap<Blob<29>, StatusHistory, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(STATUS_HISTORY_MEM_ID)))
+    });
+
+    // charge id => top up
+    pub static TOP_UP_LOGS: RefCell<StableBTreeMap<u64, TopUpLog, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(TOP_UP_LOG_MEM_ID)))
+    });
+
+    // created_at + charge id => charge id
+    pub static TOP_UP_LOG_INDEX: RefCell<StableBTreeMap<TopUpLogKey, u64, VM>> = MEMORY_MANAGER.with(|mm| {
+        RefCell::new(StableBTreeMap::init(mm.borrow().get(TOP_UP_LOG_INDEX_MEM_ID)))
+    });
 }
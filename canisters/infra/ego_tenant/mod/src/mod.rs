@@ -1,5 +1,6 @@
 pub mod c2c;
 pub mod service;
+pub mod forecast;
 pub mod state;
 pub mod memory;
 pub mod types;
@@ -1,26 +1,30 @@
-use std::ops::{Div, Mul};
-
 use candid::Principal;
 use ic_cdk::trap;
 
 use ego_lib::ego_canister::TEgoCanister;
-use ego_types::app::{CanisterType, Wasm};
+use ego_types::app::{AlertKind, CanisterType, Wasm};
 use ego_types::app::EgoError;
 use ego_types::cycle_info::{CycleRecord, DEFAULT_ESTIMATE};
+use ego_utils::util::time;
 
 use crate::c2c::ego_file::TEgoFile;
 use crate::c2c::ego_store::TEgoStore;
 use crate::c2c::ic_management::TIcManagement;
+use crate::forecast::{CycleForecast, MEMORY_ALERT_DURATION, MemoryForecast};
 use crate::state::{canister_get_one, error_log_add, info_log_add};
-use crate::types::EgoTenantErr;
-use crate::types::EgoTenantErr::CycleNotEnough;
-use crate::types::task::Task;
+use crate::types::{CanisterSettingsUpdate, EgoTenantErr};
+use crate::types::cycle_reserve::CycleReserve;
+use crate::types::status_history::{StatusHistory, StatusRecord};
+use crate::types::task::{MAX_TRY_COUNT, Task, TopUpPolicy};
+use crate::types::top_up_log::TopUpLog;
 
 pub struct EgoTenantService {}
 
 pub const NEXT_CHECK_DURATION: u64 = 60 * 60;
 // 1 hour
 pub const CREATE_CANISTER_CYCLES_FEE: u128 = 200_000_000_000;
+// the reject code of canister_status called by a principal that is not a controller
+pub const STATUS_REFUSED_CODE: u16 = 5;
 
 impl EgoTenantService {
   pub fn canister_main_track(
@@ -33,6 +37,7 @@ impl EgoTenantService {
 
   pub fn canister_main_untrack(canister_id: &Principal) {
     Task::remove(canister_id);
+    StatusHistory::remove(canister_id);
   }
 
   pub async fn app_main_install<F: TEgoFile, M: TIcManagement, EC: TEgoCanister>(
@@ -51,6 +56,9 @@ impl EgoTenantService {
       return Err(EgoTenantErr::SystemError("not implemented".to_string()).into());
     }
 
+    // the tenant pays the creation, refused before its balance runs under the reserve floor
+    CycleReserve::check(management.canister_balance(), CREATE_CANISTER_CYCLES_FEE)?;
+
     info_log_add(format!("1 load wasm data from ego_file:{}，fid:{}", wasm.canister_id, wasm.fid()).as_str());
     let data = ego_file
       .file_main_read(wasm.canister_id, wasm.fid())
@@ -60,6 +68,7 @@ impl EgoTenantService {
     let canister_id = management
       .canister_main_create(CREATE_CANISTER_CYCLES_FEE)
       .await?;
+    CycleReserve::spend(CREATE_CANISTER_CYCLES_FEE, time());
     info_log_add(format!("2 create canister {}", canister_id).as_str());
 
     info_log_add("3 install code");
@@ -89,27 +98,29 @@ impl EgoTenantService {
     );
     ego_canister.ego_owner_set(canister_id, vec![wallet_id, user_id, canister_id]);
 
+    // the tenant stays a controller. the wallet scoped status, start, stop and settings calls of
+    // ego_store are carried out by it, and it polls canister_status of the canisters it tracks.
+    // the wallet may still remove it, those calls are then refused and the polling stops
     info_log_add(
       format!(
-        "7 set canister controller to [wallet: {}, user: {}, self: {}]",
-        wallet_id, user_id, canister_id
+        "7 set canister controller to [wallet: {}, user: {}, self: {}, tenant: {}]",
+        wallet_id, user_id, canister_id, ego_tenant_id
       )
         .as_str(),
     );
     management
-      .controllers_update(canister_id, vec![wallet_id, user_id, canister_id])
+      .controllers_update(canister_id, vec![wallet_id, user_id, canister_id, ego_tenant_id])
       .await?;
 
     Ok(canister_id)
   }
 
-  pub async fn app_main_upgrade<F: TEgoFile, M: TIcManagement, EC: TEgoCanister>(
+  /// the tenant stays a controller after the upgrade, see app_main_install
+  pub async fn app_main_upgrade<F: TEgoFile, M: TIcManagement>(
     ego_file: F,
     management: M,
-    ego_canister: EC,
     canister_id: Principal,
     wasm: Wasm,
-    tenant_id: Principal,
   ) -> Result<bool, EgoError> {
     // TODO: checked whether user has add tenant as one of the canister's controller
 
@@ -125,9 +136,6 @@ impl EgoTenantService {
     info_log_add("2 install code");
     management.canister_code_upgrade(canister_id, data).await?;
 
-    info_log_add("3 remove [ego_tenant] from canister controller");
-    ego_canister.ego_controller_remove(canister_id, tenant_id);
-
     Ok(true)
   }
 
@@ -185,9 +193,6 @@ impl EgoTenantService {
     info_log_add("6 set canister owner to backup owners");
     ego_canister.ego_owner_set(canister_id, owners);
 
-    info_log_add("7 remove [ego_tenant] from canister controller");
-    ego_canister.ego_controller_remove(canister_id, ego_tenant_id);
-
     Ok(true)
   }
 
@@ -207,25 +212,26 @@ impl EgoTenantService {
     records: &Vec<CycleRecord>,
     threshold: u128,
   ) -> Result<(), EgoError> {
-    let mut current_cycle = records[0].balance;
+    let current_cycle = records[0].balance;
     let current_ts: u64 = records[0].ts; // second
 
+    let forecast = CycleForecast::new(records);
+
     task.last_cycle = Some(current_cycle);
     task.try_count = 0;
+    // answered, so alive again even if dead lettered
+    task.dead_at = None;
+    task.last_check_time = Some(current_ts);
+    task.last_threshold = Some(threshold);
     task.next_check_time = current_ts + NEXT_CHECK_DURATION;
+    task.forecast = forecast.clone();
     task.save();
 
-    let mut last_cycle = 0;
-    let mut last_ts: u64 = 0;
-
-    if records.len() > 1 {
-      last_cycle = records[1].balance;
-      last_ts = records[1].ts; // second
+    if let Some(mut history) = StatusHistory::get(canister_id).filter(|history| history.unresponsive_since.is_some()) {
+      history.unresponsive_since = None;
+      history.save();
     }
 
-    let delta_cycle: i128 = last_cycle as i128 - current_cycle as i128;
-    let delta_time = current_ts - last_ts; // in second
-
     let mut estimate_duration = DEFAULT_ESTIMATE;
 
     info_log_add(
@@ -236,71 +242,170 @@ impl EgoTenantService {
         .as_str(),
     );
 
-    if current_cycle < threshold {
-      let cycle_required_to_top_up = threshold.mul(15).div(10) - current_cycle;
+    let (min_balance, _) = task.top_up_range(threshold);
+    let current_cycle = EgoTenantService::cycle_top_up(management, &ego_store, task, current_cycle, threshold).await;
 
-      info_log_add(
-        format!(
-          "1.1. cycle_required_to_top_up: {}",
-          cycle_required_to_top_up
-        )
-          .as_str(),
-      );
-      match EgoTenantService::wallet_cycle_recharge(
+    match &forecast {
+      None => {
+        // for the first time checking, or only top ups in the history
+        info_log_add("2. no cycle consumption to forecast. use default estimation");
+      }
+      Some(forecast) => {
+        info_log_add(
+          format!(
+            "2. burn_per_second: {}, samples: {}, confidence: {}",
+            forecast.burn_per_second, forecast.samples, forecast.confidence
+          )
+            .as_str(),
+        );
+
+        estimate_duration = forecast.remaining(current_cycle);
+
+        let next_check_duration = forecast.next_check_duration(current_cycle, min_balance);
+        info_log_add(format!("3. next_check_duration: {}", next_check_duration).as_str());
+        task.next_check_time = current_ts + next_check_duration;
+        task.save();
+      }
+    }
+
+    info_log_add(format!("4. estimate_duration: {}", estimate_duration).as_str());
+    ego_canister.ego_cycle_estimate_set(*canister_id, estimate_duration);
+
+    // the canisters built before memory was sampled send none
+    if let Some(memory) = MemoryForecast::new(records) {
+      // known only where the tenant polls canister_status
+      let memory_allocation = StatusHistory::get(canister_id)
+        .and_then(|history| history.latest().and_then(|record| record.memory_allocation));
+      let remaining = memory.remaining(memory_allocation);
+      info_log_add(format!("5. heap_size: {}, stable_size: {}, memory limit in: {:?}", memory.heap_size, memory.stable_size, remaining).as_str());
+
+      if let Some(remaining) = remaining.filter(|remaining| *remaining <= MEMORY_ALERT_DURATION) {
+        ego_store.canister_alert_add(
+          *canister_id,
+          AlertKind::MEMORY_LIMIT,
+          format!("heap {} bytes, stable {} bytes, a memory limit reached in {} seconds", memory.heap_size, memory.stable_size, remaining),
+        );
+      }
+    }
+
+    Ok(())
+  }
+
+  /// tops up the canister under the min balance, alerts when it can not. returns the balance after
+  async fn cycle_top_up<M: TIcManagement, S: TEgoStore>(
+    management: M,
+    ego_store: &S,
+    task: &mut Task,
+    current_cycle: u128,
+    threshold: u128,
+  ) -> u128 {
+    let (min_balance, target_balance) = task.top_up_range(threshold);
+
+    // a target under the reported threshold lowers the point of topping up too
+    if current_cycle >= min_balance.min(target_balance) {
+      info_log_add("1.2. cycle enough");
+      return current_cycle;
+    }
+
+    let cycle_required_to_top_up = target_balance - current_cycle;
+    info_log_add(
+      format!(
+        "1.1. cycle_required_to_top_up: {}",
+        cycle_required_to_top_up
+      )
+        .as_str(),
+    );
+
+    // the policy holding the top up back is not a failure of the wallet
+    let result = match task.top_up_allowance(cycle_required_to_top_up, time()) {
+      Err(e) => Err((AlertKind::LOW_BALANCE, e)),
+      Ok(_) => EgoTenantService::wallet_cycle_recharge(
         management,
         ego_store,
         task,
         cycle_required_to_top_up,
       )
-        .await{
-        Ok(_) => {
-          current_cycle = threshold.mul(15).div(10);
-        }
-        Err(_) => {
+        .await
+        .map_err(|e| (AlertKind::TOP_UP_FAILED, e)),
+    };
 
-        }
+    match result {
+      Ok(cycles) => current_cycle + cycles,
+      Err((kind, e)) => {
+        error_log_add(format!("1.1.1. canister {} under min balance {}, not topped up: {}", task.canister_id, min_balance, e.msg).as_str());
+        ego_store.canister_alert_add(
+          task.canister_id,
+          kind,
+          format!("balance {} under min balance {}: {}", current_cycle, min_balance, e.msg),
+        );
+        current_cycle
       }
-    } else {
-      info_log_add("1.2. cycle enough");
     }
+  }
 
-    info_log_add(format!("2. delta cycle {}, delta time {}", delta_cycle, delta_time).as_str());
-
-    if last_cycle == 0 {
-      // for the first time checking, we will check it again after 30 minutes
-      info_log_add("2.1 last cycle is 0. skip estimation");
-    } else if delta_time == 0 {
-      info_log_add("2.2 delta_time is zero. skip estimation");
-    } else if delta_cycle < 0 {
-      info_log_add("2.3. more cycle then before. use default estimation");
-    } else {
-      let cycle_consume_per_second = (delta_cycle / (delta_time as i128)) as u128;
-      info_log_add(
-        format!("3. cycle_consume_per_second: {}", cycle_consume_per_second).as_str(),
-      );
-
-      if cycle_consume_per_second == 0 {
-        info_log_add("3.1. cycle_consume_per_second is zero. use default estimation");
-      } else {
-        // the remain cycles can be used in estimate_duration second
-        estimate_duration = (current_cycle / cycle_consume_per_second) as u64;
+  /// polls canister_status, which needs no cooperation of the canister but the tenant being its controller.
+  /// once a cycle check is left unanswered, the canister is flagged and topped up by the status instead
+  /// unanswered tells whether the cycle checks sent before this run went unanswered
+  pub async fn canister_status_check<M: TIcManagement, S: TEgoStore>(
+    management: M,
+    ego_store: &S,
+    canister_id: &Principal,
+    unanswered: bool,
+    now: u64,
+  ) -> Result<StatusRecord, EgoError> {
+    let record = match management.canister_status_get(*canister_id).await {
+      Ok(record) => record,
+      Err(e) => {
+        // only controllers read the status, the canister is not polled again until it is requeued
+        if e.code == STATUS_REFUSED_CODE {
+          if let Some(mut task) = Task::get(canister_id) {
+            task.controller = Some(false);
+            task.save();
+          }
+        }
+        return Err(e);
       }
+    };
+
+    // the cycle check may be answered while polling
+    let mut task = Task::get(canister_id).ok_or(EgoError::from(EgoTenantErr::CanisterNotFounded))?;
+    if task.controller != Some(true) {
+      task.controller = Some(true);
+      task.save();
     }
 
-    info_log_add(format!("4. estimate_duration: {}", estimate_duration).as_str());
-    ego_canister.ego_cycle_estimate_set(*canister_id, estimate_duration);
+    let mut history = StatusHistory::get(canister_id).unwrap_or_else(|| StatusHistory::new(canister_id));
+    history.record_add(record.clone());
+    let unresponsive = unanswered && task.try_count > 0;
+    if unresponsive && history.unresponsive_since.is_none() {
+      info_log_add(format!("canister {} stopped answering ego_cycle_check", canister_id).as_str());
+      history.unresponsive_since = Some(now);
+    }
+    history.save();
 
-    Ok(())
+    let threshold = task.top_up_policy.as_ref().and_then(|policy| policy.min_balance).or(task.last_threshold);
+    if let (true, Some(threshold)) = (unresponsive, threshold) {
+      task.last_cycle = Some(record.cycles);
+      task.save();
+      EgoTenantService::cycle_top_up(management, ego_store, &mut task, record.cycles, threshold).await;
+    }
+
+    Ok(record)
   }
 
+  /// tops up within the policy of the task, returns the cycles actually topped up
   pub async fn wallet_cycle_recharge<M: TIcManagement, S: TEgoStore>(
     management: M,
-    ego_store: S,
-    task: &Task,
+    ego_store: &S,
+    task: &mut Task,
     cycles: u128,
-  ) -> Result<(), EgoError> {
-    let charge_ret = ego_store
-      .wallet_cycle_charge(
+  ) -> Result<u128, EgoError> {
+    let cycles = task.top_up_allowance(cycles, time())?;
+    // the wallet is charged, but the top up comes out of the balance of the tenant
+    CycleReserve::check(management.canister_balance(), cycles)?;
+
+    let charge_id = ego_store
+      .wallet_cycle_reserve(
         task.canister_id,
         cycles,
         format!(
@@ -310,13 +415,160 @@ impl EgoTenantService {
       )
       .await?;
 
-    if charge_ret {
-      management
-        .canister_cycle_top_up(task.canister_id, cycles)
-        .await?;
-      Ok(())
-    } else {
-      Err(CycleNotEnough.into())
+    // the wallet pays only for a top up gone through, ego_store reconciles the charges left unsettled against the log
+    match management.canister_cycle_top_up(task.canister_id, cycles).await {
+      Ok(_) => {
+        TopUpLog::new(charge_id, &task.canister_id, cycles).save();
+        ego_store.wallet_cycle_commit(charge_id);
+        CycleReserve::spend(cycles, time());
+
+        task.top_up_record(cycles, time());
+        task.save();
+        Ok(cycles)
+      }
+      Err(e) => {
+        ego_store.wallet_cycle_release(charge_id);
+        Err(e)
+      }
     }
   }
+
+  /// sends the cycle checks of the earliest due tasks and reschedules them before anything is
+  /// awaited, so an overlapping run does not pick them up again. returns the canisters to poll
+  /// the status of, the ones the tenant is a controller of, along with whether their previous
+  /// checks went unanswered
+  pub fn task_batch_check<S: TEgoStore, EC: TEgoCanister>(ego_store: &S, ego_canister: &EC, sentinel: u64, limit: usize) -> Vec<(Principal, bool)> {
+    Task::by_sentinel(sentinel, limit).into_iter().filter_map(|mut task| {
+      let unanswered = task.try_count > 0;
+      EgoTenantService::task_check(ego_store, ego_canister, &mut task, sentinel);
+      match task.controller {
+        Some(false) => None,
+        _ => Some((task.canister_id, unanswered)),
+      }
+    }).collect()
+  }
+
+  /// sends a cycle check to a due task, moves it to the dead letters once the retries are used up
+  pub fn task_check<S: TEgoStore, EC: TEgoCanister>(ego_store: &S, ego_canister: &EC, task: &mut Task, now: u64) {
+    if task.try_count < MAX_TRY_COUNT {
+      info_log_add(format!("calling ego_cycle_check on {}", task.canister_id).as_str());
+      ego_canister.ego_cycle_check(task.canister_id);
+
+      task.check_sent(now);
+      task.save();
+      return;
+    }
+
+    // the status tells for sure, the forecast guesses without it
+    let frozen = match StatusHistory::get(&task.canister_id).as_ref().and_then(|history| history.latest()) {
+      Some(record) => record.is_frozen(),
+      None => task.frozen_expected(now),
+    };
+    let (kind, message) = match frozen {
+      true => (
+        AlertKind::CANISTER_FROZEN,
+        format!("no answer to {} cycle checks, the last balance {:?} should be burnt out", task.try_count, task.last_cycle),
+      ),
+      false => (
+        AlertKind::TASK_RETRIES_EXHAUSTED,
+        format!("no answer to {} cycle checks, stop checking", task.try_count),
+      ),
+    };
+    error_log_add(format!("canister {}: {}", task.canister_id, message).as_str());
+    ego_store.canister_alert_add(task.canister_id, kind, message);
+
+    task.dead_letter(now);
+    task.save();
+  }
+
+  pub fn task_requeue(canister_id: &Principal, now: u64) -> Result<Task, EgoError> {
+    let mut task = Task::get(canister_id).ok_or(EgoError::from(EgoTenantErr::CanisterNotFounded))?;
+    task.requeue(now);
+    task.save();
+    Ok(task)
+  }
+
+  /// None restores the default, topping up to 1.5 times the threshold reported by the canister
+  pub fn canister_top_up_policy_set(canister_id: &Principal, policy: Option<TopUpPolicy>) -> Result<Task, EgoError> {
+    let mut task = Task::get(canister_id).ok_or(EgoError::from(EgoTenantErr::CanisterNotFounded))?;
+    if let Some(policy) = &policy {
+      policy.check()?;
+    }
+
+    task.top_up_policy = policy;
+    task.save();
+    Ok(task)
+  }
+
+  /// the load and health ego_store places wallets by, and the spending it refills the tenant by
+  pub fn tenant_metric_report<S: TEgoStore>(ego_store: &S, cycles: u128, now: u64) {
+    let reserve = CycleReserve::get();
+    ego_store.tenant_metric_report(
+      Task::len(),
+      Task::dead_len(),
+      cycles,
+      reserve.spend_per_day(now),
+      reserve.threshold(now),
+    );
+  }
+
+  /// the charges of the ids a top up is logged for
+  pub fn top_up_list(charge_ids: &[u64]) -> Vec<u64> {
+    charge_ids.iter().filter(|charge_id| TopUpLog::get(**charge_id).is_some()).copied().collect()
+  }
+
+  pub fn canister_top_up_policy_get(canister_id: &Principal) -> Result<Option<TopUpPolicy>, EgoError> {
+    let task = Task::get(canister_id).ok_or(EgoError::from(EgoTenantErr::CanisterNotFounded))?;
+    Ok(task.top_up_policy)
+  }
+
+  /// reads canister_status for the wallet, kept in the history when the canister is tracked
+  pub async fn canister_main_status<M: TIcManagement>(
+    management: M,
+    canister_id: &Principal,
+  ) -> Result<StatusRecord, EgoError> {
+    let record = management.canister_status_get(*canister_id).await?;
+
+    if Task::get(canister_id).is_some() {
+      let mut history = StatusHistory::get(canister_id).unwrap_or_else(|| StatusHistory::new(canister_id));
+      history.record_add(record.clone());
+      history.save();
+    }
+
+    Ok(record)
+  }
+
+  /// a started canister answers cycle checks again, so its dead lettered task is requeued
+  pub async fn canister_main_start<M: TIcManagement>(
+    management: M,
+    canister_id: &Principal,
+    now: u64,
+  ) -> Result<(), EgoError> {
+    management.canister_main_start(*canister_id).await?;
+
+    if let Some(mut task) = Task::get(canister_id).filter(|task| task.dead_at.is_some()) {
+      info_log_add(format!("canister {} started, requeue its task", canister_id).as_str());
+      task.requeue(now);
+      task.save();
+    }
+
+    Ok(())
+  }
+
+  pub async fn canister_main_stop<M: TIcManagement>(
+    management: M,
+    canister_id: &Principal,
+  ) -> Result<(), EgoError> {
+    management.canister_main_stop(*canister_id).await
+  }
+
+  pub async fn canister_settings_update<M: TIcManagement>(
+    management: M,
+    canister_id: &Principal,
+    settings: CanisterSettingsUpdate,
+  ) -> Result<(), EgoError> {
+    settings.check()?;
+
+    management.canister_settings_update(*canister_id, settings).await
+  }
 }
@@ -5,12 +5,27 @@ use ego_backup::inject_backup_data;
 use ego_macros::{inject_cycle_info, inject_ego_data};
 
 use crate::memory::CONFIG;
+use crate::types::cycle_reserve::CycleReserve;
 use crate::types::stable_state::StableState;
+use crate::types::task::Task;
+use crate::types::top_up_log::TopUpLog;
 
 inject_ego_data!();
 inject_cycle_info!();
 inject_backup_data!();
 
+thread_local! {
+  pub static RESERVE: RefCell<CycleReserve> = RefCell::new(CycleReserve::default());
+}
+
+pub fn reserve_pre_upgrade() -> CycleReserve {
+  RESERVE.with(|reserve| reserve.borrow().clone())
+}
+
+pub fn reserve_post_upgrade(stable_reserve: CycleReserve) {
+  RESERVE.with(|reserve| *reserve.borrow_mut() = stable_reserve);
+}
+
 /********************  methods for ego_registry   ********************/
 fn on_canister_added(name: &str, canister_id: Principal) {
   info_log_add(&format!(
@@ -39,4 +54,8 @@ pub fn post_upgrade() {
 
     StableState::restore(state.to_owned());
   });
+
+  Task::migrate();
+  Task::index_rebuild();
+  TopUpLog::index_rebuild();
 }
@@ -0,0 +1,66 @@
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+
+use crate::state::RESERVE;
+use crate::types::EgoTenantErr;
+
+// the balance the tenant never spends on canisters
+pub const RESERVE_FLOOR: u128 = 1_000_000_000_000;
+// the days of spending kept on top of the floor
+pub const RESERVE_DAYS: u128 = 7;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// what the tenant spent of its own balance on creating and topping up canisters, by day
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct CycleReserve {
+  // days since the epoch
+  pub day: u64,
+  pub spent_today: u128,
+  pub spent_last_day: u128,
+}
+
+impl CycleReserve {
+  pub fn get() -> Self {
+    RESERVE.with(|reserve| reserve.borrow().clone())
+  }
+
+  pub fn spend(cycles: u128, now: u64) {
+    RESERVE.with(|reserve| {
+      let mut reserve = reserve.borrow_mut();
+      reserve.roll(now);
+      reserve.spent_today += cycles;
+    });
+  }
+
+  /// the busier of today and the last day
+  pub fn spend_per_day(&self, now: u64) -> u128 {
+    let mut reserve = self.clone();
+    reserve.roll(now);
+    reserve.spent_today.max(reserve.spent_last_day)
+  }
+
+  /// the balance the tenant wants to hold, ego_store refills it once under
+  pub fn threshold(&self, now: u64) -> u128 {
+    RESERVE_FLOOR * 2 + self.spend_per_day(now) * RESERVE_DAYS
+  }
+
+  /// refuses spending which would take the balance under the floor
+  pub fn check(balance: u128, cycles: u128) -> Result<(), EgoTenantErr> {
+    match balance.checked_sub(cycles) {
+      Some(left) if left >= RESERVE_FLOOR => Ok(()),
+      _ => Err(EgoTenantErr::ReserveNotEnough),
+    }
+  }
+
+  fn roll(&mut self, now: u64) {
+    let day = now / DAY;
+    if day == self.day {
+      return;
+    }
+
+    self.spent_last_day = if day == self.day + 1 { self.spent_today } else { 0 };
+    self.spent_today = 0;
+    self.day = day;
+  }
+}
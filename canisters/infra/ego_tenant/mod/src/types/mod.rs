@@ -7,7 +7,11 @@ use ego_types::app::Wasm;
 use crate::types::stable_state::StableState;
 
 pub mod task;
+pub mod schedule_key;
+pub mod status_history;
 pub mod stable_state;
+pub mod cycle_reserve;
+pub mod top_up_log;
 
 #[derive(Clone, Debug, CandidType, Deserialize, Serialize)]
 pub enum EgoTenantErr {
@@ -16,6 +20,11 @@ pub enum EgoTenantErr {
   AppNotInstalled,
   CanisterNotFounded,
   CycleNotEnough,
+  TopUpPolicyInvalid,
+  TopUpDisabled,
+  TopUpLimitReached,
+  CanisterSettingsInvalid,
+  ReserveNotEnough,
   SystemError(String),
 }
 
@@ -31,6 +40,15 @@ impl From<EgoTenantErr> for EgoError {
         EgoError::new(4004, "ego-tenant: can not find canister to installed")
       }
       EgoTenantErr::CycleNotEnough => EgoError::new(4004, "ego-tenant: cycle not enough"),
+      EgoTenantErr::TopUpPolicyInvalid => EgoError::new(4005, "ego-tenant: top up policy invalid"),
+      EgoTenantErr::TopUpDisabled => {
+        EgoError::new(4006, "ego-tenant: auto top up disabled by the top up policy")
+      }
+      EgoTenantErr::TopUpLimitReached => EgoError::new(4007, "ego-tenant: daily top up limit reached"),
+      EgoTenantErr::CanisterSettingsInvalid => EgoError::new(4008, "ego-tenant: canister settings invalid"),
+      EgoTenantErr::ReserveNotEnough => {
+        EgoError::new(4009, "ego-tenant: cycle reserve of the tenant too low, retry after ego_store refills it")
+      }
       EgoTenantErr::SystemError(msg) => msg.into(),
     }
   }
@@ -55,6 +73,40 @@ pub struct AppMainUpgradeRequest {
   pub wasm: Wasm,
 }
 
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct CanisterTopUpPolicySetRequest {
+  pub canister_id: Principal,
+  // None restores the default policy
+  pub policy: Option<task::TopUpPolicy>,
+}
+
+/// the settings a wallet may change, None keeps the current value. controllers are left to the wallet
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct CanisterSettingsUpdate {
+  // percent, 0 to 100
+  pub compute_allocation: Option<u64>,
+  // bytes
+  pub memory_allocation: Option<u64>,
+  // second
+  pub freezing_threshold: Option<u64>,
+}
+
+impl CanisterSettingsUpdate {
+  pub fn check(&self) -> Result<(), EgoTenantErr> {
+    let nothing_set = self.compute_allocation.is_none() && self.memory_allocation.is_none() && self.freezing_threshold.is_none();
+    if nothing_set || self.compute_allocation.map_or(false, |allocation| allocation > 100) {
+      return Err(EgoTenantErr::CanisterSettingsInvalid);
+    }
+    Ok(())
+  }
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct CanisterSettingsUpdateRequest {
+  pub canister_id: Principal,
+  pub settings: CanisterSettingsUpdate,
+}
+
 #[derive(CandidType, Deserialize, Serialize)]
 pub struct AppMainReInstallRequest {
   pub canister_id: Principal,
@@ -0,0 +1,117 @@
+use std::borrow::Cow;
+use std::ops::Bound;
+
+use candid::Principal;
+use ic_stable_structures::{BoundedStorable, Storable};
+
+const PRINCIPAL_SIZE: usize = 30;
+
+/// length prefixed principal bytes, padded to a fixed size so the encoded keys sort like the structs
+fn principal_bytes(principal: &Principal) -> [u8; PRINCIPAL_SIZE] {
+  let bytes = principal.as_slice();
+  let mut fixed = [0u8; PRINCIPAL_SIZE];
+  fixed[0] = bytes.len() as u8;
+  fixed[1..=bytes.len()].copy_from_slice(bytes);
+  fixed
+}
+
+fn bytes_principal(fixed: &[u8]) -> Principal {
+  let len = fixed[0] as usize;
+  Principal::from_slice(&fixed[1..=len])
+}
+
+/// index key of the tasks, ordered by the time they are due
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ScheduleKey {
+  next_check_time: u64,
+  canister: [u8; PRINCIPAL_SIZE],
+}
+
+impl ScheduleKey {
+  pub fn new(next_check_time: u64, canister_id: &Principal) -> Self {
+    ScheduleKey {
+      next_check_time,
+      canister: principal_bytes(canister_id),
+    }
+  }
+
+  pub fn canister_id(&self) -> Principal {
+    bytes_principal(&self.canister)
+  }
+
+  /// the tasks due at the sentinel
+  pub fn range(sentinel: u64) -> (Bound<Self>, Bound<Self>) {
+    (
+      Bound::Unbounded,
+      Bound::Included(ScheduleKey { next_check_time: sentinel, canister: [u8::MAX; PRINCIPAL_SIZE] }),
+    )
+  }
+}
+
+impl Storable for ScheduleKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    let mut bytes = Vec::with_capacity(8 + PRINCIPAL_SIZE);
+    // big endian, so the bytes sort like the numbers
+    bytes.extend_from_slice(&self.next_check_time.to_be_bytes());
+    bytes.extend_from_slice(&self.canister);
+    Cow::Owned(bytes)
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    let mut next_check_time = [0u8; 8];
+    next_check_time.copy_from_slice(&bytes[0..8]);
+    let mut canister = [0u8; PRINCIPAL_SIZE];
+    canister.copy_from_slice(&bytes[8..8 + PRINCIPAL_SIZE]);
+    ScheduleKey { next_check_time: u64::from_be_bytes(next_check_time), canister }
+  }
+}
+
+impl BoundedStorable for ScheduleKey {
+  const MAX_SIZE: u32 = 8 + PRINCIPAL_SIZE as u32;
+  const IS_FIXED_SIZE: bool = true;
+}
+
+/// index key of the top up logs, ordered by the time they are created
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TopUpLogKey {
+  created_at: u64,
+  charge_id: u64,
+}
+
+impl TopUpLogKey {
+  pub fn new(created_at: u64, charge_id: u64) -> Self {
+    TopUpLogKey { created_at, charge_id }
+  }
+
+  pub fn charge_id(&self) -> u64 {
+    self.charge_id
+  }
+
+  /// the logs created before the time
+  pub fn range(before: u64) -> (Bound<Self>, Bound<Self>) {
+    (Bound::Unbounded, Bound::Excluded(TopUpLogKey { created_at: before, charge_id: 0 }))
+  }
+}
+
+impl Storable for TopUpLogKey {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    let mut bytes = Vec::with_capacity(16);
+    // big endian, so the bytes sort like the numbers
+    bytes.extend_from_slice(&self.created_at.to_be_bytes());
+    bytes.extend_from_slice(&self.charge_id.to_be_bytes());
+    Cow::Owned(bytes)
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    let mut created_at = [0u8; 8];
+    created_at.copy_from_slice(&bytes[0..8]);
+    let mut charge_id = [0u8; 8];
+    charge_id.copy_from_slice(&bytes[8..16]);
+    TopUpLogKey { created_at: u64::from_be_bytes(created_at), charge_id: u64::from_be_bytes(charge_id) }
+  }
+}
+
+impl BoundedStorable for TopUpLogKey {
+  const MAX_SIZE: u32 = 16;
+  const IS_FIXED_SIZE: bool = true;
+}
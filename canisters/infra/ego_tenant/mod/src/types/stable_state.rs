@@ -9,7 +9,8 @@ use serde::Serialize;
 use ego_types::cycle_info::CycleInfo;
 use ego_types::registry::Registry;
 use ego_types::user::User;
-use crate::state::{backup_info_post_upgrade, backup_info_pre_upgrade, cycle_info_post_upgrade, cycle_info_pre_upgrade, registry_post_upgrade, registry_pre_upgrade, users_post_upgrade, users_pre_upgrade};
+use crate::state::{backup_info_post_upgrade, backup_info_pre_upgrade, cycle_info_post_upgrade, cycle_info_pre_upgrade, registry_post_upgrade, registry_pre_upgrade, reserve_post_upgrade, reserve_pre_upgrade, users_post_upgrade, users_pre_upgrade};
+use crate::types::cycle_reserve::CycleReserve;
 
 const STATE_SIZE: u32 = 4 * 1024 * 1024; // 4M
 
@@ -20,6 +21,7 @@ pub struct StableState {
   pub registry: Option<Registry>,
   pub cycle_info: Option<CycleInfo>,
   pub backup_info: Option<BackupInfo>,
+  pub reserve: Option<CycleReserve>,
 }
 
 impl Default for StableState {
@@ -29,6 +31,7 @@ impl Default for StableState {
       registry: None,
       cycle_info: None,
       backup_info: None,
+      reserve: None,
     }
   }
 }
@@ -40,6 +43,7 @@ impl StableState {
       registry: Some(registry_pre_upgrade()),
       cycle_info: Some(cycle_info_pre_upgrade()),
       backup_info: Some(backup_info_pre_upgrade()),
+      reserve: Some(reserve_pre_upgrade()),
     }
   }
 
@@ -48,6 +52,7 @@ impl StableState {
     registry_post_upgrade(state.registry.unwrap_or(Registry::default()));
     cycle_info_post_upgrade(state.cycle_info.unwrap_or(CycleInfo::default()));
     backup_info_post_upgrade(state.backup_info.unwrap_or(BackupInfo::default()));
+    reserve_post_upgrade(state.reserve.unwrap_or_default());
   }
 }
 
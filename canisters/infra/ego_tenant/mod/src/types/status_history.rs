@@ -0,0 +1,152 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use ic_stable_structures::storable::Blob;
+use serde::Serialize;
+
+use ego_utils::util::time;
+
+use crate::memory::STATUS_HISTORIES;
+
+// the same amount CycleInfo keeps
+pub const STATUS_RECORD_MAX: usize = 12;
+
+const DAY: u128 = 24 * 60 * 60;
+
+#[allow(non_camel_case_types)]
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum CanisterRunStatus {
+  RUNNING,
+  STOPPING,
+  STOPPED,
+}
+
+/// what canister_status reports, readable without the cooperation of the canister
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StatusRecord {
+  pub ts: u64, // second
+  pub status: CanisterRunStatus,
+  pub cycles: u128,
+  pub memory_size: u64,
+  pub idle_burn_per_day: u128,
+  // second
+  pub freezing_threshold: u64,
+  // bytes, None when the canister has no memory allocation
+  pub memory_allocation: Option<u64>,
+}
+
+impl StatusRecord {
+  /// the canister can not run once the cycles fall under what it burns idle within the freezing threshold
+  pub fn is_frozen(&self) -> bool {
+    self.cycles <= self.idle_burn_per_day * self.freezing_threshold as u128 / DAY
+  }
+}
+
+/// the canister_status records of a tracked canister, newest first
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct StatusHistory {
+  pub canister_id: Principal,
+  pub records: Vec<StatusRecord>,
+  // second, since when the canister has not answered ego_cycle_check
+  pub unresponsive_since: Option<u64>,
+  pub last_update: u64, // second
+}
+
+impl StatusHistory {
+  pub fn new(canister_id: &Principal) -> Self {
+    Self {
+      canister_id: *canister_id,
+      records: vec![],
+      unresponsive_since: None,
+      last_update: 0,
+    }
+  }
+
+  pub fn record_add(&mut self, record: StatusRecord) {
+    self.records.insert(0, record);
+    self.records.truncate(STATUS_RECORD_MAX);
+  }
+
+  pub fn latest(&self) -> Option<&StatusRecord> {
+    self.records.first()
+  }
+
+  pub fn len() -> u64 {
+    STATUS_HISTORIES.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, history)| Some(history))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, history)| match history.last_update >= last_update {
+      true => { Some(history) }
+      false => { None }
+    })
+  }
+
+  pub fn unresponsive() -> Vec<Self> {
+    Self::iter(0, Self::len() as usize, |(_, history)| match history.unresponsive_since.is_some() {
+      true => { Some(history) }
+      false => { None }
+    })
+  }
+
+  pub fn get(canister_id: &Principal) -> Option<Self> {
+    STATUS_HISTORIES.with(|cell| {
+      let inst = cell.borrow();
+      let key = Blob::try_from(canister_id.as_slice()).unwrap();
+      inst.get(&key)
+    })
+  }
+
+  pub fn save(&mut self) {
+    STATUS_HISTORIES.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      let key = Blob::try_from(self.canister_id.as_slice()).unwrap();
+      self.last_update = time();
+      inst.insert(key, self.clone());
+    });
+  }
+
+  pub fn remove(canister_id: &Principal) {
+    STATUS_HISTORIES.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      let key = Blob::try_from(canister_id.as_slice()).unwrap();
+      inst.remove(&key);
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((Blob<29>, Self)) -> Option<Self>,
+  {
+    STATUS_HISTORIES.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for StatusHistory {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for StatusHistory {
+  const MAX_SIZE: u32 = 2048;
+  const IS_FIXED_SIZE: bool = false;
+}
@@ -6,11 +6,59 @@ use ic_stable_structures::{BoundedStorable, Storable};
 use ic_stable_structures::storable::Blob;
 use serde::Serialize;
 
+use ego_types::app::EgoError;
 use ego_utils::util::time;
 
-use crate::memory::TASKS;
+use crate::forecast::CycleForecast;
+use crate::memory::{TASK_DEAD_INDEX, TASK_SCHEDULE, TASKS, TASKS_LEGACY};
+use crate::types::EgoTenantErr;
+use crate::types::schedule_key::ScheduleKey;
 
-pub const MAX_TRY_COUNT: u8 = 5; // 4M
+pub const MAX_TRY_COUNT: u8 = 5;
+// the wait after the first unanswered check, doubled with each retry
+pub const RETRY_DURATION: u64 = 10 * 60;
+
+const DAY: u64 = 24 * 60 * 60;
+
+// a task with every field set to the largest values encodes to about 400 bytes
+pub const TASK_SIZE: u32 = 1024;
+// the bound of the tasks saved before the retry, policy and status fields
+pub const LEGACY_TASK_SIZE: usize = 512;
+
+/// how the tenant tops up a canister, set by the wallet owner through ego_store
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct TopUpPolicy {
+  // top up once the balance falls under it, the threshold reported by the canister when None
+  pub min_balance: Option<u128>,
+  // the balance to top up to, 1.5 times the min balance when None
+  pub target_balance: Option<u128>,
+  // the cycles topped up at most within a day, no limit when None
+  pub max_daily_top_up: Option<u128>,
+  // never top up automatically, only report the low balance
+  pub alert_only: bool,
+}
+
+impl TopUpPolicy {
+  pub fn check(&self) -> Result<(), EgoError> {
+    if let (Some(min_balance), Some(target_balance)) = (self.min_balance, self.target_balance) {
+      if target_balance <= min_balance {
+        return Err(EgoTenantErr::TopUpPolicyInvalid.into());
+      }
+    }
+    // a zero limit is alert_only
+    if self.max_daily_top_up == Some(0) {
+      return Err(EgoTenantErr::TopUpPolicyInvalid.into());
+    }
+    Ok(())
+  }
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct TopUpUsage {
+  // days since the epoch
+  pub day: u64,
+  pub cycles: u128,
+}
 
 // Task
 #[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
@@ -22,6 +70,19 @@ pub struct Task {
   pub last_update: u64,
   // second
   pub try_count: u8,
+  // the forecast of the last cycle check
+  pub forecast: Option<CycleForecast>,
+  pub top_up_policy: Option<TopUpPolicy>,
+  // the cycles topped up today, for max_daily_top_up
+  pub top_up_usage: Option<TopUpUsage>,
+  // second, when the canister last answered a cycle check
+  pub last_check_time: Option<u64>,
+  // second, when the retries were used up. dead tasks are not scheduled until requeued
+  pub dead_at: Option<u64>,
+  // the threshold the canister reported in its last cycle check
+  pub last_threshold: Option<u128>,
+  // whether the tenant is a controller, as canister_status told. None until the status is polled
+  pub controller: Option<bool>,
 }
 
 impl Task {
@@ -32,6 +93,78 @@ impl Task {
       last_cycle,
       last_update: 0,
       try_count: 0,
+      forecast: None,
+      top_up_policy: None,
+      top_up_usage: None,
+      last_check_time: None,
+      dead_at: None,
+      last_threshold: None,
+      controller: None,
+    }
+  }
+
+  /// the min balance and the target balance of topping up, by the policy or the threshold
+  pub fn top_up_range(&self, threshold: u128) -> (u128, u128) {
+    let policy = self.top_up_policy.clone().unwrap_or_default();
+    let min_balance = policy.min_balance.unwrap_or(threshold);
+    let target_balance = policy.target_balance.unwrap_or(min_balance * 15 / 10);
+    (min_balance, target_balance)
+  }
+
+  /// a check is sent, wait longer for each unanswered one
+  pub fn check_sent(&mut self, now: u64) {
+    self.next_check_time = now + (RETRY_DURATION << self.try_count.min(MAX_TRY_COUNT));
+    self.try_count += 1;
+  }
+
+  pub fn dead_letter(&mut self, now: u64) {
+    self.dead_at = Some(now);
+  }
+
+  /// the status is polled again as well, the tenant may be a controller by now
+  pub fn requeue(&mut self, now: u64) {
+    self.dead_at = None;
+    self.try_count = 0;
+    self.next_check_time = now;
+    self.controller = None;
+  }
+
+  /// whether the balance of the last check should be burnt out by now
+  pub fn frozen_expected(&self, now: u64) -> bool {
+    match (&self.forecast, self.last_cycle, self.last_check_time) {
+      (Some(forecast), Some(last_cycle), Some(last_check_time)) => {
+        forecast.burn_per_second > 0 && forecast.remaining(last_cycle) <= now.saturating_sub(last_check_time)
+      }
+      _ => false,
+    }
+  }
+
+  /// the part of the requested cycles the policy lets through now
+  pub fn top_up_allowance(&self, cycles: u128, now: u64) -> Result<u128, EgoError> {
+    let policy = self.top_up_policy.clone().unwrap_or_default();
+    if policy.alert_only {
+      return Err(EgoTenantErr::TopUpDisabled.into());
+    }
+
+    match policy.max_daily_top_up {
+      None => Ok(cycles),
+      Some(max_daily_top_up) => {
+        let used = self.top_up_usage.as_ref()
+          .filter(|usage| usage.day == now / DAY)
+          .map_or(0, |usage| usage.cycles);
+        match max_daily_top_up.saturating_sub(used).min(cycles) {
+          0 => Err(EgoTenantErr::TopUpLimitReached.into()),
+          allowance => Ok(allowance),
+        }
+      }
+    }
+  }
+
+  pub fn top_up_record(&mut self, cycles: u128, now: u64) {
+    let day = now / DAY;
+    match self.top_up_usage.as_mut().filter(|usage| usage.day == day) {
+      Some(usage) => usage.cycles += cycles,
+      None => self.top_up_usage = Some(TopUpUsage { day, cycles }),
     }
   }
 
@@ -51,14 +184,29 @@ impl Task {
     })
   }
 
-  pub fn by_sentinel(sentinel: u64) -> Vec<Task> {
-    Self::iter(0, Self::len() as usize, |(_, task)|
-    match task.next_check_time <= sentinel && task.try_count < MAX_TRY_COUNT {
-      true => { Some(task) }
-      false => { None }
+  /// the earliest due tasks, at most limit of them
+  pub fn by_sentinel(sentinel: u64, limit: usize) -> Vec<Task> {
+    let canister_ids: Vec<Principal> = TASK_SCHEDULE.with(|cell| {
+      let inst = cell.borrow();
+      inst.range(ScheduleKey::range(sentinel)).take(limit).map(|(key, _)| key.canister_id()).collect()
+    });
+
+    canister_ids.iter().filter_map(Self::get).collect()
+  }
+
+  pub fn dead_list() -> Vec<Task> {
+    let keys: Vec<Blob<29>> = TASK_DEAD_INDEX.with(|cell| cell.borrow().iter().map(|(key, _)| key).collect());
+
+    TASKS.with(|cell| {
+      let inst = cell.borrow();
+      keys.iter().filter_map(|key| inst.get(key)).collect()
     })
   }
 
+  pub fn dead_len() -> u64 {
+    TASK_DEAD_INDEX.with(|cell| cell.borrow().len())
+  }
+
   pub fn list(start: usize, end: usize) -> Vec<Task> {
     Self::iter(start, end, |(_, task)| Some(task))
   }
@@ -72,19 +220,74 @@ impl Task {
   }
 
   pub fn save(&mut self) {
-    TASKS.with(|cell| {
+    let previous = TASKS.with(|cell| {
       let mut inst = cell.borrow_mut();
       let key = Blob::try_from(self.canister_id.as_slice()).unwrap();
       self.last_update = time();
       inst.insert(key, self.clone())
     });
+
+    if let Some(previous) = previous {
+      previous.index_remove();
+    }
+    self.index_insert();
   }
 
   pub fn remove(canister_id: &Principal) {
-    TASKS.with(|cell| {
+    let removed = TASKS.with(|cell| {
       let mut inst = cell.borrow_mut();
       let key = Blob::try_from(canister_id.as_slice()).unwrap();
-      inst.remove(&key);
+      inst.remove(&key)
+    });
+
+    if let Some(removed) = removed {
+      removed.index_remove();
+    }
+  }
+
+  /// moves the tasks saved under the legacy bound to the tasks map, keeping them as they were
+  pub fn migrate() {
+    let legacy: Vec<(Blob<29>, Blob<LEGACY_TASK_SIZE>)> = TASKS_LEGACY.with(|cell| cell.borrow().iter().collect());
+
+    legacy.into_iter().for_each(|(key, value)| {
+      let task = Task::from_bytes(value.to_bytes());
+      TASKS_LEGACY.with(|cell| cell.borrow_mut().remove(&key));
+      TASKS.with(|cell| cell.borrow_mut().insert(key, task));
+    });
+  }
+
+  /// build the schedule index and the dead index for the tasks saved before the indexes existed
+  pub fn index_rebuild() {
+    let tasks = Self::list(0, Self::len() as usize);
+    let dead_len = tasks.iter().filter(|task| task.dead_at.is_some()).count() as u64;
+
+    let schedule_len = TASK_SCHEDULE.with(|cell| cell.borrow().len());
+    if schedule_len == tasks.len() as u64 - dead_len && Self::dead_len() == dead_len {
+      return;
+    }
+
+    tasks.iter().for_each(|task| task.index_insert());
+  }
+
+  /// the live tasks go to the schedule, the dead ones to the dead index
+  fn index_insert(&self) {
+    let key = Blob::try_from(self.canister_id.as_slice()).unwrap();
+    match self.dead_at {
+      Some(dead_at) => TASK_DEAD_INDEX.with(|cell| {
+        cell.borrow_mut().insert(key, dead_at);
+      }),
+      None => TASK_SCHEDULE.with(|cell| {
+        cell.borrow_mut().insert(ScheduleKey::new(self.next_check_time, &self.canister_id), self.last_update);
+      }),
+    }
+  }
+
+  fn index_remove(&self) {
+    TASK_SCHEDULE.with(|cell| {
+      cell.borrow_mut().remove(&ScheduleKey::new(self.next_check_time, &self.canister_id));
+    });
+    TASK_DEAD_INDEX.with(|cell| {
+      cell.borrow_mut().remove(&Blob::try_from(self.canister_id.as_slice()).unwrap());
     });
   }
 
@@ -113,6 +316,6 @@ impl Storable for Task {
 }
 
 impl BoundedStorable for Task {
-  const MAX_SIZE: u32 = 512;
+  const MAX_SIZE: u32 = TASK_SIZE;
   const IS_FIXED_SIZE: bool = false;
 }
@@ -0,0 +1,131 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{BoundedStorable, Storable};
+use serde::Serialize;
+
+use ego_utils::util::time;
+
+use crate::memory::{TOP_UP_LOG_INDEX, TOP_UP_LOGS};
+use crate::types::schedule_key::TopUpLogKey;
+
+// kept longer than ego_store reconciles the charges
+pub const TOP_UP_LOG_DURATION: u64 = 30 * 24 * 60 * 60;
+
+/// a top up paid by a wallet charge, what ego_store reconciles the charges against
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct TopUpLog {
+  // the charge id given by ego_store
+  pub charge_id: u64,
+  pub canister_id: Principal,
+  pub cycles: u128,
+  pub created_at: u64,
+  pub last_update: u64, // second
+}
+
+impl TopUpLog {
+  pub fn new(charge_id: u64, canister_id: &Principal, cycles: u128) -> Self {
+    Self {
+      charge_id,
+      canister_id: *canister_id,
+      cycles,
+      created_at: time(),
+      last_update: 0,
+    }
+  }
+
+  pub fn len() -> u64 {
+    TOP_UP_LOGS.with(|cell| {
+      let inst = cell.borrow();
+      inst.len()
+    })
+  }
+
+  pub fn list(start: usize, end: usize) -> Vec<Self> {
+    Self::iter(start, end, |(_, log)| Some(log))
+  }
+
+  pub fn by_last_update(start: usize, end: usize, last_update: u64) -> Vec<Self> {
+    Self::iter(start, end, |(_, log)| match log.last_update >= last_update {
+      true => { Some(log) }
+      false => { None }
+    })
+  }
+
+  pub fn get(charge_id: u64) -> Option<Self> {
+    TOP_UP_LOGS.with(|cell| {
+      let inst = cell.borrow();
+      inst.get(&charge_id)
+    })
+  }
+
+  pub fn save(&mut self) {
+    let previous = TOP_UP_LOGS.with(|cell| {
+      let mut inst = cell.borrow_mut();
+      self.last_update = time();
+      inst.insert(self.charge_id, self.clone())
+    });
+
+    TOP_UP_LOG_INDEX.with(|cell| {
+      let mut index = cell.borrow_mut();
+      if let Some(previous) = previous {
+        index.remove(&TopUpLogKey::new(previous.created_at, previous.charge_id));
+      }
+      index.insert(TopUpLogKey::new(self.created_at, self.charge_id), self.charge_id);
+    });
+  }
+
+  /// drops the logs created before the time, walking the expired part of the index only
+  pub fn prune(before: u64) {
+    let expired: Vec<TopUpLogKey> = TOP_UP_LOG_INDEX.with(|cell| {
+      cell.borrow().range(TopUpLogKey::range(before)).map(|(key, _)| key).collect()
+    });
+
+    expired.iter().for_each(|key| {
+      TOP_UP_LOGS.with(|cell| cell.borrow_mut().remove(&key.charge_id()));
+      TOP_UP_LOG_INDEX.with(|cell| cell.borrow_mut().remove(key));
+    });
+  }
+
+  /// build the time index for the logs saved before the index existed
+  pub fn index_rebuild() {
+    let index_len = TOP_UP_LOG_INDEX.with(|cell| cell.borrow().len());
+    if index_len == Self::len() {
+      return;
+    }
+
+    Self::list(0, Self::len() as usize).iter().for_each(|log| {
+      TOP_UP_LOG_INDEX.with(|cell| {
+        cell.borrow_mut().insert(TopUpLogKey::new(log.created_at, log.charge_id), log.charge_id);
+      });
+    });
+  }
+
+  fn iter<F>(start: usize, end: usize, filter: F) -> Vec<Self>
+  where
+    F: Fn((u64, Self)) -> Option<Self>,
+  {
+    TOP_UP_LOGS.with(|cell| {
+      let inst = cell.borrow();
+      inst.iter().skip(start).take(end - start).filter_map(|entry| {
+        filter(entry)
+      }).collect()
+    })
+  }
+}
+
+impl Storable for TopUpLog {
+  fn to_bytes(&self) -> Cow<[u8]> {
+    Cow::Owned(Encode!(self).unwrap())
+  }
+
+  fn from_bytes(bytes: Cow<[u8]>) -> Self {
+    Decode!(bytes.as_ref(), Self).unwrap()
+  }
+}
+
+impl BoundedStorable for TopUpLog {
+  const MAX_SIZE: u32 = 128;
+  const IS_FIXED_SIZE: bool = false;
+}
@@ -29,13 +29,16 @@ fn test_job_list() {
   set_up();
 
   let jobs = job_list();
-  assert_eq!(2, jobs.len());
+  assert_eq!(4, jobs.len());
 
   assert_eq!("config", jobs.get(0).unwrap().name);
   assert_eq!(1, jobs.get(0).unwrap().amount);
 
   assert_eq!("tasks", jobs.get(1).unwrap().name);
   assert_eq!(2, jobs.get(1).unwrap().amount);
+
+  assert_eq!("status_histories", jobs.get(2).unwrap().name);
+  assert_eq!(0, jobs.get(2).unwrap().amount);
 }
 
 #[test]
@@ -0,0 +1,30 @@
+use ego_tenant_mod::types::cycle_reserve::{CycleReserve, RESERVE_DAYS, RESERVE_FLOOR};
+
+const DAY: u64 = 24 * 60 * 60;
+
+#[test]
+pub fn spend_per_day() {
+  CycleReserve::spend(100, 10 * DAY);
+  CycleReserve::spend(200, 10 * DAY + 1);
+
+  let reserve = CycleReserve::get();
+  assert_eq!(300, reserve.spend_per_day(10 * DAY + 2));
+  assert_eq!(RESERVE_FLOOR * 2 + 300 * RESERVE_DAYS, reserve.threshold(10 * DAY + 2));
+
+  // the last day still counts while today is quieter
+  CycleReserve::spend(50, 11 * DAY);
+  let reserve = CycleReserve::get();
+  assert_eq!(300, reserve.spent_last_day);
+  assert_eq!(300, reserve.spend_per_day(11 * DAY));
+
+  // forgotten after a day without spending
+  assert_eq!(50, reserve.spend_per_day(12 * DAY));
+  assert_eq!(0, reserve.spend_per_day(13 * DAY));
+}
+
+#[test]
+pub fn check() {
+  assert!(CycleReserve::check(RESERVE_FLOOR + 100, 100).is_ok());
+  assert!(CycleReserve::check(RESERVE_FLOOR + 100, 101).is_err());
+  assert!(CycleReserve::check(50, 100).is_err());
+}
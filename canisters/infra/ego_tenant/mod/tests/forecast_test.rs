@@ -0,0 +1,104 @@
+use ego_tenant_mod::forecast::{CycleForecast, HEAP_LIMIT, MAX_CHECK_DURATION, MemoryForecast};
+use ego_tenant_mod::service::NEXT_CHECK_DURATION;
+use ego_types::cycle_info::{CycleRecord, DEFAULT_ESTIMATE};
+
+#[test]
+fn steady_burn() {
+  // newest first, 100 cycles per second over the full history
+  let records: Vec<CycleRecord> = (0..12).map(|i| CycleRecord {
+    balance: 100_000 + 6000 * i as u128,
+    ts: 10_000 - 60 * i as u64,
+    heap_size: None,
+    stable_size: None,
+  }).collect();
+
+  let forecast = CycleForecast::new(&records).unwrap();
+  assert_eq!(100, forecast.burn_per_second);
+  assert_eq!(11, forecast.samples);
+  assert_eq!(100, forecast.confidence);
+
+  assert_eq!(1000, forecast.remaining(100_000));
+}
+
+#[test]
+fn top_up_excluded() {
+  let records = vec![
+    CycleRecord { balance: 900, ts: 300, heap_size: None, stable_size: None },
+    CycleRecord { balance: 1000, ts: 200, heap_size: None, stable_size: None },
+    // topped up between these two
+    CycleRecord { balance: 500, ts: 100, heap_size: None, stable_size: None },
+    CycleRecord { balance: 600, ts: 0, heap_size: None, stable_size: None },
+  ];
+
+  let forecast = CycleForecast::new(&records).unwrap();
+  assert_eq!(1, forecast.burn_per_second);
+  assert_eq!(2, forecast.samples);
+  assert_eq!(18, forecast.confidence);
+}
+
+#[test]
+fn recent_intervals_weigh_more() {
+  let records = vec![
+    CycleRecord { balance: 1000, ts: 120, heap_size: None, stable_size: None },
+    CycleRecord { balance: 13000, ts: 60, heap_size: None, stable_size: None },
+    CycleRecord { balance: 19000, ts: 0, heap_size: None, stable_size: None },
+  ];
+
+  // 200 per second lately, 100 before
+  let forecast = CycleForecast::new(&records).unwrap();
+  assert_eq!(152, forecast.burn_per_second);
+}
+
+#[test]
+fn nothing_to_forecast() {
+  assert!(CycleForecast::new(&[CycleRecord { balance: 1000, ts: 10, heap_size: None, stable_size: None }]).is_none());
+
+  let records = vec![
+    CycleRecord { balance: 1000, ts: 20, heap_size: None, stable_size: None },
+    CycleRecord { balance: 500, ts: 10, heap_size: None, stable_size: None },
+  ];
+  assert!(CycleForecast::new(&records).is_none());
+}
+
+#[test]
+fn next_check_duration() {
+  let forecast = CycleForecast { burn_per_second: 100, samples: 11, confidence: 50 };
+
+  // 20000 seconds to the threshold, half of it scaled by the confidence
+  assert_eq!(5000, forecast.next_check_duration(12_000_000, 10_000_000));
+  assert_eq!(MAX_CHECK_DURATION, forecast.next_check_duration(100_000_000_000, 0));
+
+  let forecast = CycleForecast { burn_per_second: 0, samples: 11, confidence: 100 };
+  assert_eq!(NEXT_CHECK_DURATION, forecast.next_check_duration(12_000_000, 10_000_000));
+  assert_eq!(DEFAULT_ESTIMATE, forecast.remaining(12_000_000));
+}
+
+#[test]
+fn memory_forecast() {
+  let records = vec![
+    CycleRecord { balance: 1000, ts: 86_400 * 2, heap_size: Some(3_000), stable_size: Some(1_000) },
+    CycleRecord { balance: 1000, ts: 86_400, heap_size: None, stable_size: None },
+    CycleRecord { balance: 1000, ts: 0, heap_size: Some(1_000), stable_size: Some(2_000) },
+  ];
+
+  let forecast = MemoryForecast::new(&records).unwrap();
+  assert_eq!(3_000, forecast.heap_size);
+  assert_eq!(1_000, forecast.heap_growth_per_day);
+  // the stable memory shrank
+  assert_eq!(0, forecast.stable_growth_per_day);
+
+  // 6_000 bytes left in the allocation, 6 days at 1_000 bytes a day
+  assert_eq!(Some(6 * 86_400), forecast.remaining(Some(10_000)));
+  assert_eq!(Some(0), forecast.remaining(Some(1_000)));
+  assert_eq!(Some((HEAP_LIMIT - 3_000) * 86_400 / 1_000), forecast.remaining(None));
+}
+
+#[test]
+fn memory_forecast_not_growing() {
+  // the records of canisters which do not sample memory
+  let records = vec![CycleRecord { balance: 1000, ts: 10, heap_size: None, stable_size: None }];
+  assert!(MemoryForecast::new(&records).is_none());
+
+  let records = vec![CycleRecord { balance: 1000, ts: 10, heap_size: Some(1_000), stable_size: Some(1_000) }];
+  assert_eq!(None, MemoryForecast::new(&records).unwrap().remaining(Some(10_000)));
+}
@@ -1,3 +1,5 @@
+use std::sync::{Arc, Mutex};
+
 use async_trait::async_trait;
 use candid::Principal;
 use mockall::mock;
@@ -8,6 +10,8 @@ use ego_tenant_mod::c2c::ego_file::TEgoFile;
 use ego_tenant_mod::c2c::ic_management::TIcManagement;
 use ego_tenant_mod::service::EgoTenantService;
 use ego_tenant_mod::state::canister_add;
+use ego_tenant_mod::types::CanisterSettingsUpdate;
+use ego_tenant_mod::types::status_history::{CanisterRunStatus, StatusRecord};
 use ego_types::app::{App, AppId};
 use ego_types::app::{Wasm, WasmId};
 use ego_types::app::CanisterType::BACKEND;
@@ -64,11 +68,25 @@ mock! {
 
     async fn canister_main_delete(&self, canister_id: Principal) -> Result<(), EgoError>;
 
+    async fn canister_main_start(&self, canister_id: Principal) -> Result<(), EgoError>;
+
+    async fn canister_main_stop(&self, canister_id: Principal) -> Result<(), EgoError>;
+
     async fn controllers_update(
         &self,
         canister_id: Principal,
         controllers: Vec<Principal>,
     ) -> Result<(), EgoError>;
+
+    async fn canister_settings_update(
+        &self,
+        canister_id: Principal,
+        settings: CanisterSettingsUpdate,
+    ) -> Result<(), EgoError>;
+
+    async fn canister_status_get(&self, canister_id: Principal) -> Result<StatusRecord, EgoError>;
+
+    fn canister_balance(&self) -> u128;
   }
 }
 
@@ -106,6 +124,9 @@ async fn app_main_install() {
   let created_canister_id = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
   let fake_wasm_module = vec![1, 0, 1, 0, 0, 1, 0, 1];
 
+  mock_management
+    .expect_canister_balance()
+    .returning(|| 10_000_000_000_000);
   mock_management
     .expect_canister_main_create()
     .returning(move |_cycles_to_use| Ok(created_canister_id.clone()));
@@ -198,6 +219,9 @@ async fn app_main_install_failed() {
     Ok(vec![])
   });
 
+  mock_management
+    .expect_canister_balance()
+    .returning(|| 10_000_000_000_000);
   mock_management
     .expect_canister_main_create()
     .returning(move |_cycles_to_use| Err(EgoError::from("error".to_string())));
@@ -220,6 +244,50 @@ async fn app_main_install_failed() {
   }
 }
 
+#[tokio::test]
+async fn app_main_install_reserve_not_enough() {
+  set_up();
+
+  let tenant_canister_id = Principal::from_text(TENANT_CANISTER_ID.to_string()).unwrap();
+  let wallet_principal = Principal::from_text(EXISTS_WALLET_ID.to_string()).unwrap();
+  let user_principal = Principal::from_text(EXISTS_USER_ID.to_string()).unwrap();
+  let file_canister = Principal::from_text(FILE_CANISTER_ID.to_string()).unwrap();
+
+  let mut mock_management = MockManagement::new();
+  let mut mock_ego_file = MockEgoFile::new();
+
+  let version = Version {
+    major: 1,
+    minor: 0,
+    patch: 0,
+  };
+  let backend = Wasm::new(EXISTS_APP_ID.to_string(), version, BACKEND, file_canister);
+
+  // refused before the wasm is read or the canister created
+  mock_ego_file.expect_file_main_read().times(0);
+  mock_management
+    .expect_canister_balance()
+    .returning(|| 1_100_000_000_000);
+  mock_management.expect_canister_main_create().times(0);
+
+  match EgoTenantService::app_main_install(
+    tenant_canister_id,
+    mock_ego_file,
+    mock_management,
+    MockCanister::new(),
+    wallet_principal,
+    user_principal,
+    backend,
+  )
+    .await
+  {
+    Ok(_principal) => panic!("should not go here"),
+    Err(e) => {
+      assert_eq!(4009, e.code)
+    }
+  }
+}
+
 #[tokio::test]
 #[should_panic]
 async fn app_main_install_canister_code_install_fail() {
@@ -245,6 +313,9 @@ async fn app_main_install_canister_code_install_fail() {
   let created_canister_id = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
   let fake_wasm_module = vec![1, 0, 1, 0, 0, 1, 0, 1];
 
+  mock_management
+    .expect_canister_balance()
+    .returning(|| 10_000_000_000_000);
   mock_management
     .expect_canister_main_create()
     .returning(move |_cycles_to_use| Ok(created_canister_id.clone()));
@@ -317,6 +388,9 @@ async fn app_main_install_ego_faile_fail() {
 
   let created_canister_id = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
   let backend = Wasm::new(EXISTS_APP_ID.to_string(), version, BACKEND, file_canister);
+  mock_management
+    .expect_canister_balance()
+    .returning(|| 10_000_000_000_000);
   mock_management
     .expect_canister_main_create()
     .returning(move |_cycles_to_use| Ok(created_canister_id.clone()));
@@ -372,7 +446,6 @@ async fn app_main_upgrade() {
 
   let mut mock_management = MockManagement::new();
   let mut mock_ego_file = MockEgoFile::new();
-  let mut mock_ego_canister = MockCanister::new();
 
   let version = Version {
     major: 1,
@@ -382,7 +455,6 @@ async fn app_main_upgrade() {
   let backend = Wasm::new(EXISTS_APP_ID.to_string(), version, BACKEND, file_canister);
 
   let exists_canister_id = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
-  let tenant_id = Principal::from_text(TENANT_CANISTER_ID.to_string()).unwrap();
   let fake_wasm_module = vec![1, 0, 1, 0, 0, 1, 0, 1];
 
   mock_ego_file
@@ -396,22 +468,11 @@ async fn app_main_upgrade() {
       Ok(())
     });
 
-  mock_ego_canister
-    .expect_ego_controller_set()
-    .returning(|_canister_id, _user_ids| ());
-
-  let mut ego_canister = MockCanister::new();
-  ego_canister
-    .expect_ego_controller_remove()
-    .returning(|_, _| ());
-
   match EgoTenantService::app_main_upgrade(
     mock_ego_file,
     mock_management,
-    ego_canister,
     exists_canister_id,
     backend,
-    tenant_id,
   )
     .await
   {
@@ -440,7 +501,6 @@ async fn app_main_upgrade_ego_file_failed() {
   };
   let backend = Wasm::new(EXISTS_APP_ID.to_string(), version, BACKEND, file_canister);
   let exists_canister_id = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
-  let tenant_id = Principal::from_text(TENANT_CANISTER_ID.to_string()).unwrap();
 
   mock_ego_file
     .expect_file_main_read()
@@ -452,18 +512,11 @@ async fn app_main_upgrade_ego_file_failed() {
       Ok(())
     });
 
-  let mut ego_canister = MockCanister::new();
-  ego_canister
-    .expect_ego_controller_remove()
-    .returning(|_, _| ());
-
   match EgoTenantService::app_main_upgrade(
     mock_ego_file,
     mock_management,
-    ego_canister,
     exists_canister_id,
     backend,
-    tenant_id,
   )
     .await
   {
@@ -491,7 +544,6 @@ async fn app_main_upgrade_ego_management_failed() {
   let backend = Wasm::new(EXISTS_APP_ID.to_string(), version, BACKEND, file_canister);
   let fake_wasm_module = vec![1, 0, 1, 0, 0, 1, 0, 1];
   let exist_canister_id = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
-  let tenant_id = Principal::from_text(TENANT_CANISTER_ID.to_string()).unwrap();
 
   mock_ego_file
     .expect_file_main_read()
@@ -502,18 +554,11 @@ async fn app_main_upgrade_ego_management_failed() {
       Err(EgoError::from("management error".to_string()))
     });
 
-  let mut ego_canister = MockCanister::new();
-  ego_canister
-    .expect_ego_controller_remove()
-    .returning(|_, _| ());
-
   match EgoTenantService::app_main_upgrade(
     mock_ego_file,
     mock_management,
-    ego_canister,
     exist_canister_id,
     backend,
-    tenant_id,
   )
     .await
   {
@@ -526,3 +571,106 @@ async fn app_main_upgrade_ego_management_failed() {
     }
   }
 }
+
+// a management canister that only serves its controllers
+fn controlled_management(controllers: Arc<Mutex<Vec<Principal>>>, caller: Principal) -> MockManagement {
+  let is_controller = move || -> Result<(), EgoError> {
+    match controllers.lock().unwrap().contains(&caller) {
+      true => Ok(()),
+      false => Err(EgoError::new(5, "only the controllers of the canister can call it")),
+    }
+  };
+
+  let mut management = MockManagement::new();
+  let check = is_controller.clone();
+  management.expect_canister_main_start().returning(move |_| check());
+  let check = is_controller.clone();
+  management.expect_canister_main_stop().returning(move |_| check());
+  let check = is_controller.clone();
+  management.expect_canister_settings_update().returning(move |_, _| check());
+  let check = is_controller.clone();
+  management.expect_canister_code_upgrade().returning(move |_, _| check());
+  let check = is_controller;
+  management.expect_canister_status_get().returning(move |_| {
+    check()?;
+    Ok(StatusRecord {
+      ts: 100,
+      status: CanisterRunStatus::RUNNING,
+      cycles: 1_000_000,
+      memory_size: 1024,
+      idle_burn_per_day: 86_400,
+      freezing_threshold: 1_000,
+      memory_allocation: None,
+    })
+  });
+  management
+}
+
+#[tokio::test]
+async fn canister_lifecycle_by_controller() {
+  set_up();
+
+  let tenant_canister_id = Principal::from_text(TENANT_CANISTER_ID.to_string()).unwrap();
+  let wallet_principal = Principal::from_text(EXISTS_WALLET_ID.to_string()).unwrap();
+  let user_principal = Principal::from_text(EXISTS_USER_ID.to_string()).unwrap();
+  let file_canister = Principal::from_text(FILE_CANISTER_ID.to_string()).unwrap();
+  let created_canister_id = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+  let backend = Wasm::new(EXISTS_APP_ID.to_string(), Version::new(1, 0, 0), BACKEND, file_canister);
+
+  let controllers = Arc::new(Mutex::new(vec![tenant_canister_id]));
+
+  // install
+  let mut management = MockManagement::new();
+  management.expect_canister_balance().returning(|| 10_000_000_000_000);
+  management.expect_canister_main_create().returning(move |_| Ok(created_canister_id));
+  management.expect_canister_code_install().returning(|_, _| Ok(()));
+  let installed = controllers.clone();
+  management.expect_controllers_update().returning(move |_, principals| {
+    *installed.lock().unwrap() = principals;
+    Ok(())
+  });
+
+  let mut ego_file = MockEgoFile::new();
+  ego_file.expect_file_main_read().returning(|_, _| Ok(vec![1, 0, 1]));
+
+  let mut ego_canister = MockCanister::new();
+  ego_canister.expect_ego_canister_add().returning(|_, _, _| ());
+  ego_canister.expect_ego_op_add().returning(|_, _| ());
+  ego_canister.expect_ego_owner_set().returning(|_, _| ());
+
+  let canister_id = EgoTenantService::app_main_install(
+    tenant_canister_id,
+    ego_file,
+    management,
+    ego_canister,
+    wallet_principal,
+    user_principal,
+    backend.clone(),
+  ).await.unwrap();
+  assert!(controllers.lock().unwrap().contains(&wallet_principal));
+
+  // upgrade
+  let mut ego_file = MockEgoFile::new();
+  ego_file.expect_file_main_read().returning(|_, _| Ok(vec![1, 0, 1]));
+  let management = controlled_management(controllers.clone(), tenant_canister_id);
+  assert!(EgoTenantService::app_main_upgrade(ego_file, management, canister_id, backend).await.unwrap());
+
+  // the lifecycle calls of the wallet go through the tenant
+  let management = controlled_management(controllers.clone(), tenant_canister_id);
+  assert!(EgoTenantService::canister_main_stop(management, &canister_id).await.is_ok());
+
+  let management = controlled_management(controllers.clone(), tenant_canister_id);
+  assert!(EgoTenantService::canister_main_start(management, &canister_id, 100).await.is_ok());
+
+  let management = controlled_management(controllers.clone(), tenant_canister_id);
+  assert!(EgoTenantService::canister_main_status(management, &canister_id).await.is_ok());
+
+  let management = controlled_management(controllers.clone(), tenant_canister_id);
+  let settings = CanisterSettingsUpdate { freezing_threshold: Some(2_592_000), compute_allocation: None, memory_allocation: None };
+  assert!(EgoTenantService::canister_settings_update(management, &canister_id, settings).await.is_ok());
+
+  // refused once the wallet takes the tenant off
+  controllers.lock().unwrap().retain(|controller| *controller != tenant_canister_id);
+  let management = controlled_management(controllers.clone(), tenant_canister_id);
+  assert_eq!(5, EgoTenantService::canister_main_stop(management, &canister_id).await.unwrap_err().code);
+}
@@ -0,0 +1,41 @@
+use candid::Principal;
+
+use ego_tenant_mod::types::status_history::{CanisterRunStatus, STATUS_RECORD_MAX, StatusHistory, StatusRecord};
+
+static CANISTER_ID1: &str = "22fyd-yaaaa-aaaaf-aml4q-cai";
+
+fn status_record(ts: u64, cycles: u128) -> StatusRecord {
+  StatusRecord {
+    ts,
+    status: CanisterRunStatus::RUNNING,
+    cycles,
+    memory_size: 1024,
+    idle_burn_per_day: 86_400,
+    freezing_threshold: 1_000,
+    memory_allocation: None,
+  }
+}
+
+#[test]
+pub fn record_add() {
+  let canister1 = Principal::from_text(CANISTER_ID1.to_string()).unwrap();
+
+  let mut history = StatusHistory::new(&canister1);
+  for ts in 0..(STATUS_RECORD_MAX as u64 + 3) {
+    history.record_add(status_record(ts, 10_000));
+  }
+  history.save();
+
+  let history = StatusHistory::get(&canister1).unwrap();
+  assert_eq!(STATUS_RECORD_MAX, history.records.len());
+  // newest first
+  assert_eq!(STATUS_RECORD_MAX as u64 + 2, history.latest().unwrap().ts);
+  assert_eq!(1, StatusHistory::len());
+}
+
+#[test]
+pub fn is_frozen() {
+  // burns 1 cycle per second idle, frozen under 1000 seconds of it
+  assert!(status_record(0, 1_000).is_frozen());
+  assert!(!status_record(0, 1_001).is_frozen());
+}
@@ -1,6 +1,11 @@
 use candid::Principal;
+use ic_stable_structures::Storable;
+use ic_stable_structures::storable::Blob;
+
+use ego_tenant_mod::forecast::CycleForecast;
+use ego_tenant_mod::memory::TASKS_LEGACY;
 use ego_tenant_mod::service::EgoTenantService;
-use ego_tenant_mod::types::task::Task;
+use ego_tenant_mod::types::task::{RETRY_DURATION, Task, TASK_SIZE, TopUpPolicy, TopUpUsage};
 use ego_utils::util::time;
 
 static CANISTER_ID1: &str = "22fyd-yaaaa-aaaaf-aml4q-cai";
@@ -53,13 +58,46 @@ pub fn by_sentinel() {
   let canister3 = Principal::from_text(CANISTER_ID3.to_string()).unwrap();
   let mut task3 = Task::new(&canister3, 1500, None);
   task3.try_count = 5;
+  task3.dead_letter(1500);
   task3.save();
 
   // task1
-  assert_eq!(1, Task::by_sentinel(500).len());
+  assert_eq!(1, Task::by_sentinel(500, 100).len());
+
+  // task3 will not be return, cause it is dead lettered
+  assert_eq!(2, Task::by_sentinel(1500, 100).len());
+
+  // the earliest due first
+  let tasks = Task::by_sentinel(1500, 1);
+  assert_eq!(1, tasks.len());
+  assert_eq!(0, tasks[0].next_check_time);
+
+  // rescheduled tasks move in the index
+  task2.next_check_time = 2000;
+  task2.save();
+  assert_eq!(1, Task::by_sentinel(1500, 100).len());
+
+  task3.requeue(1500);
+  task3.save();
+  assert_eq!(2, Task::by_sentinel(1500, 100).len());
+  assert!(Task::dead_list().is_empty());
+}
 
-  // task3 will not be return, cause the try_count is over the limit
-  assert_eq!(2, Task::by_sentinel(1500).len());
+#[test]
+pub fn check_sent() {
+  set_up();
+
+  let canister1 = Principal::from_text(CANISTER_ID1.to_string()).unwrap();
+  let mut task = Task::get(&canister1).unwrap();
+
+  // 10, 20, 40 minutes
+  task.check_sent(1000);
+  assert_eq!(1000 + RETRY_DURATION, task.next_check_time);
+  task.check_sent(1000);
+  assert_eq!(1000 + 2 * RETRY_DURATION, task.next_check_time);
+  task.check_sent(1000);
+  assert_eq!(1000 + 4 * RETRY_DURATION, task.next_check_time);
+  assert_eq!(3, task.try_count);
 }
 
 #[test]
@@ -98,7 +136,55 @@ pub fn remove() {
   assert!(result.is_some());
 
   Task::remove(&canister1);
+  assert!(Task::by_sentinel(u64::MAX, 100).is_empty());
 
   let result = Task::get(&canister2);
   assert!(result.is_none());
 }
+
+#[test]
+pub fn max_size() {
+  let canister2 = Principal::from_text(CANISTER_ID2.to_string()).unwrap();
+  let mut task = Task::new(&canister2, u64::MAX, Some(u128::MAX));
+  task.try_count = u8::MAX;
+  task.forecast = Some(CycleForecast { burn_per_second: u128::MAX, samples: u8::MAX, confidence: u8::MAX });
+  task.top_up_policy = Some(TopUpPolicy {
+    min_balance: Some(u128::MAX),
+    target_balance: Some(u128::MAX),
+    max_daily_top_up: Some(u128::MAX),
+    alert_only: true,
+  });
+  task.top_up_usage = Some(TopUpUsage { day: u64::MAX, cycles: u128::MAX });
+  task.last_check_time = Some(u64::MAX);
+  task.dead_at = Some(u64::MAX);
+  task.last_threshold = Some(u128::MAX);
+  task.controller = Some(true);
+
+  assert!(task.to_bytes().len() <= TASK_SIZE as usize);
+  task.save();
+  assert_eq!(Some(u128::MAX), Task::get(&canister2).unwrap().last_threshold);
+}
+
+#[test]
+pub fn migrate() {
+  let canister2 = Principal::from_text(CANISTER_ID2.to_string()).unwrap();
+  let mut task = Task::new(&canister2, 10, Some(100));
+  task.last_update = 20;
+
+  TASKS_LEGACY.with(|cell| {
+    let key = Blob::try_from(canister2.as_slice()).unwrap();
+    cell.borrow_mut().insert(key, Blob::try_from(task.to_bytes().as_ref()).unwrap());
+  });
+
+  Task::migrate();
+
+  let migrated = Task::get(&canister2).unwrap();
+  assert_eq!(10, migrated.next_check_time);
+  assert_eq!(Some(100), migrated.last_cycle);
+  assert_eq!(20, migrated.last_update);
+  assert_eq!(0, TASKS_LEGACY.with(|cell| cell.borrow().len()));
+
+  // nothing left to move the next time
+  Task::migrate();
+  assert_eq!(1, Task::len());
+}
@@ -6,13 +6,20 @@ use ego_lib::ego_canister::TEgoCanister;
 use ego_lib::inject_mock_ego_canister;
 use ego_tenant_mod::c2c::ego_store::TEgoStore;
 use ego_tenant_mod::c2c::ic_management::TIcManagement;
+use ego_tenant_mod::forecast::{HEAP_LIMIT, MIN_CHECK_DURATION};
 use ego_tenant_mod::service::{EgoTenantService, NEXT_CHECK_DURATION};
 use ego_tenant_mod::state::canister_add;
-use ego_tenant_mod::types::task::Task;
-use ego_types::app::{App, AppId, Version};
+use ego_tenant_mod::forecast::CycleForecast;
+use ego_tenant_mod::types::CanisterSettingsUpdate;
+use ego_tenant_mod::types::cycle_reserve::{CycleReserve, RESERVE_DAYS, RESERVE_FLOOR};
+use ego_tenant_mod::types::status_history::{CanisterRunStatus, StatusHistory, StatusRecord};
+use ego_tenant_mod::types::task::{MAX_TRY_COUNT, RETRY_DURATION, Task, TopUpPolicy};
+use ego_tenant_mod::types::top_up_log::TopUpLog;
+use ego_types::app::{AlertKind, App, AppId, Version};
 use ego_types::app::EgoError;
 use ego_types::app_info::AppInfo;
 use ego_utils::ic_management::Cycles;
+use ego_utils::util::time;
 
 static STORE_CANISTER_ID: &str = "qhbym-qaaaa-aaaaa-aaafq-cai";
 
@@ -62,11 +69,25 @@ mock! {
 
     async fn canister_main_delete(&self, canister_id: Principal) -> Result<(), EgoError>;
 
+    async fn canister_main_start(&self, canister_id: Principal) -> Result<(), EgoError>;
+
+    async fn canister_main_stop(&self, canister_id: Principal) -> Result<(), EgoError>;
+
     async fn controllers_update(
         &self,
         canister_id: Principal,
         controllers: Vec<Principal>,
     ) -> Result<(), EgoError>;
+
+    async fn canister_settings_update(
+        &self,
+        canister_id: Principal,
+        settings: CanisterSettingsUpdate,
+    ) -> Result<(), EgoError>;
+
+    async fn canister_status_get(&self, canister_id: Principal) -> Result<StatusRecord, EgoError>;
+
+    fn canister_balance(&self) -> u128;
   }
 }
 
@@ -75,12 +96,20 @@ mock! {
 
   #[async_trait]
   impl TEgoStore for Store {
-    async fn wallet_cycle_charge(
+    async fn wallet_cycle_reserve(
       &self,
       canister_id: Principal,
       cycle: u128,
       comment: String,
-    ) -> Result<bool, EgoError>;
+    ) -> Result<u64, EgoError>;
+
+    fn wallet_cycle_commit(&self, charge_id: u64);
+
+    fn wallet_cycle_release(&self, charge_id: u64);
+
+    fn canister_alert_add(&self, canister_id: Principal, kind: AlertKind, message: String);
+
+    fn tenant_metric_report(&self, task_count: u64, dead_task_count: u64, cycles: u128, spend_per_day: u128, reserve_threshold: u128);
   }
 }
 
@@ -128,21 +157,23 @@ async fn canister_cycles_check_first_time() {
   let ts = 10u64;
   let cycle = 1_000_000u128;
 
-  let records = vec![CycleRecord { balance: cycle, ts }];
+  let records = vec![CycleRecord { balance: cycle, ts, heap_size: None, stable_size: None }];
 
   let mut task = Task::get(&canister_principal).unwrap();
 
   let mut management = MockManagement::new();
+  management.expect_canister_balance().returning(|| 10_000_000_000_000);
   let mut ego_store = MockStore::new();
   let mut ego_canister = MockCanister::new();
 
   ego_store
-    .expect_wallet_cycle_charge()
+    .expect_wallet_cycle_reserve()
     .returning(move |canister_id, cycle, _comment| {
       assert_eq!(canister_id, canister_principal);
       assert_eq!(2_000_000, cycle);
-      Ok(true)
+      Ok(1)
     });
+  ego_store.expect_wallet_cycle_commit().returning(|_| ());
 
   management
     .expect_canister_cycle_top_up()
@@ -191,26 +222,32 @@ async fn canister_cycles_check_second_time_zero_cycle_consumption() {
     CycleRecord {
       balance: cycle,
       ts: ts2,
+      heap_size: None,
+      stable_size: None,
     },
     CycleRecord {
       balance: cycle,
       ts: ts1,
+      heap_size: None,
+      stable_size: None,
     },
   ];
 
   let mut task = Task::get(&canister_principal).unwrap();
 
   let mut management = MockManagement::new();
+  management.expect_canister_balance().returning(|| 10_000_000_000_000);
   let mut ego_store = MockStore::new();
   let mut ego_canister = MockCanister::new();
 
   ego_store
-    .expect_wallet_cycle_charge()
+    .expect_wallet_cycle_reserve()
     .returning(move |wallet_id, cycle, _comment| {
       assert_eq!(wallet_principal, wallet_id);
       assert_eq!(2_000_000, cycle);
-      Ok(true)
+      Ok(1)
     });
+  ego_store.expect_wallet_cycle_commit().returning(|_| ());
 
   management
     .expect_canister_cycle_top_up()
@@ -259,26 +296,32 @@ async fn canister_cycles_check_second_time_none_zero_cycle_consumption() {
     CycleRecord {
       balance: cycle2,
       ts: ts2,
+      heap_size: None,
+      stable_size: None,
     },
     CycleRecord {
       balance: cycle1,
       ts: ts1,
+      heap_size: None,
+      stable_size: None,
     },
   ];
 
   let mut task = Task::get(&canister_principal).unwrap();
 
   let mut management = MockManagement::new();
+  management.expect_canister_balance().returning(|| 10_000_000_000_000);
   let mut ego_store = MockStore::new();
   let mut ego_canister = MockCanister::new();
 
   ego_store
-    .expect_wallet_cycle_charge()
+    .expect_wallet_cycle_reserve()
     .returning(move |canister_id, cycle, _comment| {
       assert_eq!(canister_principal, canister_id);
       assert_eq!(1_000_000, cycle);
-      Ok(true)
+      Ok(1)
     });
+  ego_store.expect_wallet_cycle_commit().returning(|_| ());
 
   management
     .expect_canister_cycle_top_up()
@@ -308,5 +351,595 @@ async fn canister_cycles_check_second_time_none_zero_cycle_consumption() {
 
   let task = Task::get(&canister_principal).unwrap();
 
-  assert_eq!(ts2 + NEXT_CHECK_DURATION, task.next_check_time);
+  // the balance reaches the threshold within seconds, check again as soon as allowed
+  assert_eq!(ts2 + MIN_CHECK_DURATION, task.next_check_time);
+  assert_eq!(50_000, task.forecast.unwrap().burn_per_second);
+}
+
+#[test]
+fn canister_top_up_policy_set() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+  let untracked_principal = Principal::from_text(TEST_CANISTER_ID.to_string()).unwrap();
+
+  let policy = TopUpPolicy {
+    min_balance: Some(2_000_000),
+    target_balance: Some(1_000_000),
+    max_daily_top_up: None,
+    alert_only: false,
+  };
+  let result = EgoTenantService::canister_top_up_policy_set(&canister_principal, Some(policy));
+  assert_eq!(4005, result.unwrap_err().code);
+
+  let result = EgoTenantService::canister_top_up_policy_set(&untracked_principal, Some(TopUpPolicy::default()));
+  assert_eq!(4004, result.unwrap_err().code);
+
+  let policy = TopUpPolicy {
+    min_balance: Some(2_000_000),
+    target_balance: Some(3_000_000),
+    max_daily_top_up: Some(500_000),
+    alert_only: false,
+  };
+  EgoTenantService::canister_top_up_policy_set(&canister_principal, Some(policy.clone())).unwrap();
+  assert_eq!(Some(policy), Task::get(&canister_principal).unwrap().top_up_policy);
+
+  EgoTenantService::canister_top_up_policy_set(&canister_principal, None).unwrap();
+  assert!(Task::get(&canister_principal).unwrap().top_up_policy.is_none());
+}
+
+#[tokio::test]
+async fn canister_cycles_check_daily_top_up_limit() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+
+  EgoTenantService::canister_top_up_policy_set(&canister_principal, Some(TopUpPolicy {
+    min_balance: Some(3_000_000),
+    target_balance: Some(4_000_000),
+    max_daily_top_up: Some(500_000),
+    alert_only: false,
+  })).unwrap();
+
+  let records = vec![CycleRecord { balance: 1_000_000, ts: 10, heap_size: None, stable_size: None }];
+
+  // the policy min balance applies, capped by the daily limit
+  let mut management = MockManagement::new();
+  management.expect_canister_balance().returning(|| 10_000_000_000_000);
+  let mut ego_store = MockStore::new();
+  let mut ego_canister = MockCanister::new();
+
+  ego_store
+    .expect_wallet_cycle_reserve()
+    .times(1)
+    .returning(|_canister_id, cycle, _comment| {
+      assert_eq!(500_000, cycle);
+      Ok(1)
+    });
+  ego_store.expect_wallet_cycle_commit().times(1).returning(|_| ());
+  management
+    .expect_canister_cycle_top_up()
+    .times(1)
+    .returning(|_canister_id, cycle| {
+      assert_eq!(500_000, cycle);
+      Ok(())
+    });
+  ego_canister.expect_ego_cycle_estimate_set().returning(|_, _| ());
+
+  let mut task = Task::get(&canister_principal).unwrap();
+  let _result = EgoTenantService::ego_cycle_check_cb(management, ego_store, ego_canister, &mut task, &canister_principal, &records, 1_000_000).await;
+
+  // nothing left for today
+  let mut management = MockManagement::new();
+  let mut ego_store = MockStore::new();
+  let mut ego_canister = MockCanister::new();
+
+  ego_store.expect_wallet_cycle_reserve().times(0);
+  ego_store
+    .expect_canister_alert_add()
+    .times(1)
+    .returning(|_canister_id, kind, _message| assert_eq!(AlertKind::LOW_BALANCE, kind));
+  management.expect_canister_cycle_top_up().times(0);
+  ego_canister.expect_ego_cycle_estimate_set().returning(|_, _| ());
+
+  let mut task = Task::get(&canister_principal).unwrap();
+  assert_eq!(500_000, task.top_up_usage.clone().unwrap().cycles);
+  let _result = EgoTenantService::ego_cycle_check_cb(management, ego_store, ego_canister, &mut task, &canister_principal, &records, 1_000_000).await;
+}
+
+#[tokio::test]
+async fn canister_cycles_check_alert_only() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+
+  EgoTenantService::canister_top_up_policy_set(&canister_principal, Some(TopUpPolicy {
+    alert_only: true,
+    ..TopUpPolicy::default()
+  })).unwrap();
+
+  let records = vec![CycleRecord { balance: 1_000_000, ts: 10, heap_size: None, stable_size: None }];
+
+  let mut management = MockManagement::new();
+  let mut ego_store = MockStore::new();
+  let mut ego_canister = MockCanister::new();
+
+  ego_store.expect_wallet_cycle_reserve().times(0);
+  ego_store
+    .expect_canister_alert_add()
+    .times(1)
+    .returning(|_canister_id, kind, _message| assert_eq!(AlertKind::LOW_BALANCE, kind));
+  management.expect_canister_cycle_top_up().times(0);
+  ego_canister.expect_ego_cycle_estimate_set().returning(|_, _| ());
+
+  let mut task = Task::get(&canister_principal).unwrap();
+  let _result = EgoTenantService::ego_cycle_check_cb(management, ego_store, ego_canister, &mut task, &canister_principal, &records, 2_000_000).await;
+
+  let mut task = Task::get(&canister_principal).unwrap();
+  let result = EgoTenantService::wallet_cycle_recharge(MockManagement::new(), &MockStore::new(), &mut task, 1_000_000).await;
+  assert_eq!(4006, result.unwrap_err().code);
+}
+
+#[tokio::test]
+async fn canister_cycles_check_top_up_failed() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+
+  let records = vec![CycleRecord { balance: 1_000_000, ts: 10, heap_size: None, stable_size: None }];
+
+  let mut management = MockManagement::new();
+  management.expect_canister_balance().returning(|| 10_000_000_000_000);
+  let mut ego_store = MockStore::new();
+  let mut ego_canister = MockCanister::new();
+
+  // the wallet can not pay for the top up
+  ego_store
+    .expect_wallet_cycle_reserve()
+    .times(1)
+    .returning(|_canister_id, _cycle, _comment| Err(EgoError::new(3003, "ego-store: cycles not enough")));
+  ego_store
+    .expect_canister_alert_add()
+    .times(1)
+    .returning(move |canister_id, kind, _message| {
+      assert_eq!(canister_principal, canister_id);
+      assert_eq!(AlertKind::TOP_UP_FAILED, kind);
+    });
+  management.expect_canister_cycle_top_up().times(0);
+  ego_canister.expect_ego_cycle_estimate_set().returning(|_, _| ());
+
+  let mut task = Task::get(&canister_principal).unwrap();
+  let _result = EgoTenantService::ego_cycle_check_cb(management, ego_store, ego_canister, &mut task, &canister_principal, &records, 2_000_000).await;
+
+  let task = Task::get(&canister_principal).unwrap();
+  assert_eq!(Some(10), task.last_check_time);
+}
+
+#[tokio::test]
+async fn canister_cycles_check_memory_limit() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+
+  // the heap grows 100 MiB a day, 200 MiB below the wasm32 limit
+  let heap_size = HEAP_LIMIT - 200 * 1024 * 1024;
+  let records = vec![
+    CycleRecord { balance: 10_000_000_000, ts: 86_400, heap_size: Some(heap_size), stable_size: Some(0) },
+    CycleRecord { balance: 10_000_000_000, ts: 0, heap_size: Some(heap_size - 100 * 1024 * 1024), stable_size: Some(0) },
+  ];
+
+  let management = MockManagement::new();
+  let mut ego_store = MockStore::new();
+  let mut ego_canister = MockCanister::new();
+
+  ego_store.expect_wallet_cycle_reserve().times(0);
+  ego_store
+    .expect_canister_alert_add()
+    .times(1)
+    .returning(move |canister_id, kind, _message| {
+      assert_eq!(canister_principal, canister_id);
+      assert_eq!(AlertKind::MEMORY_LIMIT, kind);
+    });
+  ego_canister.expect_ego_cycle_estimate_set().returning(|_, _| ());
+
+  let mut task = Task::get(&canister_principal).unwrap();
+  let _result = EgoTenantService::ego_cycle_check_cb(management, ego_store, ego_canister, &mut task, &canister_principal, &records, 1_000_000).await;
+}
+
+#[test]
+fn task_check() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+
+  // no alert before the last retry, each retry waits longer
+  let mut ego_store = MockStore::new();
+  ego_store.expect_canister_alert_add().times(0);
+  let mut ego_canister = MockCanister::new();
+  ego_canister.expect_ego_cycle_check().times(MAX_TRY_COUNT as usize).returning(|_| ());
+
+  let mut task = Task::get(&canister_principal).unwrap();
+  for _ in 0..MAX_TRY_COUNT {
+    EgoTenantService::task_check(&ego_store, &ego_canister, &mut task, 100);
+  }
+  let task_saved = Task::get(&canister_principal).unwrap();
+  assert_eq!(MAX_TRY_COUNT, task_saved.try_count);
+  assert_eq!(100 + (RETRY_DURATION << (MAX_TRY_COUNT - 1)), task_saved.next_check_time);
+
+  // nothing known about the balance
+  let mut ego_store = MockStore::new();
+  ego_store
+    .expect_canister_alert_add()
+    .times(1)
+    .returning(|_canister_id, kind, _message| assert_eq!(AlertKind::TASK_RETRIES_EXHAUSTED, kind));
+  let mut ego_canister = MockCanister::new();
+  ego_canister.expect_ego_cycle_check().times(0);
+
+  EgoTenantService::task_check(&ego_store, &ego_canister, &mut task, 100);
+  assert_eq!(1, Task::dead_list().len());
+  assert!(Task::by_sentinel(u64::MAX, 100).is_empty());
+
+  // requeued by the admin
+  let task = EgoTenantService::task_requeue(&canister_principal, 200).unwrap();
+  assert_eq!(0, task.try_count);
+  assert_eq!(1, Task::by_sentinel(200, 100).len());
+  assert!(Task::dead_list().is_empty());
+
+  // the last balance lasts 100 seconds, checked 200 seconds ago
+  let mut task = Task::get(&canister_principal).unwrap();
+  task.try_count = MAX_TRY_COUNT;
+  task.last_cycle = Some(1_000);
+  task.last_check_time = Some(100);
+  task.forecast = Some(CycleForecast {
+    burn_per_second: 10,
+    samples: 1,
+    confidence: 9,
+  });
+
+  let mut ego_store = MockStore::new();
+  ego_store
+    .expect_canister_alert_add()
+    .times(1)
+    .returning(|_canister_id, kind, _message| assert_eq!(AlertKind::CANISTER_FROZEN, kind));
+
+  EgoTenantService::task_check(&ego_store, &ego_canister, &mut task, 300);
+}
+
+#[test]
+fn task_batch_check() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+
+  let ego_store = MockStore::new();
+  let mut ego_canister = MockCanister::new();
+  ego_canister.expect_ego_cycle_check().times(1).returning(|_| ());
+
+  let tasks = EgoTenantService::task_batch_check(&ego_store, &ego_canister, 100, 10);
+  assert_eq!(vec![(canister_principal, false)], tasks);
+
+  // rescheduled already, an overlapping run gets nothing
+  let tasks = EgoTenantService::task_batch_check(&ego_store, &ego_canister, 100, 10);
+  assert!(tasks.is_empty());
+  assert_eq!(1, Task::get(&canister_principal).unwrap().try_count);
+}
+
+#[test]
+fn task_index_rebuild_dead() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+  let mut task = Task::get(&canister_principal).unwrap();
+  task.dead_letter(100);
+  task.save();
+
+  // the dead task stays out of the schedule, counted by the dead index
+  Task::index_rebuild();
+  assert!(Task::by_sentinel(u64::MAX, 100).is_empty());
+  assert_eq!(1, Task::dead_len());
+
+  // and leaves the dead index once requeued
+  EgoTenantService::task_requeue(&canister_principal, 200).unwrap();
+  assert_eq!(0, Task::dead_len());
+  assert!(Task::dead_list().is_empty());
+}
+
+#[test]
+fn task_requeue_not_exists() {
+  set_up();
+
+  let canister_principal = Principal::from_text(TEST_CANISTER_ID.to_string()).unwrap();
+
+  let result = EgoTenantService::task_requeue(&canister_principal, 200);
+  assert_eq!(4004, result.unwrap_err().code);
+}
+
+fn status_record(cycles: u128) -> StatusRecord {
+  StatusRecord {
+    ts: 100,
+    status: CanisterRunStatus::RUNNING,
+    cycles,
+    memory_size: 1024,
+    idle_burn_per_day: 86_400,
+    freezing_threshold: 1_000,
+    memory_allocation: None,
+  }
+}
+
+#[tokio::test]
+async fn canister_status_check_answering() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+
+  // the canister answers ego_cycle_check, the status is only recorded
+  let mut management = MockManagement::new();
+  management.expect_canister_status_get().returning(|_| Ok(status_record(500)));
+  management.expect_canister_cycle_top_up().times(0);
+  let mut ego_store = MockStore::new();
+  ego_store.expect_wallet_cycle_reserve().times(0);
+
+  let record = EgoTenantService::canister_status_check(management, &ego_store, &canister_principal, false, 100).await.unwrap();
+  assert!(record.is_frozen());
+
+  let history = StatusHistory::get(&canister_principal).unwrap();
+  assert_eq!(1, history.records.len());
+  assert!(history.unresponsive_since.is_none());
+
+  // not a controller of the canister
+  let mut management = MockManagement::new();
+  management.expect_canister_status_get().returning(|_| Err(EgoError::new(5, "not a controller")));
+
+  let result = EgoTenantService::canister_status_check(management, &ego_store, &canister_principal, false, 200).await;
+  assert_eq!(5, result.unwrap_err().code);
+  assert_eq!(1, StatusHistory::get(&canister_principal).unwrap().records.len());
+  assert_eq!(Some(false), Task::get(&canister_principal).unwrap().controller);
+
+  // still checked for cycles, but its status is not polled anymore
+  let mut ego_canister = MockCanister::new();
+  ego_canister.expect_ego_cycle_check().times(1).returning(|_| ());
+  assert!(EgoTenantService::task_batch_check(&ego_store, &ego_canister, u64::MAX, 10).is_empty());
+
+  // until it is requeued
+  EgoTenantService::task_requeue(&canister_principal, 300).unwrap();
+  assert_eq!(None, Task::get(&canister_principal).unwrap().controller);
+}
+
+#[tokio::test]
+async fn canister_status_check_unresponsive() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+
+  let mut task = Task::get(&canister_principal).unwrap();
+  task.try_count = 1;
+  task.last_threshold = Some(2_000_000);
+  task.save();
+
+  // topped up by the status instead of the cycle check
+  let mut management = MockManagement::new();
+  management.expect_canister_balance().returning(|| 10_000_000_000_000);
+  management.expect_canister_status_get().returning(|_| Ok(status_record(1_000_000)));
+  management
+    .expect_canister_cycle_top_up()
+    .times(1)
+    .returning(|_canister_id, cycle| {
+      assert_eq!(2_000_000, cycle);
+      Ok(())
+    });
+  let mut ego_store = MockStore::new();
+  ego_store.expect_wallet_cycle_reserve().times(1).returning(|_, _, _| Ok(1));
+  ego_store.expect_wallet_cycle_commit().times(1).returning(|_| ());
+
+  EgoTenantService::canister_status_check(management, &ego_store, &canister_principal, true, 100).await.unwrap();
+
+  let history = StatusHistory::get(&canister_principal).unwrap();
+  assert_eq!(Some(100), history.unresponsive_since);
+  assert_eq!(1, StatusHistory::unresponsive().len());
+
+  // answering again clears the flag
+  let mut ego_canister = MockCanister::new();
+  ego_canister.expect_ego_cycle_estimate_set().returning(|_, _| ());
+
+  let records = vec![CycleRecord { balance: 3_000_000, ts: 200, heap_size: None, stable_size: None }];
+  let mut task = Task::get(&canister_principal).unwrap();
+  let _result = EgoTenantService::ego_cycle_check_cb(MockManagement::new(), MockStore::new(), ego_canister, &mut task, &canister_principal, &records, 2_000_000).await;
+
+  assert!(StatusHistory::get(&canister_principal).unwrap().unresponsive_since.is_none());
+  assert!(StatusHistory::unresponsive().is_empty());
+}
+
+#[test]
+fn task_check_frozen_by_status() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+
+  let mut history = StatusHistory::new(&canister_principal);
+  history.record_add(status_record(500));
+  history.save();
+
+  let mut task = Task::get(&canister_principal).unwrap();
+  task.try_count = MAX_TRY_COUNT;
+
+  let mut ego_store = MockStore::new();
+  ego_store
+    .expect_canister_alert_add()
+    .times(1)
+    .returning(|_canister_id, kind, _message| assert_eq!(AlertKind::CANISTER_FROZEN, kind));
+  let ego_canister = MockCanister::new();
+
+  EgoTenantService::task_check(&ego_store, &ego_canister, &mut task, 300);
+
+  // untracked with its history
+  EgoTenantService::canister_main_untrack(&canister_principal);
+  assert!(StatusHistory::get(&canister_principal).is_none());
+}
+
+#[tokio::test]
+async fn canister_main_start_requeue() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+
+  let mut task = Task::get(&canister_principal).unwrap();
+  task.try_count = MAX_TRY_COUNT;
+  task.dead_letter(100);
+  task.save();
+
+  let mut management = MockManagement::new();
+  management
+    .expect_canister_main_start()
+    .times(1)
+    .returning(move |canister_id| {
+      assert_eq!(canister_principal, canister_id);
+      Ok(())
+    });
+
+  EgoTenantService::canister_main_start(management, &canister_principal, 200).await.unwrap();
+
+  // checked again right away
+  let task = Task::get(&canister_principal).unwrap();
+  assert_eq!(None, task.dead_at);
+  assert_eq!(0, task.try_count);
+  assert_eq!(200, task.next_check_time);
+}
+
+#[tokio::test]
+async fn canister_settings_update() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+
+  // nothing to update, or more than the whole compute
+  for settings in [
+    CanisterSettingsUpdate::default(),
+    CanisterSettingsUpdate { compute_allocation: Some(101), ..CanisterSettingsUpdate::default() },
+  ] {
+    let mut management = MockManagement::new();
+    management.expect_canister_settings_update().times(0);
+    let result = EgoTenantService::canister_settings_update(management, &canister_principal, settings).await;
+    assert_eq!(4008, result.unwrap_err().code);
+  }
+
+  let settings = CanisterSettingsUpdate { freezing_threshold: Some(7_776_000), ..CanisterSettingsUpdate::default() };
+  let expected = settings.clone();
+
+  let mut management = MockManagement::new();
+  management
+    .expect_canister_settings_update()
+    .times(1)
+    .returning(move |canister_id, settings| {
+      assert_eq!(canister_principal, canister_id);
+      assert_eq!(expected, settings);
+      Ok(())
+    });
+  let result = EgoTenantService::canister_settings_update(management, &canister_principal, settings).await;
+  assert!(result.is_ok());
+}
+
+#[test]
+fn tenant_metric_report() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+  let mut task = Task::get(&canister_principal).unwrap();
+  task.dead_letter(100);
+  task.save();
+  EgoTenantService::canister_main_track(&Principal::from_text(TEST_CANISTER_ID.to_string()).unwrap(), 0);
+
+  let mut ego_store = MockStore::new();
+  ego_store
+    .expect_tenant_metric_report()
+    .times(1)
+    .returning(|task_count, dead_task_count, cycles, spend_per_day, reserve_threshold| {
+      assert_eq!(2, task_count);
+      assert_eq!(1, dead_task_count);
+      assert_eq!(5_000_000, cycles);
+      assert_eq!(0, spend_per_day);
+      assert_eq!(RESERVE_FLOOR * 2, reserve_threshold);
+    });
+
+  EgoTenantService::tenant_metric_report(&ego_store, 5_000_000, 100);
+}
+
+#[tokio::test]
+async fn wallet_cycle_recharge_reserve() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+  let mut task = Task::get(&canister_principal).unwrap();
+
+  // the wallet is not charged for a top up the tenant can not pay
+  let mut management = MockManagement::new();
+  management.expect_canister_balance().returning(|| RESERVE_FLOOR + 1_000_000);
+  management.expect_canister_cycle_top_up().times(0);
+  let mut ego_store = MockStore::new();
+  ego_store.expect_wallet_cycle_reserve().times(0);
+
+  let result = EgoTenantService::wallet_cycle_recharge(management, &ego_store, &mut task, 2_000_000).await;
+  assert_eq!(4009, result.unwrap_err().code);
+
+  // a paid top up counts into the spending reported
+  let mut management = MockManagement::new();
+  management.expect_canister_balance().returning(|| 10_000_000_000_000);
+  management.expect_canister_cycle_top_up().times(1).returning(|_, _| Ok(()));
+  let mut ego_store = MockStore::new();
+  ego_store.expect_wallet_cycle_reserve().times(1).returning(|_, _, _| Ok(1));
+  ego_store.expect_wallet_cycle_commit().times(1).returning(|_| ());
+
+  EgoTenantService::wallet_cycle_recharge(management, &ego_store, &mut task, 2_000_000).await.unwrap();
+
+  let reserve = CycleReserve::get();
+  assert_eq!(2_000_000, reserve.spend_per_day(time()));
+  assert_eq!(RESERVE_FLOOR * 2 + 2_000_000 * RESERVE_DAYS, reserve.threshold(time()));
+}
+
+#[tokio::test]
+async fn wallet_cycle_recharge_two_phase() {
+  set_up();
+
+  let canister_principal = Principal::from_text(EXISTS_CANISTER_ID.to_string()).unwrap();
+  let mut task = Task::get(&canister_principal).unwrap();
+
+  // a failed top up releases the charge
+  let mut management = MockManagement::new();
+  management.expect_canister_balance().returning(|| 10_000_000_000_000);
+  management.expect_canister_cycle_top_up().times(1).returning(|_, _| Err(EgoError::new(255, "top up failed")));
+  let mut ego_store = MockStore::new();
+  ego_store.expect_wallet_cycle_reserve().times(1).returning(|_, _, _| Ok(7));
+  ego_store.expect_wallet_cycle_commit().times(0);
+  ego_store.expect_wallet_cycle_release().times(1).returning(|charge_id| assert_eq!(7, charge_id));
+
+  let result = EgoTenantService::wallet_cycle_recharge(management, &ego_store, &mut task, 2_000_000).await;
+  assert!(result.is_err());
+  assert!(TopUpLog::get(7).is_none());
+
+  // a top up gone through is logged and commits the charge
+  let mut management = MockManagement::new();
+  management.expect_canister_balance().returning(|| 10_000_000_000_000);
+  management.expect_canister_cycle_top_up().times(1).returning(|_, _| Ok(()));
+  let mut ego_store = MockStore::new();
+  ego_store.expect_wallet_cycle_reserve().times(1).returning(|_, _, _| Ok(8));
+  ego_store.expect_wallet_cycle_commit().times(1).returning(|charge_id| assert_eq!(8, charge_id));
+  ego_store.expect_wallet_cycle_release().times(0);
+
+  EgoTenantService::wallet_cycle_recharge(management, &ego_store, &mut task, 2_000_000).await.unwrap();
+
+  let log = TopUpLog::get(8).unwrap();
+  assert_eq!(canister_principal, log.canister_id);
+  assert_eq!(2_000_000, log.cycles);
+
+  // ego_store reconciles against the charges logged
+  assert_eq!(vec![8], EgoTenantService::top_up_list(&[7, 8]));
+
+  // only the logs created before the time are dropped
+  let mut newer = TopUpLog::new(9, &canister_principal, 1_000);
+  newer.created_at = log.created_at + 10;
+  newer.save();
+
+  TopUpLog::prune(log.created_at + 1);
+  assert!(EgoTenantService::top_up_list(&[8]).is_empty());
+  assert_eq!(vec![9], EgoTenantService::top_up_list(&[9]));
+  assert_eq!(1, TopUpLog::len());
 }
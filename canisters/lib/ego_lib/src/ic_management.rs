@@ -10,14 +10,25 @@ pub async fn controllers_update(
   canister_id: Principal,
   controllers: Vec<Principal>,
 ) -> Result<(), EgoError> {
-  let in_arg = UpdateSettingsArgument {
+  settings_update(
     canister_id,
-    settings: CanisterSettings {
+    CanisterSettings {
       controllers: Some(controllers),
       compute_allocation: None,
       memory_allocation: None,
       freezing_threshold: None,
     },
+  ).await
+}
+
+// the settings left None are kept as they are
+pub async fn settings_update(
+  canister_id: Principal,
+  settings: CanisterSettings,
+) -> Result<(), EgoError> {
+  let in_arg = UpdateSettingsArgument {
+    canister_id,
+    settings,
   };
 
   match update_settings(in_arg).await {
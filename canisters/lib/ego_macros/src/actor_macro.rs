@@ -282,7 +282,7 @@ macro_rules! inject_app_info_api {
             let ego_store = EgoStore::new(ego_store_id);
 
             ego_store
-                .wallet_app_upgrade_v2(AppUpgradeRequest{wallet_id: app_info.wallet_id.unwrap()})
+                .wallet_app_upgrade_v2(AppUpgradeRequest{wallet_id: app_info.wallet_id.unwrap(), version_req: None})
                 .await;
 
             Ok(())
@@ -365,7 +365,14 @@ macro_rules! inject_cycle_info_api {
             let balance = ic_cdk::api::canister_balance128();
             let ts = ic_cdk::api::time().div(1e9 as u64);
 
-            cycle_record_add(balance, ts);
+            // both counted in 64KiB wasm pages
+            #[cfg(target_arch = "wasm32")]
+            let heap_pages = core::arch::wasm32::memory_size(0) as u64;
+            #[cfg(not(target_arch = "wasm32"))]
+            let heap_pages = 0u64;
+            let stable_pages = ic_cdk::api::stable::stable64_size();
+
+            cycle_memory_record_add(balance, ts, heap_pages * 65536, stable_pages * 65536);
 
             let ego_tenant_id = canister_get_one("ego_tenant").unwrap();
             let ego_tenant = EgoTenant::new(ego_tenant_id);
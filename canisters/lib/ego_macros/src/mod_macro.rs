@@ -50,6 +50,12 @@ macro_rules! inject_cycle_info {
             })
         }
 
+        pub fn cycle_memory_record_add(balance: u128, ts: u64, heap_size: u64, stable_size: u64) {
+            CYCLE_INFO.with(|cycle_info| {
+                cycle_info.borrow_mut().memory_record_add(balance, ts, heap_size, stable_size);
+            })
+        }
+
         pub fn cycle_record_list() -> Vec<CycleRecord> {
             CYCLE_INFO.with(|cycle_info| cycle_info.borrow().record_list())
         }
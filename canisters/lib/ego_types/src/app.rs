@@ -93,10 +93,52 @@ impl ToString for Version {
   }
 }
 
-#[derive(Clone, Debug, CandidType, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, CandidType, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Category {
   System,
   Vault,
+  Finance,
+  Social,
+  Game,
+  Tool,
+  Media,
+  Other,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReleaseNote {
+  pub version: Version,
+  pub notes: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AuditDecision {
+  APPROVE,
+  REJECT,
+}
+
+// an auditor's review of an app version, published with the released version
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AppAuditReview {
+  pub auditor_id: Principal,
+  pub decision: AuditDecision,
+  pub comment: String,
+  // hash or url of the full audit report
+  pub report: Option<String>,
+  pub created_at: u64,
+  // whether the auditor rebuilt the wasm from source and got the same sha256
+  pub reproduced: Option<bool>,
+}
+
+// developer defined catalog information, kept apart from App so the released App stays small
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct AppMetadata {
+  pub tags: Vec<String>,
+  pub screenshots: Vec<String>,
+  pub media: Vec<String>,
+  pub homepage: Option<String>,
+  pub support_link: Option<String>,
+  pub release_notes: Vec<ReleaseNote>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -251,10 +293,31 @@ pub struct CashFlow {
   pub comment: String,
 }
 
+#[allow(non_camel_case_types)]
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum CashFlowType {
   CHARGE,
   RECHARGE,
+  TRANSFER_IN,
+  TRANSFER_OUT,
+  WITHDRAW,
+  WITHDRAW_FEE,
+}
+
+/// the alerts ego_tenant raises for the canisters it tracks
+#[allow(non_camel_case_types)]
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AlertKind {
+  // under the min balance and not topped up by the top up policy
+  LOW_BALANCE,
+  // the wallet could not pay for the top up
+  TOP_UP_FAILED,
+  // stopped answering the cycle checks after burning out its forecast balance
+  CANISTER_FROZEN,
+  // stopped answering the cycle checks
+  TASK_RETRIES_EXHAUSTED,
+  // the memory is projected to reach its limit soon
+  MEMORY_LIMIT,
 }
 
 impl CashFlow {
@@ -7,6 +7,9 @@ pub const DEFAULT_ESTIMATE: u64 = 86400 * 7;
 pub struct CycleRecord {
   pub balance: u128,
   pub ts: u64, // timestamp in seconds
+  // bytes, None from the canisters built before memory was sampled
+  pub heap_size: Option<u64>,
+  pub stable_size: Option<u64>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -34,7 +37,15 @@ impl CycleInfo {
   }
 
   pub fn record_add(&mut self, balance: u128, ts: u64) {
-    self.records.insert(0, CycleRecord { balance, ts });
+    self.record_insert(CycleRecord { balance, ts, heap_size: None, stable_size: None });
+  }
+
+  pub fn memory_record_add(&mut self, balance: u128, ts: u64, heap_size: u64, stable_size: u64) {
+    self.record_insert(CycleRecord { balance, ts, heap_size: Some(heap_size), stable_size: Some(stable_size) });
+  }
+
+  fn record_insert(&mut self, record: CycleRecord) {
+    self.records.insert(0, record);
     if self.records.len() > 12 {
       self.records.truncate(12);
     }
@@ -5,4 +5,5 @@ pub mod log;
 pub mod registry;
 pub mod user;
 pub mod types;
-pub mod seq;
\ No newline at end of file
+pub mod seq;
+pub mod semver;
\ No newline at end of file
@@ -0,0 +1,353 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::app::Version;
+
+/// a semver 2.0 version, the numeric core plus the pre-release and build labels
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SemVer {
+  pub major: u32,
+  pub minor: u32,
+  pub patch: u32,
+  pub pre_release: Vec<String>,
+  pub build: Vec<String>,
+}
+
+impl SemVer {
+  pub fn new(version: &Version, pre_release: &Option<String>, build: &Option<String>) -> Result<Self, String> {
+    let mut semver = SemVer::from(*version);
+    if let Some(pre_release) = pre_release {
+      semver.pre_release = parse_identifiers(pre_release, true)?;
+    }
+    if let Some(build) = build {
+      semver.build = parse_identifiers(build, false)?;
+    }
+    Ok(semver)
+  }
+
+  pub fn core(&self) -> Version {
+    Version::new(self.major, self.minor, self.patch)
+  }
+
+  pub fn is_pre_release(&self) -> bool {
+    !self.pre_release.is_empty()
+  }
+
+  pub fn pre_release_label(&self) -> Option<String> {
+    match self.is_pre_release() {
+      true => Some(self.pre_release.join(".")),
+      false => None,
+    }
+  }
+
+  pub fn build_label(&self) -> Option<String> {
+    match self.build.is_empty() {
+      true => None,
+      false => Some(self.build.join(".")),
+    }
+  }
+
+  /// the semver ordering, build metadata is ignored and a pre-release
+  /// sorts before the release of the same core
+  pub fn cmp_precedence(&self, other: &SemVer) -> Ordering {
+    self.core().cmp(&other.core()).then_with(|| {
+      match (self.is_pre_release(), other.is_pre_release()) {
+        (false, false) => Ordering::Equal,
+        (false, true) => Ordering::Greater,
+        (true, false) => Ordering::Less,
+        (true, true) => cmp_identifiers(&self.pre_release, &other.pre_release),
+      }
+    })
+  }
+}
+
+impl From<Version> for SemVer {
+  fn from(version: Version) -> Self {
+    SemVer {
+      major: version.major,
+      minor: version.minor,
+      patch: version.patch,
+      pre_release: vec![],
+      build: vec![],
+    }
+  }
+}
+
+impl FromStr for SemVer {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (rest, build) = match s.split_once('+') {
+      Some((rest, build)) => (rest, parse_identifiers(build, false)?),
+      None => (s, vec![]),
+    };
+    let (core, pre_release) = match rest.split_once('-') {
+      Some((core, pre_release)) => (core, parse_identifiers(pre_release, true)?),
+      None => (rest, vec![]),
+    };
+
+    let parts: Vec<_> = core.split('.').collect();
+    if parts.len() != 3 {
+      return Err(format!("Unable to parse version: {}", s));
+    }
+
+    Ok(SemVer {
+      major: parse_number(parts[0])?,
+      minor: parse_number(parts[1])?,
+      patch: parse_number(parts[2])?,
+      pre_release,
+      build,
+    })
+  }
+}
+
+impl fmt::Display for SemVer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+    if let Some(pre_release) = self.pre_release_label() {
+      write!(f, "-{}", pre_release)?;
+    }
+    if let Some(build) = self.build_label() {
+      write!(f, "+{}", build)?;
+    }
+    Ok(())
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+  Exact,
+  Greater,
+  GreaterEq,
+  Less,
+  LessEq,
+  Tilde,
+  Caret,
+  Wildcard,
+}
+
+/// one comparator of a requirement, minor and patch are optional ("^1", "~1.2", "1.*")
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comparator {
+  pub op: Op,
+  pub major: u32,
+  pub minor: Option<u32>,
+  pub patch: Option<u32>,
+  pub pre_release: Vec<String>,
+}
+
+impl Comparator {
+  pub fn matches(&self, semver: &SemVer) -> bool {
+    match self.op {
+      Op::Exact | Op::Wildcard => self.matches_exact(semver),
+      Op::Greater => self.matches_greater(semver),
+      Op::GreaterEq => self.matches_exact(semver) || self.matches_greater(semver),
+      Op::Less => !self.matches_exact(semver) && !self.matches_greater(semver),
+      Op::LessEq => !self.matches_greater(semver),
+      Op::Tilde => self.matches_tilde(semver),
+      Op::Caret => self.matches_caret(semver),
+    }
+  }
+
+  fn matches_exact(&self, semver: &SemVer) -> bool {
+    semver.major == self.major
+      && self.minor.map_or(true, |minor| semver.minor == minor)
+      && self.patch.map_or(true, |patch| semver.patch == patch)
+      && (self.patch.is_none() || semver.pre_release == self.pre_release)
+  }
+
+  fn matches_greater(&self, semver: &SemVer) -> bool {
+    if semver.major != self.major {
+      return semver.major > self.major;
+    }
+    match self.minor {
+      None => return false,
+      Some(minor) if semver.minor != minor => return semver.minor > minor,
+      _ => {}
+    }
+    match self.patch {
+      None => return false,
+      Some(patch) if semver.patch != patch => return semver.patch > patch,
+      _ => {}
+    }
+    cmp_pre_release(&semver.pre_release, &self.pre_release) == Ordering::Greater
+  }
+
+  fn matches_tilde(&self, semver: &SemVer) -> bool {
+    semver.major == self.major
+      && self.minor.map_or(true, |minor| semver.minor == minor)
+      && self.patch.map_or(true, |patch| semver.patch >= patch)
+      && (self.patch != Some(semver.patch) || cmp_pre_release(&semver.pre_release, &self.pre_release) != Ordering::Less)
+  }
+
+  fn matches_caret(&self, semver: &SemVer) -> bool {
+    if semver.major != self.major {
+      return false;
+    }
+    let minor = match self.minor {
+      None => return true,
+      Some(minor) => minor,
+    };
+    let patch = match self.patch {
+      None => return match self.major {
+        0 => semver.minor == minor,
+        _ => semver.minor >= minor,
+      },
+      Some(patch) => patch,
+    };
+
+    let above_patch = semver.patch > patch
+      || (semver.patch == patch && cmp_pre_release(&semver.pre_release, &self.pre_release) != Ordering::Less);
+    match (self.major, minor) {
+      (0, 0) => semver.minor == 0 && semver.patch == patch && cmp_pre_release(&semver.pre_release, &self.pre_release) != Ordering::Less,
+      (0, _) => semver.minor == minor && above_patch,
+      _ => semver.minor > minor || (semver.minor == minor && above_patch),
+    }
+  }
+
+  fn same_core(&self, semver: &SemVer) -> bool {
+    self.major == semver.major && self.minor == Some(semver.minor) && self.patch == Some(semver.patch)
+  }
+}
+
+impl FromStr for Comparator {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let s = s.trim();
+    let (op, rest) = [
+      (">=", Op::GreaterEq),
+      ("<=", Op::LessEq),
+      (">", Op::Greater),
+      ("<", Op::Less),
+      ("=", Op::Exact),
+      ("~", Op::Tilde),
+      ("^", Op::Caret),
+    ].into_iter().find_map(|(prefix, op)| s.strip_prefix(prefix).map(|rest| (Some(op), rest.trim())))
+      .unwrap_or((None, s));
+
+    let (rest, pre_release) = match rest.split_once('-') {
+      Some((rest, pre_release)) => (rest, parse_identifiers(pre_release, true)?),
+      None => (rest, vec![]),
+    };
+
+    let mut parts = rest.split('.');
+    let mut wildcard = false;
+    let mut next_part = |wildcard: &mut bool| -> Result<Option<u32>, String> {
+      match parts.next() {
+        None => Ok(None),
+        Some("*") | Some("x") | Some("X") => {
+          *wildcard = true;
+          Ok(None)
+        }
+        Some(part) if *wildcard => Err(format!("Unable to parse version requirement: {}", part)),
+        Some(part) => parse_number(part).map(Some),
+      }
+    };
+
+    let major = next_part(&mut wildcard)?;
+    let minor = next_part(&mut wildcard)?;
+    let patch = next_part(&mut wildcard)?;
+    if parts.next().is_some() {
+      return Err(format!("Unable to parse version requirement: {}", s));
+    }
+    if !pre_release.is_empty() && patch.is_none() {
+      return Err(format!("Unable to parse version requirement: {}", s));
+    }
+
+    let major = match major {
+      Some(major) => major,
+      None => return Err(format!("Unable to parse version requirement: {}", s)),
+    };
+    let op = match op {
+      Some(op) => op,
+      None if wildcard => Op::Wildcard,
+      None => Op::Caret,
+    };
+
+    Ok(Comparator { op, major, minor, patch, pre_release })
+  }
+}
+
+/// a version requirement like "^1.2", ">=1.0.0, <2.0.0", "~1.4.2" or "1.*",
+/// a bare version means caret, like in cargo
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VersionReq {
+  pub comparators: Vec<Comparator>,
+}
+
+impl VersionReq {
+  /// all comparators have to match, a pre-release only matches when one of
+  /// the comparators names a pre-release of the same core
+  pub fn matches(&self, semver: &SemVer) -> bool {
+    self.comparators.iter().all(|comparator| comparator.matches(semver))
+      && (!semver.is_pre_release() || self.comparators.iter().any(|comparator| {
+      comparator.same_core(semver) && !comparator.pre_release.is_empty()
+    }))
+  }
+
+  pub fn matches_version(&self, version: &Version) -> bool {
+    self.matches(&SemVer::from(*version))
+  }
+}
+
+impl FromStr for VersionReq {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let s = s.trim();
+    if s == "*" || s.is_empty() {
+      return Ok(VersionReq { comparators: vec![] });
+    }
+
+    let comparators = s.split(',').map(Comparator::from_str).collect::<Result<Vec<_>, _>>()?;
+    Ok(VersionReq { comparators })
+  }
+}
+
+fn parse_number(s: &str) -> Result<u32, String> {
+  if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) || (s.len() > 1 && s.starts_with('0')) {
+    return Err(format!("Unable to parse version number: {}", s));
+  }
+  u32::from_str(s).map_err(|e| e.to_string())
+}
+
+fn parse_identifiers(s: &str, numeric_check: bool) -> Result<Vec<String>, String> {
+  s.split('.').map(|identifier| {
+    if identifier.is_empty() || !identifier.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-') {
+      return Err(format!("Unable to parse version identifier: {}", s));
+    }
+    if numeric_check && identifier.bytes().all(|b| b.is_ascii_digit()) && identifier.len() > 1 && identifier.starts_with('0') {
+      return Err(format!("Unable to parse version identifier: {}", s));
+    }
+    Ok(identifier.to_string())
+  }).collect()
+}
+
+// an empty pre-release is a release, which is greater than any pre-release
+fn cmp_pre_release(a: &[String], b: &[String]) -> Ordering {
+  match (a.is_empty(), b.is_empty()) {
+    (true, true) => Ordering::Equal,
+    (true, false) => Ordering::Greater,
+    (false, true) => Ordering::Less,
+    (false, false) => cmp_identifiers(a, b),
+  }
+}
+
+// numeric identifiers compare as numbers and sort before alphanumeric ones,
+// a longer list wins when all the preceding identifiers are equal
+fn cmp_identifiers(a: &[String], b: &[String]) -> Ordering {
+  for (x, y) in a.iter().zip(b.iter()) {
+    let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+      (Ok(x), Ok(y)) => x.cmp(&y),
+      (Ok(_), Err(_)) => Ordering::Less,
+      (Err(_), Ok(_)) => Ordering::Greater,
+      (Err(_), Err(_)) => x.cmp(y),
+    };
+    if ordering != Ordering::Equal {
+      return ordering;
+    }
+  }
+  a.len().cmp(&b.len())
+}
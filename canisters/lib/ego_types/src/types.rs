@@ -7,6 +7,8 @@ use crate::app::AppId;
 #[derive(CandidType, Deserialize, Serialize)]
 pub struct AppInstallRequest {
   pub app_id: AppId,
+  // like "^1.2", the app's current version has to match it
+  pub version_req: Option<String>,
 }
 
 #[derive(CandidType, Deserialize, Serialize)]
@@ -17,9 +19,13 @@ pub struct AppReInstallRequest {
 #[derive(CandidType, Deserialize, Serialize)]
 pub struct AppUpgradeRequest {
   pub wallet_id: Principal,
+  // like "^1.2", the app's current version has to match it
+  pub version_req: Option<String>,
 }
 
 #[derive(CandidType, Deserialize, Serialize)]
 pub struct WalletUpgradeAppRequest {
   pub canister_id: Principal,
+  // like "^1.2", the app's current version has to match it
+  pub version_req: Option<String>,
 }
\ No newline at end of file
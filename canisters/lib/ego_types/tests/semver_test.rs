@@ -0,0 +1,104 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use ego_types::app::Version;
+use ego_types::semver::{SemVer, VersionReq};
+
+fn semver(s: &str) -> SemVer {
+  SemVer::from_str(s).unwrap()
+}
+
+fn matches(req: &str, version: &str) -> bool {
+  VersionReq::from_str(req).unwrap().matches(&semver(version))
+}
+
+#[test]
+fn parse_test() {
+  let version = semver("1.2.3-alpha.1+build.5");
+  assert_eq!(Version::new(1, 2, 3), version.core());
+  assert_eq!(Some("alpha.1".to_string()), version.pre_release_label());
+  assert_eq!(Some("build.5".to_string()), version.build_label());
+  assert_eq!("1.2.3-alpha.1+build.5", version.to_string());
+
+  assert!(!semver("1.2.3").is_pre_release());
+}
+
+#[test]
+fn parse_failed_test() {
+  assert!(SemVer::from_str("1.2").is_err());
+  assert!(SemVer::from_str("01.2.3").is_err());
+  assert!(SemVer::from_str("1.2.3-01").is_err());
+  assert!(SemVer::from_str("1.2.3-").is_err());
+  assert!(SemVer::from_str("1.2.3-al_pha").is_err());
+  assert!(SemVer::from_str("1.2.3+").is_err());
+}
+
+#[test]
+fn precedence_test() {
+  let versions = [
+    "1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-alpha.beta", "1.0.0-beta", "1.0.0-beta.2",
+    "1.0.0-beta.11", "1.0.0-rc.1", "1.0.0", "1.0.1", "2.0.0",
+  ];
+  versions.windows(2).for_each(|pair| {
+    assert_eq!(Ordering::Less, semver(pair[0]).cmp_precedence(&semver(pair[1])));
+  });
+
+  // build metadata is ignored
+  assert_eq!(Ordering::Equal, semver("1.0.0+a").cmp_precedence(&semver("1.0.0+b")));
+}
+
+#[test]
+fn caret_test() {
+  assert!(matches("^1.2", "1.2.0"));
+  assert!(matches("^1.2", "1.9.9"));
+  assert!(!matches("^1.2", "1.1.9"));
+  assert!(!matches("^1.2", "2.0.0"));
+
+  // a bare version means caret
+  assert!(matches("1.2.3", "1.4.0"));
+  assert!(!matches("1.2.3", "1.2.2"));
+
+  assert!(matches("^0.2.3", "0.2.9"));
+  assert!(!matches("^0.2.3", "0.3.0"));
+  assert!(matches("^0.0.3", "0.0.3"));
+  assert!(!matches("^0.0.3", "0.0.4"));
+}
+
+#[test]
+fn tilde_and_wildcard_test() {
+  assert!(matches("~1.2.3", "1.2.9"));
+  assert!(!matches("~1.2.3", "1.3.0"));
+  assert!(matches("~1", "1.9.0"));
+
+  assert!(matches("1.*", "1.7.2"));
+  assert!(!matches("1.*", "2.0.0"));
+  assert!(matches("*", "3.0.0"));
+}
+
+#[test]
+fn range_test() {
+  assert!(matches(">=1.0.0, <2.0.0", "1.5.0"));
+  assert!(!matches(">=1.0.0, <2.0.0", "2.0.0"));
+  assert!(matches("=1.2.3", "1.2.3"));
+  assert!(!matches("=1.2.3", "1.2.4"));
+  assert!(matches("<1.2", "1.1.5"));
+  assert!(matches("<=1.2", "1.2.5"));
+  assert!(!matches(">1.2", "1.2.9"));
+}
+
+#[test]
+fn pre_release_test() {
+  // pre-releases have to be asked for explicitly
+  assert!(!matches("^1.2", "1.3.0-beta"));
+  assert!(matches("^1.3.0-beta", "1.3.0-beta.2"));
+  assert!(matches("^1.3.0-beta", "1.3.0"));
+  assert!(!matches("^1.3.0-beta", "1.3.1-beta"));
+}
+
+#[test]
+fn req_parse_failed_test() {
+  assert!(VersionReq::from_str("abc").is_err());
+  assert!(VersionReq::from_str("^1.x.2").is_err());
+  assert!(VersionReq::from_str("^1.2-beta").is_err());
+  assert!(VersionReq::from_str(">=1.0.0,").is_err());
+}
@@ -1,5 +1,5 @@
 use candid::{CandidType, Principal};
-use ic_cdk::api::management_canister::main::{CanisterInstallMode, CanisterSettings, create_canister, CreateCanisterArgument, delete_canister, deposit_cycles, install_code, InstallCodeArgument, stop_canister};
+use ic_cdk::api::management_canister::main::{CanisterInstallMode, CanisterSettings, create_canister, CreateCanisterArgument, delete_canister, deposit_cycles, install_code, InstallCodeArgument, start_canister, stop_canister};
 use ic_cdk::api::management_canister::provisional::CanisterIdRecord;
 
 use ego_types::app::EgoError;
@@ -54,15 +54,29 @@ pub async fn canister_main_create(cycles_to_use: Cycles) -> Result<Principal, Eg
   }
 }
 
-pub async fn canister_main_delete(canister_id: Principal) -> Result<(), EgoError> {
-  // stop the canister
-  let _stop_result = match stop_canister(CanisterIdRecord { canister_id }).await {
+pub async fn canister_main_start(canister_id: Principal) -> Result<(), EgoError> {
+  match start_canister(CanisterIdRecord { canister_id }).await {
     Ok(_) => Ok(()),
     Err((code, msg)) => {
       let code = code as u16;
       Err(EgoError { code, msg })
     }
-  }?;
+  }
+}
+
+pub async fn canister_main_stop(canister_id: Principal) -> Result<(), EgoError> {
+  match stop_canister(CanisterIdRecord { canister_id }).await {
+    Ok(_) => Ok(()),
+    Err((code, msg)) => {
+      let code = code as u16;
+      Err(EgoError { code, msg })
+    }
+  }
+}
+
+pub async fn canister_main_delete(canister_id: Principal) -> Result<(), EgoError> {
+  // stop the canister
+  canister_main_stop(canister_id).await?;
 
   let _delete_result = match delete_canister(CanisterIdRecord { canister_id }).await {
     Ok(_) => Ok(()),